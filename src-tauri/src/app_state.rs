@@ -1,4 +1,4 @@
-use crate::db;
+use crate::{db, logs, notes};
 use rusqlite::Connection;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -16,6 +16,7 @@ pub struct AppPaths {
     pub app_data_dir: PathBuf,
     pub drafts_dir: PathBuf,
     pub trash_dir: PathBuf,
+    pub templates_dir: PathBuf,
     #[allow(dead_code)]
     pub db_path: PathBuf,
 }
@@ -33,13 +34,19 @@ impl AppState {
 
         let drafts_dir = app_data_dir.join("drafts");
         let trash_dir = app_data_dir.join("trash");
+        let templates_dir = app_data_dir.join("templates");
         std::fs::create_dir_all(&drafts_dir)
             .map_err(|err| format!("Failed to create drafts dir: {err}"))?;
         std::fs::create_dir_all(&trash_dir)
             .map_err(|err| format!("Failed to create trash dir: {err}"))?;
+        std::fs::create_dir_all(&templates_dir)
+            .map_err(|err| format!("Failed to create templates dir: {err}"))?;
 
         let db_path = app_data_dir.join("augenblick.db");
         let conn = db::open(&db_path).map_err(|err| format!("Failed to open db: {err}"))?;
+        if let Err(err) = notes::backfill_content_index(&conn) {
+            logs::error("content_index", &format!("backfill failed: err=\"{err}\""));
+        }
 
         Ok(Self {
             db: Arc::new(Mutex::new(conn)),
@@ -47,6 +54,7 @@ impl AppState {
                 app_data_dir,
                 drafts_dir,
                 trash_dir,
+                templates_dir,
                 db_path,
             },
         })
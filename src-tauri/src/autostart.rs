@@ -0,0 +1,105 @@
+//! Launch at login through `tauri-plugin-autostart`: an XDG autostart `.desktop` file on Linux,
+//! a LaunchAgent on macOS and a `Run` registry value on Windows. The OS entry is the only record
+//! of whether it's on, so removing it in the system settings turns it off here too. The entry
+//! always passes [`HIDDEN_FLAG`], since the plugin's arguments are fixed when it's registered;
+//! whether that start leaves the main window closed and the app in the tray is
+//! [`HIDDEN_SETTING`].
+
+use crate::i18n::Message;
+use crate::logs;
+use crate::types::Autostart;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+pub const HIDDEN_FLAG: &str = "--hidden";
+pub const HIDDEN_SETTING: &str = "autostart_hidden";
+
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![HIDDEN_FLAG]))
+}
+
+pub fn status<R: Runtime>(app: &AppHandle<R>, conn: &Connection) -> Result<Autostart, String> {
+    let enabled = app.autolaunch().is_enabled().unwrap_or_else(|err| {
+        logs::error("autostart", &err.to_string());
+        false
+    });
+    Ok(Autostart {
+        enabled,
+        hidden: starts_hidden(conn)?,
+    })
+}
+
+pub fn set<R: Runtime>(
+    app: &AppHandle<R>,
+    conn: &Connection,
+    enabled: bool,
+    hidden: bool,
+) -> Result<(), Message> {
+    let autolaunch = app.autolaunch();
+    let changed = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    changed.map_err(|err| Message::new("error.autostart").arg("error", err))?;
+    conn.execute(
+        "INSERT INTO settings(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![HIDDEN_SETTING, hidden.to_string()],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Whether a launch with [`HIDDEN_FLAG`] should stay in the tray.
+pub fn starts_hidden(conn: &Connection) -> Result<bool, String> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![HIDDEN_SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Points an existing entry at the running executable, which moves with updates and AppImages.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let autolaunch = app.autolaunch();
+    if autolaunch.is_enabled().unwrap_or(false) {
+        if let Err(err) = autolaunch.enable() {
+            logs::error("autostart", &err.to_string());
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use tauri_plugin_autostart::AutoLaunchManager;
+
+    #[test]
+    fn writes_and_removes_the_xdg_entry() {
+        let config = tempfile::tempdir().unwrap();
+        std::env::set_var("XDG_CONFIG_HOME", config.path());
+        let manager = AutoLaunchManager::new(
+            "Augenblick",
+            "/opt/Augen blick/augenblick",
+            MacosLauncher::LaunchAgent,
+            &[HIDDEN_FLAG],
+        );
+        let entry = config.path().join("autostart").join("Augenblick.desktop");
+
+        assert!(!manager.is_enabled().unwrap());
+        manager.enable().unwrap();
+        let text = std::fs::read_to_string(&entry).unwrap();
+        assert!(text.contains(HIDDEN_FLAG), "{text}");
+        assert!(manager.is_enabled().unwrap());
+
+        manager.disable().unwrap();
+        assert!(!entry.exists());
+        assert!(!manager.is_enabled().unwrap());
+    }
+}
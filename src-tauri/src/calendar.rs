@@ -0,0 +1,290 @@
+//! iCalendar export: when notes will expire, when trashed notes will be purged and when
+//! reminders are due, for calendar apps to subscribe to.
+
+use crate::app_state::AppState;
+use crate::expiry;
+use crate::i18n::Message;
+use crate::notes;
+use crate::types::NoteMeta;
+use chrono::{TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const FEED_PATH_SETTING: &str = "calendar_feed_path";
+
+/// Writes the calendar to `path` once.
+pub fn export(conn: &Connection, path: &Path) -> Result<(), Message> {
+    let ics = build(conn, notes::now_ms())?;
+    std::fs::write(path, ics).map_err(|err| Message::new("error.write_failed").arg("error", err))
+}
+
+/// Rewrites the feed file chosen in the settings, if any, when its content changed.
+pub fn update_feed(state: &AppState) -> Result<(), Message> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    update_feed_with(&conn)
+}
+
+pub fn update_feed_with(conn: &Connection) -> Result<(), Message> {
+    let path: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![FEED_PATH_SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    match path.filter(|path| !path.trim().is_empty()) {
+        Some(path) => write_feed(conn, Path::new(&path)),
+        None => Ok(()),
+    }
+}
+
+/// Writes the feed to `path` unless it already holds the same calendar.
+pub fn write_feed(conn: &Connection, path: &Path) -> Result<(), Message> {
+    let ics = build(conn, notes::now_ms())?;
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == ics) {
+        return Ok(());
+    }
+    std::fs::write(path, ics).map_err(|err| Message::new("error.write_failed").arg("error", err))
+}
+
+/// The calendar as of `now`. `DTSTAMP`s come from the notes rather than the clock, so the output
+/// only changes when a note does.
+pub fn build(conn: &Connection, now: i64) -> Result<String, Message> {
+    let list = notes::list(conn)?;
+    let active: HashMap<&str, &NoteMeta> = list
+        .active
+        .iter()
+        .map(|note| (note.id.as_str(), note))
+        .collect();
+
+    let mut out = String::new();
+    push(&mut out, "BEGIN:VCALENDAR");
+    push(&mut out, "VERSION:2.0");
+    push(&mut out, "PRODID:-//Augenblick//Notes//EN");
+    push(&mut out, "CALSCALE:GREGORIAN");
+    push(&mut out, "X-WR-CALNAME:Augenblick");
+
+    let policy = expiry::load_policy(conn)?;
+    let mut deadlines = expiry::deadlines(conn, &policy)?;
+    deadlines.retain(|(_, deadline)| *deadline > now);
+    deadlines.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    for (id, deadline) in deadlines {
+        let Some(note) = active.get(id.as_str()) else {
+            continue;
+        };
+        push(&mut out, "BEGIN:VEVENT");
+        push_common(&mut out, "expiry", note, note.last_interaction);
+        push(&mut out, &format!("DTSTART:{}", utc(deadline)));
+        push(&mut out, "DURATION:PT15M");
+        push(&mut out, "TRANSP:TRANSPARENT");
+        push(
+            &mut out,
+            &format!("SUMMARY:{}", text(&summary("calendar.expires", note))),
+        );
+        push(&mut out, "END:VEVENT");
+    }
+
+    let mut reminders: Vec<&NoteMeta> = list
+        .active
+        .iter()
+        .filter(|note| note.remind_at.is_some())
+        .collect();
+    reminders.sort_by_key(|note| note.remind_at);
+    for note in reminders {
+        let remind_at = note.remind_at.unwrap_or_default();
+        push(&mut out, "BEGIN:VEVENT");
+        push_common(&mut out, "reminder", note, note.last_interaction);
+        push(&mut out, &format!("DTSTART:{}", utc(remind_at)));
+        push(&mut out, "DURATION:PT15M");
+        push(
+            &mut out,
+            &format!("SUMMARY:{}", text(&summary("calendar.reminder", note))),
+        );
+        push(&mut out, "BEGIN:VALARM");
+        push(&mut out, "ACTION:DISPLAY");
+        push(&mut out, &format!("DESCRIPTION:{}", text(&note.title)));
+        push(&mut out, "TRIGGER:PT0S");
+        push(&mut out, "END:VALARM");
+        push(&mut out, "END:VEVENT");
+    }
+
+    let retention_ms = expiry::trash_retention_ms(conn)?;
+    let mut trashed: Vec<(&NoteMeta, i64)> = list
+        .trashed
+        .iter()
+        .filter_map(|note| Some((note, note.trashed_at?)))
+        .filter(|(_, trashed_at)| trashed_at + retention_ms > now)
+        .collect();
+    trashed.sort_by_key(|(note, trashed_at)| (*trashed_at, note.id.clone()));
+    for (note, trashed_at) in trashed {
+        push(&mut out, "BEGIN:VTODO");
+        push_common(&mut out, "purge", note, trashed_at);
+        push(&mut out, &format!("DUE:{}", utc(trashed_at + retention_ms)));
+        push(&mut out, "STATUS:NEEDS-ACTION");
+        push(
+            &mut out,
+            &format!("SUMMARY:{}", text(&summary("calendar.purged", note))),
+        );
+        push(&mut out, "END:VTODO");
+    }
+
+    push(&mut out, "END:VCALENDAR");
+    Ok(out)
+}
+
+/// An event title such as "Reminder: Groceries", in the locale of the export.
+fn summary(key: &'static str, note: &NoteMeta) -> String {
+    Message::new(key).arg("title", &note.title).to_string()
+}
+
+fn push_common(out: &mut String, kind: &str, note: &NoteMeta, stamp: i64) {
+    push(out, &format!("UID:{kind}-{}@augenblick", note.id));
+    push(out, &format!("DTSTAMP:{}", utc(stamp)));
+    if !note.preview.is_empty() {
+        push(out, &format!("DESCRIPTION:{}", text(&note.preview)));
+    }
+    push(out, &format!("X-AUGENBLICK-NOTE-ID:{}", note.id));
+}
+
+/// Appends a content line, folded to 75 octets and ended with CRLF as RFC 5545 asks.
+fn push(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+fn utc(ms: i64) -> String {
+    Utc.timestamp_millis_opt(ms)
+        .single()
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_800_000_000_000;
+    const HOUR: i64 = 3_600_000;
+
+    fn insert(
+        conn: &Connection,
+        id: &str,
+        title: &str,
+        remind_at: Option<i64>,
+        trashed_at: Option<i64>,
+    ) {
+        conn.execute(
+            r#"
+INSERT INTO notes (id, title, preview, file_path, storage, sort_order, created_at, last_interaction,
+                   remind_at, is_trashed, trashed_at)
+VALUES (?1, ?2, 'a, b', '', 'draft', 0, ?3, ?3, ?4, ?5, ?6)
+"#,
+            params![
+                id,
+                title,
+                NOW - HOUR,
+                remind_at,
+                trashed_at.is_some(),
+                trashed_at
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn builds_events_and_todos() {
+        let conn = crate::db::open(Path::new(":memory:")).unwrap();
+        insert(&conn, "a", "Alpha", None, None);
+        insert(&conn, "b", "Beta", Some(NOW + 2 * HOUR), None);
+        insert(&conn, "c", "Gamma", None, Some(NOW - HOUR));
+        // Purges that already happened are left out.
+        insert(&conn, "d", "Delta", None, Some(NOW - 31 * 24 * HOUR));
+        // A reminder stays in the feed until it is dismissed, even once it is overdue.
+        insert(&conn, "e", "Epsilon", Some(NOW - HOUR), None);
+
+        let ics = build(&conn, NOW).unwrap();
+        let lines: Vec<&str> = ics.split("\r\n").collect();
+        assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+        assert_eq!(lines[lines.len() - 2], "END:VCALENDAR");
+
+        let expiry = [
+            "BEGIN:VEVENT",
+            "UID:expiry-a@augenblick",
+            &format!("DTSTAMP:{}", utc(NOW - HOUR)),
+            "DESCRIPTION:a\\, b",
+            "X-AUGENBLICK-NOTE-ID:a",
+            &format!("DTSTART:{}", utc(NOW - HOUR + 7 * 24 * HOUR)),
+            "DURATION:PT15M",
+            "TRANSP:TRANSPARENT",
+            "SUMMARY:Expires: Alpha",
+            "END:VEVENT",
+        ]
+        .join("\r\n");
+        assert!(ics.contains(&expiry), "{ics}");
+
+        assert!(ics.contains("UID:reminder-b@augenblick"));
+        assert!(ics.contains(&format!(
+            "DTSTART:{}\r\nDURATION:PT15M\r\nSUMMARY:Reminder: Beta\r\nBEGIN:VALARM",
+            utc(NOW + 2 * HOUR)
+        )));
+        assert!(!ics.contains("UID:expiry-b@augenblick"));
+        assert!(ics.contains(&format!(
+            "DTSTART:{}\r\nDURATION:PT15M\r\nSUMMARY:Reminder: Epsilon",
+            utc(NOW - HOUR)
+        )));
+
+        let purge = [
+            "BEGIN:VTODO",
+            "UID:purge-c@augenblick",
+            &format!("DTSTAMP:{}", utc(NOW - HOUR)),
+            "DESCRIPTION:a\\, b",
+            "X-AUGENBLICK-NOTE-ID:c",
+            &format!("DUE:{}", utc(NOW - HOUR + 30 * 24 * HOUR)),
+            "STATUS:NEEDS-ACTION",
+            "SUMMARY:Purged from trash: Gamma",
+            "END:VTODO",
+        ]
+        .join("\r\n");
+        assert!(ics.contains(&purge), "{ics}");
+        assert!(!ics.contains("-d@augenblick"));
+    }
+
+    #[test]
+    fn folds_and_escapes() {
+        let mut out = String::new();
+        push(&mut out, &format!("SUMMARY:{}", "ä".repeat(40)));
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert!(lines[1].starts_with(' '));
+
+        assert_eq!(text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+        assert_eq!(utc(0), "19700101T000000Z");
+    }
+}
@@ -0,0 +1,314 @@
+//! Command-line launches: `augenblick <file…>` imports and opens each file, `--new` starts an
+//! empty note and `--new-from-stdin` one with whatever was piped in. Only one instance runs: a
+//! later launch hands its arguments to the running one (see the single-instance plugin in
+//! `lib.rs`) and exits.
+
+use crate::app_state::AppState;
+use crate::deep_link;
+use crate::events;
+use crate::i18n::Message;
+use crate::logs;
+use crate::notes;
+use crate::types::{ChangeReason, ConvertedImportTarget, NoteChange};
+use crate::window_state::show_main_window;
+use rusqlite::params;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    Open(PathBuf),
+    New,
+    /// The spool file named by `--stdin-spool`, if anything was piped in.
+    NewFromStdin(Option<PathBuf>),
+}
+
+/// Names the file `spool_stdin` saved the piped text in; added to the relaunch's arguments.
+const SPOOL_FLAG: &str = "--stdin-spool=";
+
+/// Reads the actions from `args` (including the program name). Relative paths are resolved
+/// against `cwd`, the directory the launch happened in; unknown flags and `augenblick:` links
+/// are ignored.
+pub fn parse(args: &[String], cwd: &Path) -> Vec<Action> {
+    let mut actions = Vec::new();
+    let mut spool = None;
+    let mut options_done = false;
+    for arg in args.iter().skip(1) {
+        if !options_done && arg.starts_with('-') {
+            match arg.as_str() {
+                "--" => options_done = true,
+                "--new" => actions.push(Action::New),
+                "--new-from-stdin" => actions.push(Action::NewFromStdin(None)),
+                _ => {
+                    if let Some(path) = arg.strip_prefix(SPOOL_FLAG) {
+                        spool = Some(PathBuf::from(path));
+                    }
+                }
+            }
+            continue;
+        }
+        if arg.starts_with(&format!("{}:", deep_link::SCHEME)) {
+            // Handled by the deep-link plugin.
+            continue;
+        }
+        // Desktop launchers pass files as `file://` URLs.
+        let path = match Url::parse(arg).ok().filter(|url| url.scheme() == "file") {
+            Some(url) => match url.to_file_path() {
+                Ok(path) => path,
+                Err(()) => continue,
+            },
+            None => cwd.join(arg),
+        };
+        actions.push(Action::Open(path));
+    }
+    let stdin = actions.iter_mut().find_map(|action| match action {
+        Action::NewFromStdin(spooled) => Some(spooled),
+        _ => None,
+    });
+    if let Some(spooled) = stdin {
+        *spooled = spool;
+    }
+    actions
+}
+
+/// Saves piped stdin for `--new-from-stdin` and relaunches with `--stdin-spool=<file>`. It has to
+/// happen before the app starts: a second launch exits inside the single-instance plugin and
+/// only its arguments reach the running instance, so they have to name the file. The relaunch
+/// becomes the app or hands over to it; this process waits for it and exits with its status.
+pub fn spool_stdin(args: &[String]) {
+    if !args.iter().any(|arg| arg == "--new-from-stdin")
+        || args.iter().any(|arg| arg.starts_with(SPOOL_FLAG))
+        || std::io::stdin().is_terminal()
+    {
+        return;
+    }
+    let mut bytes = Vec::new();
+    if let Err(err) = std::io::stdin().read_to_end(&mut bytes) {
+        eprintln!("Couldn't read the note from stdin: {err}");
+        return;
+    }
+    let path = match spool(&spool_dir(), &bytes) {
+        Ok(Some(path)) => path,
+        Ok(None) => return,
+        Err(err) => {
+            eprintln!("Couldn't read the note from stdin: {err}");
+            return;
+        }
+    };
+    // Before `--`, so the flag isn't taken for a file name.
+    let relaunch = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe)
+            .arg(format!("{SPOOL_FLAG}{}", path.display()))
+            .args(&args[1..])
+            .stdin(std::process::Stdio::null())
+            .status()
+    });
+    match relaunch {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => {
+            let _ = std::fs::remove_file(&path);
+            eprintln!("Couldn't hand the note to Augenblick: {err}");
+        }
+    }
+}
+
+/// Writes the decoded text as UTF-8 under a temporary name and renames it into place, so the
+/// running instance never reads a half-written file. Blank input is dropped.
+fn spool(dir: &Path, bytes: &[u8]) -> std::io::Result<Option<PathBuf>> {
+    let text = notes::decode_text(bytes);
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    let id = uuid::Uuid::new_v4();
+    let partial = dir.join(format!(".{id}.part"));
+    let path = dir.join(format!("{id}.md"));
+    create_private_dir(dir)?;
+    std::fs::write(&partial, text)?;
+    if let Err(err) = std::fs::rename(&partial, &path) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(err);
+    }
+    Ok(Some(path))
+}
+
+/// Creates `dir` readable by the user only. In a shared temp dir someone else may have created
+/// it first; their directory can't be chmodded, so it is refused rather than written into.
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    if !std::fs::symlink_metadata(dir)?.is_dir() {
+        return Err(std::io::Error::other("the spool directory is a link"));
+    }
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)
+}
+
+/// Whether `path` is a spool file, so a forwarded `--stdin-spool` can't import (and delete) any
+/// other file.
+fn is_spooled(dir: &Path, path: &Path) -> bool {
+    path.parent() == Some(dir) && path.extension().is_some_and(|ext| ext == "md")
+}
+
+/// Per user where the platform has a runtime dir; otherwise the temp dir, which is per user on
+/// macOS and Windows and shared on Linux.
+fn spool_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("augenblick-stdin")
+}
+
+/// Runs the launch's actions. `forwarded` is true for arguments handed over by a later launch;
+/// one without actions just brings the running instance to front.
+pub fn handle(app: &AppHandle, args: &[String], cwd: &Path, forwarded: bool) {
+    let actions = parse(args, cwd);
+    if actions.is_empty() {
+        if forwarded {
+            show_main_window(app);
+        }
+        return;
+    }
+    for action in actions {
+        let result = match action {
+            Action::Open(path) => open_file(app, &path),
+            Action::New => new_note(app, ""),
+            Action::NewFromStdin(spooled) => from_stdin(app, spooled.as_deref()),
+        };
+        if let Err(err) = result {
+            logs::error("cli", &err);
+        }
+    }
+}
+
+/// Opens files handed to the app by the OS, e.g. "Open With" on macOS.
+pub fn open_paths(app: &AppHandle, paths: Vec<PathBuf>) {
+    for path in paths {
+        if let Err(err) = open_file(app, &path) {
+            logs::error("cli", &err);
+        }
+    }
+}
+
+fn open_file(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let opened = notes::import_file(&conn, &state.paths, path, ConvertedImportTarget::default())?;
+    drop(conn);
+    if opened.created {
+        let change = NoteChange::created(opened.note.meta.clone());
+        events::publish_one(app, ChangeReason::Imported, change);
+    }
+    show_note(app, &opened.note.meta.id)
+}
+
+fn new_note(app: &AppHandle, content: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let meta = notes::create_with_content(&conn, &state.paths, content, None)?;
+    drop(conn);
+    let id = meta.id.clone();
+    events::publish_one(app, ChangeReason::Created, NoteChange::created(meta));
+    show_note(app, &id)
+}
+
+/// A note with the text this launch spooled, or an empty one when nothing was piped in. The
+/// spool file is only removed once its note exists.
+fn from_stdin(app: &AppHandle, spooled: Option<&Path>) -> Result<(), String> {
+    let Some(path) = spooled else {
+        return new_note(app, "");
+    };
+    if !is_spooled(&spool_dir(), path) {
+        return Err(format!("not a spooled note: {}", path.display()));
+    }
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    new_note(app, &text)?;
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+/// Shows the main window on note `id`. It also becomes the selected note, so a window that
+/// hasn't loaded yet opens it at startup.
+pub fn show_note(app: &AppHandle, id: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    for (key, value) in [("selectedNoteId", id), ("viewMode", "notes")] {
+        conn.execute(
+            "INSERT INTO app_state(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    drop(conn);
+    show_main_window(app);
+    let _ = app.emit("open-note", id.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_files_and_flags() {
+        let args = [
+            "app",
+            "--new",
+            "notes.md",
+            "/abs/todo.md",
+            "-x",
+            "--",
+            "-odd.md",
+            "augenblick://new",
+            "file:///abs/a%20b.md",
+        ];
+        let args = args.map(String::from);
+        assert_eq!(
+            parse(&args, Path::new("/home/me")),
+            [
+                Action::New,
+                Action::Open(PathBuf::from("/home/me/notes.md")),
+                Action::Open(PathBuf::from("/abs/todo.md")),
+                Action::Open(PathBuf::from("/home/me/-odd.md")),
+                Action::Open(PathBuf::from("/abs/a b.md")),
+            ]
+        );
+        let args = ["app", "--new-from-stdin"].map(String::from);
+        assert_eq!(parse(&args, Path::new("/")), [Action::NewFromStdin(None)]);
+        let args = ["app", "--stdin-spool=/run/s/a.md", "--new-from-stdin"].map(String::from);
+        assert_eq!(
+            parse(&args, Path::new("/")),
+            [Action::NewFromStdin(Some(PathBuf::from("/run/s/a.md")))]
+        );
+    }
+
+    #[test]
+    fn spools_decoded_stdin_in_one_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool_dir = dir.path().join("spool");
+
+        assert_eq!(spool(&spool_dir, b" \n").unwrap(), None);
+        // Windows-1252 from a legacy pipe, with CRLF line breaks.
+        let path = spool(&spool_dir, b"Gr\xfc\xdfe\r\nTsch\xfcss")
+            .unwrap()
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Grüße\nTschüss");
+        assert!(is_spooled(&spool_dir, &path));
+        assert!(!is_spooled(&spool_dir, &spool_dir.join("../notes.md")));
+        assert!(!is_spooled(&spool_dir, &dir.path().join("notes.md")));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&spool_dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+}
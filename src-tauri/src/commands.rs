@@ -1,176 +1,679 @@
 use crate::app_state::AppState;
-use crate::types::{AppSettings, NoteMeta, NoteWithContent, NotesList};
-use crate::{expiry, notes};
+use crate::i18n::{self, CommandError, Message};
+use crate::types::{
+    AppSettings, BatchReport, ChangeReason, ConvertedImportTarget, ExternalImportReport,
+    ExternalSource, Keybinding, NoteChange, NoteFromTemplate, NoteLink, NoteMeta, NoteStorage,
+    NoteTemplate, NoteWindowMode, NoteWithContent, NotesList, ObsidianExportItem, Recurrence,
+    RecurrenceInput, TaskFilter, TaskItem,
+};
+use crate::{autostart, calendar, events, expiry, keybindings, notes, quick_capture};
 use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 
 #[tauri::command]
-pub fn notes_list(state: State<'_, AppState>) -> Result<NotesList, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::list(&conn)
+pub fn notes_list(state: State<'_, AppState>) -> Result<NotesList, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::list(&conn)?)
 }
 
 #[tauri::command]
-pub fn note_create(state: State<'_, AppState>) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::create_draft(&conn, &state.paths)
+pub fn note_create(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let meta = notes::create_draft(&conn, &state.paths)?;
+    drop(conn);
+    let change = NoteChange::created(meta.clone());
+    events::publish_one(&app, ChangeReason::Created, change);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn templates_list(state: State<'_, AppState>) -> Result<Vec<NoteTemplate>, CommandError> {
+    Ok(notes::templates(&state.paths)?)
+}
+
+/// New draft from `templates/<name>.md`. The frontend passes the clipboard text when the
+/// template uses `{{clipboard}}`.
+#[tauri::command]
+pub fn note_create_from_template(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    clipboard: Option<String>,
+) -> Result<NoteFromTemplate, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let created = notes::create_from_template(&conn, &state.paths, &name, clipboard.as_deref())?;
+    drop(conn);
+    let change = NoteChange::created(created.note.meta.clone());
+    events::publish_one(&app, ChangeReason::Created, change);
+    Ok(created)
+}
+
+/// Opens the daily note for `date` (`YYYY-MM-DD`, default today), creating it if needed.
+#[tauri::command]
+pub fn note_daily(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    date: Option<String>,
+) -> Result<NoteWithContent, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let opened = notes::daily(&conn, &state.paths, date.as_deref())?;
+    drop(conn);
+    if opened.created {
+        let change = NoteChange::created(opened.note.meta.clone());
+        events::publish_one(&app, ChangeReason::Created, change);
+    }
+    Ok(opened.note)
+}
+
+#[tauri::command]
+pub fn recurrences_list(state: State<'_, AppState>) -> Result<Vec<Recurrence>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::recurrences(&conn)?)
+}
+
+/// Creates a recurring note, or updates the one with `recurrence.id`.
+#[tauri::command]
+pub fn recurrence_save(
+    state: State<'_, AppState>,
+    recurrence: RecurrenceInput,
+) -> Result<Recurrence, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::save_recurrence(&conn, &state.paths, &recurrence)?)
 }
 
 #[tauri::command]
-pub fn note_get(state: State<'_, AppState>, id: String) -> Result<NoteWithContent, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::get(&conn, &id)
+pub fn recurrence_delete(state: State<'_, AppState>, id: String) -> Result<(), CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::delete_recurrence(&conn, &id)?)
 }
 
 #[tauri::command]
-pub fn note_set_active(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::set_active(&conn, &id)
+pub fn note_get(state: State<'_, AppState>, id: String) -> Result<NoteWithContent, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::get(&conn, &id)?)
+}
+
+#[tauri::command]
+pub fn note_remove_front_matter(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<NoteWithContent, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let note = notes::remove_front_matter(&conn, &id)?;
+    drop(conn);
+    let change = NoteChange::updated(before, note.meta.clone());
+    events::publish_one(&app, ChangeReason::Written, change);
+    Ok(note)
+}
+
+#[tauri::command]
+pub fn note_set_active(state: State<'_, AppState>, id: String) -> Result<(), CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::set_active(&conn, &id)?)
 }
 
 #[tauri::command]
 pub fn note_write_draft(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
     content: String,
-) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::write_draft(&conn, &id, &content)
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::write_draft(&conn, &id, &content)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Written, change);
+    Ok(meta)
 }
 
 #[tauri::command]
 pub fn note_save(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    content: String,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::save(&conn, &id, &content)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Saved, change);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn note_convert_to_utf8(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
     content: String,
-) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::save(&conn, &id, &content)
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::convert_to_utf8(&conn, &id, &content)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Saved, change);
+    Ok(meta)
 }
 
 #[tauri::command]
 pub fn note_save_as(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
     path: String,
     content: String,
-) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
     let new_path = PathBuf::from(path);
-    notes::save_as(&conn, &state.paths, &id, &new_path, &content)
+    let meta = notes::save_as(&conn, &state.paths, &id, &new_path, &content)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Saved, change);
+    Ok(meta)
 }
 
 #[tauri::command]
 pub fn note_import_file(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    converted_target: Option<ConvertedImportTarget>,
+) -> Result<NoteWithContent, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let opened = notes::import_file(
+        &conn,
+        &state.paths,
+        PathBuf::from(path).as_path(),
+        converted_target.unwrap_or_default(),
+    )?;
+    drop(conn);
+    if opened.created {
+        let change = NoteChange::created(opened.note.meta.clone());
+        events::publish_one(&app, ChangeReason::Imported, change);
+    }
+    Ok(opened.note)
+}
+
+/// Imports a Google Keep Takeout folder or Simplenote export. Call with `dry_run` first to get
+/// the report, then again without it to commit.
+#[tauri::command]
+pub fn notes_import_external(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
+    source: ExternalSource,
     path: String,
-) -> Result<NoteWithContent, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::import_file(&conn, &state.paths, PathBuf::from(path).as_path())
+    dry_run: bool,
+) -> Result<ExternalImportReport, CommandError> {
+    let mut conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let report = notes::import_external(
+        &mut conn,
+        &state.paths,
+        source,
+        PathBuf::from(path).as_path(),
+        dry_run,
+    )?;
+    let changes = report
+        .notes
+        .iter()
+        .filter_map(|item| item.note_id.as_deref())
+        .map(|id| notes::get_meta(&conn, id).map(NoteChange::created))
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(conn);
+    events::publish(&app, ChangeReason::Imported, changes);
+    Ok(report)
+}
+
+/// Writes the notes into `dir` (e.g. a folder in an Obsidian vault) as markdown with front
+/// matter. Existing files are never overwritten.
+#[tauri::command]
+pub fn notes_export_obsidian(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    dir: String,
+) -> Result<Vec<ObsidianExportItem>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let policy = expiry::load_policy(&conn)?;
+    let deadlines: HashMap<String, i64> = expiry::deadlines(&conn, &policy)?.into_iter().collect();
+    Ok(notes::export_obsidian(
+        &conn,
+        &ids,
+        PathBuf::from(dir).as_path(),
+        &deadlines,
+    )?)
+}
+
+/// `[[wikilinks]]` written in the note, resolved against the current notes.
+#[tauri::command]
+pub fn note_outgoing_links(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<NoteLink>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::outgoing_links(&conn, &id)?)
+}
+
+/// Links in other active notes that resolve to this note.
+#[tauri::command]
+pub fn note_backlinks(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<NoteLink>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::backlinks(&conn, &id)?)
+}
+
+/// Task items across active notes; open tasks unless the filter asks otherwise.
+#[tauri::command]
+pub fn tasks_list(
+    state: State<'_, AppState>,
+    filter: Option<TaskFilter>,
+) -> Result<Vec<TaskItem>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::tasks_list(&conn, &filter.unwrap_or_default())?)
+}
+
+/// Checks or unchecks the task on `line` and writes the note like an edit would.
+#[tauri::command]
+pub fn task_toggle(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    note_id: String,
+    line: i64,
+    checked: bool,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &note_id)?;
+    let meta = notes::task_toggle(&conn, &note_id, line, checked)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Written, change);
+    Ok(meta)
+}
+
+/// Sets the reminder time (Unix ms), or dismisses the reminder when `at` is `None`.
+#[tauri::command]
+pub fn note_set_reminder(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    at: Option<i64>,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::set_reminder(&conn, &id, at)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Reminder, change);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn note_snooze_reminder(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    minutes: i64,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::snooze_reminder(&conn, &id, minutes)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Reminder, change);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn note_trash(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::trash(&conn, &state.paths, &id)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Trashed, change);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn note_restore(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::restore(&conn, &state.paths, &id)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Restored, change);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn note_delete_forever(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    notes::delete_forever(&conn, &id)?;
+    drop(conn);
+    events::publish_one(&app, ChangeReason::Deleted, NoteChange::deleted(before));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn note_pin(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    pinned: bool,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let before = notes::get_meta(&conn, &id)?;
+    let meta = notes::set_pinned(&conn, &id, pinned)?;
+    drop(conn);
+    let change = NoteChange::updated(before, meta.clone());
+    events::publish_one(&app, ChangeReason::Pinned, change);
+    Ok(meta)
+}
+
+/// Batch commands take the lock once for all ids and run in one transaction: if any id fails,
+/// nothing changes and the report says which ids failed.
+#[tauri::command]
+pub fn notes_trash_many(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<BatchReport, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let report = notes::trash_many(&conn, &state.paths, &ids)?;
+    drop(conn);
+    Ok(publish_batch(&app, ChangeReason::Trashed, report))
+}
+
+#[tauri::command]
+pub fn notes_restore_many(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<BatchReport, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let report = notes::restore_many(&conn, &state.paths, &ids)?;
+    drop(conn);
+    Ok(publish_batch(&app, ChangeReason::Restored, report))
+}
+
+#[tauri::command]
+pub fn notes_delete_forever_many(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<BatchReport, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let report = notes::delete_forever_many(&conn, &ids)?;
+    drop(conn);
+    Ok(publish_batch(&app, ChangeReason::Deleted, report))
 }
 
+/// Pinning more notes than the cap allows fails the whole batch.
 #[tauri::command]
-pub fn note_trash(state: State<'_, AppState>, id: String) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::trash(&conn, &state.paths, &id)
+pub fn notes_pin_many(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    pinned: bool,
+) -> Result<BatchReport, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let report = notes::pin_many(&conn, &ids, pinned)?;
+    drop(conn);
+    Ok(publish_batch(&app, ChangeReason::Pinned, report))
+}
+
+/// Writes the notes into `dir` as markdown files named after their titles; existing files are
+/// never overwritten.
+#[tauri::command]
+pub fn notes_export_many(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    dir: String,
+) -> Result<BatchReport, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(notes::export_many(&conn, &ids, Path::new(&dir))?)
 }
 
 #[tauri::command]
-pub fn note_restore(state: State<'_, AppState>, id: String) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::restore(&conn, &state.paths, &id)
+pub fn trash_empty(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BatchReport, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let report = notes::empty_trash(&conn)?;
+    drop(conn);
+    Ok(publish_batch(&app, ChangeReason::Deleted, report))
+}
+
+fn publish_batch(
+    app: &tauri::AppHandle,
+    reason: ChangeReason,
+    mut report: BatchReport,
+) -> BatchReport {
+    events::publish(app, reason, std::mem::take(&mut report.changes));
+    report
 }
 
 #[tauri::command]
-pub fn note_delete_forever(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::delete_forever(&conn, &id)
+pub fn notes_reorder(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<(), CommandError> {
+    let mut conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    // Unknown or deleted ids are skipped, as `notes::reorder` skips them.
+    let before: Vec<NoteMeta> = ids
+        .iter()
+        .filter_map(|id| notes::get_meta(&conn, id).ok())
+        .collect();
+    notes::reorder(&mut conn, &ids)?;
+    let mut changes = Vec::with_capacity(before.len());
+    for before in before {
+        let after = notes::get_meta(&conn, &before.id)?;
+        changes.push(NoteChange::updated(before, after));
+    }
+    drop(conn);
+    events::publish(&app, ChangeReason::Reordered, changes);
+    Ok(())
 }
 
+/// Undoes the latest trash, delete, pin, reorder or auto-expiry and returns its label, or `None`
+/// when there is nothing to undo.
 #[tauri::command]
-pub fn note_pin(state: State<'_, AppState>, id: String, pinned: bool) -> Result<NoteMeta, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::set_pinned(&conn, &id, pinned)
+pub fn history_undo(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, CommandError> {
+    Ok(step_history(&app, &state, false)?)
 }
 
 #[tauri::command]
-pub fn notes_reorder(state: State<'_, AppState>, ids: Vec<String>) -> Result<(), String> {
-    let mut conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    notes::reorder(&mut conn, &ids)
+pub fn history_redo(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, CommandError> {
+    Ok(step_history(&app, &state, true)?)
+}
+
+/// Shared with the Edit menu items.
+pub fn step_history(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    redo: bool,
+) -> Result<Option<String>, Message> {
+    let mut conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let step = if redo {
+        notes::redo(&mut conn, &state.paths)?
+    } else {
+        notes::undo(&mut conn, &state.paths)?
+    };
+    drop(conn);
+    let Some(step) = step else {
+        return Ok(None);
+    };
+    let reason = if redo {
+        ChangeReason::Redo
+    } else {
+        ChangeReason::Undo
+    };
+    events::publish(app, reason, step.changes);
+    Ok(Some(step.label))
 }
 
 #[tauri::command]
-pub fn settings_get_all(state: State<'_, AppState>) -> Result<AppSettings, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
+pub fn settings_get_all(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
     Ok(AppSettings {
         expiry_minutes: get_setting_int(&conn, "expiry_minutes", 10_080)?,
         trash_retention_days: get_setting_int(&conn, "trash_retention_days", 30)?,
         theme: get_setting_string(&conn, "theme", "dark")?,
+        front_matter_write_back: get_setting_string(&conn, "front_matter_write_back", "false")?
+            == "true",
+        open_tasks_expiry: get_setting_string(&conn, "open_tasks_expiry", "off")?,
+        open_tasks_max_days: get_setting_int(&conn, "open_tasks_max_days", 30)?,
+        done_tasks_grace_minutes: get_setting_int(&conn, "done_tasks_grace_minutes", 0)?,
+        daily_expiry_minutes: get_setting_int(&conn, "daily_expiry_minutes", 4320)?,
+        daily_carry_tasks: get_setting_string(&conn, "daily_carry_tasks", "true")? == "true",
+        calendar_feed_path: get_setting_string(&conn, calendar::FEED_PATH_SETTING, "")?,
+        quick_capture_shortcut: get_setting_string(
+            &conn,
+            quick_capture::SHORTCUT_SETTING,
+            quick_capture::DEFAULT_SHORTCUT,
+        )?,
+        quick_capture_inbox_id: get_setting_string(&conn, quick_capture::INBOX_SETTING, "")?,
+        autostart: autostart::status(&app, &conn)?,
+        locale: get_setting_string(&conn, i18n::SETTING, i18n::SYSTEM)?,
     })
 }
 
 #[tauri::command]
-pub fn settings_set(state: State<'_, AppState>, key: String, value: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
-    set_setting(&conn, &key, &value)?;
+pub fn settings_set(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    key: String,
+    value: String,
+) -> Result<(), CommandError> {
+    // The shortcut is registered before it is saved so one the OS refuses is never stored.
+    let mut replaced_shortcut = None;
+    if key == quick_capture::SHORTCUT_SETTING {
+        let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+        let previous = get_setting_string(&conn, &key, quick_capture::DEFAULT_SHORTCUT)?;
+        if !value.trim().is_empty() {
+            keybindings::ensure_free(&conn, &value, None)?;
+        }
+        drop(conn);
+        quick_capture::replace_shortcut(&app, &previous, &value)?;
+        replaced_shortcut = Some(previous);
+    }
+    if key == i18n::SETTING {
+        i18n::validate(&value)?;
+    }
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    // A feed path that can't be written is refused instead of being saved.
+    if key == calendar::FEED_PATH_SETTING && !value.trim().is_empty() {
+        calendar::write_feed(&conn, Path::new(&value))?;
+    }
+    if let Err(err) = set_setting(&conn, &key, &value) {
+        // Put the old shortcut back so the registered one matches the stored setting.
+        if let Some(previous) = replaced_shortcut {
+            let _ = quick_capture::replace_shortcut(&app, &value, &previous);
+        }
+        return Err(err.into());
+    }
+    if key == i18n::SETTING {
+        i18n::load(&conn);
+        calendar::update_feed_with(&conn)?;
+    }
+    drop(conn);
+    // The tray and the history items follow through the notes-changed event below.
+    if key == i18n::SETTING {
+        crate::refresh_app_menu(&app)?;
+    }
+    events::publish(&app, ChangeReason::Settings, Vec::new());
+    Ok(())
+}
+
+/// Turns launching at login on or off; `hidden` starts in the tray without the main window.
+#[tauri::command]
+pub fn autostart_set(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+    hidden: bool,
+) -> Result<(), CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    autostart::set(&app, &conn, enabled, hidden)?;
+    drop(conn);
+    events::publish(&app, ChangeReason::Settings, Vec::new());
     Ok(())
 }
 
 #[tauri::command]
-pub fn app_state_get_all(state: State<'_, AppState>) -> Result<HashMap<String, String>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
+pub fn keybindings_get(state: State<'_, AppState>) -> Result<Vec<Keybinding>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(keybindings::list(&conn)?)
+}
+
+/// Binds a menu action to `accelerator` and rebuilds the menu. `None` restores the default and
+/// an empty accelerator removes the shortcut.
+#[tauri::command]
+pub fn keybindings_set(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    accelerator: Option<String>,
+) -> Result<Vec<Keybinding>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    keybindings::set(&conn, &id, accelerator.as_deref())?;
+    let bindings = keybindings::list(&conn)?;
+    drop(conn);
+    crate::refresh_app_menu(&app)?;
+    events::publish(&app, ChangeReason::Settings, Vec::new());
+    Ok(bindings)
+}
+
+#[tauri::command]
+pub fn calendar_export(state: State<'_, AppState>, path: String) -> Result<(), CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    Ok(calendar::export(&conn, Path::new(&path))?)
+}
+
+#[tauri::command]
+pub fn app_state_get_all(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, String>, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
     let mut stmt = conn
         .prepare("SELECT key, value FROM app_state")
         .map_err(|err| err.to_string())?;
@@ -185,11 +688,12 @@ pub fn app_state_get_all(state: State<'_, AppState>) -> Result<HashMap<String, S
 }
 
 #[tauri::command]
-pub fn app_state_set(state: State<'_, AppState>, key: String, value: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
+pub fn app_state_set(
+    state: State<'_, AppState>,
+    key: String,
+    value: String,
+) -> Result<(), CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
     conn.execute(
         "INSERT INTO app_state(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
         params![key, value],
@@ -199,25 +703,37 @@ pub fn app_state_set(state: State<'_, AppState>, key: String, value: String) ->
 }
 
 #[tauri::command]
-pub fn expiry_run_now(state: State<'_, AppState>) -> Result<(), String> {
-    expiry::sweep(&state)
+pub fn expiry_run_now(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let changes = expiry::sweep(&state)?;
+    events::publish(&app, ChangeReason::Expired, changes);
+    Ok(())
 }
 
 #[tauri::command]
-pub fn app_exit(app: tauri::AppHandle) -> Result<(), String> {
+pub fn app_exit(app: tauri::AppHandle) -> Result<(), CommandError> {
     app.exit(0);
     Ok(())
 }
 
 #[tauri::command]
-pub fn app_set_activation_policy(app: tauri::AppHandle, policy: String) -> Result<(), String> {
+pub fn app_set_activation_policy(
+    app: tauri::AppHandle,
+    policy: String,
+) -> Result<(), CommandError> {
     #[cfg(target_os = "macos")]
     {
         let policy = match policy.as_str() {
             "regular" => ActivationPolicy::Regular,
             "accessory" => ActivationPolicy::Accessory,
             "prohibited" => ActivationPolicy::Prohibited,
-            _ => return Err(format!("Unknown activation policy: {policy}")),
+            _ => {
+                return Err(Message::new("error.activation_policy")
+                    .arg("policy", &policy)
+                    .into())
+            }
         };
         app.set_activation_policy(policy)
             .map_err(|err| err.to_string())?;
@@ -228,11 +744,52 @@ pub fn app_set_activation_policy(app: tauri::AppHandle, policy: String) -> Resul
 }
 
 #[tauri::command]
-pub fn app_show_main_window(app: tauri::AppHandle) -> Result<(), String> {
+pub fn app_show_main_window(app: tauri::AppHandle) -> Result<(), CommandError> {
     crate::window_state::show_main_window(&app);
     Ok(())
 }
 
+/// Shows the note in its own window, or as a sticky note that stays on top of other apps.
+#[tauri::command]
+pub fn note_open_window(
+    app: tauri::AppHandle,
+    id: String,
+    mode: NoteWindowMode,
+) -> Result<(), CommandError> {
+    Ok(crate::note_windows::open(&app, &id, mode)?)
+}
+
+/// Files the text typed into the quick-capture window and hides it.
+#[tauri::command]
+pub fn quick_capture_submit(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<NoteMeta, CommandError> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let inbox = quick_capture::inbox_id(&conn)?;
+    let change = notes::capture(&conn, &state.paths, inbox.as_deref(), &text)?;
+    drop(conn);
+    let reason = match &change.before {
+        None => ChangeReason::Created,
+        Some(before) if before.storage == NoteStorage::Saved => ChangeReason::Saved,
+        Some(_) => ChangeReason::Written,
+    };
+    let meta = change
+        .after
+        .clone()
+        .ok_or_else(|| Message::new("error.capture_missing"))?;
+    events::publish_one(&app, reason, change);
+    quick_capture::hide(&app);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub fn quick_capture_hide(app: tauri::AppHandle) -> Result<(), CommandError> {
+    quick_capture::hide(&app);
+    Ok(())
+}
+
 fn get_setting_int(conn: &Connection, key: &str, default: i64) -> Result<i64, String> {
     let existing: Option<String> = conn
         .query_row(
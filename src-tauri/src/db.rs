@@ -1,6 +1,6 @@
 use rusqlite::{Connection, Result};
 
-pub const DB_SCHEMA_VERSION: i32 = 1;
+pub const DB_SCHEMA_VERSION: i32 = 12;
 
 pub fn open(path: &std::path::Path) -> Result<Connection> {
     let conn = Connection::open(path)?;
@@ -43,8 +43,157 @@ CREATE TABLE IF NOT EXISTS settings (
 );
 "#,
         )?;
-        conn.pragma_update(None, "user_version", DB_SCHEMA_VERSION)?;
+        conn.pragma_update(None, "user_version", 1)?;
     }
 
+    if current_version < 2 {
+        conn.execute_batch(
+            r#"
+ALTER TABLE notes ADD COLUMN encoding TEXT NOT NULL DEFAULT 'utf-8';
+ALTER TABLE notes ADD COLUMN has_bom INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE notes ADD COLUMN line_ending TEXT NOT NULL DEFAULT 'lf';
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 2)?;
+    }
+
+    if current_version < 3 {
+        conn.execute_batch("ALTER TABLE notes ADD COLUMN expires_at INTEGER;")?;
+        conn.pragma_update(None, "user_version", 3)?;
+    }
+
+    if current_version < 4 {
+        conn.execute_batch("ALTER TABLE notes ADD COLUMN front_matter TEXT;")?;
+        conn.pragma_update(None, "user_version", 4)?;
+    }
+
+    if current_version < 5 {
+        conn.execute_batch(
+            r#"
+CREATE TABLE IF NOT EXISTS note_links (
+  source_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  target TEXT NOT NULL,
+  label TEXT,
+  PRIMARY KEY (source_id, position)
+);
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 5)?;
+    }
+
+    if current_version < 6 {
+        conn.execute_batch(
+            r#"
+CREATE TABLE IF NOT EXISTS note_tasks (
+  note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+  line INTEGER NOT NULL,
+  text TEXT NOT NULL,
+  checked INTEGER NOT NULL,
+  PRIMARY KEY (note_id, line)
+);
+CREATE INDEX IF NOT EXISTS idx_note_tasks_checked ON note_tasks(checked);
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 6)?;
+    }
+
+    if current_version < 7 {
+        conn.execute_batch(
+            r#"
+ALTER TABLE notes ADD COLUMN daily_date TEXT;
+CREATE INDEX IF NOT EXISTS idx_notes_daily_date ON notes(daily_date);
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 7)?;
+    }
+
+    if current_version < 8 {
+        conn.execute_batch(
+            r#"
+CREATE TABLE IF NOT EXISTS recurrences (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  template TEXT NOT NULL,
+  rule TEXT NOT NULL,
+  expiry_minutes INTEGER,
+  created_at INTEGER NOT NULL,
+  last_occurrence_at INTEGER,
+  last_error TEXT
+);
+ALTER TABLE notes ADD COLUMN recurrence_id TEXT;
+ALTER TABLE notes ADD COLUMN occurrence_at INTEGER;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notes_occurrence
+  ON notes(recurrence_id, occurrence_at) WHERE recurrence_id IS NOT NULL;
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 8)?;
+    }
+
+    if current_version < 9 {
+        conn.execute_batch(
+            r#"
+ALTER TABLE notes ADD COLUMN remind_at INTEGER;
+ALTER TABLE notes ADD COLUMN reminder_notified_at INTEGER;
+CREATE INDEX IF NOT EXISTS idx_notes_remind_at ON notes(remind_at);
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 9)?;
+    }
+
+    if current_version < 10 {
+        conn.execute_batch(
+            r#"
+ALTER TABLE notes ADD COLUMN expiry_directive TEXT;
+ALTER TABLE notes ADD COLUMN directive_expires_at INTEGER;
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 10)?;
+    }
+
+    if current_version < 11 {
+        conn.execute_batch(
+            r#"
+CREATE TABLE IF NOT EXISTS history (
+  seq INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  label TEXT NOT NULL,
+  label_count INTEGER,
+  entry TEXT NOT NULL,
+  undone INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS history_files (
+  seq INTEGER NOT NULL REFERENCES history(seq) ON DELETE CASCADE,
+  note_id TEXT NOT NULL,
+  bytes BLOB NOT NULL,
+  PRIMARY KEY (seq, note_id)
+);
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 11)?;
+    }
+
+    if current_version < 12 {
+        conn.execute_batch(
+            r#"
+CREATE TABLE IF NOT EXISTS note_windows (
+  note_id TEXT PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+  mode TEXT NOT NULL,
+  x INTEGER,
+  y INTEGER,
+  width INTEGER,
+  height INTEGER,
+  is_open INTEGER NOT NULL DEFAULT 0
+);
+"#,
+        )?;
+        conn.pragma_update(None, "user_version", 12)?;
+    }
+
+    debug_assert_eq!(
+        conn.pragma_query_value(None, "user_version", |row| row.get::<_, i32>(0))?,
+        DB_SCHEMA_VERSION
+    );
+
     Ok(())
 }
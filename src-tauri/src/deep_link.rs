@@ -0,0 +1,253 @@
+//! `augenblick://` links: `note/<id>` opens a note, `new?text=…&expires=…` creates a draft and
+//! `search?q=…` opens the command palette with the query filled in. Links come from other apps,
+//! so anything that isn't exactly one of these is rejected with a message rather than guessed at.
+
+use crate::app_state::AppState;
+use crate::cli;
+use crate::events;
+use crate::i18n::{self, Message};
+use crate::logs;
+use crate::notes;
+use crate::types::{ChangeReason, NoteChange};
+use crate::window_state::show_main_window;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use uuid::Uuid;
+
+pub const SCHEME: &str = "augenblick";
+const MAX_TEXT_BYTES: usize = 64 * 1024;
+const MAX_QUERY_CHARS: usize = 200;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Link {
+    Note(String),
+    New {
+        text: String,
+        expiry_minutes: Option<i64>,
+    },
+    Search(String),
+}
+
+pub fn parse(url: &Url) -> Result<Link, String> {
+    if url.scheme() != SCHEME {
+        return Err(Message::new("error.link_scheme")
+            .arg("scheme", SCHEME)
+            .into());
+    }
+    if !url.username().is_empty()
+        || url.password().is_some()
+        || url.port().is_some()
+        || url.fragment().is_some()
+    {
+        return Err(Message::new("error.link_authority").into());
+    }
+    let action = url.host_str().unwrap_or_default();
+    let path = url.path();
+    match action {
+        "note" => {
+            if url.query().is_some() {
+                return Err(Message::new("error.link_note_params").into());
+            }
+            let id = path.strip_prefix('/').unwrap_or_default();
+            let id = Uuid::try_parse(id)
+                .map_err(|_| Message::new("error.link_note_id").arg("id", id).to_string())?;
+            Ok(Link::Note(id.hyphenated().to_string()))
+        }
+        "new" | "search" if !path.is_empty() && path != "/" => Err(Message::new("error.link_path")
+            .arg("path", path)
+            .arg("action", action)
+            .into()),
+        "new" => {
+            let mut params = Params::new(url, &["text", "expires"])?;
+            let text = params.take("text").unwrap_or_default();
+            if text.len() > MAX_TEXT_BYTES {
+                return Err(Message::new("error.link_text_size")
+                    .arg("size", MAX_TEXT_BYTES / 1024)
+                    .into());
+            }
+            let expiry_minutes = params
+                .take("expires")
+                .map(|raw| expiry_minutes(&raw))
+                .transpose()?;
+            Ok(Link::New {
+                text,
+                expiry_minutes,
+            })
+        }
+        "search" => {
+            let mut params = Params::new(url, &["q"])?;
+            let query = params.take("q").unwrap_or_default();
+            let query = query.trim();
+            if query.is_empty() {
+                return Err(Message::new("error.link_search_empty").into());
+            }
+            if query.chars().count() > MAX_QUERY_CHARS {
+                return Err(Message::new("error.link_search_length")
+                    .arg("max", MAX_QUERY_CHARS)
+                    .into());
+            }
+            Ok(Link::Search(query.to_string()))
+        }
+        "" => Err(Message::new("error.link_no_action").into()),
+        other => Err(Message::new("error.link_action")
+            .arg("action", other)
+            .into()),
+    }
+}
+
+fn expiry_minutes(raw: &str) -> Result<i64, String> {
+    notes::duration_minutes(raw)
+        .filter(|minutes| *minutes <= notes::MAX_EXPIRY_MINUTES)
+        .ok_or_else(|| {
+            Message::new("error.link_expires")
+                .arg("value", raw)
+                .to_string()
+        })
+}
+
+/// Query parameters, each at most once and only from `allowed`.
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn new(url: &Url, allowed: &[&str]) -> Result<Self, String> {
+        let mut pairs: Vec<(String, String)> = Vec::new();
+        for (key, value) in url.query_pairs() {
+            if !allowed.contains(&key.as_ref()) {
+                return Err(Message::new("error.link_param_unknown")
+                    .arg("key", key)
+                    .into());
+            }
+            if pairs.iter().any(|(seen, _)| *seen == key) {
+                return Err(Message::new("error.link_param_twice")
+                    .arg("key", key)
+                    .into());
+            }
+            if value.contains('\0') {
+                return Err(Message::new("error.link_param_nul").arg("key", key).into());
+            }
+            pairs.push((key.into_owned(), value.into_owned()));
+        }
+        Ok(Self(pairs))
+    }
+
+    fn take(&mut self, key: &str) -> Option<String> {
+        let index = self.0.iter().position(|(seen, _)| seen == key)?;
+        Some(self.0.remove(index).1)
+    }
+}
+
+/// Runs the links the deep-link plugin received. A rejected link is logged and shown in a
+/// dialog, since whoever followed it has no other way to see what went wrong.
+pub fn handle(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let result = parse(&url).and_then(|link| open(app, link));
+        if let Err(err) = result {
+            logs::error("deep-link", &err);
+            app.dialog()
+                .message(err)
+                .title(i18n::t("deep_link.not_opened"))
+                .kind(MessageDialogKind::Error)
+                .show(|_| {});
+        }
+    }
+}
+
+fn open(app: &AppHandle, link: Link) -> Result<(), String> {
+    match link {
+        Link::Note(id) => {
+            let state = app.state::<AppState>();
+            let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+            let meta = notes::get_meta(&conn, &id).map_err(|_| {
+                Message::new("error.link_note_missing")
+                    .arg("id", &id)
+                    .to_string()
+            })?;
+            drop(conn);
+            if meta.is_trashed {
+                return Err(Message::new("error.link_note_trashed")
+                    .arg("title", meta.title)
+                    .into());
+            }
+            cli::show_note(app, &id)
+        }
+        Link::New {
+            text,
+            expiry_minutes,
+        } => {
+            let state = app.state::<AppState>();
+            let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+            let meta = notes::create_with_content(&conn, &state.paths, &text, expiry_minutes)?;
+            drop(conn);
+            let id = meta.id.clone();
+            events::publish_one(app, ChangeReason::Created, NoteChange::created(meta));
+            cli::show_note(app, &id)
+        }
+        Link::Search(query) => {
+            show_main_window(app);
+            let _ = app.emit("open-search", query);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(raw: &str) -> Result<Link, String> {
+        parse(&Url::parse(raw).unwrap())
+    }
+
+    #[test]
+    fn parses_links() {
+        let id = "0b7c6a38-2f1e-4d5a-9c3b-8e1f2a4d6c70";
+        assert_eq!(
+            link(&format!("augenblick://note/{}", id.to_uppercase())),
+            Ok(Link::Note(id.to_string()))
+        );
+        assert_eq!(
+            link("augenblick://new?text=Call%20Anna%0A-%20re%3A+invoice&expires=3d"),
+            Ok(Link::New {
+                text: "Call Anna\n- re: invoice".to_string(),
+                expiry_minutes: Some(4320),
+            })
+        );
+        assert_eq!(
+            link("augenblick://new/"),
+            Ok(Link::New {
+                text: String::new(),
+                expiry_minutes: None,
+            })
+        );
+        assert_eq!(
+            link("augenblick://search?q=+invoice+"),
+            Ok(Link::Search("invoice".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        for raw in [
+            "https://note/0b7c6a38-2f1e-4d5a-9c3b-8e1f2a4d6c70",
+            "augenblick://note/../../etc/passwd",
+            "augenblick://note/0b7c6a38-2f1e-4d5a-9c3b-8e1f2a4d6c70?x=1",
+            "augenblick://note/0b7c6a38-2f1e-4d5a-9c3b-8e1f2a4d6c70/more",
+            "augenblick://user@new?text=hi",
+            "augenblick://new?text=hi#top",
+            "augenblick://new?text=a&text=b",
+            "augenblick://new?title=hi",
+            "augenblick://new?expires=soon",
+            "augenblick://new?expires=2000d",
+            "augenblick://new?text=%00",
+            "augenblick://new/extra?text=hi",
+            "augenblick://search",
+            "augenblick://search?q=%20",
+            "augenblick://delete/everything",
+            "augenblick:new",
+        ] {
+            assert!(link(raw).is_err(), "{raw} was accepted");
+        }
+        let long = "x".repeat(MAX_TEXT_BYTES + 1);
+        assert!(link(&format!("augenblick://new?text={long}")).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! The `notes-changed` event: every mutation publishes what it changed, with the note's meta
+//! before and after, and the windows and the tray subscribe to it instead of re-querying.
+
+use crate::logs;
+use crate::types::{ChangeReason, NoteChange, NotesChanged};
+use tauri::{AppHandle, Emitter, Listener, Runtime};
+
+pub const NOTES_CHANGED: &str = "notes-changed";
+
+/// Publishes a mutation to every window and to subscribers in this process. Call it with the DB
+/// lock released: Rust listeners run synchronously and may query the database.
+pub fn publish<R: Runtime>(app: &AppHandle<R>, reason: ChangeReason, changes: Vec<NoteChange>) {
+    if changes.is_empty() && reason != ChangeReason::Settings {
+        return;
+    }
+    if let Err(err) = app.emit(NOTES_CHANGED, NotesChanged { reason, changes }) {
+        logs::error(
+            "events",
+            &format!("emit failed: reason={reason:?} err=\"{err}\""),
+        );
+    }
+}
+
+pub fn publish_one<R: Runtime>(app: &AppHandle<R>, reason: ChangeReason, change: NoteChange) {
+    publish(app, reason, vec![change]);
+}
+
+/// Rebuilds the tray menu when notes change in a way it shows.
+pub fn subscribe_tray<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    app.listen_any(NOTES_CHANGED, move |event| {
+        if affects_tray(event.payload()) {
+            crate::refresh_tray_menu(&handle);
+        }
+    });
+}
+
+/// Drafts are written on every pause in typing, so a write only counts when it changes a title,
+/// a pin, the order or the trash state. The recent list catches up on the next other change.
+fn affects_tray(payload: &str) -> bool {
+    const SHOWN: [&str; 4] = ["title", "isPinned", "sortOrder", "isTrashed"];
+    let Ok(event) = serde_json::from_str::<serde_json::Value>(payload) else {
+        return true;
+    };
+    if event["reason"] != "written" {
+        return true;
+    }
+    event["changes"].as_array().is_none_or(|changes| {
+        changes.iter().any(|change| {
+            SHOWN
+                .iter()
+                .any(|key| change["before"][key] != change["after"][key])
+        })
+    })
+}
+
+/// Keeps the Edit menu's note undo/redo items naming what they would do.
+pub fn subscribe_history_menu<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    app.listen_any(NOTES_CHANGED, move |_| crate::refresh_history_menu(&handle));
+}
+
+/// Closes note windows whose note went away and retitles the rest.
+pub fn subscribe_note_windows(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any(NOTES_CHANGED, move |_| crate::note_windows::sync(&handle));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn payload(reason: &str, before: serde_json::Value, after: serde_json::Value) -> String {
+        json!({ "reason": reason, "changes": [{ "id": "a", "before": before, "after": after }] })
+            .to_string()
+    }
+
+    #[test]
+    fn only_writes_that_change_the_tray_rebuild_it() {
+        let note = json!({ "title": "A", "isPinned": false, "sortOrder": 1, "isTrashed": false, "preview": "x" });
+        let mut typed = note.clone();
+        typed["preview"] = json!("xy");
+        assert!(!affects_tray(&payload(
+            "written",
+            note.clone(),
+            typed.clone()
+        )));
+        assert!(affects_tray(&payload("saved", note.clone(), typed)));
+
+        let mut retitled = note.clone();
+        retitled["title"] = json!("B");
+        assert!(affects_tray(&payload("written", note.clone(), retitled)));
+        assert!(affects_tray(&payload("written", json!(null), note)));
+    }
+}
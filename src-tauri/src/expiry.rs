@@ -1,103 +1,222 @@
 use crate::app_state::AppState;
+use crate::i18n::Message;
 use crate::notes;
-use crate::types::NoteStorage;
+use crate::types::{NoteChange, NoteStorage};
 use rusqlite::{params, Connection, OptionalExtension};
 use std::time::Duration;
 
-pub fn start_background_sweeper(state: AppState) {
+/// Sweeps once a minute; `on_change` gets the notes each sweep trashed or purged.
+pub fn start_background_sweeper(
+    state: AppState,
+    on_change: impl Fn(Vec<NoteChange>) + Send + 'static,
+) {
     std::thread::spawn(move || loop {
-        if let Err(err) = sweep(&state) {
-            eprintln!("expiry sweep error: {err}");
+        match sweep(&state) {
+            Ok(changes) => on_change(changes),
+            Err(err) => eprintln!("expiry sweep error: {err}"),
+        }
+        if let Err(err) = crate::calendar::update_feed(&state) {
+            crate::logs::error("expiry", &format!("calendar feed error: {err}"));
         }
         std::thread::sleep(Duration::from_secs(60));
     });
 }
 
-pub fn sweep(state: &AppState) -> Result<(), String> {
+pub fn sweep(state: &AppState) -> Result<Vec<NoteChange>, String> {
     let now = notes::now_ms();
-    let (expiry_minutes, trash_days, selected_note_id) = {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|_| "DB lock poisoned".to_string())?;
-        let expiry_minutes = get_int_setting(&conn, "expiry_minutes", 10_080)?;
-        let trash_days = get_int_setting(&conn, "trash_retention_days", 30)?;
-        let selected_note_id = get_app_state_string(&conn, "selectedNoteId")?;
-        (expiry_minutes, trash_days, selected_note_id)
+    let (policy, trash_ms, protected) = {
+        let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+        (
+            load_policy(&conn)?,
+            trash_retention_ms(&conn)?,
+            protected_ids(&conn)?,
+        )
     };
 
-    let expiry_ms = expiry_minutes * 60_000;
-    let trash_ms = trash_days * 86_400_000;
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let mut changes = trash_expired(&conn, state, now, &policy, &protected)?;
+    changes.extend(drop_expired_trash(&conn, state, now - trash_ms)?);
+    Ok(changes)
+}
+
+/// The expiry settings as the sweeper applies them.
+pub fn load_policy(conn: &Connection) -> Result<ExpiryPolicy, String> {
+    let expiry_minutes = get_int_setting(conn, "expiry_minutes", 10_080)?;
+    let open_tasks = get_string_setting(conn, "open_tasks_expiry")?;
+    Ok(ExpiryPolicy {
+        expiry_ms: expiry_minutes * 60_000,
+        open_tasks: OpenTasksRule::from_setting(open_tasks.as_deref()),
+        open_tasks_max_ms: get_int_setting(conn, "open_tasks_max_days", 30)? * 86_400_000,
+        done_tasks_grace_ms: get_int_setting(conn, "done_tasks_grace_minutes", 0)? * 60_000,
+        daily_expiry_ms: get_int_setting(conn, "daily_expiry_minutes", 4320)? * 60_000,
+    })
+}
+
+/// How long trashed notes are kept before they are deleted for good.
+pub fn trash_retention_ms(conn: &Connection) -> Result<i64, String> {
+    Ok(get_int_setting(conn, "trash_retention_days", 30)? * 86_400_000)
+}
+
+/// Active, unpinned notes and when the sweeper will trash them; notes the policy keeps are left
+/// out.
+pub fn deadlines(conn: &Connection, policy: &ExpiryPolicy) -> Result<Vec<(String, i64)>, String> {
+    Ok(expiry_facts(conn, &[])?
+        .into_iter()
+        .filter_map(|(id, facts)| policy.deadline(&facts).map(|deadline| (id, deadline)))
+        .collect())
+}
 
-    {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|_| "DB lock poisoned".to_string())?;
-        trash_expired(&conn, state, now - expiry_ms, selected_note_id.as_deref())?;
-        drop_expired_trash(&conn, state, now - trash_ms)?;
+/// What open and finished task items do to a note's inactivity deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenTasksRule {
+    Off,
+    /// Keep notes with open tasks for up to `open_tasks_max_ms` of inactivity.
+    Extend,
+    /// Never expire notes with open tasks.
+    Exempt,
+}
+
+impl OpenTasksRule {
+    fn from_setting(raw: Option<&str>) -> Self {
+        match raw {
+            Some("extend") => Self::Extend,
+            Some("exempt") => Self::Exempt,
+            _ => Self::Off,
+        }
     }
+}
 
-    Ok(())
+#[derive(Debug, Clone, Copy)]
+pub struct ExpiryPolicy {
+    pub expiry_ms: i64,
+    pub open_tasks: OpenTasksRule,
+    pub open_tasks_max_ms: i64,
+    /// Inactivity after which a note whose tasks are all checked expires; 0 turns this off.
+    pub done_tasks_grace_ms: i64,
+    /// Replaces `expiry_ms` for daily notes.
+    pub daily_expiry_ms: i64,
 }
 
-fn trash_expired(
+impl ExpiryPolicy {
+    /// When the note is due for the trash; `None` keeps it. A reminder keeps the note until it
+    /// is dismissed; otherwise a fixed expiry wins.
+    pub fn deadline(&self, note: &ExpiryFacts) -> Option<i64> {
+        if note.has_reminder {
+            return None;
+        }
+        if let Some(expires_at) = note.expires_at {
+            return Some(expires_at);
+        }
+        let base = if note.is_daily {
+            self.daily_expiry_ms
+        } else {
+            self.expiry_ms
+        };
+        let window = if note.open_tasks > 0 {
+            match self.open_tasks {
+                OpenTasksRule::Off => base,
+                OpenTasksRule::Extend => base.max(self.open_tasks_max_ms),
+                OpenTasksRule::Exempt => return None,
+            }
+        } else if note.done_tasks > 0 && self.done_tasks_grace_ms > 0 {
+            base.min(self.done_tasks_grace_ms)
+        } else {
+            base
+        };
+        Some(note.last_interaction + window)
+    }
+}
+
+/// What the policy looks at for one note.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpiryFacts {
+    pub last_interaction: i64,
+    pub expires_at: Option<i64>,
+    pub open_tasks: i64,
+    pub done_tasks: i64,
+    pub is_daily: bool,
+    pub has_reminder: bool,
+}
+
+/// Notes someone is looking at: the one selected in the main window and every note open in a
+/// window of its own.
+fn protected_ids(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut ids: Vec<String> = notes::open_windows(conn)?
+        .into_iter()
+        .map(|window| window.note_id)
+        .collect();
+    ids.extend(get_app_state_string(conn, "selectedNoteId")?);
+    Ok(ids)
+}
+
+fn expiry_facts(
     conn: &Connection,
-    state: &AppState,
-    cutoff_last_interaction: i64,
-    selected_note_id: Option<&str>,
-) -> Result<(), String> {
-    let ids = if let Some(selected_note_id) = selected_note_id {
-        let mut stmt = conn
-            .prepare(
-                r#"
-	SELECT id FROM notes
-	WHERE is_trashed = 0
-	  AND is_pinned = 0
-	  AND last_interaction <= ?1
-	  AND id != ?2
-"#,
-            )
-            .map_err(|err| err.to_string())?;
-        let rows = stmt
-            .query_map(params![cutoff_last_interaction, selected_note_id], |row| {
-                row.get::<_, String>(0)
-            })
-            .map_err(|err| err.to_string())?;
-        rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|err| err.to_string())?
-    } else {
-        let mut stmt = conn
-            .prepare(
-                r#"
-	SELECT id FROM notes
-	WHERE is_trashed = 0
-	  AND is_pinned = 0
-	  AND last_interaction <= ?1
+    protected: &[String],
+) -> Result<Vec<(String, ExpiryFacts)>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  id, last_interaction, COALESCE(directive_expires_at, expires_at),
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 0),
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 1),
+  daily_date IS NOT NULL,
+  remind_at IS NOT NULL
+FROM notes
+WHERE is_trashed = 0
+  AND is_pinned = 0
 "#,
-            )
-            .map_err(|err| err.to_string())?;
-        let rows = stmt
-            .query_map(params![cutoff_last_interaction], |row| row.get::<_, String>(0))
-            .map_err(|err| err.to_string())?;
-        rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|err| err.to_string())?
-    };
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ExpiryFacts {
+                    last_interaction: row.get(1)?,
+                    expires_at: row.get(2)?,
+                    open_tasks: row.get(3)?,
+                    done_tasks: row.get(4)?,
+                    is_daily: row.get(5)?,
+                    has_reminder: row.get(6)?,
+                },
+            ))
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    let rows = rows
+        .into_iter()
+        .filter(|(id, _)| !protected.contains(id))
+        .collect();
+    Ok(rows)
+}
 
-    for id in ids {
-        if let Err(err) = crate::notes::trash(conn, &state.paths, &id) {
-            eprintln!("auto-trash failed for {id}: {err}");
-        }
-    }
+fn trash_expired(
+    conn: &Connection,
+    state: &AppState,
+    now: i64,
+    policy: &ExpiryPolicy,
+    protected: &[String],
+) -> Result<Vec<NoteChange>, String> {
+    let ids: Vec<String> = expiry_facts(conn, protected)?
+        .into_iter()
+        .filter(|(_, facts)| {
+            policy
+                .deadline(facts)
+                .is_some_and(|deadline| deadline <= now)
+        })
+        .map(|(id, _)| id)
+        .collect();
 
-    Ok(())
+    Ok(notes::trash_expired(conn, &state.paths, &ids))
 }
 
 fn drop_expired_trash(
     conn: &Connection,
     _state: &AppState,
     cutoff_trashed_at: i64,
-) -> Result<(), String> {
+) -> Result<Vec<NoteChange>, String> {
     let mut stmt = conn
         .prepare(
             r#"
@@ -121,7 +240,9 @@ WHERE is_trashed = 1
         .collect::<Result<Vec<_>, _>>()
         .map_err(|err| err.to_string())?;
 
+    let mut changes = Vec::new();
     for (id, storage_raw, file_path) in rows {
+        let before = notes::get_meta(conn, &id)?;
         let storage = if storage_raw == "saved" {
             NoteStorage::Saved
         } else {
@@ -134,9 +255,10 @@ WHERE is_trashed = 1
 
         conn.execute("DELETE FROM notes WHERE id = ?1", params![id])
             .map_err(|err| err.to_string())?;
+        changes.push(NoteChange::deleted(before));
     }
 
-    Ok(())
+    Ok(changes)
 }
 
 fn get_int_setting(conn: &Connection, key: &str, default: i64) -> Result<i64, String> {
@@ -162,6 +284,16 @@ fn get_int_setting(conn: &Connection, key: &str, default: i64) -> Result<i64, St
     Ok(default)
 }
 
+fn get_string_setting(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|err| err.to_string())
+}
+
 fn get_app_state_string(conn: &Connection, key: &str) -> Result<Option<String>, String> {
     conn.query_row(
         "SELECT value FROM app_state WHERE key = ?1 LIMIT 1",
@@ -171,3 +303,134 @@ fn get_app_state_string(conn: &Connection, key: &str) -> Result<Option<String>,
     .optional()
     .map_err(|err| err.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+    use crate::types::NoteWindowMode;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn policy(open_tasks: OpenTasksRule, done_tasks_grace_ms: i64) -> ExpiryPolicy {
+        ExpiryPolicy {
+            expiry_ms: 24 * HOUR,
+            open_tasks,
+            open_tasks_max_ms: 72 * HOUR,
+            done_tasks_grace_ms,
+            daily_expiry_ms: 12 * HOUR,
+        }
+    }
+
+    fn note(open_tasks: i64, done_tasks: i64) -> ExpiryFacts {
+        ExpiryFacts {
+            open_tasks,
+            done_tasks,
+            ..ExpiryFacts::default()
+        }
+    }
+
+    #[test]
+    fn open_tasks_extend_or_exempt() {
+        assert_eq!(
+            policy(OpenTasksRule::Off, 0).deadline(&note(3, 0)),
+            Some(24 * HOUR)
+        );
+        assert_eq!(
+            policy(OpenTasksRule::Extend, 0).deadline(&note(3, 0)),
+            Some(72 * HOUR)
+        );
+        assert_eq!(policy(OpenTasksRule::Exempt, 0).deadline(&note(3, 1)), None);
+        assert_eq!(
+            policy(OpenTasksRule::Exempt, 0).deadline(&note(0, 0)),
+            Some(24 * HOUR)
+        );
+    }
+
+    #[test]
+    fn finished_tasks_expire_early() {
+        assert_eq!(
+            policy(OpenTasksRule::Off, HOUR).deadline(&note(0, 2)),
+            Some(HOUR)
+        );
+        assert_eq!(
+            policy(OpenTasksRule::Off, HOUR).deadline(&note(1, 2)),
+            Some(24 * HOUR)
+        );
+        assert_eq!(
+            policy(OpenTasksRule::Off, 0).deadline(&note(0, 2)),
+            Some(24 * HOUR)
+        );
+    }
+
+    #[test]
+    fn fixed_expiry_wins() {
+        let fixed = ExpiryFacts {
+            expires_at: Some(5),
+            ..note(3, 0)
+        };
+        assert_eq!(policy(OpenTasksRule::Exempt, 0).deadline(&fixed), Some(5));
+    }
+
+    #[test]
+    fn reminders_keep_the_note() {
+        let reminded = ExpiryFacts {
+            expires_at: Some(5),
+            has_reminder: true,
+            ..note(0, 2)
+        };
+        assert_eq!(policy(OpenTasksRule::Off, HOUR).deadline(&reminded), None);
+    }
+
+    #[test]
+    fn daily_notes_have_their_own_window() {
+        let daily = ExpiryFacts {
+            is_daily: true,
+            ..note(0, 0)
+        };
+        assert_eq!(
+            policy(OpenTasksRule::Off, 0).deadline(&daily),
+            Some(12 * HOUR)
+        );
+        let daily_with_tasks = ExpiryFacts {
+            is_daily: true,
+            ..note(1, 0)
+        };
+        assert_eq!(
+            policy(OpenTasksRule::Extend, 0).deadline(&daily_with_tasks),
+            Some(72 * HOUR)
+        );
+    }
+
+    #[test]
+    fn every_open_window_is_protected_along_with_the_selection() {
+        let env = TestEnv::new();
+        let selected = env.draft("Selected");
+        let windowed = env.draft("Windowed");
+        let sticky = env.draft("Sticky");
+        let closed = env.draft("Closed");
+        let idle = env.draft("Idle");
+        notes::open_window(&env.conn, &windowed, NoteWindowMode::Normal).unwrap();
+        notes::open_window(&env.conn, &sticky, NoteWindowMode::Sticky).unwrap();
+        notes::open_window(&env.conn, &closed, NoteWindowMode::Normal).unwrap();
+        notes::close_window(&env.conn, &closed).unwrap();
+        env.conn
+            .execute(
+                "INSERT INTO app_state (key, value) VALUES ('selectedNoteId', ?1)",
+                params![selected],
+            )
+            .unwrap();
+
+        let protected = protected_ids(&env.conn).unwrap();
+        assert_eq!(protected, vec![windowed, sticky, selected]);
+        let mut candidates: Vec<String> = expiry_facts(&env.conn, &protected)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        candidates.sort();
+        let mut expected = vec![closed, idle];
+        expected.sort();
+        assert_eq!(candidates, expected);
+    }
+}
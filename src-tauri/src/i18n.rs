@@ -0,0 +1,300 @@
+//! Localization of what the backend shows: menus, the tray, notifications and error messages.
+//! Catalogs in `locales/` are flat JSON objects of message key to text, compiled in; `{name}`
+//! placeholders are filled from a [`Message`]'s arguments. The locale follows the system unless
+//! the `locale` setting picks one, and a key missing from a catalog falls back to English.
+
+use crate::logs;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+pub const SETTING: &str = "locale";
+/// Setting value for following the system language.
+pub const SYSTEM: &str = "system";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    De,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::De];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    /// The locale for a BCP 47 or POSIX tag such as `de-AT` or `de_DE.UTF-8`; English for
+    /// languages without a catalog.
+    pub fn from_tag(tag: &str) -> Locale {
+        let language = tag.split(['-', '_', '.', '@']).next().unwrap_or_default();
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+            .unwrap_or(Locale::En)
+    }
+
+    fn catalog(self) -> &'static HashMap<String, String> {
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static DE: OnceLock<HashMap<String, String>> = OnceLock::new();
+        let (cell, json) = match self {
+            Locale::En => (&EN, include_str!("../locales/en.json")),
+            Locale::De => (&DE, include_str!("../locales/de.json")),
+        };
+        cell.get_or_init(|| serde_json::from_str(json).expect("invalid message catalog"))
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn current() -> Locale {
+    let index = usize::from(CURRENT.load(Ordering::Relaxed));
+    Locale::ALL.get(index).copied().unwrap_or(Locale::En)
+}
+
+fn set(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+fn system() -> Locale {
+    sys_locale::get_locale()
+        .map(|tag| Locale::from_tag(&tag))
+        .unwrap_or(Locale::En)
+}
+
+/// Checks a value for the `locale` setting: `system` or one of the catalogs' codes.
+pub fn validate(value: &str) -> Result<(), Message> {
+    if value == SYSTEM || Locale::ALL.iter().any(|locale| locale.code() == value) {
+        Ok(())
+    } else {
+        Err(Message::new("error.locale_unknown").arg("locale", value))
+    }
+}
+
+/// Switches to the locale the settings ask for. Call it at launch and after the setting changed.
+pub fn load(conn: &Connection) {
+    let value: Result<Option<String>, String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string());
+    let value = value.unwrap_or_else(|err| {
+        logs::error("i18n", &err);
+        None
+    });
+    let locale = match value.as_deref() {
+        None | Some(SYSTEM) => system(),
+        Some(code) => Locale::from_tag(code),
+    };
+    set(locale);
+}
+
+/// A message key with its arguments, and the error type of the notes API and the settings
+/// modules. It renders in the current locale when it is shown or turned into a `String` error;
+/// text that has no key of its own (SQLite and I/O errors) becomes `error.other`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    key: &'static str,
+    args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        let template = locale
+            .catalog()
+            .get(self.key)
+            .or_else(|| Locale::En.catalog().get(self.key))
+            .map_or(self.key, String::as_str);
+        // One pass over the template, so a `{name}` inside an argument's value stays as written.
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let arg = after.find('}').and_then(|end| {
+                let name = &after[..end];
+                self.args
+                    .iter()
+                    .find(|(arg, _)| *arg == name)
+                    .map(|(_, value)| (value, end))
+            });
+            match arg {
+                Some((value, end)) => {
+                    text.push_str(value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(current()))
+    }
+}
+
+impl From<Message> for String {
+    fn from(message: Message) -> Self {
+        message.to_string()
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message::new("error.other").arg("error", text)
+    }
+}
+
+/// What a command fails with: the message key and arguments, for the frontend to tell errors
+/// apart, and the message rendered in the current locale, for showing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandError {
+    pub key: &'static str,
+    pub args: BTreeMap<&'static str, String>,
+    pub message: String,
+}
+
+impl From<Message> for CommandError {
+    fn from(message: Message) -> Self {
+        Self {
+            key: message.key,
+            message: message.to_string(),
+            args: message.args.into_iter().collect(),
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(text: String) -> Self {
+        Message::from(text).into()
+    }
+}
+
+/// The catalogs' own copy of `key`, for a key that was stored and read back.
+pub fn catalog_key(key: &str) -> Option<&'static str> {
+    Locale::En
+        .catalog()
+        .get_key_value(key)
+        .map(|(key, _)| key.as_str())
+}
+
+/// The text for `key` in the current locale.
+pub fn t(key: &'static str) -> String {
+    Message::new(key).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys_and_placeholders() {
+        let en = Locale::En.catalog();
+        for locale in Locale::ALL {
+            let catalog = locale.catalog();
+            let mut missing: Vec<_> = en
+                .keys()
+                .filter(|key| !catalog.contains_key(*key))
+                .collect();
+            missing.extend(catalog.keys().filter(|key| !en.contains_key(*key)));
+            assert!(missing.is_empty(), "{}: {missing:?}", locale.code());
+            for (key, text) in catalog {
+                assert_eq!(placeholders(text), placeholders(&en[key]), "{key}");
+            }
+        }
+    }
+
+    #[test]
+    fn renders_messages() {
+        let message = Message::new("error.pin_limit").arg("max", 5);
+        assert_eq!(
+            message.render(Locale::En),
+            "You can only pin up to 5 notes."
+        );
+        assert_eq!(
+            message.render(Locale::De),
+            "Es können höchstens 5 Notizen angeheftet werden."
+        );
+        assert_eq!(
+            Message::new("no.such.key").render(Locale::De),
+            "no.such.key"
+        );
+        assert_eq!(Locale::from_tag("de_AT.UTF-8"), Locale::De);
+        assert_eq!(Locale::from_tag("fr-FR"), Locale::En);
+        assert!(validate("de").is_ok() && validate(SYSTEM).is_ok());
+        assert!(validate("xx").is_err());
+    }
+
+    #[test]
+    fn placeholders_in_values_are_not_substituted() {
+        let message = Message::new("error.template_expiry")
+            .arg("name", "{value}")
+            .arg("key", "expires_after")
+            .arg("value", "soon");
+        assert_eq!(
+            message.render(Locale::En),
+            "Template \"{value}\": expires_after \"soon\" is not a duration like 12h, 3d or 2w."
+        );
+        assert_eq!(
+            Message::new("error.pin_limit").render(Locale::En),
+            "You can only pin up to {max} notes."
+        );
+    }
+
+    #[test]
+    fn commands_fail_with_key_args_and_text() {
+        let error = CommandError::from(Message::new("error.pin_limit").arg("max", 5));
+        assert_eq!(error.key, "error.pin_limit");
+        assert_eq!(error.args.get("max").map(String::as_str), Some("5"));
+        assert_eq!(
+            error.message,
+            Message::new("error.pin_limit").arg("max", 5).to_string()
+        );
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["key"], "error.pin_limit");
+        assert_eq!(json["args"]["max"], "5");
+
+        let error = CommandError::from("disk I/O error".to_string());
+        assert_eq!(error.key, "error.other");
+        assert_eq!(error.message, "disk I/O error");
+    }
+}
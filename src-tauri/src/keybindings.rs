@@ -0,0 +1,462 @@
+//! Keyboard shortcuts of the native menu. Every menu action with a shortcut is listed once in
+//! [`ACTIONS`] with its label key, default accelerator and the event it sends the frontend. User
+//! overrides live in the `keybindings` setting as a JSON object of action id to accelerator
+//! (empty for none); the menu is rebuilt from them whenever they change.
+
+use crate::i18n::{self, Message};
+use crate::logs;
+use crate::quick_capture;
+use crate::types::Keybinding;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+
+pub const SETTING: &str = "keybindings";
+
+pub struct Action {
+    pub id: &'static str,
+    /// Message key of the menu label.
+    pub label: &'static str,
+    pub accelerator: &'static str,
+    /// Sent to the frontend when the item is chosen; `None` for actions handled in Rust.
+    pub event: Option<&'static str>,
+}
+
+pub const ACTIONS: &[Action] = &[
+    Action {
+        id: "app_settings",
+        label: "action.app_settings",
+        accelerator: "CmdOrCtrl+Comma",
+        event: Some("menu-settings"),
+    },
+    Action {
+        id: "app_quit",
+        label: "action.app_quit",
+        accelerator: "CmdOrCtrl+KeyQ",
+        event: None,
+    },
+    Action {
+        id: "file_new_note",
+        label: "action.file_new_note",
+        accelerator: "CmdOrCtrl+KeyN",
+        event: Some("menu-new-note"),
+    },
+    Action {
+        id: "file_open_markdown",
+        label: "action.file_open_markdown",
+        accelerator: "CmdOrCtrl+KeyO",
+        event: Some("menu-open-markdown"),
+    },
+    Action {
+        id: "file_save_note",
+        label: "action.file_save_note",
+        accelerator: "CmdOrCtrl+KeyS",
+        event: Some("menu-save"),
+    },
+    Action {
+        id: "file_save_as_note",
+        label: "action.file_save_as_note",
+        accelerator: "CmdOrCtrl+Shift+KeyS",
+        event: Some("menu-save-as"),
+    },
+    Action {
+        id: "file_trash_note",
+        label: "action.file_trash_note",
+        accelerator: "Delete",
+        event: Some("menu-trash"),
+    },
+    Action {
+        id: "edit_history_undo",
+        label: "action.edit_history_undo",
+        accelerator: "CmdOrCtrl+Alt+KeyZ",
+        event: None,
+    },
+    Action {
+        id: "edit_history_redo",
+        label: "action.edit_history_redo",
+        accelerator: "CmdOrCtrl+Alt+Shift+KeyZ",
+        event: None,
+    },
+    Action {
+        id: "view_toggle_devtools",
+        label: "action.view_toggle_devtools",
+        accelerator: "CmdOrCtrl+Alt+KeyI",
+        event: None,
+    },
+];
+
+/// Shortcuts the page handles itself, with the message key of what they do. No menu action or
+/// quick capture may take them.
+const RESERVED: &[(&str, &str)] = &[
+    ("CmdOrCtrl+KeyK", "shortcut.command_palette"),
+    ("CmdOrCtrl+KeyP", "shortcut.toggle_pin"),
+    ("CmdOrCtrl+KeyZ", "shortcut.undo"),
+    ("CmdOrCtrl+Shift+KeyZ", "shortcut.redo"),
+];
+
+pub fn action(id: &str) -> Option<&'static Action> {
+    ACTIONS.iter().find(|action| action.id == id)
+}
+
+/// The user's accelerators by action id. A broken setting is logged and ignored, so it can't
+/// keep the menu from being built.
+pub fn overrides(conn: &Connection) -> Result<BTreeMap<String, String>, Message> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let Some(json) = json else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(serde_json::from_str(&json).unwrap_or_else(|err| {
+        logs::error(
+            "keybindings",
+            &format!("ignoring the {SETTING} setting: {err}"),
+        );
+        BTreeMap::new()
+    }))
+}
+
+/// The accelerator `action` uses, empty for none.
+pub fn accelerator<'a>(overrides: &'a BTreeMap<String, String>, action: &'a Action) -> &'a str {
+    overrides
+        .get(action.id)
+        .map(String::as_str)
+        .unwrap_or(action.accelerator)
+}
+
+pub fn list(conn: &Connection) -> Result<Vec<Keybinding>, Message> {
+    let overrides = overrides(conn)?;
+    Ok(ACTIONS
+        .iter()
+        .map(|action| Keybinding {
+            id: action.id.to_string(),
+            label: i18n::t(action.label),
+            accelerator: accelerator(&overrides, action).to_string(),
+            default_accelerator: action.accelerator.to_string(),
+        })
+        .collect())
+}
+
+/// Binds `id` to `accelerator`: `None` restores the default and an empty one removes the
+/// shortcut. Fails if another action or the quick-capture shortcut already uses it.
+pub fn set(conn: &Connection, id: &str, accelerator: Option<&str>) -> Result<(), Message> {
+    let action = action(id).ok_or_else(|| Message::new("error.keybinding_action").arg("id", id))?;
+    let mut overrides = overrides(conn)?;
+    let next = match accelerator.map(str::trim) {
+        None => action.accelerator.to_string(),
+        Some("") => String::new(),
+        Some(raw) => canonical(raw)?,
+    };
+    if !next.is_empty() {
+        ensure_free(conn, &next, Some(id))?;
+    }
+    if next == action.accelerator {
+        overrides.remove(id);
+    } else {
+        overrides.insert(id.to_string(), next);
+    }
+    let json = serde_json::to_string(&overrides).map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO settings(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![SETTING, json],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Fails if a menu action other than `except`, the quick-capture shortcut when `except` is a
+/// menu action, or one of the page's own shortcuts already uses `accelerator`. One that can't be
+/// read is taken as free and left for the menu or the shortcut plugin to reject.
+pub fn ensure_free(
+    conn: &Connection,
+    accelerator: &str,
+    except: Option<&str>,
+) -> Result<(), Message> {
+    let Ok(wanted) = comparable(accelerator) else {
+        return Ok(());
+    };
+    let overrides = overrides(conn)?;
+    let mut taken: Vec<(&str, String)> = ACTIONS
+        .iter()
+        .filter(|action| Some(action.id) != except)
+        .map(|action| {
+            (
+                action.label,
+                self::accelerator(&overrides, action).to_string(),
+            )
+        })
+        .collect();
+    if except.is_some() {
+        taken.push(("quick_capture.title", quick_capture::shortcut(conn)?));
+    }
+    taken.extend(
+        RESERVED
+            .iter()
+            .map(|(accelerator, label)| (*label, accelerator.to_string())),
+    );
+    match taken
+        .iter()
+        .find(|(_, used)| comparable(used).is_ok_and(|used| used == wanted))
+    {
+        Some((label, _)) => Err(Message::new("error.keybinding_taken")
+            .arg("accelerator", accelerator)
+            .arg("label", i18n::t(label))),
+        None => Ok(()),
+    }
+}
+
+/// `accelerator` in the menu's syntax with its modifiers in a fixed order, e.g.
+/// `cmdorctrl+shift+s` → `CmdOrCtrl+Shift+KeyS`.
+pub fn canonical(accelerator: &str) -> Result<String, Message> {
+    let invalid = || Message::new("error.keybinding_invalid").arg("accelerator", accelerator);
+    let mut modifiers = [false; MODIFIERS.len()];
+    let mut key = None;
+    for token in accelerator.split('+').map(str::trim) {
+        if let Some(index) = modifier(token) {
+            if modifiers[index] {
+                return Err(invalid());
+            }
+            modifiers[index] = true;
+        } else if key.is_none() {
+            key = Some(key_code(token).ok_or_else(invalid)?);
+        } else {
+            return Err(invalid());
+        }
+    }
+    let key = key.ok_or_else(invalid)?;
+    let shift = modifiers[3];
+    let others = modifiers.iter().filter(|on| **on).count() - usize::from(shift);
+    if others == 0 && printable(&key) {
+        return Err(Message::new("error.keybinding_modifier").arg("accelerator", accelerator));
+    }
+    let mut parts: Vec<String> = MODIFIERS
+        .iter()
+        .zip(modifiers)
+        .filter(|(_, on)| *on)
+        .map(|(name, _)| name.to_string())
+        .collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+const MODIFIERS: [&str; 5] = ["CmdOrCtrl", "Ctrl", "Alt", "Shift", "Super"];
+
+fn modifier(token: &str) -> Option<usize> {
+    match token.to_ascii_lowercase().as_str() {
+        "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => Some(0),
+        "ctrl" | "control" => Some(1),
+        "alt" | "option" => Some(2),
+        "shift" => Some(3),
+        "super" | "cmd" | "command" | "meta" => Some(4),
+        _ => None,
+    }
+}
+
+const NAMED_KEYS: &[&str] = &[
+    "Backspace",
+    "Delete",
+    "Enter",
+    "Escape",
+    "Tab",
+    "Space",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+    "Comma",
+    "Period",
+    "Slash",
+    "Backslash",
+    "Semicolon",
+    "Quote",
+    "Backquote",
+    "Minus",
+    "Equal",
+    "BracketLeft",
+    "BracketRight",
+];
+
+fn key_code(token: &str) -> Option<String> {
+    let upper = token.to_ascii_uppercase();
+    let bare = upper
+        .strip_prefix("KEY")
+        .or_else(|| upper.strip_prefix("DIGIT"))
+        .filter(|rest| rest.len() == 1)
+        .unwrap_or(&upper);
+    let mut chars = bare.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return match c {
+            'A'..='Z' => Some(format!("Key{c}")),
+            '0'..='9' => Some(format!("Digit{c}")),
+            ',' => Some("Comma".to_string()),
+            '.' => Some("Period".to_string()),
+            '/' => Some("Slash".to_string()),
+            '-' => Some("Minus".to_string()),
+            '=' => Some("Equal".to_string()),
+            _ => None,
+        };
+    }
+    if let Some(number) = upper.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&number).then(|| format!("F{number}"));
+    }
+    let alias = match upper.as_str() {
+        "DEL" => "DELETE",
+        "RETURN" => "ENTER",
+        "ESC" => "ESCAPE",
+        "UP" => "ARROWUP",
+        "DOWN" => "ARROWDOWN",
+        "LEFT" => "ARROWLEFT",
+        "RIGHT" => "ARROWRIGHT",
+        other => other,
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(alias))
+        .map(|name| name.to_string())
+}
+
+/// Keys that type something when pressed alone or with Shift.
+fn printable(key: &str) -> bool {
+    key.starts_with("Key")
+        || key.starts_with("Digit")
+        || !matches!(
+            key,
+            "Backspace"
+                | "Delete"
+                | "Enter"
+                | "Escape"
+                | "Tab"
+                | "Insert"
+                | "Home"
+                | "End"
+                | "PageUp"
+                | "PageDown"
+                | "ArrowUp"
+                | "ArrowDown"
+                | "ArrowLeft"
+                | "ArrowRight"
+        ) && !key.starts_with('F')
+}
+
+/// Canonical form with `CmdOrCtrl` resolved for this platform, for telling whether two
+/// accelerators are the same keys.
+fn comparable(accelerator: &str) -> Result<String, Message> {
+    let host = if cfg!(target_os = "macos") {
+        "Super"
+    } else {
+        "Ctrl"
+    };
+    let resolved = accelerator
+        .split('+')
+        .map(|token| match modifier(token.trim()) {
+            Some(0) => host,
+            _ => token,
+        })
+        .collect::<Vec<_>>()
+        .join("+");
+    canonical(&resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalizes_accelerators() {
+        assert_eq!(
+            canonical("shift + cmdorctrl+s"),
+            Ok("CmdOrCtrl+Shift+KeyS".into())
+        );
+        assert_eq!(canonical("Ctrl+Alt+Digit1"), Ok("Ctrl+Alt+Digit1".into()));
+        assert_eq!(canonical("CmdOrCtrl+,"), Ok("CmdOrCtrl+Comma".into()));
+        assert_eq!(canonical("del"), Ok("Delete".into()));
+        assert_eq!(canonical("Shift+F5"), Ok("Shift+F5".into()));
+        for invalid in [
+            "",
+            "CmdOrCtrl",
+            "Ctrl+Ctrl+N",
+            "Ctrl+N+M",
+            "Ctrl+Hyper",
+            "F25",
+            "N",
+        ] {
+            assert!(canonical(invalid).is_err(), "{invalid} was accepted");
+        }
+        assert!(canonical("Shift+KeyN").is_err());
+    }
+
+    #[test]
+    fn compares_with_cmd_or_ctrl_resolved() {
+        let host = if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Ctrl"
+        };
+        assert_eq!(
+            comparable("CmdOrCtrl+KeyN"),
+            comparable(&format!("{host}+n"))
+        );
+        assert_ne!(
+            comparable("CmdOrCtrl+KeyN"),
+            comparable("CmdOrCtrl+Shift+KeyN")
+        );
+    }
+
+    fn memory_db() -> Connection {
+        crate::db::open(std::path::Path::new(":memory:")).unwrap()
+    }
+
+    #[test]
+    fn refuses_an_accelerator_that_is_taken() {
+        let conn = memory_db();
+        let err = set(&conn, "file_new_note", Some("CmdOrCtrl+S"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&i18n::t("action.file_save_note")), "{err}");
+        let err = set(&conn, "file_new_note", Some("CmdOrCtrl+K"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&i18n::t("shortcut.command_palette")), "{err}");
+        for reserved in ["CmdOrCtrl+P", "CmdOrCtrl+Z", "CmdOrCtrl+Shift+Z"] {
+            assert!(set(&conn, "file_save_note", Some(reserved)).is_err());
+        }
+        assert!(overrides(&conn).unwrap().is_empty());
+        // The quick-capture shortcut may not take them either.
+        assert!(ensure_free(&conn, "CmdOrCtrl+KeyP", None).is_err());
+    }
+
+    #[test]
+    fn refuses_the_quick_capture_shortcut() {
+        let conn = memory_db();
+        let err = set(&conn, "file_new_note", Some("CmdOrCtrl+Alt+N"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&i18n::t("quick_capture.title")), "{err}");
+        assert!(ensure_free(&conn, "CmdOrCtrl+Alt+KeyN", None).is_ok());
+    }
+
+    #[test]
+    fn rebinds_and_resets_to_the_default() {
+        let conn = memory_db();
+        set(&conn, "file_new_note", Some("shift+cmdorctrl+n")).unwrap();
+        set(&conn, "file_trash_note", Some("")).unwrap();
+        let bindings = list(&conn).unwrap();
+        let binding = |id: &str| bindings.iter().find(|b| b.id == id).unwrap().clone();
+        assert_eq!(binding("file_new_note").accelerator, "CmdOrCtrl+Shift+KeyN");
+        assert_eq!(binding("file_trash_note").accelerator, "");
+
+        set(&conn, "file_new_note", None).unwrap();
+        set(&conn, "file_trash_note", Some("Delete")).unwrap();
+        assert!(overrides(&conn).unwrap().is_empty());
+        assert!(set(&conn, "no_such_action", None).is_err());
+    }
+}
@@ -1,141 +1,108 @@
 mod app_state;
+mod autostart;
+mod calendar;
+mod cli;
 mod commands;
 mod db;
+mod deep_link;
+mod events;
 mod expiry;
+mod i18n;
+mod keybindings;
 mod logs;
+mod note_windows;
 mod notes;
+mod quick_capture;
+mod recurring;
+mod reminders;
 mod scoped_file;
 mod types;
 mod window_state;
 
 use app_state::AppState;
-use tauri::menu::{AboutMetadata, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use i18n::Message;
+use std::path::Path;
+use tauri::menu::{AboutMetadata, Menu, MenuBuilder, MenuItem, MenuItemBuilder, SubmenuBuilder};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, RunEvent};
+use tauri_plugin_deep_link::DeepLinkExt;
+use types::ChangeReason;
 use window_state::{show_main_window, toggle_main_window, MainWindowToggleResult};
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 #[cfg(target_os = "macos")]
-use tauri::RunEvent;
-#[cfg(target_os = "macos")]
 use window_state::show_main_window_if_hidden;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args: Vec<String> = std::env::args().collect();
+    let cwd = std::env::current_dir().unwrap_or_default();
+    cli::spool_stdin(&args);
+
     let app = tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            cli::handle(app, &argv, Path::new(&cwd), true)
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_process::init())
-        .plugin(tauri_plugin_window_state::Builder::new().build())
+        .plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(window_state::restored_state())
+                .with_filter(|label| {
+                    !label.starts_with(note_windows::LABEL_PREFIX) && label != quick_capture::LABEL
+                })
+                .build(),
+        )
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .setup(|app| {
+        .plugin(quick_capture::plugin())
+        .plugin(autostart::plugin())
+        .setup(move |app| {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(ActivationPolicy::Accessory);
 
             let app_handle = app.handle().clone();
             let state = AppState::init(&app_handle).map_err(std::io::Error::other)?;
             app.manage(state.clone());
-            expiry::start_background_sweeper(state);
+            let mut hidden_start = false;
+            if let Ok(conn) = state.db.lock() {
+                i18n::load(&conn);
+                hidden_start = args.iter().any(|arg| arg == autostart::HIDDEN_FLAG)
+                    && autostart::starts_hidden(&conn).unwrap_or(false);
+            }
+            let sweeper_handle = app_handle.clone();
+            expiry::start_background_sweeper(state.clone(), move |changes| {
+                events::publish(&sweeper_handle, ChangeReason::Expired, changes)
+            });
+            recurring::start_background_scheduler(app_handle.clone(), state.clone());
+            reminders::start_background_reminders(app_handle.clone(), state);
             let _ = window_state::restore_and_clamp(&app_handle);
-            show_main_window(&app_handle);
-
-            let quit_item = MenuItemBuilder::with_id("app_quit", "Quit Augenblick")
-                .accelerator("CmdOrCtrl+KeyQ")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let settings_item = MenuItemBuilder::with_id("app_settings", "Settings…")
-                .accelerator("CmdOrCtrl+Comma")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let about_metadata = AboutMetadata {
-                credits: Some("Built by Robin Ebers (@robinebers)".into()),
-                ..Default::default()
-            };
-            let app_menu = SubmenuBuilder::new(app, "Augenblick")
-                .about_with_text("About Augenblick", Some(about_metadata))
-                .separator()
-                .item(&settings_item)
-                .separator()
-                .services()
-                .separator()
-                .hide_with_text("Hide Augenblick")
-                .hide_others()
-                .show_all()
-                .separator()
-                .item(&quit_item)
-                .build()
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-
-            let open_item = MenuItemBuilder::with_id("file_open_markdown", "Open…")
-                .accelerator("CmdOrCtrl+KeyO")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let new_note_item = MenuItemBuilder::with_id("file_new_note", "New note")
-                .accelerator("CmdOrCtrl+KeyN")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let save_note_item = MenuItemBuilder::with_id("file_save_note", "Save")
-                .accelerator("CmdOrCtrl+KeyS")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let save_as_note_item = MenuItemBuilder::with_id("file_save_as_note", "Save As…")
-                .accelerator("CmdOrCtrl+Shift+KeyS")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let trash_note_item = MenuItemBuilder::with_id("file_trash_note", "Move to Trash")
-                .accelerator("Delete")
-                .build(app)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            let file_menu = SubmenuBuilder::new(app, "File")
-                .item(&new_note_item)
-                .separator()
-                .item(&open_item)
-                .separator()
-                .item(&save_note_item)
-                .item(&save_as_note_item)
-                .separator()
-                .item(&trash_note_item)
-                .build()
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-
-            let edit_menu = SubmenuBuilder::new(app, "Edit")
-                .undo()
-                .redo()
-                .separator()
-                .cut()
-                .copy()
-                .paste()
-                .select_all()
-                .build()
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-
-            let view_menu = SubmenuBuilder::new(app, "View")
-                .item(
-                    &MenuItemBuilder::with_id("view_toggle_devtools", "Toggle Developer Tools")
-                        .accelerator("CmdOrCtrl+Alt+KeyI")
-                        .build(app)
-                        .map_err(|err| std::io::Error::other(err.to_string()))?,
-                )
-                .separator()
-                .fullscreen_with_text("Enter Full Screen")
-                .build()
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-
-            let window_menu = SubmenuBuilder::new(app, "Window")
-                .minimize()
-                .maximize_with_text("Zoom")
-                .separator()
-                .close_window()
-                .build()
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-
-            let menu = MenuBuilder::new(app)
-                .items(&[&app_menu, &file_menu, &edit_menu, &view_menu, &window_menu])
-                .build()
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
+            if !hidden_start {
+                show_main_window(&app_handle);
+            }
+            autostart::refresh(&app_handle);
+            note_windows::reopen_all(&app_handle);
+            quick_capture::register_saved(&app_handle);
+            cli::handle(&app_handle, &args, &cwd, false);
+            let link_handle = app_handle.clone();
+            app.deep_link()
+                .on_open_url(move |event| deep_link::handle(&link_handle, event.urls()));
+            // Installed builds register the scheme through the bundle; dev builds do it here.
+            #[cfg(all(debug_assertions, any(windows, target_os = "linux")))]
+            let _ = app.deep_link().register_all();
+            // macOS delivers the launch link to `on_open_url` too; elsewhere it is in argv.
+            #[cfg(not(target_os = "macos"))]
+            {
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    deep_link::handle(&app_handle, urls);
+                }
+            }
 
+            let menu = build_app_menu(&app_handle).map_err(std::io::Error::other)?;
             app.set_menu(menu)
                 .map_err(|err| std::io::Error::other(err.to_string()))?;
+            refresh_history_menu(&app_handle);
 
             app.on_menu_event(|app_handle, event| {
                 let id = event.id().0.as_str();
@@ -145,6 +112,12 @@ pub fn run() {
                     return;
                 }
 
+                if id == "tray_daily_note" {
+                    show_main_window(app_handle);
+                    let _ = app_handle.emit("tray-daily-note", ());
+                    return;
+                }
+
                 if id == "tray_quit" {
                     let _ = app_handle.emit("tray-quit", ());
                     return;
@@ -167,30 +140,37 @@ pub fn run() {
                     return;
                 }
 
+                if let Some(rest) = id.strip_prefix(TRAY_REMINDER_SNOOZE_PREFIX) {
+                    if let Some((minutes, note_id)) = rest.split_once(':') {
+                        let minutes = minutes.parse().unwrap_or(10);
+                        reminders::update(app_handle, note_id, Some(minutes));
+                    }
+                    return;
+                }
+
+                if let Some(note_id) = id.strip_prefix(TRAY_REMINDER_DONE_PREFIX) {
+                    reminders::update(app_handle, note_id, None);
+                    return;
+                }
+
                 if let Some(note_id) = id.strip_prefix(TRAY_NOTE_PREFIX) {
                     show_main_window(app_handle);
                     let _ = app_handle.emit("tray-select-note", note_id.to_string());
                     return;
                 }
 
+                if let Some(event) = keybindings::action(id).and_then(|action| action.event) {
+                    let _ = app_handle.emit(event, ());
+                    return;
+                }
+
                 match id {
-                    "app_settings" => {
-                        let _ = app_handle.emit("menu-settings", ());
-                    }
-                    "file_open_markdown" => {
-                        let _ = app_handle.emit("menu-open-markdown", ());
-                    }
-                    "file_new_note" => {
-                        let _ = app_handle.emit("menu-new-note", ());
-                    }
-                    "file_save_note" => {
-                        let _ = app_handle.emit("menu-save", ());
-                    }
-                    "file_save_as_note" => {
-                        let _ = app_handle.emit("menu-save-as", ());
-                    }
-                    "file_trash_note" => {
-                        let _ = app_handle.emit("menu-trash", ());
+                    HISTORY_UNDO_ID | HISTORY_REDO_ID => {
+                        let state = app_handle.state::<AppState>();
+                        let redo = id == HISTORY_REDO_ID;
+                        if let Err(err) = commands::step_history(app_handle, &state, redo) {
+                            logs::error("history", &err.to_string());
+                        }
                     }
                     "view_toggle_devtools" => {
                         if let Some(window) = app_handle.get_webview_window("main") {
@@ -219,17 +199,12 @@ pub fn run() {
             let tray_icon = tauri::image::Image::from_path(tray_icon_path)
                 .map_err(|err| std::io::Error::other(err.to_string()))?;
 
-            TrayIconBuilder::new()
+            TrayIconBuilder::with_id(TRAY_ID)
                 .icon(tray_icon)
                 .icon_as_template(true)
                 .menu(&tray_menu)
                 .show_menu_on_left_click(false)
                 .on_tray_icon_event(|tray, event| {
-                    if matches!(event, TrayIconEvent::Enter { .. }) {
-                        if let Ok(menu) = build_tray_menu(tray.app_handle()) {
-                            let _ = tray.set_menu(Some(menu));
-                        }
-                    }
                     if let TrayIconEvent::Click {
                         button: tauri::tray::MouseButton::Left,
                         button_state: tauri::tray::MouseButtonState::Up,
@@ -243,42 +218,81 @@ pub fn run() {
                 })
                 .build(&app_handle)
                 .map_err(|err| std::io::Error::other(err.to_string()))?;
+            events::subscribe_tray(&app_handle);
+            events::subscribe_history_menu(&app_handle);
+            events::subscribe_note_windows(&app_handle);
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::notes_list,
             commands::note_create,
+            commands::templates_list,
+            commands::note_create_from_template,
+            commands::note_daily,
+            commands::recurrences_list,
+            commands::recurrence_save,
+            commands::recurrence_delete,
             commands::note_get,
+            commands::note_remove_front_matter,
             commands::note_set_active,
             commands::note_write_draft,
             commands::note_save,
+            commands::note_convert_to_utf8,
             commands::note_save_as,
             commands::note_import_file,
+            commands::notes_import_external,
+            commands::notes_export_obsidian,
+            commands::note_outgoing_links,
+            commands::note_backlinks,
+            commands::tasks_list,
+            commands::task_toggle,
+            commands::note_set_reminder,
+            commands::note_snooze_reminder,
             commands::note_trash,
             commands::note_restore,
             commands::note_delete_forever,
             commands::note_pin,
+            commands::notes_trash_many,
+            commands::notes_restore_many,
+            commands::notes_delete_forever_many,
+            commands::notes_pin_many,
+            commands::notes_export_many,
+            commands::trash_empty,
             commands::notes_reorder,
+            commands::history_undo,
+            commands::history_redo,
             commands::settings_get_all,
             commands::settings_set,
+            commands::calendar_export,
             commands::app_state_get_all,
             commands::app_state_set,
             commands::expiry_run_now,
             commands::app_set_activation_policy,
             commands::app_show_main_window,
+            commands::note_open_window,
+            commands::quick_capture_submit,
+            commands::quick_capture_hide,
+            commands::autostart_set,
+            commands::keybindings_get,
+            commands::keybindings_set,
             commands::app_exit
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
 
-    app.run(|app_handle, event| {
+    app.run(|app_handle, event| match event {
+        RunEvent::ExitRequested { .. } => note_windows::save_all_bounds(app_handle),
+        #[cfg(target_os = "macos")]
+        RunEvent::Resumed => show_main_window_if_hidden(app_handle),
         #[cfg(target_os = "macos")]
-        match event {
-            RunEvent::Resumed => show_main_window_if_hidden(app_handle),
-            RunEvent::Reopen { .. } => show_main_window(app_handle),
-            _ => {}
+        RunEvent::Reopen { .. } => show_main_window(app_handle),
+        #[cfg(target_os = "macos")]
+        RunEvent::Opened { urls } => {
+            let paths = urls.iter().filter_map(|url| url.to_file_path().ok());
+            cli::open_paths(app_handle, paths.collect());
         }
+        _ => {}
     });
 }
 
@@ -286,11 +300,10 @@ fn build_tray_menu<R: tauri::Runtime>(
     app_handle: &AppHandle<R>,
 ) -> Result<tauri::menu::Menu<R>, String> {
     let state = app_handle.state::<AppState>();
-    let conn = state
-        .db
-        .lock()
-        .map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
     let notes_list = notes::list(&conn)?;
+    let due = notes::due_reminders(&conn, notes::now_ms())?;
+    drop(conn);
 
     let pinned: Vec<_> = notes_list.active.iter().filter(|note| note.is_pinned).collect();
     let mut recent: Vec<_> = notes_list
@@ -301,10 +314,39 @@ fn build_tray_menu<R: tauri::Runtime>(
     recent.sort_by(|a, b| b.last_interaction.cmp(&a.last_interaction));
     recent.truncate(5);
 
-    let mut menu = MenuBuilder::new(app_handle).text("tray_new_note", "New note");
+    let mut menu = MenuBuilder::new(app_handle)
+        .text("tray_new_note", i18n::t("tray.new_note"))
+        .text("tray_daily_note", i18n::t("tray.today"));
+
+    if !due.is_empty() {
+        let due_header = MenuItemBuilder::new(i18n::t("tray.due"))
+            .enabled(false)
+            .build(app_handle)
+            .map_err(|err| err.to_string())?;
+        menu = menu.separator().item(&due_header);
+        for note in &due {
+            let submenu = SubmenuBuilder::new(app_handle, tray_note_label(note.title.as_str()))
+                .text(tray_note_id(&note.id), i18n::t("tray.open"))
+                .text(
+                    format!("{TRAY_REMINDER_SNOOZE_PREFIX}10:{}", note.id),
+                    i18n::t("tray.snooze_10m"),
+                )
+                .text(
+                    format!("{TRAY_REMINDER_SNOOZE_PREFIX}60:{}", note.id),
+                    i18n::t("tray.snooze_1h"),
+                )
+                .text(
+                    format!("{TRAY_REMINDER_DONE_PREFIX}{}", note.id),
+                    i18n::t("tray.done"),
+                )
+                .build()
+                .map_err(|err| err.to_string())?;
+            menu = menu.item(&submenu);
+        }
+    }
 
     if !pinned.is_empty() {
-        let pinned_header = MenuItemBuilder::new("Pinned")
+        let pinned_header = MenuItemBuilder::new(i18n::t("tray.pinned"))
             .enabled(false)
             .build(app_handle)
             .map_err(|err| err.to_string())?;
@@ -315,7 +357,7 @@ fn build_tray_menu<R: tauri::Runtime>(
     }
 
     if !recent.is_empty() {
-        let recent_header = MenuItemBuilder::new("Recent")
+        let recent_header = MenuItemBuilder::new(i18n::t("tray.recent"))
             .enabled(false)
             .build(app_handle)
             .map_err(|err| err.to_string())?;
@@ -327,8 +369,8 @@ fn build_tray_menu<R: tauri::Runtime>(
 
     menu = menu
         .separator()
-        .text("tray_show_all", "Show all notes")
-        .text("tray_quit", "Quit Augenblick");
+        .text("tray_show_all", i18n::t("tray.show_all"))
+        .text("tray_quit", i18n::t("tray.quit"));
 
     menu.build().map_err(|err| err.to_string())
 }
@@ -337,15 +379,178 @@ fn build_basic_tray_menu<R: tauri::Runtime>(
     app_handle: &AppHandle<R>,
 ) -> Result<tauri::menu::Menu<R>, String> {
     MenuBuilder::new(app_handle)
-        .text("tray_new_note", "New note")
+        .text("tray_new_note", i18n::t("tray.new_note"))
+        .text("tray_daily_note", i18n::t("tray.today"))
         .separator()
-        .text("tray_show_all", "Show all notes")
-        .text("tray_quit", "Quit Augenblick")
+        .text("tray_show_all", i18n::t("tray.show_all"))
+        .text("tray_quit", i18n::t("tray.quit"))
         .build()
         .map_err(|err| err.to_string())
 }
 
+const TRAY_ID: &str = "main";
 const TRAY_NOTE_PREFIX: &str = "tray_note:";
+const TRAY_REMINDER_SNOOZE_PREFIX: &str = "tray_reminder_snooze:";
+const TRAY_REMINDER_DONE_PREFIX: &str = "tray_reminder_done:";
+const HISTORY_UNDO_ID: &str = "edit_history_undo";
+const HISTORY_REDO_ID: &str = "edit_history_redo";
+
+/// Rebuilds the tray menu. Runs on every `notes-changed` event and when a reminder comes due.
+pub(crate) fn refresh_tray_menu<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app_handle) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(err) => logs::error("tray-menu", &err),
+    }
+}
+
+/// Builds the app menu in the current locale. Items with a shortcut come from
+/// [`keybindings::ACTIONS`], bound to the user's accelerators; the layout lives here.
+fn build_app_menu<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<Menu<R>, String> {
+    let overrides = match app_handle.state::<AppState>().db.lock() {
+        Ok(conn) => keybindings::overrides(&conn)?,
+        Err(_) => return Err(Message::new("error.db_lock").into()),
+    };
+    let item = |id: &str| -> Result<MenuItemBuilder, String> {
+        let action = keybindings::action(id)
+            .ok_or_else(|| Message::new("error.menu_action").arg("id", id))?;
+        let builder = MenuItemBuilder::with_id(action.id, i18n::t(action.label));
+        Ok(match keybindings::accelerator(&overrides, action) {
+            "" => builder,
+            accelerator => builder.accelerator(accelerator),
+        })
+    };
+    let build = |builder: MenuItemBuilder| builder.build(app_handle).map_err(|err| err.to_string());
+
+    let about_metadata = AboutMetadata {
+        credits: Some("Built by Robin Ebers (@robinebers)".into()),
+        ..Default::default()
+    };
+    let app_menu = SubmenuBuilder::new(app_handle, "Augenblick")
+        .about_with_text(i18n::t("menu.about"), Some(about_metadata))
+        .separator()
+        .item(&build(item("app_settings")?)?)
+        .separator()
+        .services_with_text(i18n::t("menu.services"))
+        .separator()
+        .hide_with_text(i18n::t("menu.hide"))
+        .hide_others_with_text(i18n::t("menu.hide_others"))
+        .show_all_with_text(i18n::t("menu.show_all"))
+        .separator()
+        .item(&build(item("app_quit")?)?)
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let file_menu = SubmenuBuilder::new(app_handle, i18n::t("menu.file"))
+        .item(&build(item("file_new_note")?)?)
+        .separator()
+        .item(&build(item("file_open_markdown")?)?)
+        .separator()
+        .item(&build(item("file_save_note")?)?)
+        .item(&build(item("file_save_as_note")?)?)
+        .separator()
+        .item(&build(item("file_trash_note")?)?)
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    // Text undo stays with the editor; these reverse note operations from the journal.
+    let edit_menu = SubmenuBuilder::new(app_handle, i18n::t("menu.edit"))
+        .undo_with_text(i18n::t("menu.undo"))
+        .redo_with_text(i18n::t("menu.redo"))
+        .separator()
+        .item(&build(item(HISTORY_UNDO_ID)?.enabled(false))?)
+        .item(&build(item(HISTORY_REDO_ID)?.enabled(false))?)
+        .separator()
+        .cut_with_text(i18n::t("menu.cut"))
+        .copy_with_text(i18n::t("menu.copy"))
+        .paste_with_text(i18n::t("menu.paste"))
+        .select_all_with_text(i18n::t("menu.select_all"))
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let view_menu = SubmenuBuilder::new(app_handle, i18n::t("menu.view"))
+        .item(&build(item("view_toggle_devtools")?)?)
+        .separator()
+        .fullscreen_with_text(i18n::t("menu.fullscreen"))
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let window_menu = SubmenuBuilder::new(app_handle, i18n::t("menu.window"))
+        .minimize_with_text(i18n::t("menu.minimize"))
+        .maximize_with_text(i18n::t("menu.zoom"))
+        .separator()
+        .close_window_with_text(i18n::t("menu.close_window"))
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    MenuBuilder::new(app_handle)
+        .items(&[&app_menu, &file_menu, &edit_menu, &view_menu, &window_menu])
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+/// Rebuilds the app menu after the keybindings or the locale changed.
+pub(crate) fn refresh_app_menu<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let menu = build_app_menu(app_handle)?;
+    app_handle.set_menu(menu).map_err(|err| err.to_string())?;
+    refresh_history_menu(app_handle);
+    Ok(())
+}
+
+/// An item of the app menu's submenus.
+fn app_menu_item<R: tauri::Runtime>(app_handle: &AppHandle<R>, id: &str) -> Option<MenuItem<R>> {
+    let menu = app_handle.menu()?;
+    menu.items()
+        .ok()?
+        .iter()
+        .find_map(|kind| kind.as_submenu()?.get(id)?.as_menuitem().cloned())
+}
+
+/// Names the next undo and redo in the Edit menu, e.g. "Undo Trash".
+pub(crate) fn refresh_history_menu<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let (Some(undo_item), Some(redo_item)) = (
+        app_menu_item(app_handle, HISTORY_UNDO_ID),
+        app_menu_item(app_handle, HISTORY_REDO_ID),
+    ) else {
+        return;
+    };
+    let history = match app_handle.state::<AppState>().db.lock() {
+        Ok(conn) => notes::history_state(&conn),
+        Err(_) => Err(Message::new("error.db_lock")),
+    };
+    let history = match history {
+        Ok(history) => history,
+        Err(err) => {
+            logs::error("history", &err.to_string());
+            return;
+        }
+    };
+    for (item, named, unnamed, label) in [
+        (
+            undo_item,
+            "menu.history_undo",
+            "action.edit_history_undo",
+            history.undo,
+        ),
+        (
+            redo_item,
+            "menu.history_redo",
+            "action.edit_history_redo",
+            history.redo,
+        ),
+    ] {
+        let text = match &label {
+            Some(label) => Message::new(named).arg("label", label).to_string(),
+            None => i18n::t(unnamed),
+        };
+        let _ = item.set_text(text);
+        let _ = item.set_enabled(label.is_some());
+    }
+}
 
 fn tray_note_id(id: &str) -> String {
     format!("{TRAY_NOTE_PREFIX}{id}")
@@ -0,0 +1,177 @@
+//! Notes in their own windows: a normal editor window or a small always-on-top sticky note.
+//! Which notes are open, in which mode and where is stored in `note_windows`, so they come back
+//! on launch and the expiry sweeper leaves them alone.
+
+use crate::app_state::AppState;
+use crate::i18n::Message;
+use crate::logs;
+use crate::notes;
+use crate::types::{NoteWindow, NoteWindowMode, WindowBounds};
+use crate::window_state;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Position, Size, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+pub const LABEL_PREFIX: &str = "note-";
+
+pub fn label(id: &str) -> String {
+    format!("{LABEL_PREFIX}{id}")
+}
+
+/// Opens the note in its own window, or focuses the one already showing it. Switching between
+/// normal and sticky rebuilds the window.
+pub fn open(app: &AppHandle, id: &str, mode: NoteWindowMode) -> Result<(), Message> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    let previous = notes::note_window(&conn, id)?.filter(|window| window.is_open);
+    let record = notes::open_window(&conn, id, mode)?;
+    let title = notes::get_meta(&conn, id)?.title;
+    drop(conn);
+
+    if let Some(window) = app.get_webview_window(&label(id)) {
+        if previous.is_some_and(|previous| previous.mode == mode) {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+            return Ok(());
+        }
+        let _ = window.destroy();
+    }
+    build(app, &record, &title)
+}
+
+/// Reopens the windows that were open when the app quit.
+pub fn reopen_all(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let open = match state.db.lock() {
+        Ok(conn) => notes::open_windows(&conn).and_then(|windows| {
+            windows
+                .into_iter()
+                .map(|window| {
+                    let title = notes::get_meta(&conn, &window.note_id)?.title;
+                    Ok((window, title))
+                })
+                .collect::<Result<Vec<_>, Message>>()
+        }),
+        Err(_) => Err(Message::new("error.db_lock")),
+    };
+    match open {
+        Ok(open) => {
+            for (window, title) in open {
+                if let Err(err) = build(app, &window, &title) {
+                    logs::error("note-windows", &format!("reopen failed: {err}"));
+                }
+            }
+        }
+        Err(err) => logs::error("note-windows", &err.to_string()),
+    }
+}
+
+/// Closes windows whose note was trashed or deleted and keeps the other titles current. Runs on
+/// every `notes-changed` event.
+pub fn sync(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    for (label, window) in app.webview_windows() {
+        let Some(id) = label.strip_prefix(LABEL_PREFIX) else {
+            continue;
+        };
+        let Ok(conn) = state.db.lock() else {
+            return;
+        };
+        match notes::get_meta(&conn, id) {
+            Ok(meta) if !meta.is_trashed => {
+                drop(conn);
+                let _ = window.set_title(&meta.title);
+            }
+            _ => {
+                let _ = notes::close_window(&conn, id);
+                drop(conn);
+                let _ = window.destroy();
+            }
+        }
+    }
+}
+
+fn build(app: &AppHandle, record: &NoteWindow, title: &str) -> Result<(), Message> {
+    let id = record.note_id.clone();
+    let sticky = record.mode == NoteWindowMode::Sticky;
+    let url = format!("index.html?note={id}&mode={}", record.mode.as_str());
+    let (width, height, min_width, min_height) = if sticky {
+        (300.0, 320.0, 200.0, 160.0)
+    } else {
+        (640.0, 560.0, 360.0, 280.0)
+    };
+
+    let builder = WebviewWindowBuilder::new(app, label(&id), WebviewUrl::App(url.into()))
+        .title(title)
+        .inner_size(width, height)
+        .min_inner_size(min_width, min_height)
+        .disable_drag_drop_handler()
+        .visible(false)
+        .always_on_top(sticky)
+        .skip_taskbar(sticky)
+        .visible_on_all_workspaces(sticky);
+    #[cfg(target_os = "macos")]
+    let builder = builder
+        .title_bar_style(tauri::TitleBarStyle::Overlay)
+        .hidden_title(true);
+    let window = builder.build().map_err(|err| err.to_string())?;
+
+    if let Some(bounds) = record.bounds {
+        let _ = window.set_size(Size::Physical(PhysicalSize {
+            width: bounds.width,
+            height: bounds.height,
+        }));
+        let _ = window.set_position(Position::Physical(PhysicalPosition {
+            x: bounds.x,
+            y: bounds.y,
+        }));
+    }
+    window_state::clamp_to_monitors(&window);
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    // Bounds are saved when the window closes or the app quits, like the main window's, rather
+    // than on every step of a drag.
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            save_bounds(&handle, &id);
+            let state = handle.state::<AppState>();
+            if let Ok(conn) = state.db.lock() {
+                let _ = notes::close_window(&conn, &id);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Saves where every open note window is. Runs when the app is about to exit, which closes the
+/// windows without a `CloseRequested`.
+pub fn save_all_bounds(app: &AppHandle) {
+    for (label, window) in app.webview_windows() {
+        if let Some(id) = label.strip_prefix(LABEL_PREFIX) {
+            save_bounds(&window, id);
+        }
+    }
+}
+
+fn save_bounds(window: &WebviewWindow, id: &str) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let (Ok(pos), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let bounds = WindowBounds {
+        x: pos.x,
+        y: pos.y,
+        width: size.width,
+        height: size.height,
+    };
+    let state = window.state::<AppState>();
+    if let Ok(conn) = state.db.lock() {
+        let _ = notes::set_window_bounds(&conn, id, bounds);
+    }
+}
@@ -1,54 +1,170 @@
+mod batch;
+mod capture;
+mod content_index;
+mod daily;
 mod derive;
+mod directives;
+mod encoding;
+mod export;
 mod files;
+mod frontmatter;
+mod history;
 mod import;
+mod links;
 mod list;
 mod meta;
 mod ordering;
+mod recurring;
+mod reminders;
 mod scoped_updates;
+mod tasks;
+mod templates;
+#[cfg(test)]
+pub(crate) mod test_support;
 mod time;
 mod trash;
+mod windows;
 mod write;
 
 use crate::app_state::AppPaths;
-use crate::types::{NoteMeta, NoteWithContent, NotesList};
+use crate::i18n::Message;
+use crate::types::{
+    BatchReport, BatchStatus, ConvertedImportTarget, ExternalImportReport, ExternalSource,
+    HistoryState, HistoryStep, NoteChange, NoteFromTemplate, NoteLink, NoteMeta, NoteTemplate,
+    NoteWindow, NoteWindowMode, NoteWithContent, NotesList, ObsidianExportItem, OpenedNote,
+    Recurrence, RecurrenceInput, TaskFilter, TaskItem, WindowBounds,
+};
+use chrono::{DateTime, Local};
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::path::Path;
 
 pub fn now_ms() -> i64 {
     time::now_ms()
 }
 
-pub fn list(conn: &Connection) -> Result<NotesList, String> {
+/// Indexes links and tasks of notes written before those indexes existed. Runs once.
+pub fn backfill_content_index(conn: &Connection) -> Result<(), Message> {
+    content_index::backfill(conn)
+}
+
+pub fn list(conn: &Connection) -> Result<NotesList, Message> {
     list::list(conn)
 }
 
-pub fn create_draft(conn: &Connection, paths: &AppPaths) -> Result<NoteMeta, String> {
+pub fn create_draft(conn: &Connection, paths: &AppPaths) -> Result<NoteMeta, Message> {
     write::create_draft(conn, paths)
 }
 
-pub fn get(conn: &Connection, id: &str) -> Result<NoteWithContent, String> {
+pub fn create_with_content(
+    conn: &Connection,
+    paths: &AppPaths,
+    content: &str,
+    expiry_minutes: Option<i64>,
+) -> Result<NoteMeta, Message> {
+    write::create_with_content(conn, paths, content, expiry_minutes)
+}
+
+/// Text from bytes in any encoding a note file may have (UTF-8, UTF-16, Windows-1252, with or
+/// without a BOM), with `\n` line breaks.
+pub fn decode_text(bytes: &[u8]) -> String {
+    encoding::decode(bytes).content
+}
+
+/// The longest fixed expiry a link or a recurring note can ask for.
+pub const MAX_EXPIRY_MINUTES: i64 = 365 * 1440;
+
+/// `90m`, `12h`, `3d`, `2w` or a bare number of minutes, as in `expires_after`.
+pub fn duration_minutes(raw: &str) -> Option<i64> {
+    time::duration_minutes(raw)
+}
+
+pub fn templates(paths: &AppPaths) -> Result<Vec<NoteTemplate>, Message> {
+    templates::list(paths)
+}
+
+pub fn create_from_template(
+    conn: &Connection,
+    paths: &AppPaths,
+    name: &str,
+    clipboard: Option<&str>,
+) -> Result<NoteFromTemplate, Message> {
+    templates::create_from_template(conn, paths, name, clipboard)
+}
+
+/// The daily note for `date` (`YYYY-MM-DD`, default today), created if it doesn't exist yet.
+pub fn daily(
+    conn: &Connection,
+    paths: &AppPaths,
+    date: Option<&str>,
+) -> Result<OpenedNote, Message> {
+    daily::open(conn, paths, date)
+}
+
+pub fn recurrences(conn: &Connection) -> Result<Vec<Recurrence>, Message> {
+    recurring::list(conn)
+}
+
+pub fn save_recurrence(
+    conn: &Connection,
+    paths: &AppPaths,
+    input: &RecurrenceInput,
+) -> Result<Recurrence, Message> {
+    recurring::save(conn, paths, input)
+}
+
+pub fn delete_recurrence(conn: &Connection, id: &str) -> Result<(), Message> {
+    recurring::delete(conn, id)
+}
+
+/// Generates the notes of recurrences that came due by `now`; returns their ids.
+pub fn materialize_recurring(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    now: DateTime<Local>,
+) -> Result<Vec<String>, Message> {
+    recurring::materialize_due(conn, paths, now)
+}
+
+/// The note's current meta, e.g. to report it as the `before` side of a change.
+pub fn get_meta(conn: &Connection, id: &str) -> Result<NoteMeta, Message> {
+    meta::get_meta(conn, id)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<NoteWithContent, Message> {
+    write::get(conn, id)
+}
+
+/// Takes the front matter out of the note's file. The editor never shows it, so this is the
+/// only way to get rid of it from the app.
+pub fn remove_front_matter(conn: &Connection, id: &str) -> Result<NoteWithContent, Message> {
+    frontmatter::remove(conn, id)?;
     write::get(conn, id)
 }
 
-pub fn set_active(conn: &Connection, id: &str) -> Result<(), String> {
+pub fn set_active(conn: &Connection, id: &str) -> Result<(), Message> {
     write::set_active(conn, id)
 }
 
-pub fn write_draft(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, String> {
+pub fn write_draft(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, Message> {
     write::write_draft(conn, id, content)
 }
 
-pub fn save(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, String> {
+pub fn save(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, Message> {
     write::save(conn, id, content)
 }
 
+pub fn convert_to_utf8(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, Message> {
+    write::convert_to_utf8(conn, id, content)
+}
+
 pub fn save_as(
     conn: &Connection,
     paths: &AppPaths,
     id: &str,
     new_path: &Path,
     content: &str,
-) -> Result<NoteMeta, String> {
+) -> Result<NoteMeta, Message> {
     write::save_as(conn, paths, id, new_path, content)
 }
 
@@ -56,26 +172,258 @@ pub fn import_file(
     conn: &Connection,
     paths: &AppPaths,
     path: &Path,
-) -> Result<NoteWithContent, String> {
-    import::import_file(conn, paths, path)
+    converted_target: ConvertedImportTarget,
+) -> Result<OpenedNote, Message> {
+    import::import_file(conn, paths, path, converted_target)
+}
+
+pub fn import_external(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    source: ExternalSource,
+    path: &Path,
+    dry_run: bool,
+) -> Result<ExternalImportReport, Message> {
+    import::import_external(conn, paths, source, path, dry_run)
+}
+
+pub fn export_obsidian(
+    conn: &Connection,
+    ids: &[String],
+    dir: &Path,
+    deadlines: &HashMap<String, i64>,
+) -> Result<Vec<ObsidianExportItem>, Message> {
+    export::export_obsidian(conn, ids, dir, deadlines)
+}
+
+pub fn outgoing_links(conn: &Connection, id: &str) -> Result<Vec<NoteLink>, Message> {
+    links::outgoing(conn, id)
+}
+
+pub fn backlinks(conn: &Connection, id: &str) -> Result<Vec<NoteLink>, Message> {
+    links::backlinks(conn, id)
+}
+
+pub fn tasks_list(conn: &Connection, filter: &TaskFilter) -> Result<Vec<TaskItem>, Message> {
+    tasks::list(conn, filter)
 }
 
-pub fn trash(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, String> {
-    trash::trash(conn, paths, id)
+pub fn task_toggle(
+    conn: &Connection,
+    note_id: &str,
+    line: i64,
+    checked: bool,
+) -> Result<NoteMeta, Message> {
+    tasks::toggle(conn, note_id, line, checked)
+}
+
+/// Sets the reminder of a note, or dismisses it with `None`.
+pub fn set_reminder(conn: &Connection, id: &str, at: Option<i64>) -> Result<NoteMeta, Message> {
+    reminders::set(conn, id, at)
+}
+
+pub fn snooze_reminder(conn: &Connection, id: &str, minutes: i64) -> Result<NoteMeta, Message> {
+    reminders::snooze(conn, id, minutes)
+}
+
+pub fn due_reminders(conn: &Connection, now: i64) -> Result<Vec<NoteMeta>, Message> {
+    reminders::due(conn, now)
 }
 
-pub fn restore(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, String> {
+/// Due reminders that haven't been notified yet; they are marked as notified.
+pub fn take_unnotified_reminders(conn: &Connection, now: i64) -> Result<Vec<NoteMeta>, Message> {
+    reminders::take_unnotified(conn, now)
+}
+
+pub fn trash(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, Message> {
+    let before = get_meta(conn, id)?;
+    let meta = trash::trash(conn, paths, id)?;
+    if !before.is_trashed {
+        let notes = vec![history::Placement::of(&before)];
+        let label = history::Label::new("history.trash");
+        record_history(conn, label, history::Entry::Trash { notes });
+    }
+    Ok(meta)
+}
+
+/// Trashes notes the expiry sweeper found stale, as one undoable step. Failures are logged and
+/// skipped.
+pub fn trash_expired(conn: &Connection, paths: &AppPaths, ids: &[String]) -> Vec<NoteChange> {
+    let mut placements = Vec::new();
+    let mut changes = Vec::new();
+    for id in ids {
+        let trashed =
+            get_meta(conn, id).and_then(|before| Ok((before, trash::trash(conn, paths, id)?)));
+        match trashed {
+            Ok((before, after)) => {
+                placements.push(history::Placement::of(&before));
+                changes.push(NoteChange::updated(before, after));
+            }
+            Err(err) => crate::logs::error("expiry", &format!("auto-trash failed for {id}: {err}")),
+        }
+    }
+    let entry = history::Entry::Trash { notes: placements };
+    record_history(conn, history::Label::new("history.expire"), entry);
+    changes
+}
+
+pub fn restore(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, Message> {
     trash::restore(conn, paths, id)
 }
 
-pub fn delete_forever(conn: &Connection, id: &str) -> Result<(), String> {
-    trash::delete_forever(conn, id)
+pub fn delete_forever(conn: &Connection, id: &str) -> Result<(), Message> {
+    let snapshot = history::capture(conn, id)?;
+    trash::delete_forever(conn, id)?;
+    let entry = history::Entry::Delete {
+        notes: vec![snapshot],
+    };
+    record_history(conn, history::Label::new("history.delete"), entry);
+    Ok(())
+}
+
+pub fn set_pinned(conn: &Connection, id: &str, pinned: bool) -> Result<NoteMeta, Message> {
+    let before = get_meta(conn, id)?;
+    let meta = ordering::set_pinned(conn, id, pinned)?;
+    let label = history::Label::new(if pinned {
+        "history.pin"
+    } else {
+        "history.unpin"
+    });
+    let entry = history::Entry::Arrange {
+        before: vec![history::Placement::of(&before)],
+        after: vec![history::Placement::of(&meta)],
+    };
+    record_history(conn, label, entry);
+    if let Err(err) = frontmatter::write_back(conn, id) {
+        crate::logs::error(
+            "front_matter",
+            &format!("write-back failed: id={id} err=\"{err}\""),
+        );
+    }
+    Ok(meta)
+}
+
+pub fn reorder(conn: &mut Connection, ids: &[String]) -> Result<(), Message> {
+    let placements = |conn: &Connection| -> Vec<history::Placement> {
+        ids.iter()
+            .filter_map(|id| get_meta(conn, id).ok())
+            .filter(|meta| !meta.is_trashed)
+            .map(|meta| history::Placement::of(&meta))
+            .collect()
+    };
+    let before = placements(conn);
+    ordering::reorder(conn, ids)?;
+    let after = placements(conn);
+    let entry = history::Entry::Arrange { before, after };
+    record_history(conn, history::Label::new("history.reorder"), entry);
+    Ok(())
+}
+
+/// Batch versions of trash, restore, delete and pin: one transaction each, all or nothing, with a
+/// result per id.
+pub fn trash_many(
+    conn: &Connection,
+    paths: &AppPaths,
+    ids: &[String],
+) -> Result<BatchReport, Message> {
+    batch::trash_many(conn, paths, ids)
+}
+
+pub fn restore_many(
+    conn: &Connection,
+    paths: &AppPaths,
+    ids: &[String],
+) -> Result<BatchReport, Message> {
+    batch::restore_many(conn, paths, ids)
+}
+
+pub fn delete_forever_many(conn: &Connection, ids: &[String]) -> Result<BatchReport, Message> {
+    batch::delete_forever_many(conn, ids)
+}
+
+pub fn empty_trash(conn: &Connection) -> Result<BatchReport, Message> {
+    batch::empty_trash(conn)
+}
+
+pub fn pin_many(conn: &Connection, ids: &[String], pinned: bool) -> Result<BatchReport, Message> {
+    let report = batch::pin_many(conn, ids, pinned)?;
+    for result in &report.results {
+        if result.status != BatchStatus::Done {
+            continue;
+        }
+        if let Err(err) = frontmatter::write_back(conn, &result.id) {
+            crate::logs::error(
+                "front_matter",
+                &format!("write-back failed: id={} err=\"{err}\"", result.id),
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Writes the notes into `dir` as markdown with their front matter; removes them again if any
+/// fails.
+pub fn export_many(conn: &Connection, ids: &[String], dir: &Path) -> Result<BatchReport, Message> {
+    batch::export_many(conn, ids, dir)
+}
+
+/// Records that the note is shown in its own window, to reopen it on launch and keep it from
+/// expiring while it is open.
+pub fn open_window(
+    conn: &Connection,
+    id: &str,
+    mode: NoteWindowMode,
+) -> Result<NoteWindow, Message> {
+    windows::open(conn, id, mode)
+}
+
+pub fn note_window(conn: &Connection, id: &str) -> Result<Option<NoteWindow>, Message> {
+    windows::get(conn, id)
+}
+
+pub fn close_window(conn: &Connection, id: &str) -> Result<(), Message> {
+    windows::close(conn, id)
+}
+
+pub fn set_window_bounds(conn: &Connection, id: &str, bounds: WindowBounds) -> Result<(), Message> {
+    windows::set_bounds(conn, id, bounds)
+}
+
+pub fn open_windows(conn: &Connection) -> Result<Vec<NoteWindow>, Message> {
+    windows::list_open(conn)
+}
+
+/// Files quick-capture text under the inbox note, or in a new draft.
+pub fn capture(
+    conn: &Connection,
+    paths: &AppPaths,
+    inbox: Option<&str>,
+    text: &str,
+) -> Result<NoteChange, Message> {
+    capture::capture(conn, paths, inbox, text)
+}
+
+/// What the next undo and redo would do.
+pub fn history_state(conn: &Connection) -> Result<HistoryState, Message> {
+    history::state(conn)
+}
+
+/// Reverses the latest trash, delete, pin, reorder or auto-expiry. `None` when there is nothing
+/// left to undo.
+pub fn undo(conn: &mut Connection, paths: &AppPaths) -> Result<Option<HistoryStep>, Message> {
+    history::undo(conn, paths)
 }
 
-pub fn set_pinned(conn: &Connection, id: &str, pinned: bool) -> Result<NoteMeta, String> {
-    ordering::set_pinned(conn, id, pinned)
+pub fn redo(conn: &mut Connection, paths: &AppPaths) -> Result<Option<HistoryStep>, Message> {
+    history::redo(conn, paths)
 }
 
-pub fn reorder(conn: &mut Connection, ids: &[String]) -> Result<(), String> {
-    ordering::reorder(conn, ids)
+/// The operation already happened; a journal that can't be written only costs its undo.
+fn record_history(conn: &Connection, label: history::Label, entry: history::Entry) {
+    if let Err(err) = history::record(conn, label, &entry) {
+        crate::logs::error(
+            "history",
+            &format!("record failed: label={} err=\"{err}\"", label.key()),
+        );
+    }
 }
@@ -0,0 +1,388 @@
+//! Multi-note versions of trash, restore, delete, pin and export. Each batch runs in one
+//! transaction: if any id fails, the database is rolled back, draft files that were already moved
+//! go back, and files are only deleted once the batch has committed.
+
+use crate::app_state::AppPaths;
+use crate::i18n::Message;
+use crate::types::{BatchReport, BatchResult, BatchStatus, NoteChange, NoteMeta, NoteStorage};
+use rusqlite::{params, Connection, Transaction};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::encoding::TextFormat;
+use super::export::{file_stem, unique_path};
+use super::files::{move_file, write_file};
+use super::history::{self, Entry, Label, Placement};
+use super::meta::get_meta;
+use super::ordering;
+use super::trash;
+use super::write::read_stored;
+
+struct Attempt {
+    id: String,
+    before: Option<NoteMeta>,
+    outcome: Result<Option<NoteMeta>, Message>,
+}
+
+pub(super) fn trash_many(
+    conn: &Connection,
+    paths: &AppPaths,
+    ids: &[String],
+) -> Result<BatchReport, Message> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let attempts = attempt(&tx, ids, |tx, id| trash::trash(tx, paths, id).map(Some));
+    let notes: Vec<Placement> = done_befores(&attempts)
+        .filter(|before| !before.is_trashed)
+        .map(Placement::of)
+        .collect();
+    let label = Label::counted("history.trash", "history.trash_many", notes.len());
+    finish(tx, attempts, Some((label, Entry::Trash { notes })))
+}
+
+pub(super) fn restore_many(
+    conn: &Connection,
+    paths: &AppPaths,
+    ids: &[String],
+) -> Result<BatchReport, Message> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let attempts = attempt(&tx, ids, |tx, id| trash::restore(tx, paths, id).map(Some));
+    finish(tx, attempts, None)
+}
+
+pub(super) fn delete_forever_many(
+    conn: &Connection,
+    ids: &[String],
+) -> Result<BatchReport, Message> {
+    delete_rows(conn, ids, None)
+}
+
+/// Deletes every trashed note, undoable as one "Empty Trash".
+pub(super) fn empty_trash(conn: &Connection) -> Result<BatchReport, Message> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM notes WHERE is_trashed = 1")
+        .map_err(|err| err.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    delete_rows(conn, &ids, Some("history.empty_trash"))
+}
+
+/// Deletes the rows first and the draft files only after the batch committed, so a rollback has
+/// nothing to bring back.
+fn delete_rows(
+    conn: &Connection,
+    ids: &[String],
+    label: Option<&'static str>,
+) -> Result<BatchReport, Message> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let mut snapshots = Vec::new();
+    let mut files = Vec::new();
+    let attempts = attempt(&tx, ids, |tx, id| {
+        let meta = get_meta(tx, id)?;
+        let snapshot = history::capture(tx, id)?;
+        tx.execute("DELETE FROM notes WHERE id = ?1", params![id])
+            .map_err(|err| err.to_string())?;
+        snapshots.push(snapshot);
+        if meta.storage == NoteStorage::Draft {
+            files.push(meta.file_path);
+        }
+        Ok(None)
+    });
+    let label = label.map_or_else(
+        || Label::counted("history.delete", "history.delete_many", snapshots.len()),
+        Label::new,
+    );
+    let entry = Entry::Delete { notes: snapshots };
+    let report = finish(tx, attempts, Some((label, entry)))?;
+    if report.committed {
+        for file in files {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+    Ok(report)
+}
+
+/// Pins or unpins every note, or none of them: pinning more notes than the cap allows fails the
+/// whole batch.
+pub(super) fn pin_many(
+    conn: &Connection,
+    ids: &[String],
+    pinned: bool,
+) -> Result<BatchReport, Message> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let attempts = attempt(&tx, ids, |tx, id| {
+        ordering::set_pinned(tx, id, pinned).map(Some)
+    });
+    let (before, after): (Vec<_>, Vec<_>) = attempts
+        .iter()
+        .filter_map(|attempt| match (&attempt.before, &attempt.outcome) {
+            (Some(before), Ok(Some(after))) => Some((Placement::of(before), Placement::of(after))),
+            _ => None,
+        })
+        .unzip();
+    let (one, many) = if pinned {
+        ("history.pin", "history.pin_many")
+    } else {
+        ("history.unpin", "history.unpin_many")
+    };
+    let label = Label::counted(one, many, before.len());
+    finish(
+        tx,
+        attempts,
+        Some((label, Entry::Arrange { before, after })),
+    )
+}
+
+/// Writes each note with its front matter into `dir`, named after its title. If one can't be
+/// written, the files written so far are removed again.
+pub(super) fn export_many(
+    conn: &Connection,
+    ids: &[String],
+    dir: &Path,
+) -> Result<BatchReport, Message> {
+    let mut written = Vec::new();
+    let mut results = Vec::new();
+    for id in unique(ids) {
+        let exported = read_stored(conn, &id).and_then(|note| {
+            let path = unique_path(dir, &file_stem(&note.meta.title));
+            write_file(&path, &note.content, TextFormat::UTF8_LF)?;
+            Ok(path)
+        });
+        let (status, error, path) = match exported {
+            Ok(path) => {
+                written.push(path.clone());
+                let path = path.to_string_lossy().to_string();
+                (BatchStatus::Done, None, Some(path))
+            }
+            Err(err) => (BatchStatus::Failed, Some(err.to_string()), None),
+        };
+        results.push(BatchResult {
+            id,
+            status,
+            error,
+            meta: None,
+            path,
+        });
+    }
+
+    let committed = results.iter().all(|r| r.status == BatchStatus::Done);
+    if !committed {
+        for path in written {
+            let _ = std::fs::remove_file(path);
+        }
+        for result in &mut results {
+            if result.status == BatchStatus::Done {
+                result.status = BatchStatus::RolledBack;
+                result.path = None;
+            }
+        }
+    }
+    Ok(BatchReport {
+        committed,
+        results,
+        changes: Vec::new(),
+    })
+}
+
+/// Runs `op` for each id (duplicates once) and keeps going after a failure, so the report names
+/// every id that would fail.
+fn attempt(
+    tx: &Transaction<'_>,
+    ids: &[String],
+    mut op: impl FnMut(&Connection, &str) -> Result<Option<NoteMeta>, Message>,
+) -> Vec<Attempt> {
+    unique(ids)
+        .into_iter()
+        .map(|id| {
+            let before = get_meta(tx, &id).ok();
+            let outcome = op(tx, &id);
+            Attempt {
+                id,
+                before,
+                outcome,
+            }
+        })
+        .collect()
+}
+
+/// Commits and journals the batch when every id worked; otherwise rolls back and moves draft
+/// files back to where they were.
+fn finish(
+    tx: Transaction<'_>,
+    attempts: Vec<Attempt>,
+    journal: Option<(Label, Entry)>,
+) -> Result<BatchReport, Message> {
+    let committed = attempts.iter().all(|attempt| attempt.outcome.is_ok());
+    let ended = if committed {
+        journal
+            .map_or(Ok(()), |(label, entry)| history::record(&tx, label, &entry))
+            .and_then(|()| tx.commit().map_err(|err| err.to_string().into()))
+    } else {
+        tx.rollback().map_err(|err| err.to_string().into())
+    };
+    // A failed journal write or commit rolls the rows back too, so the files follow them.
+    if !committed || ended.is_err() {
+        for attempt in attempts.iter().rev() {
+            let (Some(before), Ok(Some(after))) = (&attempt.before, &attempt.outcome) else {
+                continue;
+            };
+            if before.storage == NoteStorage::Draft && before.file_path != after.file_path {
+                let _ = move_file(Path::new(&after.file_path), Path::new(&before.file_path));
+            }
+        }
+    }
+    ended?;
+
+    let mut results = Vec::with_capacity(attempts.len());
+    let mut changes = Vec::new();
+    for Attempt {
+        id,
+        before,
+        outcome,
+    } in attempts
+    {
+        let (status, error, meta) = match outcome {
+            Ok(after) if committed => {
+                if let Some(before) = before {
+                    changes.push(NoteChange {
+                        id: id.clone(),
+                        before: Some(before),
+                        after: after.clone(),
+                    });
+                }
+                (BatchStatus::Done, None, after)
+            }
+            Ok(_) => (BatchStatus::RolledBack, None, None),
+            Err(err) => (BatchStatus::Failed, Some(err.to_string()), None),
+        };
+        results.push(BatchResult {
+            id,
+            status,
+            error,
+            meta,
+            path: None,
+        });
+    }
+    Ok(BatchReport {
+        committed,
+        results,
+        changes,
+    })
+}
+
+fn done_befores(attempts: &[Attempt]) -> impl Iterator<Item = &NoteMeta> {
+    attempts
+        .iter()
+        .filter(|attempt| attempt.outcome.is_ok())
+        .filter_map(|attempt| attempt.before.as_ref())
+}
+
+fn unique(ids: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    ids.iter()
+        .filter(|id| seen.insert(id.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+
+    #[test]
+    fn dedupes() {
+        let ids = ["a", "b", "a"].map(String::from);
+        assert_eq!(unique(&ids), ["a", "b"]);
+    }
+
+    #[test]
+    fn failed_trash_rolls_back_the_whole_batch() {
+        let env = TestEnv::new();
+        let id = env.draft("Keep me");
+        let ids = [id.clone(), "missing".to_string()];
+
+        let report = trash_many(&env.conn, &env.paths, &ids).unwrap();
+
+        assert!(!report.committed);
+        assert_eq!(report.results[0].status, BatchStatus::RolledBack);
+        assert_eq!(report.results[1].status, BatchStatus::Failed);
+        let meta = get_meta(&env.conn, &id).unwrap();
+        assert!(!meta.is_trashed);
+        assert!(Path::new(&meta.file_path).exists());
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn a_batch_that_fails_to_commit_moves_the_files_back() {
+        let env = TestEnv::new();
+        let id = env.draft("Keep me");
+        let draft = get_meta(&env.conn, &id).unwrap().file_path;
+        env.conn
+            .execute_batch(
+                "CREATE TEMP TRIGGER fail_journal BEFORE INSERT ON history
+                 BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+            )
+            .unwrap();
+
+        assert!(trash_many(&env.conn, &env.paths, std::slice::from_ref(&id)).is_err());
+        let meta = get_meta(&env.conn, &id).unwrap();
+        assert!(!meta.is_trashed);
+        assert_eq!(meta.file_path, draft);
+        assert!(Path::new(&draft).exists());
+        assert_eq!(std::fs::read_dir(&env.paths.trash_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn pinning_past_the_cap_pins_none() {
+        let env = TestEnv::new();
+        let ids: Vec<String> = (0..=ordering::MAX_PINNED)
+            .map(|n| env.draft(&format!("Note {n}")))
+            .collect();
+
+        let report = pin_many(&env.conn, &ids, true).unwrap();
+
+        assert!(!report.committed);
+        assert_eq!(ordering::pinned_count(&env.conn).unwrap(), 0);
+        let within = pin_many(&env.conn, &ids[1..], true).unwrap();
+        assert!(within.committed);
+        assert_eq!(
+            ordering::pinned_count(&env.conn).unwrap(),
+            ordering::MAX_PINNED
+        );
+    }
+
+    #[test]
+    fn failed_export_removes_written_files_and_leaves_notes_alone() {
+        let env = TestEnv::new();
+        let id = env.draft("---\ntags: [work]\n---\nExported");
+        let before = get_meta(&env.conn, &id).unwrap();
+        let out = env.paths.app_data_dir.join("out");
+        std::fs::create_dir_all(&out).unwrap();
+
+        let report = export_many(&env.conn, std::slice::from_ref(&id), &out).unwrap();
+        assert!(report.committed);
+        let path = report.results[0].path.clone().unwrap();
+        let exported = std::fs::read_to_string(path).unwrap();
+        assert!(exported.contains("tags: [work]") && exported.ends_with("Exported"));
+        let after = get_meta(&env.conn, &id).unwrap();
+        assert_eq!(after.last_interaction, before.last_interaction);
+
+        std::fs::remove_dir_all(&out).unwrap();
+        std::fs::create_dir_all(&out).unwrap();
+        let report = export_many(&env.conn, &[id, "missing".to_string()], &out).unwrap();
+        assert!(!report.committed);
+        assert_eq!(report.results[0].status, BatchStatus::RolledBack);
+        assert_eq!(std::fs::read_dir(&out).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,116 @@
+//! Text from the quick-capture window: appended to the inbox note, or a new draft when no inbox is
+//! chosen or it is gone.
+
+use crate::app_state::AppPaths;
+use crate::i18n::Message;
+use crate::types::{NoteChange, NoteStorage};
+use rusqlite::Connection;
+
+use super::meta::get_meta;
+use super::write::{create_filled, get, save, write_draft};
+
+pub(super) fn capture(
+    conn: &Connection,
+    paths: &AppPaths,
+    inbox: Option<&str>,
+    text: &str,
+) -> Result<NoteChange, Message> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(Message::new("error.capture_empty"));
+    }
+
+    let inbox = inbox
+        .and_then(|id| get_meta(conn, id).ok())
+        .filter(|meta| !meta.is_trashed);
+    if let Some(before) = inbox {
+        let note = get(conn, &before.id)?;
+        let content = append(&note.content, text);
+        let after = match before.storage {
+            NoteStorage::Draft => write_draft(conn, &before.id, &content)?,
+            NoteStorage::Saved => save(conn, &before.id, &content)?,
+        };
+        return Ok(NoteChange::updated(before, after));
+    }
+
+    let after = create_filled(conn, paths, |conn, draft| {
+        write_draft(conn, &draft.id, text)
+    })?;
+    Ok(NoteChange::created(after))
+}
+
+/// Adds `text` as a new paragraph at the end of `content`.
+fn append(content: &str, text: &str) -> String {
+    let existing = content.trim_end();
+    if existing.is_empty() {
+        format!("{text}\n")
+    } else {
+        format!("{existing}\n\n{text}\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+    use crate::notes::write::save_as;
+
+    #[test]
+    fn appends_as_a_paragraph() {
+        assert_eq!(append("", "Call Anna"), "Call Anna\n");
+        assert_eq!(append("# Inbox\n\n", "Call Anna"), "# Inbox\n\nCall Anna\n");
+        assert_eq!(append("- milk", "- eggs"), "- milk\n\n- eggs\n");
+    }
+
+    #[test]
+    fn appends_to_a_draft_inbox() {
+        let env = TestEnv::new();
+        let inbox = env.draft("# Inbox\n");
+        let change = capture(&env.conn, &env.paths, Some(&inbox), "  Call Anna \n").unwrap();
+        assert_eq!(change.id, inbox);
+        assert!(change.before.is_some());
+        assert_eq!(
+            get(&env.conn, &inbox).unwrap().content,
+            "# Inbox\n\nCall Anna\n"
+        );
+    }
+
+    #[test]
+    fn appends_to_what_a_saved_inbox_has_on_disk() {
+        let env = TestEnv::new();
+        let inbox = env.draft("");
+        let path = env.paths.app_data_dir.join("inbox.md");
+        save_as(&env.conn, &env.paths, &inbox, &path, "# Inbox\n").unwrap();
+        // Edited elsewhere since the app last read it.
+        std::fs::write(&path, "# Inbox\n\n- milk\n").unwrap();
+
+        let change = capture(&env.conn, &env.paths, Some(&inbox), "- eggs").unwrap();
+        assert_eq!(change.after.unwrap().storage, NoteStorage::Saved);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# Inbox\n\n- milk\n\n- eggs\n"
+        );
+    }
+
+    #[test]
+    fn creates_a_draft_without_a_usable_inbox() {
+        let env = TestEnv::new();
+        let trashed = env.draft("# Old inbox\n");
+        crate::notes::trash(&env.conn, &env.paths, &trashed).unwrap();
+
+        for inbox in [None, Some("missing"), Some(trashed.as_str())] {
+            let change = capture(&env.conn, &env.paths, inbox, "Call Anna").unwrap();
+            assert!(change.before.is_none());
+            assert_ne!(change.id, trashed);
+            assert_eq!(get(&env.conn, &change.id).unwrap().content, "Call Anna");
+        }
+        assert_eq!(get(&env.conn, &trashed).unwrap().content, "# Old inbox\n");
+    }
+
+    #[test]
+    fn refuses_blank_text() {
+        let env = TestEnv::new();
+        let err = capture(&env.conn, &env.paths, None, " \n ").unwrap_err();
+        assert_eq!(err, Message::new("error.capture_empty"));
+    }
+}
@@ -0,0 +1,87 @@
+//! One-time backfill of the link and task indexes and expiry directives for notes written before
+//! they existed. Later writes keep them up to date on their own.
+
+use crate::i18n::Message;
+use crate::logs;
+use crate::scoped_file;
+use crate::types::NoteStorage;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+use super::directives;
+use super::files::read_file;
+use super::frontmatter;
+use super::links;
+use super::meta::get_meta;
+use super::scoped_updates::{apply_scoped_updates, get_bookmark};
+use super::tasks;
+
+const VERSION_KEY: &str = "content_index_version";
+/// Bump when what gets indexed changes, to re-read every note once.
+const VERSION: i64 = 2;
+
+pub(super) fn backfill(conn: &Connection) -> Result<(), Message> {
+    let current: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_state WHERE key = ?1 LIMIT 1",
+            params![VERSION_KEY],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    if current.and_then(|value| value.parse::<i64>().ok()) >= Some(VERSION) {
+        return Ok(());
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id FROM notes")
+        .map_err(|err| err.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+
+    for id in ids {
+        // An unreadable file (moved, no access) only misses out until it is opened again.
+        if let Err(err) = index_note(conn, &id) {
+            logs::error(
+                "content_index",
+                &format!("backfill skipped: id={id} err=\"{err}\""),
+            );
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO app_state(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![VERSION_KEY, VERSION.to_string()],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub(super) fn index_note(conn: &Connection, id: &str) -> Result<(), Message> {
+    let meta = get_meta(conn, id)?;
+    let content = if meta.storage == NoteStorage::Saved {
+        let bookmark = get_bookmark(conn, id)?;
+        let outcome = scoped_file::with_scoped_file(
+            Path::new(&meta.file_path),
+            bookmark.as_deref(),
+            read_file,
+        )?;
+        apply_scoped_updates(conn, id, outcome.refreshed_bookmark, outcome.resolved_path)?;
+        outcome.value.content
+    } else {
+        read_file(Path::new(&meta.file_path))?.content
+    };
+
+    let body = frontmatter::split(&content).map_or(content.as_str(), |(_, body)| body);
+    index_body(conn, id, body)
+}
+
+/// Indexes the links, tasks and expiry directives of a note's body (without front matter).
+pub(super) fn index_body(conn: &Connection, id: &str, body: &str) -> Result<(), Message> {
+    links::index(conn, id, body)?;
+    tasks::index(conn, id, body)?;
+    directives::index(conn, id, body)
+}
@@ -0,0 +1,214 @@
+//! The daily note: one draft per day, found by its date and created on demand. A new day links
+//! back to the most recent earlier daily note and can take its unchecked tasks along.
+
+use crate::app_state::AppPaths;
+use crate::i18n::Message;
+use crate::logs;
+use crate::types::OpenedNote;
+use chrono::{Datelike, Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::tasks;
+use super::time::weekday_key;
+use super::write::{create_filled, get, write_draft};
+
+const CARRY_TASKS_SETTING: &str = "daily_carry_tasks";
+
+/// The daily note for `date` (`YYYY-MM-DD`, today when `None`), created if there is none.
+pub(super) fn open(
+    conn: &Connection,
+    paths: &AppPaths,
+    date: Option<&str>,
+) -> Result<OpenedNote, Message> {
+    let date = match date {
+        Some(raw) => NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d")
+            .map_err(|_| Message::new("error.daily_date").arg("date", raw))?,
+        None => Local::now().date_naive(),
+    };
+    let key = date.format("%Y-%m-%d").to_string();
+
+    let existing: Option<String> = conn
+        .query_row(
+            r#"
+SELECT id FROM notes
+WHERE daily_date = ?1 AND is_trashed = 0
+ORDER BY created_at ASC
+LIMIT 1
+"#,
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    if let Some(id) = existing {
+        return Ok(OpenedNote {
+            note: get(conn, &id)?,
+            created: false,
+        });
+    }
+
+    let content = initial_content(conn, date, &key)?;
+    // All or nothing: a note left tagged with the date but never written would be found as the
+    // day's note from then on.
+    let meta = create_filled(conn, paths, |conn, draft| {
+        conn.execute(
+            "UPDATE notes SET daily_date = ?1 WHERE id = ?2",
+            params![key, draft.id],
+        )
+        .map_err(|err| err.to_string())?;
+        write_draft(conn, &draft.id, &content)
+    })?;
+    Ok(OpenedNote {
+        note: get(conn, &meta.id)?,
+        created: true,
+    })
+}
+
+fn initial_content(conn: &Connection, date: NaiveDate, key: &str) -> Result<String, Message> {
+    let title = Message::new("daily.title")
+        .arg("date", key)
+        .arg("weekday", Message::new(weekday_key(date.weekday())));
+    let mut content = format!("# {title}\n");
+
+    let previous: Option<(String, String)> = conn
+        .query_row(
+            r#"
+SELECT id, daily_date FROM notes
+WHERE daily_date < ?1 AND is_trashed = 0
+ORDER BY daily_date DESC, created_at ASC
+LIMIT 1
+"#,
+            params![key],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let Some((previous_id, previous_date)) = previous else {
+        return Ok(content);
+    };
+    let link = format!("[[{previous_id}|{previous_date}]]");
+    content.push_str(&format!(
+        "\n{}\n",
+        Message::new("daily.previous").arg("link", link)
+    ));
+
+    if !carry_tasks(conn)? {
+        return Ok(content);
+    }
+    // A previous note that can't be read (a saved file that moved) only costs the carry-over.
+    match get(conn, &previous_id) {
+        Ok(previous) => {
+            let open = tasks::open_items(&previous.content);
+            if !open.is_empty() {
+                content.push('\n');
+                for item in open {
+                    content.push_str(&item);
+                    content.push('\n');
+                }
+            }
+        }
+        Err(err) => logs::error(
+            "daily",
+            &format!("carry-over skipped: id={previous_id} err=\"{err}\""),
+        ),
+    }
+    Ok(content)
+}
+
+fn carry_tasks(conn: &Connection) -> Result<bool, Message> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![CARRY_TASKS_SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    Ok(value.as_deref() != Some("false"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+
+    #[test]
+    fn opening_a_day_twice_finds_the_same_note() {
+        let env = TestEnv::new();
+        let first = open(&env.conn, &env.paths, Some("2026-03-02")).unwrap();
+        assert!(first.created);
+        assert!(first.note.content.starts_with("# 2026-03-02 Monday\n"));
+
+        let again = open(&env.conn, &env.paths, Some(" 2026-03-02 ")).unwrap();
+        assert!(!again.created);
+        assert_eq!(again.note.meta.id, first.note.meta.id);
+    }
+
+    #[test]
+    fn a_new_day_links_back_and_carries_open_tasks() {
+        let env = TestEnv::new();
+        let monday = open(&env.conn, &env.paths, Some("2026-03-02")).unwrap();
+        let id = monday.note.meta.id;
+        write_draft(&env.conn, &id, "# Monday\n- [x] done\n- [ ] call **Ana**\n").unwrap();
+
+        let wednesday = open(&env.conn, &env.paths, Some("2026-03-04")).unwrap();
+        assert_eq!(
+            wednesday.note.content,
+            format!(
+                "# 2026-03-04 Wednesday\n\nPrevious: [[{id}|2026-03-02]]\n\n- [ ] call **Ana**\n"
+            )
+        );
+    }
+
+    #[test]
+    fn carrying_tasks_can_be_turned_off() {
+        let env = TestEnv::new();
+        let monday = open(&env.conn, &env.paths, Some("2026-03-02")).unwrap();
+        let id = monday.note.meta.id;
+        write_draft(&env.conn, &id, "- [ ] call Ana\n").unwrap();
+        env.conn
+            .execute(
+                "INSERT INTO settings (key, value) VALUES (?1, 'false')",
+                params![CARRY_TASKS_SETTING],
+            )
+            .unwrap();
+
+        let tuesday = open(&env.conn, &env.paths, Some("2026-03-03")).unwrap();
+        assert_eq!(
+            tuesday.note.content,
+            format!("# 2026-03-03 Tuesday\n\nPrevious: [[{id}|2026-03-02]]\n")
+        );
+    }
+
+    #[test]
+    fn a_failed_write_leaves_no_note_for_the_day() {
+        let env = TestEnv::new();
+        env.conn
+            .execute_batch(
+                "CREATE TEMP TRIGGER fail_write BEFORE UPDATE ON notes
+                 WHEN OLD.daily_date IS NOT NULL
+                 BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+            )
+            .unwrap();
+
+        assert!(open(&env.conn, &env.paths, Some("2026-03-02")).is_err());
+        let count: i64 = env
+            .conn
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(std::fs::read_dir(&env.paths.drafts_dir).unwrap().count(), 0);
+
+        env.conn.execute_batch("DROP TRIGGER fail_write").unwrap();
+        let opened = open(&env.conn, &env.paths, Some("2026-03-02")).unwrap();
+        assert!(opened.created);
+        assert!(opened.note.content.starts_with("# 2026-03-02 Monday\n"));
+    }
+
+    #[test]
+    fn refuses_malformed_dates() {
+        let env = TestEnv::new();
+        let err = open(&env.conn, &env.paths, Some("March 2")).unwrap_err();
+        assert_eq!(err, Message::new("error.daily_date").arg("date", "March 2"));
+    }
+}
@@ -1,93 +1,137 @@
+//! Title and preview for the note list, read from the rendered markdown rather than raw lines:
+//! headings and paragraphs give text, links give their label, and code blocks, tables and
+//! HTML never do.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::frontmatter;
+
+pub(super) const MAX_TITLE_GRAPHEMES: usize = 80;
+const MAX_PREVIEW_GRAPHEMES: usize = 140;
+
 pub(super) fn derive_title_preview(content: &str) -> (String, String) {
-    let lines: Vec<&str> = content
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .collect();
-
-    let title_line = lines.iter().find(|l| !clean(l).is_empty()).copied().unwrap_or("");
-    let title_raw = clean(title_line);
-    let title = if title_raw.is_empty() {
-        "New note".to_string()
-    } else {
-        truncate(title_raw, 80)
-    };
+    let content = frontmatter::split(content).map_or(content, |(_, body)| body);
+    let mut segments = text_segments(content, 2).into_iter();
 
-    let preview_line = lines
-        .iter()
-        .find(|l| **l != title_line && !clean(l).is_empty())
-        .copied()
-        .unwrap_or(title_line);
-    let preview = truncate(compact_whitespace(clean(preview_line)), 140);
-    (title, preview)
+    let Some(title) = segments.next() else {
+        return (String::new(), String::new());
+    };
+    let preview = segments.next().unwrap_or_else(|| title.clone());
+    (
+        truncate(&title, MAX_TITLE_GRAPHEMES),
+        truncate(&preview, MAX_PREVIEW_GRAPHEMES),
+    )
 }
 
-fn sanitize_heading(line: &str) -> String {
-    let trimmed = line.trim_start();
-    if let Some(rest) = trimmed.strip_prefix('\\') {
-        if rest.starts_with(['#', '-', '>', '*']) {
-            return rest.trim().to_string();
+/// The first `limit` lines of readable text. A paragraph yields one segment per source line so
+/// a plain note's first line stays its title.
+fn text_segments(content: &str, limit: usize) -> Vec<String> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_WIKILINKS;
+
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut skipped = 0usize;
+    for event in Parser::new_ext(content, options) {
+        match event {
+            Event::Start(tag) if skips(&tag) => skipped += 1,
+            Event::End(tag) if skip_ends(tag) => skipped = skipped.saturating_sub(1),
+            _ if skipped > 0 => {}
+            Event::Text(text) | Event::Code(text) | Event::InlineMath(text) => {
+                current.push_str(&text)
+            }
+            Event::SoftBreak | Event::HardBreak | Event::Rule => flush(&mut current, &mut segments),
+            Event::Start(tag) if starts_block(&tag) => flush(&mut current, &mut segments),
+            Event::End(tag) if ends_block(tag) => flush(&mut current, &mut segments),
+            _ => {}
+        }
+        if segments.len() >= limit {
+            break;
         }
     }
-    trimmed
-        .trim_start_matches(&['#', '-', '>', '*'][..])
-        .trim()
-        .to_string()
+    flush(&mut current, &mut segments);
+    segments.truncate(limit);
+    segments
 }
 
-fn decode_entities(s: &str) -> String {
-    s.replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'")
+/// Containers whose content never reads as prose.
+fn skips(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::CodeBlock(_)
+            | Tag::HtmlBlock
+            | Tag::Table(_)
+            | Tag::Image { .. }
+            | Tag::FootnoteDefinition(_)
+            | Tag::MetadataBlock(_)
+    )
 }
 
-fn strip_escapes(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars().peekable();
-    while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            if let Some(&next) = chars.peek() {
-                if "\\`*_{}[]()#+-.!>~|".contains(next) {
-                    result.push(next);
-                    chars.next();
-                    continue;
-                }
-            }
-        }
-        result.push(ch);
-    }
-    result
+fn skip_ends(tag: TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::CodeBlock
+            | TagEnd::HtmlBlock
+            | TagEnd::Table
+            | TagEnd::Image
+            | TagEnd::FootnoteDefinition
+            | TagEnd::MetadataBlock(_)
+    )
 }
 
-fn clean(line: &str) -> String {
-    decode_entities(&strip_escapes(&sanitize_heading(line)))
-        .trim()
-        .to_string()
+fn starts_block(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Paragraph
+            | Tag::Heading { .. }
+            | Tag::BlockQuote(_)
+            | Tag::List(_)
+            | Tag::Item
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+    )
 }
 
-fn compact_whitespace(s: String) -> String {
-    s.split_whitespace().collect::<Vec<_>>().join(" ")
+fn ends_block(tag: TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::BlockQuote(_)
+            | TagEnd::List(_)
+            | TagEnd::Item
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition
+    )
 }
 
-fn truncate(s: String, max_len: usize) -> String {
-    if s.len() <= max_len {
-        return s;
-    }
-    let mut out = s;
-    let mut end = max_len.min(out.len());
-    while end > 0 && !out.is_char_boundary(end) {
-        end -= 1;
+/// Ends the current line; whitespace (including decoded `&nbsp;`) collapses to single spaces.
+fn flush(current: &mut String, segments: &mut Vec<String>) {
+    let line = current.split_whitespace().collect::<Vec<_>>().join(" ");
+    current.clear();
+    if !line.is_empty() {
+        segments.push(line);
     }
-    out.truncate(end);
-    out
+}
+
+/// Cuts at a grapheme boundary so emoji sequences and combining marks stay whole.
+pub(super) fn truncate(s: &str, max_graphemes: usize) -> String {
+    s.graphemes(true)
+        .take(max_graphemes)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+    use std::path::Path;
 
     #[test]
     fn heading_extraction() {
@@ -97,15 +141,15 @@ mod tests {
     }
 
     #[test]
-    fn empty_content_defaults() {
+    fn empty_content_has_no_title() {
         let (title, _) = derive_title_preview("\n\n");
-        assert_eq!(title, "New note");
+        assert_eq!(title, "");
     }
 
     #[test]
-    fn whitespace_entity_defaults() {
+    fn whitespace_entity_has_no_title() {
         let (title, _) = derive_title_preview("&nbsp;");
-        assert_eq!(title, "New note");
+        assert_eq!(title, "");
     }
 
     #[test]
@@ -126,6 +170,13 @@ mod tests {
         assert_eq!(title, "Actual title");
     }
 
+    #[test]
+    fn ignores_front_matter() {
+        let (title, preview) = derive_title_preview("---\ntags: [a]\n---\nTitle\nBody");
+        assert_eq!(title, "Title");
+        assert_eq!(preview, "Body");
+    }
+
     #[test]
     fn truncation() {
         let long = "a".repeat(200);
@@ -147,7 +198,50 @@ mod tests {
         let (title, preview) = derive_title_preview(&line);
         assert!(!title.is_empty());
         assert!(!preview.is_empty());
-        assert!(title.len() <= 80);
-        assert!(preview.len() <= 140);
+        assert!(title.graphemes(true).count() <= 80);
+        assert!(preview.graphemes(true).count() <= 140);
+    }
+
+    #[test]
+    fn truncates_by_grapheme() {
+        let family = "👨‍👩‍👧".repeat(100);
+        let (title, _) = derive_title_preview(&family);
+        assert_eq!(title, "👨‍👩‍👧".repeat(80));
+    }
+
+    /// Each `tests/golden/derive/<case>.md` is paired with `<case>.out` holding the expected
+    /// title and preview on two lines. `AUGENBLICK_UPDATE_GOLDEN=1` rewrites the `.out` files.
+    #[test]
+    fn golden_files() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/derive");
+        let update = std::env::var_os("AUGENBLICK_UPDATE_GOLDEN").is_some();
+        let mut cases: Vec<_> = fs::read_dir(&dir)
+            .expect("golden dir")
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        cases.sort();
+        assert!(!cases.is_empty());
+
+        let mut failures = Vec::new();
+        for case in cases {
+            let input = fs::read_to_string(&case).expect("golden input");
+            let (title, preview) = derive_title_preview(&input);
+            let actual = format!("{title}\n{preview}\n");
+            let expected_path = case.with_extension("out");
+            if update {
+                fs::write(&expected_path, &actual).expect("golden output");
+                continue;
+            }
+            let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+            if actual != expected {
+                failures.push(format!(
+                    "{}:\n  expected {expected:?}\n  actual   {actual:?}",
+                    case.display()
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
     }
 }
@@ -0,0 +1,369 @@
+//! Expiry directives written into a note: `expires: friday`, `keep until 2026-11-01`,
+//! `!expire in 2h`, and in German `läuft ab: freitag`, `behalten bis 1.11.2026`,
+//! `!ablauf in 2 stunden`. The first one outside code blocks sets the note's deadline.
+
+use crate::i18n::Message;
+use crate::types::ExpiryDirective;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Phrases that start a directive. Matched case-insensitively at a word start; the ones without
+/// `!` or `:` read naturally enough not to show up in ordinary prose by accident.
+const TRIGGERS: &[&str] = &[
+    "!expire",
+    "expires:",
+    "expire:",
+    "keep until",
+    "!ablauf",
+    "läuft ab:",
+    "ablauf:",
+    "behalten bis",
+];
+
+/// Stores the directive found in `body`. A relative one (`in 2h`, `friday`) keeps the deadline
+/// from when it was first written as long as the phrase stays the same, so later edits don't
+/// push it out.
+pub(super) fn index(conn: &Connection, id: &str, body: &str) -> Result<(), Message> {
+    let found = find(body, Local::now());
+    let stored: Option<(Option<String>, Option<i64>)> = conn
+        .query_row(
+            "SELECT expiry_directive, directive_expires_at FROM notes WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let (phrase, expires_at) = match (found, stored) {
+        (Some(found), Some((Some(phrase), Some(expires_at)))) if found.phrase == phrase => {
+            (Some(phrase), Some(expires_at))
+        }
+        (Some(found), _) => (Some(found.phrase), Some(found.expires_at)),
+        (None, _) => (None, None),
+    };
+    conn.execute(
+        "UPDATE notes SET expiry_directive = ?1, directive_expires_at = ?2 WHERE id = ?3",
+        params![phrase, expires_at, id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// The first directive in `body` that parses, skipping fenced code blocks.
+pub(super) fn find(body: &str, now: DateTime<Local>) -> Option<ExpiryDirective> {
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let lower = line.to_lowercase();
+        for trigger in TRIGGERS {
+            let mut from = 0;
+            while let Some(offset) = lower[from..].find(trigger) {
+                let start = from + offset;
+                from = start + trigger.len();
+                if lower[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_alphanumeric)
+                {
+                    continue;
+                }
+                let rest = &lower[from..];
+                if let Some((len, at)) = parse_when(rest, now) {
+                    let skipped = rest.len() - rest.trim_start().len();
+                    return Some(ExpiryDirective {
+                        phrase: lower[start..from + skipped + len].to_string(),
+                        expires_at: at.timestamp_millis(),
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Parses the time after a trigger; returns how many bytes of `text` (after leading whitespace)
+/// it used and the deadline. Days count until their end.
+fn parse_when(text: &str, now: DateTime<Local>) -> Option<(usize, DateTime<Local>)> {
+    let text = text.trim_start();
+    let words: Vec<(usize, &str)> = words(text).take(3).collect();
+    let end_of = |(start, word): (usize, &str)| start + word.len();
+    let (_, first) = *words.first()?;
+    let today = now.date_naive();
+
+    if first == "in" {
+        let amount_word = *words.get(1)?;
+        // `2h` or `2 hours`.
+        let (amount, unit, used) = match split_amount(amount_word.1)? {
+            (amount, "") => {
+                let unit_word = *words.get(2)?;
+                (amount, unit_word.1, end_of(unit_word))
+            }
+            (amount, unit) => (amount, unit, end_of(amount_word)),
+        };
+        // Amounts too large for a date are no directive rather than a panic.
+        let minutes = amount.checked_mul(unit_minutes(unit)?)?;
+        return Some((
+            used,
+            now.checked_add_signed(Duration::try_minutes(minutes)?)?,
+        ));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+        if let Some(&(start, word)) = words.get(1) {
+            if let Ok(time) = NaiveTime::parse_from_str(word, "%H:%M") {
+                let at = Local.from_local_datetime(&date.and_time(time)).earliest()?;
+                return Some((start + word.len(), at));
+            }
+        }
+        return Some((first.len(), end_of_day(date)?));
+    }
+    if let Some(date) = german_date(first, today) {
+        return Some((first.len(), end_of_day(date)?));
+    }
+
+    let day = match first {
+        "today" | "heute" => Some((first.len(), today)),
+        "tomorrow" | "morgen" => Some((first.len(), today.succ_opt()?)),
+        "next" | "nächste" | "nächsten" | "nächster" => {
+            let second = *words.get(1)?;
+            if matches!(second.1, "week" | "woche") {
+                Some((end_of(second), today.checked_add_signed(Duration::days(7))?))
+            } else {
+                let weekday = weekday(second.1)?;
+                Some((end_of(second), next_weekday(today, weekday, false)?))
+            }
+        }
+        word => Some((first.len(), next_weekday(today, weekday(word)?, true)?)),
+    }?;
+    Some((day.0, end_of_day(day.1)?))
+}
+
+/// Words with their byte offsets. A word ends at whitespace or `,;)!?`; a trailing dot is
+/// dropped unless it belongs to a German date.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let rest = &text[offset..];
+        let start = offset + (rest.len() - rest.trim_start().len());
+        let rest = &text[start..];
+        if rest.is_empty() {
+            return None;
+        }
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ')' | '!' | '?'))
+            .unwrap_or(rest.len());
+        offset = start + len.max(1);
+        let word = rest[..len].trim_end_matches('.');
+        // Keep the trailing dot of German dates like `1.11.`.
+        let word = if german_date_prefix(&rest[..len]) {
+            &rest[..len]
+        } else {
+            word
+        };
+        Some((start, word))
+    })
+    .filter(|(_, word)| !word.is_empty())
+}
+
+fn german_date_prefix(word: &str) -> bool {
+    word.chars().filter(|c| *c == '.').count() >= 2
+        && word.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// `1.11.2026`, `01.11.26` or `1.11.` (the next such day, today included).
+fn german_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let mut parts = word.split('.');
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let year = match parts.next()? {
+        "" => None,
+        year if year.len() == 2 => Some(2000 + year.parse::<i32>().ok()?),
+        year if year.len() == 4 => Some(year.parse::<i32>().ok()?),
+        _ => return None,
+    };
+    if parts.next().is_some_and(|rest| !rest.is_empty()) {
+        return None;
+    }
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year, month, day),
+        None => {
+            let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            if this_year >= today {
+                Some(this_year)
+            } else {
+                NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+            }
+        }
+    }
+}
+
+fn split_amount(word: &str) -> Option<(i64, &str)> {
+    let split = word
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(word.len());
+    let amount = word[..split].parse::<i64>().ok().filter(|n| *n > 0)?;
+    Some((amount, &word[split..]))
+}
+
+fn unit_minutes(unit: &str) -> Option<i64> {
+    Some(match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" | "minuten" => 1,
+        "h" | "hr" | "hrs" | "hour" | "hours" | "std" | "stunde" | "stunden" => 60,
+        "d" | "day" | "days" | "t" | "tag" | "tage" | "tagen" => 1440,
+        "w" | "wk" | "week" | "weeks" | "woche" | "wochen" => 10_080,
+        _ => return None,
+    })
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    Some(match word {
+        "monday" | "mon" | "montag" => Weekday::Mon,
+        "tuesday" | "tue" | "dienstag" => Weekday::Tue,
+        "wednesday" | "wed" | "mittwoch" => Weekday::Wed,
+        "thursday" | "thu" | "donnerstag" => Weekday::Thu,
+        "friday" | "fri" | "freitag" => Weekday::Fri,
+        "saturday" | "sat" | "samstag" | "sonnabend" => Weekday::Sat,
+        "sunday" | "sun" | "sonntag" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// The next `weekday` from `today`; today itself only counts when `include_today`.
+fn next_weekday(today: NaiveDate, weekday: Weekday, include_today: bool) -> Option<NaiveDate> {
+    let ahead = (weekday.num_days_from_monday() as i64
+        - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    let ahead = if ahead == 0 && !include_today {
+        7
+    } else {
+        ahead
+    };
+    today.checked_add_signed(Duration::days(ahead))
+}
+
+/// Midnight at the start of the following day.
+fn end_of_day(date: NaiveDate) -> Option<DateTime<Local>> {
+    let next = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+    Local.from_local_datetime(&next).earliest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        // Monday
+        Local.with_ymd_and_hms(2026, 10, 19, 10, 30, 0).unwrap()
+    }
+
+    fn day_end(y: i32, m: u32, d: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(y, m, d, 0, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+            + 86_400_000
+    }
+
+    fn directive(body: &str) -> Option<(String, i64)> {
+        find(body, now()).map(|d| (d.phrase, d.expires_at))
+    }
+
+    #[test]
+    fn understands_english_directives() {
+        assert_eq!(
+            directive("Ship it\nExpires: Friday."),
+            Some(("expires: friday".into(), day_end(2026, 10, 23)))
+        );
+        assert_eq!(
+            directive("- keep until 2026-11-01, then archive"),
+            Some(("keep until 2026-11-01".into(), day_end(2026, 11, 1)))
+        );
+        assert_eq!(
+            directive("!expire in 2h"),
+            Some((
+                "!expire in 2h".into(),
+                now().timestamp_millis() + 2 * 3_600_000
+            ))
+        );
+        assert_eq!(
+            directive("expires: in 3 days"),
+            Some((
+                "expires: in 3 days".into(),
+                now().timestamp_millis() + 3 * 86_400_000
+            ))
+        );
+        assert_eq!(
+            directive("expires: next monday").map(|d| d.1),
+            Some(day_end(2026, 10, 26))
+        );
+        assert_eq!(
+            directive("expires: monday").map(|d| d.1),
+            Some(day_end(2026, 10, 19))
+        );
+        assert_eq!(
+            directive("keep until 2026-11-01 18:00").map(|d| d.1),
+            Some(
+                Local
+                    .with_ymd_and_hms(2026, 11, 1, 18, 0, 0)
+                    .unwrap()
+                    .timestamp_millis()
+            )
+        );
+    }
+
+    #[test]
+    fn understands_german_directives() {
+        assert_eq!(
+            directive("Läuft ab: Freitag"),
+            Some(("läuft ab: freitag".into(), day_end(2026, 10, 23)))
+        );
+        assert_eq!(
+            directive("behalten bis 1.11.2026"),
+            Some(("behalten bis 1.11.2026".into(), day_end(2026, 11, 1)))
+        );
+        assert_eq!(
+            directive("behalten bis 3.1.").map(|d| d.1),
+            Some(day_end(2027, 1, 3))
+        );
+        assert_eq!(
+            directive("!ablauf in 2 stunden").map(|d| d.1),
+            Some(now().timestamp_millis() + 2 * 3_600_000)
+        );
+        assert_eq!(
+            directive("ablauf: nächste woche").map(|d| d.1),
+            Some(day_end(2026, 10, 26))
+        );
+    }
+
+    #[test]
+    fn ignores_prose_code_and_nonsense() {
+        assert_eq!(directive("The license expires friday"), None);
+        assert_eq!(directive("```\nexpires: friday\n```"), None);
+        assert_eq!(directive("`x`\nreexpires: friday"), None);
+        assert_eq!(directive("expires: fridays"), None);
+        assert_eq!(directive("expires: soon"), None);
+        assert_eq!(directive("!expire in 2 fortnights"), None);
+        assert_eq!(directive("!expire in -2h"), None);
+        assert_eq!(directive("expires: in 0 days"), None);
+    }
+
+    #[test]
+    fn huge_amounts_are_no_directive() {
+        // Overflows the minutes-to-duration conversion.
+        assert_eq!(directive("!expire in 9999999999999999 weeks"), None);
+        assert_eq!(directive("!expire in 9223372036854775807m"), None);
+        // A valid duration that runs past the last representable date.
+        assert_eq!(directive("!expire in 200000000000 minutes"), None);
+        assert_eq!(directive("expires: 262143-12-31"), None);
+        assert_eq!(
+            directive("expires: soon\n!expire in 1d").map(|d| d.0),
+            Some("!expire in 1d".into())
+        );
+    }
+}
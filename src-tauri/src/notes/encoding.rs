@@ -0,0 +1,284 @@
+use crate::i18n::Message;
+use crate::types::{LineEnding, NoteMeta, TextEncoding};
+use encoding_rs::{UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct TextFormat {
+    pub encoding: TextEncoding,
+    pub has_bom: bool,
+    pub line_ending: LineEnding,
+}
+
+impl TextFormat {
+    pub(super) const UTF8_LF: TextFormat = TextFormat {
+        encoding: TextEncoding::Utf8,
+        has_bom: false,
+        line_ending: LineEnding::Lf,
+    };
+
+    pub(super) fn of(meta: &NoteMeta) -> Self {
+        TextFormat {
+            encoding: meta.encoding,
+            has_bom: meta.has_bom,
+            line_ending: meta.line_ending,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct DecodedText {
+    /// Editor-facing content, always with `\n` line breaks.
+    pub content: String,
+    /// `None` when the bytes are plain ASCII and could be any of the supported encodings.
+    pub encoding: Option<TextEncoding>,
+    pub has_bom: bool,
+    /// `None` when the text has no line breaks at all.
+    pub line_ending: Option<LineEnding>,
+}
+
+impl DecodedText {
+    /// Resolves the detected format, keeping `fallback` for anything the bytes left ambiguous.
+    pub(super) fn format_or(&self, fallback: TextFormat) -> TextFormat {
+        TextFormat {
+            encoding: self.encoding.unwrap_or(fallback.encoding),
+            has_bom: self.has_bom,
+            line_ending: self.line_ending.unwrap_or(fallback.line_ending),
+        }
+    }
+}
+
+pub(super) fn decode(bytes: &[u8]) -> DecodedText {
+    let (encoding, has_bom, body) = if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        // A BOM doesn't make the rest UTF-8. Invalid bytes are read like a file without one
+        // instead of being replaced, which the next save would write back.
+        let encoding = match std::str::from_utf8(rest) {
+            Ok(_) => TextEncoding::Utf8,
+            Err(_) => TextEncoding::Windows1252,
+        };
+        (Some(encoding), true, rest)
+    } else if let Some(rest) = bytes.strip_prefix(UTF16LE_BOM) {
+        (Some(TextEncoding::Utf16Le), true, rest)
+    } else if let Some(rest) = bytes.strip_prefix(UTF16BE_BOM) {
+        (Some(TextEncoding::Utf16Be), true, rest)
+    } else {
+        (sniff_encoding(bytes), false, bytes)
+    };
+
+    let text = match encoding {
+        Some(TextEncoding::Utf16Le) => UTF_16LE.decode_without_bom_handling(body).0,
+        Some(TextEncoding::Utf16Be) => UTF_16BE.decode_without_bom_handling(body).0,
+        Some(TextEncoding::Windows1252) => WINDOWS_1252.decode_without_bom_handling(body).0,
+        Some(TextEncoding::Utf8) | None => String::from_utf8_lossy(body),
+    };
+
+    DecodedText {
+        line_ending: detect_line_ending(&text),
+        content: normalize_line_endings(&text),
+        encoding,
+        has_bom,
+    }
+}
+
+pub(super) fn encode(content: &str, format: TextFormat) -> Result<Vec<u8>, Message> {
+    let normalized = normalize_line_endings(content);
+    let text = match format.line_ending {
+        LineEnding::Lf => normalized,
+        LineEnding::Crlf => normalized.replace('\n', "\r\n"),
+        LineEnding::Cr => normalized.replace('\n', "\r"),
+    };
+
+    let mut out = Vec::with_capacity(text.len() + 3);
+    match format.encoding {
+        TextEncoding::Utf8 => {
+            if format.has_bom {
+                out.extend_from_slice(UTF8_BOM);
+            }
+            out.extend_from_slice(text.as_bytes());
+        }
+        TextEncoding::Utf16Le => {
+            if format.has_bom {
+                out.extend_from_slice(UTF16LE_BOM);
+            }
+            for unit in text.encode_utf16() {
+                out.extend_from_slice(&unit.to_le_bytes());
+            }
+        }
+        TextEncoding::Utf16Be => {
+            if format.has_bom {
+                out.extend_from_slice(UTF16BE_BOM);
+            }
+            for unit in text.encode_utf16() {
+                out.extend_from_slice(&unit.to_be_bytes());
+            }
+        }
+        TextEncoding::Windows1252 => {
+            let (bytes, _, had_unmappable) = WINDOWS_1252.encode(&text);
+            if had_unmappable {
+                return Err(Message::new("error.windows_1252"));
+            }
+            // Only files that had a UTF-8 BOM in front of Windows-1252 text get one back.
+            if format.has_bom {
+                out.extend_from_slice(UTF8_BOM);
+            }
+            out.extend_from_slice(&bytes);
+        }
+    }
+    Ok(out)
+}
+
+fn sniff_encoding(bytes: &[u8]) -> Option<TextEncoding> {
+    // UTF-16 without a BOM has to be checked first: its zero bytes are valid ASCII.
+    if let Some(encoding) = sniff_utf16(bytes) {
+        return Some(encoding);
+    }
+    if bytes.is_ascii() {
+        return None;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return Some(TextEncoding::Utf8);
+    }
+    Some(TextEncoding::Windows1252)
+}
+
+fn sniff_utf16(bytes: &[u8]) -> Option<TextEncoding> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    // Mostly-Latin text in UTF-16 has a zero in every other byte.
+    let units = bytes.len() / 2;
+    let even_zeros = bytes.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_zeros = bytes.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+    if odd_zeros * 2 > units && even_zeros * 10 < units {
+        Some(TextEncoding::Utf16Le)
+    } else if even_zeros * 2 > units && odd_zeros * 10 < units {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn detect_line_ending(text: &str) -> Option<LineEnding> {
+    let bytes = text.as_bytes();
+    let (mut crlf, mut lf, mut cr) = (0usize, 0usize, 0usize);
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\r' if bytes.get(idx + 1) == Some(&b'\n') => {
+                crlf += 1;
+                idx += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        idx += 1;
+    }
+
+    if crlf == 0 && lf == 0 && cr == 0 {
+        None
+    } else if crlf >= lf && crlf >= cr {
+        Some(LineEnding::Crlf)
+    } else if lf >= cr {
+        Some(LineEnding::Lf)
+    } else {
+        Some(LineEnding::Cr)
+    }
+}
+
+fn normalize_line_endings(text: &str) -> String {
+    if !text.contains('\r') {
+        return text.to_string();
+    }
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_ascii_is_ambiguous() {
+        let decoded = decode(b"Hello");
+        assert_eq!(decoded.content, "Hello");
+        assert_eq!(decoded.encoding, None);
+        assert_eq!(decoded.line_ending, None);
+        assert!(!decoded.has_bom);
+    }
+
+    #[test]
+    fn crlf_with_bom_round_trips() {
+        let bytes = b"\xEF\xBB\xBF# Title\r\nBody\r\n";
+        let decoded = decode(bytes);
+        assert_eq!(decoded.content, "# Title\nBody\n");
+        let format = decoded.format_or(TextFormat::UTF8_LF);
+        assert_eq!(format.encoding, TextEncoding::Utf8);
+        assert!(format.has_bom);
+        assert_eq!(format.line_ending, LineEnding::Crlf);
+        assert_eq!(encode(&decoded.content, format).unwrap(), bytes.to_vec());
+    }
+
+    #[test]
+    fn latin1_falls_back_to_windows_1252() {
+        let decoded = decode(b"Gr\xFC\xDFe\n");
+        assert_eq!(decoded.content, "Grüße\n");
+        assert_eq!(decoded.encoding, Some(TextEncoding::Windows1252));
+        let format = decoded.format_or(TextFormat::UTF8_LF);
+        assert_eq!(
+            encode("Grüße\n", format).unwrap(),
+            b"Gr\xFC\xDFe\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_after_a_bom_round_trips() {
+        let bytes = b"\xEF\xBB\xBFGr\xFC\xDFe\r\n";
+        let decoded = decode(bytes);
+        assert_eq!(decoded.content, "Grüße\n");
+        assert_eq!(decoded.encoding, Some(TextEncoding::Windows1252));
+        assert!(decoded.has_bom);
+        let format = decoded.format_or(TextFormat::UTF8_LF);
+        assert_eq!(encode(&decoded.content, format).unwrap(), bytes.to_vec());
+    }
+
+    #[test]
+    fn windows_1252_rejects_unmappable_text() {
+        let format = TextFormat {
+            encoding: TextEncoding::Windows1252,
+            ..TextFormat::UTF8_LF
+        };
+        assert!(encode("日本", format).is_err());
+    }
+
+    #[test]
+    fn utf16_le_with_and_without_bom() {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "Hi\r\nÄ".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        let decoded = decode(&bytes);
+        assert_eq!(decoded.content, "Hi\nÄ");
+        let format = decoded.format_or(TextFormat::UTF8_LF);
+        assert_eq!(format.encoding, TextEncoding::Utf16Le);
+        assert_eq!(encode(&decoded.content, format).unwrap(), bytes);
+
+        let without_bom = decode(&bytes[2..]);
+        assert_eq!(without_bom.encoding, Some(TextEncoding::Utf16Le));
+        assert!(!without_bom.has_bom);
+    }
+
+    #[test]
+    fn ambiguous_bytes_keep_previous_format() {
+        let previous = TextFormat {
+            encoding: TextEncoding::Windows1252,
+            has_bom: false,
+            line_ending: LineEnding::Crlf,
+        };
+        let format = decode(b"plain").format_or(previous);
+        assert_eq!(format, previous);
+    }
+}
@@ -0,0 +1,147 @@
+//! Export into an Obsidian vault: one markdown file per note, named after its title, with the
+//! app's metadata in front matter.
+
+use crate::i18n::{self, Message};
+use crate::scoped_file;
+use crate::types::ObsidianExportItem;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::encoding::TextFormat;
+use super::files::write_file;
+use super::frontmatter::{self, FrontMatter};
+use super::write::read_stored;
+
+/// Characters Obsidian won't allow in file names or that break `[[wikilinks]]`.
+const UNSAFE_CHARS: &[char] = &[
+    '[', ']', '#', '^', '|', '\\', '/', ':', '*', '?', '"', '<', '>',
+];
+const MAX_NAME_CHARS: usize = 120;
+
+/// Writes the notes into `dir`. `deadlines` are the expiry sweeper's deadlines by note id; notes
+/// without one (pinned, kept by a reminder or open tasks) get no `expires`. Notes are read as they
+/// are on disk, so exporting doesn't change them, and if one can't be written the files written
+/// so far are removed again.
+pub(super) fn export_obsidian(
+    conn: &Connection,
+    ids: &[String],
+    dir: &Path,
+    deadlines: &HashMap<String, i64>,
+) -> Result<Vec<ObsidianExportItem>, Message> {
+    let mut prepared: Vec<(String, String, String)> = Vec::with_capacity(ids.len());
+    for id in ids {
+        let note = read_stored(conn, id)?;
+        let meta = &note.meta;
+        let full = note.content;
+        let (existing, body) = frontmatter::parse(&full);
+
+        let expires = deadlines.get(id).copied();
+        let mut tags = existing.tags.clone();
+        for tag in frontmatter::inline_tags(body) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        let content = frontmatter::apply(
+            &full,
+            &FrontMatter {
+                title: existing.title.clone(),
+                created: Some(meta.created_at),
+                expires,
+                pinned: Some(meta.is_pinned),
+                tags,
+            },
+        );
+        prepared.push((id.clone(), file_stem(&meta.title), content));
+    }
+
+    let outcome = scoped_file::with_scoped_file(dir, None, |dir| {
+        let mut exported = Vec::with_capacity(prepared.len());
+        for (id, stem, content) in &prepared {
+            let path = unique_path(dir, stem);
+            if let Err(err) = write_file(&path, content, TextFormat::UTF8_LF) {
+                for item in &exported {
+                    let _ = std::fs::remove_file(&item.path);
+                }
+                return Err(err);
+            }
+            exported.push(ObsidianExportItem {
+                id: id.clone(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+        Ok(exported)
+    })
+    .map_err(|err: Message| Message::new("error.export_failed").arg("error", err))?;
+
+    Ok(outcome.value)
+}
+
+/// A file name that works as a `[[wikilink]]` target: unsafe characters dropped, whitespace
+/// collapsed, no leading dot.
+pub(super) fn file_stem(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .filter(|ch| !UNSAFE_CHARS.contains(ch) && !ch.is_control())
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed.trim_start_matches('.').trim();
+    let stem: String = trimmed.chars().take(MAX_NAME_CHARS).collect();
+    let stem = stem.trim_end_matches(['.', ' ']).to_string();
+    if stem.is_empty() {
+        i18n::t("note.untitled")
+    } else {
+        stem
+    }
+}
+
+/// Existing files are never overwritten; Obsidian's own convention for duplicates is
+/// `Name 1.md`, `Name 2.md`, ….
+pub(super) fn unique_path(dir: &Path, stem: &str) -> PathBuf {
+    let mut candidate = dir.join(format!("{stem}.md"));
+    let mut counter = 1;
+    while candidate.exists() {
+        candidate = dir.join(format!("{stem} {counter}.md"));
+        counter += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::meta::get_meta;
+    use crate::notes::test_support::TestEnv;
+
+    #[test]
+    fn exports_the_deadline_without_touching_the_note() {
+        let env = TestEnv::new();
+        let kept = env.draft("---\ntags: [work]\n---\n# Kept\n\n- [ ] open");
+        let expiring = env.draft("# Expiring");
+        let before = get_meta(&env.conn, &kept).unwrap();
+        let deadlines = HashMap::from([(expiring.clone(), before.last_interaction + 60_000)]);
+        let out = env.paths.app_data_dir.join("vault");
+        std::fs::create_dir_all(&out).unwrap();
+
+        let items =
+            export_obsidian(&env.conn, &[kept.clone(), expiring], &out, &deadlines).unwrap();
+        let kept_file = std::fs::read_to_string(&items[0].path).unwrap();
+        let expiring_file = std::fs::read_to_string(&items[1].path).unwrap();
+        assert!(kept_file.contains("tags:") && kept_file.contains("work"));
+        assert!(!kept_file.contains("expires:"), "{kept_file}");
+        assert!(expiring_file.contains("expires:"), "{expiring_file}");
+        let after = get_meta(&env.conn, &kept).unwrap();
+        assert_eq!(after.last_interaction, before.last_interaction);
+        assert_eq!(after.title, before.title);
+    }
+
+    #[test]
+    fn file_stems_are_wikilink_safe() {
+        assert_eq!(file_stem("Plan: Q3 [draft] #work"), "Plan Q3 draft work");
+        assert_eq!(file_stem("a/b\\c|d^e"), "abcde");
+        assert_eq!(file_stem("..hidden."), "hidden");
+        assert_eq!(file_stem("???"), "Untitled");
+        assert_eq!(file_stem(&"x".repeat(200)).chars().count(), MAX_NAME_CHARS);
+    }
+}
@@ -1,19 +1,32 @@
 use std::path::Path;
 
+use crate::i18n::Message;
 use crate::logs;
 
-pub(super) fn read_file(path: &Path) -> Result<String, String> {
-    std::fs::read_to_string(path).map_err(|err| {
-        log_read_failure(path, &err);
-        err.to_string()
-    })
+use super::encoding::{decode, encode, DecodedText, TextFormat};
+
+pub(super) fn read_file(path: &Path) -> Result<DecodedText, Message> {
+    std::fs::read(path)
+        .map(|bytes| decode(&bytes))
+        .map_err(|err| {
+            log_read_failure(path, &err);
+            err.to_string().into()
+        })
+}
+
+pub(super) fn write_file(path: &Path, content: &str, format: TextFormat) -> Result<(), Message> {
+    let bytes = encode(content, format)?;
+    std::fs::write(path, bytes).map_err(|err| err.to_string().into())
 }
 
-pub(super) fn write_file(path: &Path, content: &str) -> Result<(), String> {
-    std::fs::write(path, content).map_err(|err| err.to_string())
+/// Modification time in epoch milliseconds, if the platform reports one.
+pub(super) fn modified_ms(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let since_epoch = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_millis()).ok()
 }
 
-pub(super) fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+pub(super) fn move_file(from: &Path, to: &Path) -> Result<(), Message> {
     if let Some(parent) = to.parent() {
         std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
     }
@@ -0,0 +1,600 @@
+//! YAML front matter (`---` block at the top of a markdown file). Only the keys in
+//! [`FrontMatter`] mean something to the app; other keys are kept as they are.
+//!
+//! Note rows are kept in sync with the front matter of their files. The block is stored per
+//! note and the editor only ever gets the body, so the block survives editing untouched and
+//! write-back never races content the editor still holds; [`remove`] is how it goes away.
+
+use crate::i18n::Message;
+use crate::logs;
+use crate::scoped_file;
+use crate::types::{NoteMeta, NoteStorage};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_yaml_ng::{Mapping, Value};
+use std::path::Path;
+
+use super::derive::{derive_title_preview, truncate, MAX_TITLE_GRAPHEMES};
+use super::encoding::TextFormat;
+use super::files::{read_file, write_file};
+use super::meta::get_meta;
+use super::ordering::set_pinned;
+use super::scoped_updates::{apply_scoped_updates, get_bookmark};
+
+const TITLE: &str = "title";
+const CREATED: &str = "created";
+const EXPIRES: &str = "expires";
+const PINNED: &str = "pinned";
+const TAGS: &str = "tags";
+
+const WRITE_BACK_SETTING: &str = "front_matter_write_back";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct FrontMatter {
+    pub title: Option<String>,
+    pub created: Option<i64>,
+    pub expires: Option<i64>,
+    pub pinned: Option<bool>,
+    pub tags: Vec<String>,
+}
+
+/// Splits `content` into the YAML between the fences and the rest of the note. Returns `None`
+/// when the note doesn't start with a closed `---` block.
+pub(super) fn split(content: &str) -> Option<(&str, &str)> {
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Parses the front matter of `content` and returns it with the body that follows it. Notes
+/// without (valid) front matter get an empty [`FrontMatter`] and their full content back.
+pub(super) fn parse(content: &str) -> (FrontMatter, &str) {
+    let Some((yaml, body)) = split(content) else {
+        return (FrontMatter::default(), content);
+    };
+    match mapping(yaml) {
+        Some(map) => (from_mapping(&map), body),
+        None => (FrontMatter::default(), content),
+    }
+}
+
+/// Returns `content` with the recognized keys set from `front_matter`; keys that are `None`
+/// (or empty tags) are removed. Unknown keys and the body are left alone. A note without front
+/// matter gets a new block only if there is something to write.
+pub(super) fn apply(content: &str, front_matter: &FrontMatter) -> String {
+    let (mut map, body) = match split(content).and_then(|(yaml, body)| Some((mapping(yaml)?, body)))
+    {
+        Some(existing) => existing,
+        None => (Mapping::new(), content),
+    };
+
+    set(
+        &mut map,
+        TITLE,
+        front_matter.title.clone().map(Value::String),
+    );
+    set(&mut map, CREATED, front_matter.created.map(date_value));
+    set(&mut map, EXPIRES, front_matter.expires.map(date_value));
+    set(&mut map, PINNED, front_matter.pinned.map(Value::Bool));
+    let tags = (!front_matter.tags.is_empty()).then(|| {
+        Value::Sequence(
+            front_matter
+                .tags
+                .iter()
+                .cloned()
+                .map(Value::String)
+                .collect(),
+        )
+    });
+    set(&mut map, TAGS, tags);
+
+    if map.is_empty() {
+        return body.to_string();
+    }
+    let yaml = serde_yaml_ng::to_string(&map).unwrap_or_default();
+    format!("---\n{yaml}---\n{body}")
+}
+
+/// Removes `key` from the front matter of `content` and returns its value with the remaining
+/// content. A block left empty is dropped.
+pub(super) fn take(content: &str, key: &str) -> (Option<String>, String) {
+    let Some((yaml, body)) = split(content) else {
+        return (None, content.to_string());
+    };
+    let Some(mut map) = mapping(yaml) else {
+        return (None, content.to_string());
+    };
+    let Some(value) = map.remove(key) else {
+        return (None, content.to_string());
+    };
+
+    let rest = if map.is_empty() {
+        body.to_string()
+    } else {
+        let yaml = serde_yaml_ng::to_string(&map).unwrap_or_default();
+        format!("---\n{yaml}---\n{body}")
+    };
+    (scalar_string(&value), rest)
+}
+
+/// `#tags` written in the body, as Obsidian reads them: after whitespace, not only digits, not
+/// inside code.
+pub(super) fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut prev = ' ';
+        for (idx, ch) in line.char_indices() {
+            if ch == '`' {
+                in_code = !in_code;
+            } else if ch == '#' && !in_code && prev.is_whitespace() {
+                let rest = &line[idx + 1..];
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '/')))
+                    .unwrap_or(rest.len());
+                let tag = &rest[..len];
+                if !tag.is_empty()
+                    && !tag.chars().all(|c| c.is_ascii_digit())
+                    && !tags.iter().any(|known| known == tag)
+                {
+                    tags.push(tag.to_string());
+                }
+            }
+            prev = ch;
+        }
+    }
+    tags
+}
+
+fn mapping(yaml: &str) -> Option<Mapping> {
+    if yaml.trim().is_empty() {
+        return Some(Mapping::new());
+    }
+    match serde_yaml_ng::from_str::<Value>(yaml).ok()? {
+        Value::Mapping(map) => Some(map),
+        Value::Null => Some(Mapping::new()),
+        _ => None,
+    }
+}
+
+fn from_mapping(map: &Mapping) -> FrontMatter {
+    FrontMatter {
+        title: map
+            .get(TITLE)
+            .and_then(scalar_string)
+            .filter(|title| !title.trim().is_empty()),
+        created: map.get(CREATED).and_then(date_ms),
+        expires: map.get(EXPIRES).and_then(date_ms),
+        pinned: map.get(PINNED).and_then(|value| match value {
+            Value::Bool(flag) => Some(*flag),
+            Value::String(raw) => match raw.trim().to_ascii_lowercase().as_str() {
+                "true" | "yes" => Some(true),
+                "false" | "no" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }),
+        tags: map.get(TAGS).map(tags).unwrap_or_default(),
+    }
+}
+
+fn set(map: &mut Mapping, key: &str, value: Option<Value>) {
+    match value {
+        Some(value) => {
+            map.insert(Value::String(key.to_string()), value);
+        }
+        None => {
+            map.remove(key);
+        }
+    }
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(raw) => Some(raw.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Obsidian accepts `tags: a, b`, `tags: [a, b]` and block lists, with or without `#`.
+fn tags(value: &Value) -> Vec<String> {
+    let raw: Vec<String> = match value {
+        Value::Sequence(items) => items.iter().filter_map(scalar_string).collect(),
+        other => scalar_string(other)
+            .map(|joined| {
+                joined
+                    .split([',', ' '])
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default(),
+    };
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw {
+        let tag = tag.trim().trim_start_matches('#').to_string();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn date_value(ms: i64) -> Value {
+    let formatted = Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|date| date.format("%Y-%m-%dT%H:%M:%S").to_string())
+        .unwrap_or_default();
+    Value::String(formatted)
+}
+
+/// Accepts RFC 3339 and the local date/datetime forms Obsidian writes; epoch millis pass
+/// through.
+fn date_ms(value: &Value) -> Option<i64> {
+    let raw = match value {
+        Value::Number(number) => return number.as_i64(),
+        Value::String(raw) => raw.trim(),
+        _ => return None,
+    };
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Some(date.timestamp_millis());
+    }
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|date| date.timestamp_millis())
+}
+
+/// Where content handed to [`sync`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BlockSource {
+    /// Read from disk: the file decides, so a missing block removes the stored one.
+    File,
+    /// Sent by the editor, which never sees the block: a missing block keeps the stored one.
+    Editor,
+}
+
+/// Splits the front matter off `content`, applies the keys that changed since the stored block
+/// to the note and stores the new block. Returns the body.
+pub(super) fn sync<'a>(
+    conn: &Connection,
+    id: &str,
+    content: &'a str,
+    source: BlockSource,
+) -> Result<&'a str, Message> {
+    let stored = get_block(conn, id)?;
+    let (block, body) = match split(content) {
+        Some((block, body)) => (Some(block), body),
+        None if source == BlockSource::Editor => return Ok(content),
+        None => (None, content),
+    };
+    if block == stored.as_deref() {
+        return Ok(body);
+    }
+
+    let before = stored.as_deref().map(parse_block).unwrap_or_default();
+    let after = block.map(parse_block).unwrap_or_default();
+    apply_changes(conn, id, &before, &after)?;
+    set_block(conn, id, block)?;
+    Ok(body)
+}
+
+/// The file content for `body`: the stored block (with metadata written back into it, when
+/// enabled for saved notes) followed by the body.
+pub(super) fn compose(conn: &Connection, meta: &NoteMeta, body: &str) -> Result<String, Message> {
+    let Some(block) = get_block(conn, &meta.id)? else {
+        return Ok(body.to_string());
+    };
+    let block = if write_back_enabled(conn, meta)? {
+        let updated = written_back(&block, meta);
+        if updated != block {
+            set_block(conn, &meta.id, Some(&updated))?;
+        }
+        updated
+    } else {
+        block
+    };
+    Ok(join(&block, body))
+}
+
+/// `body` with the stored block in front of it, as it was last read or written.
+pub(super) fn with_stored_block(
+    conn: &Connection,
+    id: &str,
+    body: &str,
+) -> Result<String, Message> {
+    Ok(match get_block(conn, id)? {
+        Some(block) => join(&block, body),
+        None => body.to_string(),
+    })
+}
+
+/// Title and preview from the body; a `title` in the stored block wins.
+pub(super) fn title_preview(
+    conn: &Connection,
+    id: &str,
+    body: &str,
+) -> Result<(String, String), Message> {
+    let (title, preview) = derive_title_preview(body);
+    let block_title = get_block(conn, id)?
+        .as_deref()
+        .map(parse_block)
+        .and_then(|front_matter| front_matter.title);
+    Ok(match block_title {
+        Some(block_title) => (truncate(&block_title, MAX_TITLE_GRAPHEMES), preview),
+        None => (title, preview),
+    })
+}
+
+/// Writes the note's current pin and expiry into its saved file right away, if the file has
+/// front matter and write-back is enabled.
+pub(super) fn write_back(conn: &Connection, id: &str) -> Result<(), Message> {
+    let meta = get_meta(conn, id)?;
+    if get_block(conn, id)?.is_none() || !write_back_enabled(conn, &meta)? {
+        return Ok(());
+    }
+
+    let bookmark = get_bookmark(conn, id)?;
+    let format = TextFormat::of(&meta);
+    let outcome =
+        scoped_file::with_scoped_file(Path::new(&meta.file_path), bookmark.as_deref(), |p| {
+            let decoded = read_file(p)?;
+            let Some((block, body)) = split(&decoded.content) else {
+                return Ok(None);
+            };
+            let updated = written_back(block, &meta);
+            if updated != block {
+                write_file(p, &join(&updated, body), format)?;
+            }
+            Ok(Some(updated))
+        })
+        .map_err(|err: Message| Message::new("error.write_failed").arg("error", err))?;
+    apply_scoped_updates(conn, id, outcome.refreshed_bookmark, outcome.resolved_path)?;
+    set_block(conn, id, outcome.value.as_deref())
+}
+
+/// Drops the note's front matter from its file, then syncs the note as if the file never had
+/// any: the stored block goes, and the title comes from the body again.
+pub(super) fn remove(conn: &Connection, id: &str) -> Result<(), Message> {
+    let meta = get_meta(conn, id)?;
+    let bookmark = get_bookmark(conn, id)?;
+    let format = TextFormat::of(&meta);
+    let outcome =
+        scoped_file::with_scoped_file(Path::new(&meta.file_path), bookmark.as_deref(), |p| {
+            let decoded = read_file(p)?;
+            let Some((_, body)) = split(&decoded.content) else {
+                return Ok(decoded.content);
+            };
+            let body = body.to_string();
+            write_file(p, &body, format)?;
+            Ok(body)
+        })
+        .map_err(|err: Message| Message::new("error.write_failed").arg("error", err))?;
+    apply_scoped_updates(conn, id, outcome.refreshed_bookmark, outcome.resolved_path)?;
+    let body = sync(conn, id, &outcome.value, BlockSource::File)?;
+    let (title, preview) = title_preview(conn, id, body)?;
+    conn.execute(
+        "UPDATE notes SET title = ?1, preview = ?2 WHERE id = ?3",
+        params![title, preview, id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn apply_changes(
+    conn: &Connection,
+    id: &str,
+    before: &FrontMatter,
+    after: &FrontMatter,
+) -> Result<(), Message> {
+    if after.created != before.created {
+        if let Some(created) = after.created {
+            conn.execute(
+                "UPDATE notes SET created_at = ?1 WHERE id = ?2",
+                params![created, id],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+    }
+
+    if after.expires != before.expires {
+        conn.execute(
+            "UPDATE notes SET expires_at = ?1 WHERE id = ?2",
+            params![after.expires, id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+
+    if after.pinned != before.pinned {
+        if let Some(pinned) = after.pinned {
+            // A full pin list isn't worth failing a save over.
+            if let Err(err) = set_pinned(conn, id, pinned) {
+                logs::error(
+                    "front_matter",
+                    &format!("pin from front matter ignored: id={id} err=\"{err}\""),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Only keys whose value differs from the note are touched, so files without `pinned` don't
+/// gain `pinned: false` and a missing `created` isn't added.
+fn written_back(block: &str, meta: &NoteMeta) -> String {
+    let current = parse_block(block);
+    let mut updated = current.clone();
+    if current.pinned.unwrap_or(false) != meta.is_pinned {
+        updated.pinned = Some(meta.is_pinned);
+    }
+    if current.expires != meta.expires_at {
+        updated.expires = meta.expires_at;
+    }
+    if current.created.is_some() && current.created != Some(meta.created_at) {
+        updated.created = Some(meta.created_at);
+    }
+    if updated == current {
+        return block.to_string();
+    }
+
+    let content = apply(&join(block, ""), &updated);
+    split(&content)
+        .map(|(block, _)| block.to_string())
+        .unwrap_or_default()
+}
+
+fn write_back_enabled(conn: &Connection, meta: &NoteMeta) -> Result<bool, Message> {
+    if meta.storage != NoteStorage::Saved {
+        return Ok(false);
+    }
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![WRITE_BACK_SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+fn parse_block(block: &str) -> FrontMatter {
+    parse(&join(block, "")).0
+}
+
+/// `block` fenced with `---` in front of `body`.
+pub(super) fn join(block: &str, body: &str) -> String {
+    if block.is_empty() || block.ends_with('\n') {
+        format!("---\n{block}---\n{body}")
+    } else {
+        format!("---\n{block}\n---\n{body}")
+    }
+}
+
+fn get_block(conn: &Connection, id: &str) -> Result<Option<String>, Message> {
+    conn.query_row(
+        "SELECT front_matter FROM notes WHERE id = ?1 LIMIT 1",
+        params![id],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .map_err(|err| err.to_string().into())
+}
+
+fn set_block(conn: &Connection, id: &str, block: Option<&str>) -> Result<(), Message> {
+    conn.execute(
+        "UPDATE notes SET front_matter = ?1 WHERE id = ?2",
+        params![block, id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_recognized_keys() {
+        let content = "---\ntitle: \"Plan: Q3\"\npinned: true\ntags: [work, '#plans']\naliases: [x]\ncreated: 2024-05-01\n---\n# Body\n";
+        let (front_matter, body) = parse(content);
+        assert_eq!(front_matter.title.as_deref(), Some("Plan: Q3"));
+        assert_eq!(front_matter.pinned, Some(true));
+        assert_eq!(front_matter.tags, vec!["work", "plans"]);
+        assert_eq!(
+            front_matter.created,
+            date_ms(&Value::String("2024-05-01T00:00:00".to_string()))
+        );
+        assert_eq!(body, "# Body\n");
+    }
+
+    #[test]
+    fn leaves_unclosed_or_invalid_blocks_alone() {
+        assert_eq!(parse("---\ntitle: x\n").1, "---\ntitle: x\n");
+        assert_eq!(parse("---\n- a\n---\nbody").1, "---\n- a\n---\nbody");
+        assert!(split("text\n---\n").is_none());
+    }
+
+    #[test]
+    fn apply_keeps_unknown_keys() {
+        let content = "---\naliases: [x]\npinned: true\n---\nBody\n";
+        let updated = apply(
+            content,
+            &FrontMatter {
+                tags: vec!["a".to_string()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(updated, "---\naliases:\n- x\ntags:\n- a\n---\nBody\n");
+        assert_eq!(
+            apply(&updated, &FrontMatter::default()),
+            "---\naliases:\n- x\n---\nBody\n"
+        );
+        assert_eq!(apply("Body\n", &FrontMatter::default()), "Body\n");
+    }
+
+    #[test]
+    fn takes_a_key_out() {
+        let (value, rest) = take(
+            "---\nexpires_after: 3d\ntags: [a]\n---\nBody\n",
+            "expires_after",
+        );
+        assert_eq!(value.as_deref(), Some("3d"));
+        assert_eq!(rest, "---\ntags:\n- a\n---\nBody\n");
+        let (value, rest) = take("---\nexpires_after: 12\n---\nBody\n", "expires_after");
+        assert_eq!(value.as_deref(), Some("12"));
+        assert_eq!(rest, "Body\n");
+        assert_eq!(
+            take("Body\n", "expires_after"),
+            (None, "Body\n".to_string())
+        );
+    }
+
+    #[test]
+    fn finds_inline_tags() {
+        let body = "# Heading\nSee #work and #work, #2024 `#code`\n```\n#fenced\n```\n#a/b-c";
+        assert_eq!(inline_tags(body), vec!["work", "a/b-c"]);
+    }
+
+    #[test]
+    fn dates_round_trip() {
+        let ms = date_ms(&Value::String("2024-05-01T10:30:00Z".to_string())).unwrap();
+        assert_eq!(date_ms(&date_value(ms)), Some(ms));
+    }
+}
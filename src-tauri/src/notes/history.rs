@@ -0,0 +1,876 @@
+//! Journal of trash, delete, pin, reorder and auto-expiry operations with what it takes to reverse
+//! them, including moving draft files between `drafts_dir` and `trash_dir`. Recording a new entry
+//! drops everything that could still be redone. The contents of deleted drafts are kept as blobs in
+//! `history_files`, next to the entry they belong to, rather than inside its JSON. Labels are
+//! stored as a message key and a count and rendered when read, so the Edit menu follows the
+//! locale.
+
+use crate::app_state::AppPaths;
+use crate::i18n::{self, Message};
+use crate::types::{HistoryState, HistoryStep, NoteChange, NoteMeta, NoteStorage};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use super::content_index;
+use super::files::move_file;
+use super::frontmatter;
+use super::meta::get_meta;
+use super::ordering::{next_sort_order, pinned_count, MAX_PINNED};
+use super::time::now_ms;
+use super::trash;
+
+const MAX_ENTRIES: i64 = 50;
+
+/// What the Edit menu calls an entry: "Trash", or "Trash 3 Notes" with a count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Label {
+    key: &'static str,
+    count: Option<i64>,
+}
+
+impl Label {
+    pub(super) fn new(key: &'static str) -> Self {
+        Self { key, count: None }
+    }
+
+    /// The label for one note, or the `{count}` one for several.
+    pub(super) fn counted(one: &'static str, many: &'static str, count: usize) -> Self {
+        if count == 1 {
+            Self::new(one)
+        } else {
+            Self {
+                key: many,
+                count: Some(count as i64),
+            }
+        }
+    }
+
+    pub(super) fn key(&self) -> &'static str {
+        self.key
+    }
+}
+
+/// Renders a stored label key in the current locale.
+fn render_label(stored: &str, count: Option<i64>) -> String {
+    // A key no catalog has shows as itself, as it would in a `Message`.
+    let Some(key) = i18n::catalog_key(stored) else {
+        return stored.to_string();
+    };
+    match count {
+        Some(count) => Message::new(key).arg("count", count).to_string(),
+        None => Message::new(key).to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(super) enum Entry {
+    /// Notes moved to the trash, with where they were in the list.
+    Trash { notes: Vec<Placement> },
+    /// Notes deleted for good: their rows and, for drafts, the file.
+    Delete { notes: Vec<DeletedNote> },
+    /// Pin state and sort order before and after a pin, unpin or reorder.
+    Arrange {
+        before: Vec<Placement>,
+        after: Vec<Placement>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Placement {
+    id: String,
+    is_pinned: bool,
+    sort_order: i64,
+}
+
+impl Placement {
+    pub(super) fn of(meta: &NoteMeta) -> Self {
+        Self {
+            id: meta.id.clone(),
+            is_pinned: meta.is_pinned,
+            sort_order: meta.sort_order,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct DeletedNote {
+    id: String,
+    columns: Vec<(String, Column)>,
+    /// Stored in `history_files`, not in the entry's JSON.
+    #[serde(skip)]
+    file: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Column {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+/// The row and draft file of a note about to be deleted.
+pub(super) fn capture(conn: &Connection, id: &str) -> Result<DeletedNote, Message> {
+    let meta = get_meta(conn, id)?;
+    let mut stmt = conn
+        .prepare("SELECT * FROM notes WHERE id = ?1")
+        .map_err(|err| err.to_string())?;
+    let names: Vec<String> = stmt.column_names().iter().map(|n| n.to_string()).collect();
+    let columns = stmt
+        .query_row(params![id], |row| {
+            names
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| Ok((name.clone(), Column::from(row.get_ref(idx)?))))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|err| err.to_string())?;
+    let file = if meta.storage == NoteStorage::Draft {
+        std::fs::read(&meta.file_path).ok()
+    } else {
+        None
+    };
+    Ok(DeletedNote {
+        id: id.to_string(),
+        columns,
+        file,
+    })
+}
+
+/// Adds an entry on top of the undo stack. Entries that touch no notes are not recorded.
+pub(super) fn record(conn: &Connection, label: Label, entry: &Entry) -> Result<(), Message> {
+    if entry.ids().is_empty() {
+        return Ok(());
+    }
+    let json = serde_json::to_string(entry).map_err(|err| err.to_string())?;
+    conn.execute("DELETE FROM history WHERE undone = 1", [])
+        .map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO history(created_at, label, label_count, entry) VALUES (?1, ?2, ?3, ?4)",
+        params![now_ms(), label.key, label.count, json],
+    )
+    .map_err(|err| err.to_string())?;
+    store_files(conn, conn.last_insert_rowid(), entry)?;
+    conn.execute(
+        "DELETE FROM history WHERE seq <= (SELECT MAX(seq) FROM history) - ?1",
+        params![MAX_ENTRIES],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub(super) fn state(conn: &Connection) -> Result<HistoryState, Message> {
+    Ok(HistoryState {
+        undo: next(conn, false)?.map(|(_, label, _)| label),
+        redo: next(conn, true)?.map(|(_, label, _)| label),
+    })
+}
+
+pub(super) fn undo(
+    conn: &mut Connection,
+    paths: &AppPaths,
+) -> Result<Option<HistoryStep>, Message> {
+    step(conn, paths, false)
+}
+
+pub(super) fn redo(
+    conn: &mut Connection,
+    paths: &AppPaths,
+) -> Result<Option<HistoryStep>, Message> {
+    step(conn, paths, true)
+}
+
+/// The newest entry that can be undone, or the oldest one that can be redone, with its label
+/// rendered.
+fn next(conn: &Connection, undone: bool) -> Result<Option<(i64, String, String)>, Message> {
+    let order = if undone { "ASC" } else { "DESC" };
+    conn.query_row(
+        &format!(
+            r#"
+SELECT seq, label, label_count, entry FROM history
+WHERE undone = ?1
+ORDER BY seq {order}
+LIMIT 1
+"#
+        ),
+        params![undone],
+        |row| {
+            let label: String = row.get(1)?;
+            Ok((row.get(0)?, render_label(&label, row.get(2)?), row.get(3)?))
+        },
+    )
+    .optional()
+    .map_err(|err| err.to_string().into())
+}
+
+/// Applies the entry and moves it to the other stack in one transaction, so a step that fails
+/// halfway leaves neither notes nor journal changed. Draft files moved, written or removed along
+/// the way are put back when it fails.
+fn step(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    redo: bool,
+) -> Result<Option<HistoryStep>, Message> {
+    let Some((seq, label, json)) = next(conn, redo)? else {
+        return Ok(None);
+    };
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let mut files = Vec::new();
+    let applied = serde_json::from_str::<Entry>(&json)
+        .map_err(|err| Failure::Broken(err.to_string().into()))
+        .and_then(|mut entry| {
+            load_files(&tx, seq, &mut entry)?;
+            apply(&tx, paths, entry, redo, &mut files)
+        });
+    let applied = match applied {
+        Ok(applied) => applied,
+        // Kept for when there is room again.
+        Err(Failure::Blocked(err)) => {
+            drop(tx);
+            revert(files);
+            return Err(err);
+        }
+        Err(Failure::Broken(err)) => {
+            drop(tx);
+            revert(files);
+            // A broken entry would otherwise block everything recorded before it.
+            conn.execute("DELETE FROM history WHERE seq = ?1", params![seq])
+                .map_err(|err| err.to_string())?;
+            let key = if redo {
+                "error.history_redo"
+            } else {
+                "error.history_undo"
+            };
+            return Err(Message::new(key).arg("label", label).arg("error", err));
+        }
+    };
+    if let Err(err) = commit(tx, seq, redo, &applied.entry) {
+        revert(files);
+        return Err(err);
+    }
+
+    // Front matter follows once the step is committed.
+    for change in &applied.changes {
+        let repinned = matches!(
+            (&change.before, &change.after),
+            (Some(before), Some(after)) if before.is_pinned != after.is_pinned
+        );
+        if !repinned {
+            continue;
+        }
+        if let Err(err) = frontmatter::write_back(conn, &change.id) {
+            crate::logs::error(
+                "front_matter",
+                &format!("write-back failed: id={} err=\"{err}\"", change.id),
+            );
+        }
+    }
+    Ok(Some(HistoryStep {
+        label,
+        changes: applied.changes,
+    }))
+}
+
+/// Moves the entry to the other stack and commits the step.
+fn commit(tx: Transaction<'_>, seq: i64, redo: bool, entry: &Entry) -> Result<(), Message> {
+    let json = serde_json::to_string(entry).map_err(|err| err.to_string())?;
+    tx.execute(
+        "UPDATE history SET undone = ?1, entry = ?2 WHERE seq = ?3",
+        params![!redo, json, seq],
+    )
+    .map_err(|err| err.to_string())?;
+    // A redone delete captured the notes again.
+    if redo && matches!(entry, Entry::Delete { .. }) {
+        tx.execute("DELETE FROM history_files WHERE seq = ?1", params![seq])
+            .map_err(|err| err.to_string())?;
+        store_files(&tx, seq, entry)?;
+    }
+    tx.commit().map_err(|err| err.to_string().into())
+}
+
+fn store_files(conn: &Connection, seq: i64, entry: &Entry) -> Result<(), Message> {
+    let Entry::Delete { notes } = entry else {
+        return Ok(());
+    };
+    for note in notes {
+        let Some(bytes) = &note.file else {
+            continue;
+        };
+        conn.execute(
+            "INSERT INTO history_files(seq, note_id, bytes) VALUES (?1, ?2, ?3)",
+            params![seq, note.id, bytes],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn load_files(conn: &Connection, seq: i64, entry: &mut Entry) -> Result<(), Message> {
+    let Entry::Delete { notes } = entry else {
+        return Ok(());
+    };
+    let mut stmt = conn
+        .prepare("SELECT bytes FROM history_files WHERE seq = ?1 AND note_id = ?2")
+        .map_err(|err| err.to_string())?;
+    for note in notes.iter_mut() {
+        note.file = stmt
+            .query_row(params![seq, note.id], |row| row.get(0))
+            .optional()
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// What a step did to a draft file, to be reversed if the step doesn't commit.
+enum FileChange {
+    Moved { from: PathBuf, to: PathBuf },
+    Written(PathBuf),
+    Removed { path: PathBuf, bytes: Vec<u8> },
+}
+
+/// Reverses `files` newest first. Best effort: the database has already been rolled back.
+fn revert(files: Vec<FileChange>) {
+    for change in files.into_iter().rev() {
+        let result = match &change {
+            FileChange::Moved { from, to } => move_file(to, from).map_err(|err| err.to_string()),
+            FileChange::Written(path) => std::fs::remove_file(path).map_err(|err| err.to_string()),
+            FileChange::Removed { path, bytes } => {
+                std::fs::write(path, bytes).map_err(|err| err.to_string())
+            }
+        };
+        if let Err(err) = result {
+            crate::logs::error("history", &format!("file rollback failed: err=\"{err}\""));
+        }
+    }
+}
+
+/// Runs a trash or restore and records the draft file's move.
+fn track_move(
+    conn: &Connection,
+    id: &str,
+    files: &mut Vec<FileChange>,
+    op: impl FnOnce() -> Result<NoteMeta, Message>,
+) -> Result<(), Message> {
+    let from = get_meta(conn, id)?.file_path;
+    let to = op()?.file_path;
+    if from != to {
+        files.push(FileChange::Moved {
+            from: from.into(),
+            to: to.into(),
+        });
+    }
+    Ok(())
+}
+
+struct Applied {
+    /// The entry as it should be stored for the opposite direction.
+    entry: Entry,
+    changes: Vec<NoteChange>,
+}
+
+enum Failure {
+    /// The entry can't be applied as recorded and is dropped.
+    Broken(Message),
+    /// Applying it now would break a rule, such as the pin cap; it stays for a later try.
+    Blocked(Message),
+}
+
+impl From<Message> for Failure {
+    fn from(err: Message) -> Self {
+        Failure::Broken(err)
+    }
+}
+
+impl From<String> for Failure {
+    fn from(err: String) -> Self {
+        Failure::Broken(err.into())
+    }
+}
+
+fn apply(
+    conn: &Connection,
+    paths: &AppPaths,
+    entry: Entry,
+    redo: bool,
+    files: &mut Vec<FileChange>,
+) -> Result<Applied, Failure> {
+    let ids = entry.ids();
+    let before: Vec<Option<NoteMeta>> = ids.iter().map(|id| get_meta(conn, id).ok()).collect();
+
+    let entry = match (entry, redo) {
+        (Entry::Trash { notes }, false) => {
+            let now = now_ms();
+            for placement in present(conn, &notes) {
+                track_move(conn, &placement.id, files, || {
+                    trash::restore(conn, paths, &placement.id)
+                })?;
+                // Back where it was, unless it was pinned and the pins have filled up since.
+                let pinned = placement.is_pinned && pinned_count(conn)? < MAX_PINNED;
+                let sort_order = if pinned == placement.is_pinned {
+                    placement.sort_order
+                } else {
+                    next_sort_order(conn)?
+                };
+                // A fresh interaction keeps the sweeper from trashing it again right away.
+                conn.execute(
+                    "UPDATE notes SET is_pinned = ?1, sort_order = ?2, last_interaction = ?3 WHERE id = ?4",
+                    params![pinned, sort_order, now, placement.id],
+                )
+                .map_err(|err| err.to_string())?;
+            }
+            Entry::Trash { notes }
+        }
+        (Entry::Trash { notes }, true) => {
+            for placement in present(conn, &notes) {
+                track_move(conn, &placement.id, files, || {
+                    trash::trash(conn, paths, &placement.id)
+                })?;
+            }
+            Entry::Trash { notes }
+        }
+        (Entry::Delete { notes }, false) => {
+            for note in &notes {
+                reinsert(conn, note, files)?;
+            }
+            Entry::Delete { notes }
+        }
+        (Entry::Delete { notes }, true) => {
+            // Capture again: the note may have changed since it was brought back.
+            let mut recaptured = Vec::with_capacity(notes.len());
+            for note in &notes {
+                if get_meta(conn, &note.id).is_err() {
+                    continue;
+                }
+                let captured = capture(conn, &note.id)?;
+                let path = get_meta(conn, &note.id)?.file_path;
+                trash::delete_forever(conn, &note.id)?;
+                if let Some(bytes) = &captured.file {
+                    files.push(FileChange::Removed {
+                        path: path.into(),
+                        bytes: bytes.clone(),
+                    });
+                }
+                recaptured.push(captured);
+            }
+            Entry::Delete { notes: recaptured }
+        }
+        (Entry::Arrange { before, after }, redo) => {
+            place(conn, if redo { &after } else { &before })?;
+            Entry::Arrange { before, after }
+        }
+    };
+
+    let changes = ids
+        .into_iter()
+        .zip(before)
+        .filter_map(|(id, before)| {
+            let after = get_meta(conn, &id).ok();
+            if before.is_none() && after.is_none() {
+                return None;
+            }
+            Some(NoteChange { id, before, after })
+        })
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        return Err(Failure::Broken(Message::new("error.history_gone")));
+    }
+    Ok(Applied { entry, changes })
+}
+
+/// Placements whose note still exists; notes deleted since are skipped.
+fn present<'a>(conn: &Connection, notes: &'a [Placement]) -> Vec<&'a Placement> {
+    notes
+        .iter()
+        .filter(|placement| get_meta(conn, &placement.id).is_ok())
+        .collect()
+}
+
+/// Puts notes back to the recorded pin state and order. Refused when that would pin more than
+/// [`MAX_PINNED`] notes; unpinned notes get a fresh interaction, as with a manual unpin.
+fn place(conn: &Connection, placements: &[Placement]) -> Result<(), Failure> {
+    let current: Vec<(&Placement, NoteMeta)> = placements
+        .iter()
+        .filter_map(|placement| Some((placement, get_meta(conn, &placement.id).ok()?)))
+        .filter(|(_, meta)| !meta.is_trashed)
+        .collect();
+    let pinning = current
+        .iter()
+        .filter(|(placement, meta)| placement.is_pinned && !meta.is_pinned)
+        .count() as i64;
+    let unpinning = current
+        .iter()
+        .filter(|(placement, meta)| !placement.is_pinned && meta.is_pinned)
+        .count() as i64;
+    if pinning > 0 && pinned_count(conn)? - unpinning + pinning > MAX_PINNED {
+        return Err(Failure::Blocked(
+            Message::new("error.pin_limit").arg("max", MAX_PINNED),
+        ));
+    }
+
+    let now = now_ms();
+    for (placement, meta) in current {
+        let last_interaction = if meta.is_pinned && !placement.is_pinned {
+            now
+        } else {
+            meta.last_interaction
+        };
+        conn.execute(
+            "UPDATE notes SET is_pinned = ?1, sort_order = ?2, last_interaction = ?3 WHERE id = ?4",
+            params![
+                placement.is_pinned,
+                placement.sort_order,
+                last_interaction,
+                placement.id
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn reinsert(
+    conn: &Connection,
+    note: &DeletedNote,
+    files: &mut Vec<FileChange>,
+) -> Result<(), Message> {
+    if get_meta(conn, &note.id).is_ok() {
+        return Ok(());
+    }
+    let names = note
+        .columns
+        .iter()
+        .map(|(name, _)| format!("\"{}\"", name.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let slots = (1..=note.columns.len())
+        .map(|idx| format!("?{idx}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.execute(
+        &format!("INSERT INTO notes ({names}) VALUES ({slots})"),
+        params_from_iter(note.columns.iter().map(|(_, value)| Value::from(value))),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let meta = get_meta(conn, &note.id)?;
+    if let Some(bytes) = &note.file {
+        let path = Path::new(&meta.file_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(path, bytes)
+            .map_err(|err| Message::new("error.restore_failed").arg("error", err))?;
+        files.push(FileChange::Written(path.to_path_buf()));
+    }
+    // Links and tasks went with the row.
+    if let Err(err) = content_index::index_note(conn, &note.id) {
+        crate::logs::error(
+            "history",
+            &format!("re-index failed: id={} err=\"{err}\"", note.id),
+        );
+    }
+    Ok(())
+}
+
+impl Entry {
+    fn ids(&self) -> Vec<String> {
+        let ids: BTreeSet<&str> = match self {
+            Entry::Trash { notes } => notes.iter().map(|n| n.id.as_str()).collect(),
+            Entry::Delete { notes } => notes.iter().map(|n| n.id.as_str()).collect(),
+            Entry::Arrange { before, after } => before
+                .iter()
+                .zip(after)
+                .filter(|(before, after)| before != after)
+                .map(|(before, _)| before.id.as_str())
+                .collect(),
+        };
+        ids.into_iter().map(str::to_string).collect()
+    }
+}
+
+impl From<ValueRef<'_>> for Column {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => Column::Null,
+            ValueRef::Integer(value) => Column::Integer(value),
+            ValueRef::Real(value) => Column::Real(value),
+            ValueRef::Text(value) => Column::Text(String::from_utf8_lossy(value).into_owned()),
+            ValueRef::Blob(value) => Column::Blob(value.to_vec()),
+        }
+    }
+}
+
+impl From<&Column> for Value {
+    fn from(column: &Column) -> Self {
+        match column {
+            Column::Null => Value::Null,
+            Column::Integer(value) => Value::Integer(*value),
+            Column::Real(value) => Value::Real(*value),
+            Column::Text(value) => Value::Text(value.clone()),
+            Column::Blob(value) => Value::Blob(value.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::ordering::set_pinned;
+    use crate::notes::test_support::TestEnv;
+    use crate::notes::{self, list};
+
+    fn pinned(env: &TestEnv) -> Vec<String> {
+        let list = list::list(&env.conn).unwrap();
+        let mut ids: Vec<&NoteMeta> = list.active.iter().filter(|n| n.is_pinned).collect();
+        ids.sort_by_key(|n| n.sort_order);
+        ids.into_iter().map(|n| n.id.clone()).collect()
+    }
+
+    fn order(env: &TestEnv, ids: &[String]) -> Vec<i64> {
+        ids.iter()
+            .map(|id| get_meta(&env.conn, id).unwrap().sort_order)
+            .collect()
+    }
+
+    #[test]
+    fn undo_and_redo_trash() {
+        let mut env = TestEnv::new();
+        let id = env.draft("# Keep");
+        notes::set_pinned(&env.conn, &id, true).unwrap();
+        let draft = get_meta(&env.conn, &id).unwrap().file_path;
+        notes::trash(&env.conn, &env.paths, &id).unwrap();
+        assert!(!Path::new(&draft).exists());
+
+        let step = notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert_eq!(step.changes.len(), 1);
+        let meta = get_meta(&env.conn, &id).unwrap();
+        assert!(!meta.is_trashed && meta.is_pinned);
+        assert_eq!(meta.file_path, draft);
+        assert!(Path::new(&draft).exists());
+
+        notes::redo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert!(get_meta(&env.conn, &id).unwrap().is_trashed);
+        assert!(notes::redo(&mut env.conn, &env.paths).unwrap().is_none());
+    }
+
+    #[test]
+    fn a_step_that_fails_to_commit_moves_the_files_back() {
+        let mut env = TestEnv::new();
+        let id = env.draft("# Keep");
+        let draft = get_meta(&env.conn, &id).unwrap().file_path;
+        notes::trash(&env.conn, &env.paths, &id).unwrap();
+        let trashed = get_meta(&env.conn, &id).unwrap().file_path;
+        env.conn
+            .execute_batch(
+                "CREATE TEMP TRIGGER fail_step BEFORE UPDATE ON history
+                 BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+            )
+            .unwrap();
+
+        assert!(notes::undo(&mut env.conn, &env.paths).is_err());
+        let meta = get_meta(&env.conn, &id).unwrap();
+        assert!(meta.is_trashed);
+        assert_eq!(meta.file_path, trashed);
+        assert!(Path::new(&trashed).exists() && !Path::new(&draft).exists());
+
+        env.conn.execute_batch("DROP TRIGGER fail_step").unwrap();
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert!(Path::new(&draft).exists());
+    }
+
+    #[test]
+    fn undoing_a_trash_respects_the_pin_cap() {
+        let mut env = TestEnv::new();
+        let id = env.draft("# Was pinned");
+        notes::set_pinned(&env.conn, &id, true).unwrap();
+        notes::trash(&env.conn, &env.paths, &id).unwrap();
+        // Pins that bypass the journal, as front matter does.
+        for n in 0..MAX_PINNED {
+            let other = env.draft(&format!("# Pin {n}"));
+            set_pinned(&env.conn, &other, true).unwrap();
+        }
+
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        let meta = get_meta(&env.conn, &id).unwrap();
+        assert!(!meta.is_trashed);
+        assert!(!meta.is_pinned, "restored unpinned while the pins are full");
+        assert_eq!(pinned(&env).len() as i64, MAX_PINNED);
+    }
+
+    #[test]
+    fn undo_and_redo_pin() {
+        let mut env = TestEnv::new();
+        let ids: Vec<String> = (0..=MAX_PINNED)
+            .map(|n| env.draft(&format!("# {n}")))
+            .collect();
+        notes::set_pinned(&env.conn, &ids[0], true).unwrap();
+        notes::set_pinned(&env.conn, &ids[0], false).unwrap();
+
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert_eq!(pinned(&env), vec![ids[0].clone()]);
+        notes::redo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert!(pinned(&env).is_empty());
+
+        for id in &ids[1..] {
+            set_pinned(&env.conn, id, true).unwrap();
+        }
+        let label = state(&env.conn).unwrap().undo;
+        let err = notes::undo(&mut env.conn, &env.paths)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&MAX_PINNED.to_string()), "{err}");
+        assert_eq!(pinned(&env).len() as i64, MAX_PINNED);
+        // Kept for when a pin is freed.
+        assert_eq!(state(&env.conn).unwrap().undo, label);
+
+        set_pinned(&env.conn, &ids[1], false).unwrap();
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert!(get_meta(&env.conn, &ids[0]).unwrap().is_pinned);
+    }
+
+    #[test]
+    fn undo_and_redo_reorder() {
+        let mut env = TestEnv::new();
+        let ids: Vec<String> = (0..3).map(|n| env.draft(&format!("# {n}"))).collect();
+        let original = order(&env, &ids);
+        let reversed: Vec<String> = ids.iter().rev().cloned().collect();
+        notes::reorder(&mut env.conn, &reversed).unwrap();
+        let moved = order(&env, &ids);
+        assert_ne!(moved, original);
+
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert_eq!(order(&env, &ids), original);
+        notes::redo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert_eq!(order(&env, &ids), moved);
+    }
+
+    fn indexed(env: &TestEnv, id: &str) -> (i64, i64) {
+        let count = |sql: &str| -> i64 {
+            env.conn
+                .query_row(sql, params![id], |row| row.get(0))
+                .unwrap()
+        };
+        (
+            count("SELECT COUNT(*) FROM note_links WHERE source_id = ?1"),
+            count("SELECT COUNT(*) FROM note_tasks WHERE note_id = ?1"),
+        )
+    }
+
+    #[test]
+    fn undo_and_redo_delete() {
+        let mut env = TestEnv::new();
+        let id = env.draft("# Plan\n\n- [ ] Call [[Anna]]\n- [x] Book [[Train|the train]]\n");
+        let meta = get_meta(&env.conn, &id).unwrap();
+        let bytes = std::fs::read(&meta.file_path).unwrap();
+        assert_eq!(indexed(&env, &id), (2, 2));
+
+        notes::delete_forever(&env.conn, &id).unwrap();
+        assert!(get_meta(&env.conn, &id).is_err());
+        assert!(!Path::new(&meta.file_path).exists());
+        assert_eq!(indexed(&env, &id), (0, 0));
+        let entry: String = env
+            .conn
+            .query_row("SELECT entry FROM history", [], |row| row.get(0))
+            .unwrap();
+        assert!(!entry.contains("\"file\""), "{entry}");
+
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        let restored = get_meta(&env.conn, &id).unwrap();
+        assert_eq!(restored.file_path, meta.file_path);
+        assert_eq!(restored.sort_order, meta.sort_order);
+        assert_eq!(std::fs::read(&meta.file_path).unwrap(), bytes);
+        assert_eq!(indexed(&env, &id), (2, 2));
+
+        notes::redo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert!(get_meta(&env.conn, &id).is_err());
+        assert!(!Path::new(&meta.file_path).exists());
+
+        // The redo captured the file again for the next undo.
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        assert_eq!(std::fs::read(&meta.file_path).unwrap(), bytes);
+    }
+
+    #[test]
+    fn undo_and_redo_empty_trash() {
+        let mut env = TestEnv::new();
+        let ids: Vec<String> = (0..2)
+            .map(|n| env.draft(&format!("# {n}\n\n- [ ] Task {n} for [[Other]]")))
+            .collect();
+        let kept = env.draft("# Kept");
+        let mut trashed = Vec::new();
+        for id in &ids {
+            let meta = notes::trash(&env.conn, &env.paths, id).unwrap();
+            trashed.push((
+                meta.file_path.clone(),
+                std::fs::read(&meta.file_path).unwrap(),
+            ));
+        }
+
+        let report = notes::empty_trash(&env.conn).unwrap();
+        assert!(report.committed);
+        for (id, (path, _)) in ids.iter().zip(&trashed) {
+            assert!(get_meta(&env.conn, id).is_err());
+            assert!(!Path::new(path).exists());
+        }
+        let label = state(&env.conn).unwrap().undo;
+
+        notes::undo(&mut env.conn, &env.paths).unwrap().unwrap();
+        for (id, (path, bytes)) in ids.iter().zip(&trashed) {
+            assert!(get_meta(&env.conn, id).unwrap().is_trashed);
+            assert_eq!(&std::fs::read(path).unwrap(), bytes);
+            assert_eq!(indexed(&env, id), (1, 1));
+        }
+        assert_eq!(state(&env.conn).unwrap().redo, label);
+
+        notes::redo(&mut env.conn, &env.paths).unwrap().unwrap();
+        for (id, (path, _)) in ids.iter().zip(&trashed) {
+            assert!(get_meta(&env.conn, id).is_err());
+            assert!(!Path::new(path).exists());
+        }
+        assert!(get_meta(&env.conn, &kept).is_ok());
+    }
+
+    #[test]
+    fn labels_are_stored_as_keys_and_rendered_when_read() {
+        let env = TestEnv::new();
+        let ids: Vec<String> = (0..3).map(|n| env.draft(&format!("# {n}"))).collect();
+        notes::trash_many(&env.conn, &env.paths, &ids).unwrap();
+        let stored: (String, Option<i64>) = env
+            .conn
+            .query_row("SELECT label, label_count FROM history", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(stored, ("history.trash_many".to_string(), Some(3)));
+        assert_eq!(
+            state(&env.conn).unwrap().undo,
+            Some(
+                Message::new("history.trash_many")
+                    .arg("count", 3)
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn columns_round_trip_through_json() {
+        let columns = vec![
+            ("a".to_string(), Column::Null),
+            ("b".to_string(), Column::Integer(7)),
+            ("c".to_string(), Column::Real(1.5)),
+            ("d".to_string(), Column::Text("x".to_string())),
+        ];
+        let json = serde_json::to_string(&columns).unwrap();
+        let back: Vec<(String, Column)> = serde_json::from_str(&json).unwrap();
+        assert!(matches!(back[0].1, Column::Null));
+        assert!(matches!(back[1].1, Column::Integer(7)));
+        assert!(matches!(back[2].1, Column::Real(value) if value == 1.5));
+        assert!(matches!(&back[3].1, Column::Text(value) if value == "x"));
+    }
+}
@@ -1,27 +1,156 @@
+mod external;
+mod html;
+mod keep;
+mod obsidian;
+mod rtf;
+mod simplenote;
+
 use crate::app_state::AppPaths;
-use crate::scoped_file;
-use crate::types::{NoteStorage, NoteWithContent};
+use crate::i18n::{self, Message};
+use crate::logs;
+use crate::scoped_file::{self, ScopedOutcome};
+use crate::types::{
+    ConvertedImportTarget, ExternalImportReport, ExternalSource, NoteStorage, NoteWithContent,
+    OpenedNote,
+};
 use rusqlite::{params, Connection, OptionalExtension};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use super::derive::derive_title_preview;
-use super::files::read_file;
-use super::meta::{get_meta, storage_to_db};
+use super::content_index::index_body;
+use super::encoding::{DecodedText, TextFormat};
+use super::files::{read_file, write_file};
+use super::frontmatter::{self, BlockSource};
+use super::meta::{encoding_to_db, get_meta, line_ending_to_db, storage_to_db, update_format};
 use super::ordering::next_sort_order;
 use super::time::now_ms;
-use super::write::set_active;
+use super::write::{create_filled, set_active, write_draft};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportFormat {
+    Markdown,
+    PlainText,
+    Html,
+    Rtf,
+}
 
 pub(super) fn import_file(
     conn: &Connection,
     paths: &AppPaths,
     path: &Path,
-) -> Result<NoteWithContent, String> {
+    converted_target: ConvertedImportTarget,
+) -> Result<OpenedNote, Message> {
     let outcome = scoped_file::with_scoped_file(path, None, read_file)
-        .map_err(|err| format!("Read failed: {err}"))?;
+        .map_err(|err| Message::new("error.read_failed").arg("error", err))?;
+
+    let converted = match detect_format(path, &outcome.value.content) {
+        ImportFormat::Markdown | ImportFormat::PlainText => {
+            return link_file(conn, paths, path, outcome)
+        }
+        ImportFormat::Html => html::to_markdown(&outcome.value.content),
+        ImportFormat::Rtf => rtf::to_text(&outcome.value.content),
+    };
+    let source_path = outcome.resolved_path.unwrap_or_else(|| path.to_path_buf());
+
+    if converted_target == ConvertedImportTarget::Sibling {
+        match write_sibling(&source_path, &converted) {
+            Ok(sibling) => return import_file(conn, paths, &sibling, converted_target),
+            Err(err) => logs::error(
+                "import",
+                &format!(
+                    "sibling write failed, importing as draft: path=\"{}\" err=\"{err}\"",
+                    source_path.display()
+                ),
+            ),
+        }
+    }
+
+    let meta = create_filled(conn, paths, |conn, draft| {
+        write_draft(conn, &draft.id, &converted)
+    })?;
+    Ok(OpenedNote {
+        note: NoteWithContent {
+            meta,
+            content: converted,
+        },
+        created: true,
+    })
+}
+
+/// Imports a whole export from another app. With `dry_run` nothing is written; the report shows
+/// what would be created.
+pub(super) fn import_external(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    source: ExternalSource,
+    path: &Path,
+    dry_run: bool,
+) -> Result<ExternalImportReport, Message> {
+    external::import(conn, paths, source, path, dry_run)
+}
+
+fn detect_format(path: &Path, content: &str) -> ImportFormat {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match ext.as_str() {
+        "md" | "markdown" | "mdown" | "mkd" | "mdx" => return ImportFormat::Markdown,
+        "txt" | "text" => return ImportFormat::PlainText,
+        "html" | "htm" | "xhtml" => return ImportFormat::Html,
+        "rtf" => return ImportFormat::Rtf,
+        _ => {}
+    }
+
+    let head = content.trim_start();
+    if head.starts_with("{\\rtf") {
+        return ImportFormat::Rtf;
+    }
+    let lowered: String = head
+        .chars()
+        .take(64)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if lowered.starts_with("<!doctype html") || lowered.starts_with("<html") {
+        return ImportFormat::Html;
+    }
+    ImportFormat::Markdown
+}
+
+/// Writes converted markdown next to the source (`page.html` -> `page.md`), never overwriting.
+fn write_sibling(source: &Path, content: &str) -> Result<PathBuf, Message> {
+    let parent = source
+        .parent()
+        .ok_or_else(|| Message::new("error.invalid_path"))?;
+    let stem = source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .map_or_else(|| i18n::t("note.imported_title"), str::to_string);
+
+    let mut candidate = parent.join(format!("{stem}.md"));
+    let mut counter = 2;
+    while candidate.exists() {
+        candidate = parent.join(format!("{stem} ({counter}).md"));
+        counter += 1;
+    }
+
+    scoped_file::with_scoped_file(&candidate, None, |p| {
+        write_file(p, content, TextFormat::UTF8_LF)
+    })?;
+    Ok(candidate)
+}
+
+fn link_file(
+    conn: &Connection,
+    paths: &AppPaths,
+    path: &Path,
+    outcome: ScopedOutcome<DecodedText>,
+) -> Result<OpenedNote, Message> {
     let effective_path_buf = outcome.resolved_path.unwrap_or_else(|| path.to_path_buf());
     let effective_path = effective_path_buf.as_path();
-    let content = outcome.value;
+    let decoded = outcome.value;
     let mut refreshed_bookmark = outcome.refreshed_bookmark;
 
     let existing: Option<String> = conn
@@ -74,7 +203,11 @@ pub(super) fn import_file(
             .map_err(|err| err.to_string())?;
         }
 
-        let (title, preview) = derive_title_preview(&content);
+        update_format(conn, &id, decoded.format_or(TextFormat::of(&meta)))?;
+
+        let body = frontmatter::sync(conn, &id, &decoded.content, BlockSource::File)?;
+        let (title, preview) = frontmatter::title_preview(conn, &id, body)?;
+        index_body(conn, &id, body)?;
         let now = now_ms();
         conn.execute(
             "UPDATE notes SET title = ?1, preview = ?2, last_interaction = ?3 WHERE id = ?4",
@@ -84,38 +217,86 @@ pub(super) fn import_file(
 
         set_active(conn, &id)?;
         let meta = get_meta(conn, &id)?;
-        return Ok(NoteWithContent { meta, content });
+        return Ok(OpenedNote {
+            note: NoteWithContent {
+                meta,
+                content: body.to_string(),
+            },
+            created: false,
+        });
     }
 
-    let (title, preview) = derive_title_preview(&content);
     let now = now_ms();
     let sort_order = next_sort_order(conn)?;
     let id = Uuid::new_v4().to_string();
+    let format = decoded.format_or(TextFormat::UTF8_LF);
 
     conn.execute(
         r#"
 INSERT INTO notes (
   id, title, preview, file_path, storage, bookmark, is_pinned, is_trashed,
-  sort_order, created_at, last_interaction, trashed_at
+  sort_order, created_at, last_interaction, trashed_at, encoding, has_bom, line_ending
 ) VALUES (
   ?1, ?2, ?3, ?4, ?5, ?6, 0, 0,
-  ?7, ?8, ?9, NULL
+  ?7, ?8, ?9, NULL, ?10, ?11, ?12
 )
 "#,
         params![
             id,
-            title,
-            preview,
+            "",
+            "",
             effective_path.to_string_lossy(),
             storage_to_db(NoteStorage::Saved),
             refreshed_bookmark,
             sort_order,
             now,
-            now
+            now,
+            encoding_to_db(format.encoding),
+            format.has_bom as i64,
+            line_ending_to_db(format.line_ending)
         ],
     )
     .map_err(|err| err.to_string())?;
 
+    let body = frontmatter::sync(conn, &id, &decoded.content, BlockSource::File)?;
+    let (title, preview) = frontmatter::title_preview(conn, &id, body)?;
+    index_body(conn, &id, body)?;
+    conn.execute(
+        "UPDATE notes SET title = ?1, preview = ?2 WHERE id = ?3",
+        params![title, preview, id],
+    )
+    .map_err(|err| err.to_string())?;
+
     let meta = get_meta(conn, &id)?;
-    Ok(NoteWithContent { meta, content })
+    Ok(OpenedNote {
+        note: NoteWithContent {
+            meta,
+            content: body.to_string(),
+        },
+        created: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+
+    #[test]
+    fn linked_files_get_their_expiry_directive() {
+        let env = TestEnv::new();
+        let path = env.paths.app_data_dir.join("plan.md");
+        std::fs::write(&path, "# Plan\n\nkeep until 2099-01-31\n").unwrap();
+
+        let opened = import_file(&env.conn, &env.paths, &path, Default::default()).unwrap();
+        assert!(opened.created);
+        let directive = opened.note.meta.expiry_directive.unwrap();
+        assert_eq!(directive.phrase, "keep until 2099-01-31");
+
+        // Importing it again re-reads the directive.
+        std::fs::write(&path, "# Plan\n").unwrap();
+        let opened = import_file(&env.conn, &env.paths, &path, Default::default()).unwrap();
+        assert!(!opened.created);
+        assert_eq!(opened.note.meta.expiry_directive, None);
+    }
 }
@@ -0,0 +1,461 @@
+//! Bulk import of notes exported from other apps. Parsers turn an export into
+//! [`ExternalNote`]s; this module plans how they map onto notes (pin limit, times, trash) and
+//! commits them in a single transaction.
+
+use crate::app_state::AppPaths;
+use crate::i18n::Message;
+use crate::scoped_file;
+use crate::types::{
+    ExternalImportItem, ExternalImportReport, ExternalImportSkip, ExternalSource, NoteStorage,
+};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::super::content_index::index_body;
+use super::super::derive::derive_title_preview;
+use super::super::encoding::TextFormat;
+use super::super::files::write_file;
+use super::super::frontmatter;
+use super::super::meta::storage_to_db;
+use super::super::ordering::{next_pinned_sort_order, next_sort_order, pinned_count, MAX_PINNED};
+use super::super::time::now_ms;
+use super::{keep, obsidian, simplenote};
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct ExternalNote {
+    pub source_id: String,
+    pub title: Option<String>,
+    /// Markdown body, without the title.
+    pub body: String,
+    pub checklist: Vec<(String, bool)>,
+    pub tags: Vec<String>,
+    pub is_pinned: bool,
+    pub is_archived: bool,
+    pub is_trashed: bool,
+    pub created_at: Option<i64>,
+    pub modified_at: Option<i64>,
+    pub expires_at: Option<i64>,
+    /// YAML between the `---` fences, kept in front of the body as the note's block.
+    pub front_matter: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ParsedExport {
+    pub notes: Vec<ExternalNote>,
+    pub skipped: Vec<ExternalImportSkip>,
+}
+
+struct PlannedNote {
+    item: ExternalImportItem,
+    content: String,
+}
+
+pub(super) fn import(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    source: ExternalSource,
+    path: &Path,
+    dry_run: bool,
+) -> Result<ExternalImportReport, Message> {
+    let parsed = scoped_file::with_scoped_file(path, None, |p| match source {
+        ExternalSource::Keep => keep::parse(p),
+        ExternalSource::Simplenote => simplenote::parse(p),
+        ExternalSource::Obsidian => obsidian::parse(p),
+    })
+    .map_err(|err| Message::new("error.import_failed").arg("error", err))?
+    .value;
+
+    let (mut planned, skipped) = plan(conn, parsed)?;
+    if !dry_run {
+        commit(conn, paths, &mut planned)?;
+    }
+
+    Ok(ExternalImportReport {
+        source,
+        committed: !dry_run,
+        notes: planned.into_iter().map(|note| note.item).collect(),
+        skipped,
+    })
+}
+
+fn plan(
+    conn: &Connection,
+    parsed: ParsedExport,
+) -> Result<(Vec<PlannedNote>, Vec<ExternalImportSkip>), Message> {
+    let now = now_ms();
+    let mut skipped = parsed.skipped;
+    let mut notes = parsed.notes;
+    // Most recently edited first, so they win the remaining pin slots.
+    notes.sort_by_key(|note| std::cmp::Reverse(note.modified_at.or(note.created_at)));
+
+    let mut pin_slots = (MAX_PINNED - pinned_count(conn)?).max(0);
+    let mut planned = Vec::with_capacity(notes.len());
+    for note in notes {
+        let content = render_content(&note);
+        if content.trim().is_empty() {
+            skipped.push(ExternalImportSkip {
+                source_id: note.source_id,
+                reason: Message::new("error.import_empty_note").to_string(),
+            });
+            continue;
+        }
+
+        // Notes have no archive here; archived ones go to the trash, out of the list but
+        // restorable, rather than showing up among the active notes.
+        let is_trashed = note.is_trashed || note.is_archived;
+        let wants_pin = note.is_pinned && !is_trashed;
+        let is_pinned = wants_pin && pin_slots > 0;
+        if is_pinned {
+            pin_slots -= 1;
+        }
+
+        let created_at = note.created_at.or(note.modified_at).unwrap_or(now);
+        let last_interaction = note.modified_at.unwrap_or(created_at).max(created_at);
+        let (title, preview) = derive_title_preview(&content);
+        planned.push(PlannedNote {
+            item: ExternalImportItem {
+                source_id: note.source_id,
+                title,
+                preview,
+                tags: note.tags,
+                is_pinned,
+                pin_dropped: wants_pin && !is_pinned,
+                is_archived: note.is_archived,
+                is_trashed,
+                created_at,
+                last_interaction,
+                expires_at: note.expires_at,
+                note_id: None,
+            },
+            content,
+        });
+    }
+
+    Ok((planned, skipped))
+}
+
+fn commit(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    planned: &mut [PlannedNote],
+) -> Result<(), Message> {
+    let mut written: Vec<PathBuf> = Vec::new();
+    let result = insert_all(conn, paths, planned, &mut written);
+    if result.is_err() {
+        for path in written {
+            let _ = std::fs::remove_file(path);
+        }
+        for note in planned.iter_mut() {
+            note.item.note_id = None;
+        }
+    }
+    result
+}
+
+fn insert_all(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    planned: &mut [PlannedNote],
+    written: &mut Vec<PathBuf>,
+) -> Result<(), Message> {
+    let now = now_ms();
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let mut sort_order = next_sort_order(&tx)?;
+    let mut pinned_sort_order = next_pinned_sort_order(&tx)?;
+
+    for note in planned.iter_mut() {
+        let id = Uuid::new_v4().to_string();
+        // Notes from the source's trash or archive start a fresh retention period in ours.
+        let (dir, trashed_at) = if note.item.is_trashed {
+            (&paths.trash_dir, Some(now))
+        } else {
+            (&paths.drafts_dir, None)
+        };
+        let file_path = dir.join(format!("{id}.md"));
+        write_file(&file_path, &note.content, TextFormat::UTF8_LF)
+            .map_err(|err| Message::new("error.write_failed").arg("error", err))?;
+        written.push(file_path.clone());
+
+        // The block is stored like a linked file's, so the editor only gets the body and keys
+        // the app doesn't know survive.
+        let (block, body) = match frontmatter::split(&note.content) {
+            Some((block, body)) => (Some(block), body),
+            None => (None, note.content.as_str()),
+        };
+        let order = if note.item.is_pinned {
+            pinned_sort_order += 1;
+            pinned_sort_order - 1
+        } else {
+            sort_order += 1;
+            sort_order - 1
+        };
+
+        tx.execute(
+            r#"
+INSERT INTO notes (
+  id, title, preview, file_path, storage, bookmark, is_pinned, is_trashed,
+  sort_order, created_at, last_interaction, trashed_at, expires_at, front_matter
+) VALUES (
+  ?1, ?2, ?3, ?4, ?5, NULL, ?6, ?7,
+  ?8, ?9, ?10, ?11, ?12, ?13
+)
+"#,
+            params![
+                id,
+                note.item.title,
+                note.item.preview,
+                file_path.to_string_lossy(),
+                storage_to_db(NoteStorage::Draft),
+                note.item.is_pinned as i64,
+                note.item.is_trashed as i64,
+                order,
+                note.item.created_at,
+                note.item.last_interaction,
+                trashed_at,
+                note.item.expires_at,
+                block
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        index_body(&tx, &id, body)?;
+        note.item.note_id = Some(id);
+    }
+
+    tx.commit().map_err(|err| err.to_string().into())
+}
+
+fn render_content(note: &ExternalNote) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    if let Some(title) = note.title.as_deref().map(str::trim) {
+        if !title.is_empty() {
+            blocks.push(format!("# {title}"));
+        }
+    }
+
+    let body = note.body.replace("\r\n", "\n");
+    let body = body.trim_matches('\n');
+    if !body.trim().is_empty() {
+        blocks.push(body.to_string());
+    }
+
+    if !note.checklist.is_empty() {
+        let items: Vec<String> = note
+            .checklist
+            .iter()
+            .filter(|(text, _)| !text.trim().is_empty())
+            .map(|(text, checked)| {
+                let mark = if *checked { "x" } else { " " };
+                format!(
+                    "- [{mark}] {}",
+                    text.split_whitespace().collect::<Vec<_>>().join(" ")
+                )
+            })
+            .collect();
+        if !items.is_empty() {
+            blocks.push(items.join("\n"));
+        }
+    }
+
+    if blocks.is_empty() {
+        return String::new();
+    }
+
+    if !note.tags.is_empty() {
+        let tags: Vec<String> = note
+            .tags
+            .iter()
+            .map(|tag| format!("#{}", tag.split_whitespace().collect::<Vec<_>>().join("-")))
+            .collect();
+        blocks.push(tags.join(" "));
+    }
+
+    let body = blocks.join("\n\n") + "\n";
+    match note.front_matter.as_deref() {
+        Some(block) => frontmatter::join(block, &body),
+        None => body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+
+    fn external(source_id: &str, modified_at: i64) -> ExternalNote {
+        ExternalNote {
+            source_id: source_id.to_string(),
+            body: format!("Note {source_id}"),
+            is_pinned: true,
+            modified_at: Some(modified_at),
+            ..Default::default()
+        }
+    }
+
+    fn parsed(notes: Vec<ExternalNote>) -> ParsedExport {
+        ParsedExport {
+            notes,
+            skipped: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn renders_title_checklist_and_tags() {
+        let note = ExternalNote {
+            title: Some("Groceries".to_string()),
+            checklist: vec![
+                ("Milk".to_string(), false),
+                ("Bread\nrye".to_string(), true),
+            ],
+            tags: vec!["home stuff".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            render_content(&note),
+            "# Groceries\n\n- [ ] Milk\n- [x] Bread rye\n\n#home-stuff\n"
+        );
+    }
+
+    #[test]
+    fn tags_alone_are_empty() {
+        let note = ExternalNote {
+            tags: vec!["x".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(render_content(&note), "");
+    }
+
+    #[test]
+    fn the_most_recent_pins_take_the_free_slots() {
+        let env = TestEnv::new();
+        for n in 0..MAX_PINNED - 2 {
+            let id = env.draft(&format!("Pinned {n}"));
+            crate::notes::set_pinned(&env.conn, &id, true).unwrap();
+        }
+        let archived = ExternalNote {
+            is_archived: true,
+            ..external("archived", 50)
+        };
+        let trashed = ExternalNote {
+            is_trashed: true,
+            ..external("trashed", 40)
+        };
+        let blank = ExternalNote {
+            body: "\n".to_string(),
+            ..external("blank", 60)
+        };
+        let notes = vec![
+            external("old", 10),
+            archived,
+            external("new", 30),
+            trashed,
+            external("middle", 20),
+            blank,
+        ];
+
+        let (planned, skipped) = plan(&env.conn, parsed(notes)).unwrap();
+        let summary: Vec<(&str, bool, bool, bool)> = planned
+            .iter()
+            .map(|note| {
+                let item = &note.item;
+                (
+                    item.source_id.as_str(),
+                    item.is_pinned,
+                    item.pin_dropped,
+                    item.is_trashed,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("archived", false, false, true),
+                ("trashed", false, false, true),
+                ("new", true, false, false),
+                ("middle", true, false, false),
+                ("old", false, true, false),
+            ]
+        );
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].source_id, "blank");
+    }
+
+    #[test]
+    fn imported_directives_are_indexed() {
+        let mut env = TestEnv::new();
+        let note = ExternalNote {
+            body: "Ship it\n!expire in 2h".to_string(),
+            ..external("directive", 10)
+        };
+        let (mut planned, _) = plan(&env.conn, parsed(vec![note])).unwrap();
+
+        commit(&mut env.conn, &env.paths, &mut planned).unwrap();
+        let id = planned[0].item.note_id.clone().unwrap();
+        let directive: Option<String> = env
+            .conn
+            .query_row(
+                "SELECT expiry_directive FROM notes WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(directive.as_deref(), Some("!expire in 2h"));
+    }
+
+    #[test]
+    fn front_matter_is_kept_as_the_block() {
+        let mut env = TestEnv::new();
+        let note = ExternalNote {
+            body: "Body".to_string(),
+            front_matter: Some("aliases: [Plan]\ncssclasses: wide\n".to_string()),
+            ..external("plan", 10)
+        };
+        let (mut planned, _) = plan(&env.conn, parsed(vec![note])).unwrap();
+
+        commit(&mut env.conn, &env.paths, &mut planned).unwrap();
+        let id = planned[0].item.note_id.clone().unwrap();
+        let block: Option<String> = env
+            .conn
+            .query_row(
+                "SELECT front_matter FROM notes WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(
+            block.as_deref(),
+            Some("aliases: [Plan]\ncssclasses: wide\n")
+        );
+        let file = crate::notes::get_meta(&env.conn, &id).unwrap().file_path;
+        assert_eq!(
+            std::fs::read_to_string(file).unwrap(),
+            "---\naliases: [Plan]\ncssclasses: wide\n---\nBody\n"
+        );
+        assert_eq!(crate::notes::get(&env.conn, &id).unwrap().content, "Body\n");
+    }
+
+    #[test]
+    fn a_failed_insert_removes_the_written_files() {
+        let mut env = TestEnv::new();
+        let notes = vec![external("first", 20), external("second", 10)];
+        let (mut planned, _) = plan(&env.conn, parsed(notes)).unwrap();
+        env.conn
+            .execute_batch(
+                r#"
+CREATE TEMP TRIGGER fail_second BEFORE INSERT ON notes
+WHEN (SELECT COUNT(*) FROM notes) > 0
+BEGIN SELECT RAISE(ABORT, 'disk full'); END;
+"#,
+            )
+            .unwrap();
+
+        assert!(commit(&mut env.conn, &env.paths, &mut planned).is_err());
+        assert!(planned.iter().all(|note| note.item.note_id.is_none()));
+        let count: i64 = env
+            .conn
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(std::fs::read_dir(&env.paths.drafts_dir).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,744 @@
+//! Converts exported HTML (Confluence pages, e-mails, saved web pages) into GFM markdown.
+//! This is a forgiving tokenizer, not a spec-compliant parser: unknown tags are dropped and
+//! their text is kept.
+
+pub(super) fn to_markdown(html: &str) -> String {
+    let mut converter = Converter::default();
+    for token in tokenize(html) {
+        converter.token(token);
+    }
+    converter.finish()
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(String),
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    End(String),
+    Raw(&'a str),
+}
+
+/// Elements whose content is never rendered.
+const SKIPPED: &[&str] = &["head", "script", "style", "template", "noscript", "svg"];
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if lt > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..lt])));
+            rest = &rest[lt..];
+        }
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map(|end| &after[end + 3..]).unwrap_or("");
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map(|end| &rest[end + 1..]).unwrap_or("");
+            continue;
+        }
+
+        let next = rest[1..].chars().next();
+        let is_tag = matches!(next, Some(c) if c.is_ascii_alphabetic() || c == '/');
+        if !is_tag {
+            tokens.push(Token::Text("<".to_string()));
+            rest = &rest[1..];
+            continue;
+        }
+
+        let Some(gt) = find_tag_end(rest) else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        let inner = &rest[1..gt];
+        rest = &rest[gt + 1..];
+
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(Token::End(name.trim().to_ascii_lowercase()));
+            continue;
+        }
+
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_end = inner
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+        let attrs = parse_attrs(&inner[name_end..]);
+
+        // Raw text elements end at their closing tag, whatever they contain.
+        if name == "script" || name == "style" {
+            let close = format!("</{name}");
+            let end = find_ascii_case_insensitive(rest, &close).unwrap_or(rest.len());
+            tokens.push(Token::Start {
+                name: name.clone(),
+                attrs,
+                self_closing: false,
+            });
+            tokens.push(Token::Raw(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+
+        tokens.push(Token::Start {
+            name,
+            attrs,
+            self_closing,
+        });
+    }
+    tokens
+}
+
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (idx, ch) in s.char_indices().skip(1) {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '>') => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_ascii_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            name.push(c.to_ascii_lowercase());
+        }
+        if name.is_empty() {
+            break;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            match chars.peek().copied() {
+                Some(q @ ('"' | '\'')) => {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if c == q {
+                            break;
+                        }
+                        value.push(c);
+                    }
+                }
+                _ => {
+                    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                        value.push(c);
+                    }
+                }
+            }
+        }
+        attrs.push((name, decode_entities(&value)));
+    }
+    attrs
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[derive(Default)]
+struct Table {
+    rows: Vec<Vec<String>>,
+    has_header: bool,
+}
+
+#[derive(Default)]
+struct Converter {
+    out: String,
+    line: String,
+    lists: Vec<List>,
+    pending_marker: Option<String>,
+    quote_depth: usize,
+    pre: Option<(String, String)>,
+    links: Vec<Link>,
+    table: Option<Table>,
+    cell: Option<String>,
+    code_depth: usize,
+    /// Quote depth at the block break that still owes a blank line.
+    pending_blank: Option<usize>,
+    skip_depth: usize,
+}
+
+/// An open `<a>`: where its text starts in the buffer it opened in, and its target.
+struct Link {
+    start: usize,
+    href: String,
+    /// Opened in the line buffer, which block breaks flush before the link closes.
+    in_line: bool,
+}
+
+struct List {
+    /// `None` for bullet lists, `Some(next_number)` for ordered lists.
+    next_number: Option<u32>,
+    /// Column of the list markers.
+    indent: usize,
+    /// Column of the current item's content, where nested blocks line up.
+    content_indent: usize,
+}
+
+impl Converter {
+    fn token(&mut self, token: Token<'_>) {
+        match token {
+            Token::Raw(_) => {}
+            Token::Text(text) => {
+                if self.skip_depth == 0 {
+                    self.text(&text);
+                }
+            }
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                if SKIPPED.contains(&name.as_str()) {
+                    if !self_closing {
+                        self.skip_depth += 1;
+                    }
+                } else if self.skip_depth == 0 {
+                    self.start(&name, &attrs);
+                }
+            }
+            Token::End(name) => {
+                if SKIPPED.contains(&name.as_str()) {
+                    self.skip_depth = self.skip_depth.saturating_sub(1);
+                } else if self.skip_depth == 0 {
+                    self.end(&name);
+                }
+            }
+        }
+    }
+
+    fn target(&mut self) -> &mut String {
+        if let Some((_, code)) = self.pre.as_mut() {
+            return code;
+        }
+        if let Some(cell) = self.cell.as_mut() {
+            return cell;
+        }
+        &mut self.line
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some((_, code)) = self.pre.as_mut() {
+            code.push_str(text);
+            return;
+        }
+        let text = text.replace('\u{a0}', " ");
+        let escaped = if self.code_depth > 0 {
+            text
+        } else {
+            escape_markdown(&text)
+        };
+        let target = self.target();
+        for word_or_space in split_whitespace_runs(&escaped) {
+            if word_or_space.trim().is_empty() {
+                if !target.is_empty() && !target.ends_with([' ', '\n']) {
+                    target.push(' ');
+                }
+            } else {
+                target.push_str(word_or_space);
+            }
+        }
+    }
+
+    fn push_inline(&mut self, s: &str) {
+        self.target().push_str(s);
+    }
+
+    fn start(&mut self, name: &str, attrs: &[(String, String)]) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block_break();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.line.push_str(&"#".repeat(level));
+                self.line.push(' ');
+            }
+            "p" | "div" | "section" | "article" | "header" | "footer" | "main" | "aside"
+            | "nav" | "figure" | "dl" | "dt" | "dd" | "address" => self.block_break(),
+            "br" => {
+                if self.cell.is_some() {
+                    self.push_inline(" ");
+                } else if let Some((_, code)) = self.pre.as_mut() {
+                    code.push('\n');
+                } else {
+                    self.line.push_str("  \n");
+                }
+            }
+            "hr" => {
+                self.block_break();
+                self.line.push_str("---");
+                self.block_break();
+            }
+            "ul" | "ol" => {
+                self.line_break();
+                let start = attr(attrs, "start")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(1);
+                let indent = self.lists.last().map_or(0, |list| list.content_indent);
+                self.lists.push(List {
+                    next_number: (name == "ol").then_some(start),
+                    indent,
+                    content_indent: indent,
+                });
+            }
+            "li" => {
+                self.line_break();
+                if self.lists.is_empty() {
+                    self.lists.push(List {
+                        next_number: None,
+                        indent: 0,
+                        content_indent: 0,
+                    });
+                }
+                if let Some(list) = self.lists.last_mut() {
+                    let marker = match list.next_number.as_mut() {
+                        Some(next) => {
+                            *next += 1;
+                            format!("{}. ", *next - 1)
+                        }
+                        None => "- ".to_string(),
+                    };
+                    list.content_indent = list.indent + marker.len();
+                    self.pending_marker = Some(format!("{}{marker}", " ".repeat(list.indent)));
+                }
+            }
+            "input" if attr(attrs, "type") == Some("checkbox") => {
+                let checked = attr(attrs, "checked").is_some();
+                self.push_inline(if checked { "[x] " } else { "[ ] " });
+            }
+            "blockquote" => {
+                self.block_break();
+                self.quote_depth += 1;
+            }
+            "pre" => {
+                self.block_break();
+                let lang = attr(attrs, "class").and_then(language_from_class);
+                self.pre = Some((lang.unwrap_or_default(), String::new()));
+            }
+            "code" => {
+                if let Some((lang, _)) = self.pre.as_mut() {
+                    if lang.is_empty() {
+                        *lang = attr(attrs, "class")
+                            .and_then(language_from_class)
+                            .unwrap_or_default();
+                    }
+                } else {
+                    self.code_depth += 1;
+                    self.push_inline("`");
+                }
+            }
+            "strong" | "b" => self.push_inline("**"),
+            "em" | "i" => self.push_inline("*"),
+            "del" | "s" | "strike" => self.push_inline("~~"),
+            "a" => {
+                let href = attr(attrs, "href").unwrap_or_default().to_string();
+                let in_line = self.pre.is_none() && self.cell.is_none();
+                let start = self.target().len();
+                self.links.push(Link {
+                    start,
+                    href,
+                    in_line,
+                });
+            }
+            "img" => {
+                let src = attr(attrs, "src").unwrap_or_default();
+                if !src.is_empty() {
+                    let alt = attr(attrs, "alt").unwrap_or_default();
+                    let image = format!("![{}]({src})", escape_markdown(alt));
+                    self.push_inline(&image);
+                }
+            }
+            "table" => {
+                self.block_break();
+                self.table = Some(Table::default());
+            }
+            "tr" => {
+                if let Some(table) = self.table.as_mut() {
+                    table.rows.push(Vec::new());
+                }
+            }
+            "th" | "td" => {
+                if let Some(table) = self.table.as_mut() {
+                    if name == "th" && table.rows.len() <= 1 {
+                        table.has_header = true;
+                    }
+                    self.cell = Some(String::new());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "div" | "section" | "article"
+            | "header" | "footer" | "main" | "aside" | "nav" | "figure" | "dl" | "dt" | "dd"
+            | "address" => self.block_break(),
+            "ul" | "ol" => {
+                self.line_break();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block_break();
+                }
+            }
+            "li" => self.line_break(),
+            "blockquote" => {
+                self.line_break();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.block_break();
+            }
+            "pre" => {
+                if let Some((lang, code)) = self.pre.take() {
+                    let fenced = format!("```{lang}\n{}\n```", code.trim_end_matches('\n'));
+                    self.emit(&fenced, true);
+                    self.block_break();
+                }
+            }
+            "code" if self.pre.is_none() => {
+                self.code_depth = self.code_depth.saturating_sub(1);
+                self.push_inline("`");
+            }
+            "strong" | "b" => self.push_inline("**"),
+            "em" | "i" => self.push_inline("*"),
+            "del" | "s" | "strike" => self.push_inline("~~"),
+            "a" => {
+                if let Some(Link { start, href, .. }) = self.links.pop() {
+                    let target = self.target();
+                    // A link that ends in another buffer than it started in keeps its text.
+                    let pos = match start.min(target.len()) {
+                        pos if target.is_char_boundary(pos) => pos,
+                        _ => target.len(),
+                    };
+                    let text = target[pos..].trim().to_string();
+                    target.truncate(pos);
+                    if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+                        target.push_str(&text);
+                    } else if text.is_empty() {
+                        target.push_str(&format!("<{href}>"));
+                    } else {
+                        target.push_str(&format!("[{text}]({href})"));
+                    }
+                }
+            }
+            "th" | "td" => {
+                if let (Some(cell), Some(table)) = (self.cell.take(), self.table.as_mut()) {
+                    let cell = cell.trim().replace('|', "\\|");
+                    match table.rows.last_mut() {
+                        Some(row) => row.push(cell),
+                        None => table.rows.push(vec![cell]),
+                    }
+                }
+            }
+            "table" => {
+                if let Some(table) = self.table.take() {
+                    let rendered = render_table(table);
+                    if !rendered.is_empty() {
+                        self.emit(&rendered, true);
+                    }
+                    self.block_break();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Ends the current line; list items stay on consecutive lines.
+    fn line_break(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        // Links still open continue at the start of the next line.
+        for link in self.links.iter_mut().filter(|link| link.in_line) {
+            link.start = 0;
+        }
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            self.emit(trimmed, false);
+        }
+    }
+
+    /// Writes finished lines, applying the quote prefix and list indentation. Preformatted
+    /// blocks (`verbatim`) keep their own leading whitespace.
+    fn emit(&mut self, text: &str, verbatim: bool) {
+        let quote = "> ".repeat(self.quote_depth);
+        if let Some(depth) = self.pending_blank.take() {
+            if !self.out.is_empty() {
+                self.out
+                    .push_str("> ".repeat(depth.min(self.quote_depth)).trim_end());
+                self.out.push('\n');
+            }
+        }
+
+        let marker = self.pending_marker.take().unwrap_or_default();
+        let content_indent = self.lists.last().map_or(0, |list| list.content_indent);
+        let indent = if marker.is_empty() {
+            " ".repeat(content_indent)
+        } else {
+            String::new()
+        };
+        let continuation = " ".repeat(content_indent);
+        for (idx, part) in text.split('\n').enumerate() {
+            self.out.push_str(&quote);
+            if idx == 0 {
+                self.out.push_str(&indent);
+                self.out.push_str(&marker);
+            } else {
+                self.out.push_str(&continuation);
+            }
+            if verbatim {
+                self.out.push_str(part);
+            } else {
+                self.out.push_str(part.trim_start_matches(' '));
+            }
+            self.out.push('\n');
+        }
+    }
+
+    /// Ends the current block; the separating blank line is written lazily so it picks up the
+    /// quote depth of whatever follows. Blocks inside lists stay on consecutive lines.
+    fn block_break(&mut self) {
+        self.line_break();
+        if self.lists.is_empty() {
+            let depth = self
+                .pending_blank
+                .map_or(self.quote_depth, |d| d.min(self.quote_depth));
+            self.pending_blank = Some(depth);
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.line_break();
+        if let Some((lang, code)) = self.pre.take() {
+            let fenced = format!("```{lang}\n{}\n```", code.trim_end_matches('\n'));
+            self.emit(&fenced, true);
+        }
+        if let Some(table) = self.table.take() {
+            let rendered = render_table(table);
+            if !rendered.is_empty() {
+                self.emit(&rendered, true);
+            }
+        }
+        let trimmed = self.out.trim_matches('\n');
+        if trimmed.is_empty() {
+            return String::new();
+        }
+        format!("{trimmed}\n")
+    }
+}
+
+fn render_table(table: Table) -> String {
+    let rows: Vec<Vec<String>> = table.rows.into_iter().filter(|r| !r.is_empty()).collect();
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let mut rows = rows.into_iter();
+    let header = if table.has_header {
+        rows.next().unwrap_or_default()
+    } else {
+        vec![String::new(); columns]
+    };
+
+    let render_row = |row: &[String]| {
+        let mut cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        cells.resize(columns, "");
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![
+        render_row(&header),
+        format!("|{}", " --- |".repeat(columns)),
+    ];
+    lines.extend(rows.map(|row| render_row(&row)));
+    lines.join("\n")
+}
+
+fn language_from_class(class: &str) -> Option<String> {
+    class.split_whitespace().find_map(|name| {
+        name.strip_prefix("language-")
+            .or_else(|| name.strip_prefix("lang-"))
+            .map(str::to_string)
+    })
+}
+
+fn split_whitespace_runs(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_space: Option<bool> = None;
+    for (idx, ch) in s.char_indices() {
+        let is_space = ch.is_whitespace() && ch != '\u{a0}';
+        if in_space.is_some_and(|prev| prev != is_space) {
+            parts.push(&s[start..idx]);
+            start = idx;
+        }
+        in_space = Some(is_space);
+    }
+    if start < s.len() {
+        parts.push(&s[start..]);
+    }
+    parts
+}
+
+fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '`' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Character references: named ones from the full HTML5 table, numeric ones when they name a
+/// character other than NUL. Anything else stays as written.
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+            .map(|idx| idx + 1)
+            .unwrap_or(rest.len());
+        let name = &rest[1..end];
+        let decoded = if let Some(num) = name.strip_prefix('#') {
+            let code = match num.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => num.parse::<u32>().ok(),
+            };
+            code.filter(|&code| code != 0)
+                .and_then(char::from_u32)
+                .map(String::from)
+        } else {
+            named_entity(name).map(String::from)
+        };
+        match decoded {
+            Some(text) => {
+                out.push_str(&text);
+                rest = &rest[end..];
+                rest = rest.strip_prefix(';').unwrap_or(rest);
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn named_entity(name: &str) -> Option<&'static str> {
+    htmlize::ENTITIES
+        .get(format!("&{name};").as_bytes())
+        .and_then(|text| std::str::from_utf8(text).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headings_and_paragraphs() {
+        let md = to_markdown("<html><head><title>x</title></head><body><h1>Title</h1><p>Hello <b>bold</b> world</p></body></html>");
+        assert_eq!(md, "# Title\n\nHello **bold** world\n");
+    }
+
+    #[test]
+    fn nested_lists() {
+        let md = to_markdown("<ul><li>One<ul><li>Inner</li></ul></li><li>Two</li></ul><ol start=\"3\"><li>Three</li></ol>");
+        assert_eq!(md, "- One\n  - Inner\n- Two\n\n3. Three\n");
+    }
+
+    #[test]
+    fn links_code_and_entities() {
+        let md = to_markdown(
+            "<p>See <a href=\"https://example.com\">the&nbsp;docs</a> &amp; <code>cargo_run&#32;--x</code></p>",
+        );
+        assert_eq!(
+            md,
+            "See [the docs](https://example.com) & `cargo_run --x`\n"
+        );
+    }
+
+    #[test]
+    fn decodes_the_whole_entity_table() {
+        assert_eq!(decode_entities("&hearts; &Omega; &frac12;"), "♥ Ω ½");
+        assert_eq!(
+            decode_entities("a&#0;b &#x0; &#xD800; &bogus; &amp"),
+            "a&#0;b &#x0; &#xD800; &bogus; &"
+        );
+    }
+
+    #[test]
+    fn block_inside_a_link() {
+        let md = to_markdown("<p>abc<a href=\"u\"><div>x</div>éé</a></p>");
+        assert_eq!(md, "abc\n\nx\n\n[éé](u)\n");
+        let md = to_markdown("<a href=\"u\">one<p>two</p>three</a>");
+        assert_eq!(md, "one\n\ntwo\n\n[three](u)\n");
+    }
+
+    #[test]
+    fn preformatted_code() {
+        let md = to_markdown(
+            "<pre><code class=\"language-rust\">fn main() {\n    1 &lt; 2;\n}\n</code></pre>",
+        );
+        assert_eq!(md, "```rust\nfn main() {\n    1 < 2;\n}\n```\n");
+    }
+
+    #[test]
+    fn tables() {
+        let md = to_markdown(
+            "<table><tr><th>Name</th><th>Age</th></tr><tr><td>Ann</td><td>3|4</td></tr></table>",
+        );
+        assert_eq!(md, "| Name | Age |\n| --- | --- |\n| Ann | 3\\|4 |\n");
+    }
+
+    #[test]
+    fn skips_scripts_and_comments() {
+        let md = to_markdown(
+            "<p>a<!-- hidden --></p><script>if (a < b) {}</script><style>p{}</style><p>b</p>",
+        );
+        assert_eq!(md, "a\n\nb\n");
+    }
+
+    #[test]
+    fn checklists_and_quotes() {
+        let md = to_markdown(
+            "<blockquote><p>Quoted</p></blockquote><ul><li><input type=\"checkbox\" checked> Done</li><li><input type=\"checkbox\"> Todo</li></ul>",
+        );
+        assert_eq!(md, "> Quoted\n\n- [x] Done\n- [ ] Todo\n");
+    }
+}
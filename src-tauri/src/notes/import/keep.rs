@@ -0,0 +1,188 @@
+//! Google Keep Takeout: one `.json` (plus an `.html` rendering) per note in `Takeout/Keep`.
+//! Attachments are not imported.
+
+use crate::i18n::Message;
+use crate::types::ExternalImportSkip;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::super::files::{modified_ms, read_file};
+use super::external::{ExternalNote, ParsedExport};
+use super::html;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct KeepNote {
+    title: String,
+    text_content: String,
+    list_content: Vec<KeepListItem>,
+    labels: Vec<KeepLabel>,
+    is_pinned: bool,
+    is_archived: bool,
+    is_trashed: bool,
+    created_timestamp_usec: Option<i64>,
+    user_edited_timestamp_usec: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct KeepListItem {
+    text: String,
+    is_checked: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KeepLabel {
+    name: String,
+}
+
+pub(super) fn parse(path: &Path) -> Result<ParsedExport, Message> {
+    let dir = find_keep_dir(path).ok_or_else(|| Message::new("error.keep_missing"))?;
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|err| err.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+
+    let mut parsed = ParsedExport::default();
+    let mut json_stems = HashSet::new();
+    for file in files.iter().filter(|file| has_extension(file, "json")) {
+        let source_id = file_name(file);
+        match read_file(file).and_then(|decoded| parse_note(&decoded.content)) {
+            Ok(mut note) => {
+                note.source_id = source_id;
+                json_stems.insert(file.file_stem().map(|stem| stem.to_os_string()));
+                parsed.notes.push(note);
+            }
+            Err(err) => parsed.skipped.push(ExternalImportSkip {
+                source_id,
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    // Older exports only contain the HTML rendering.
+    for file in files.iter().filter(|file| has_extension(file, "html")) {
+        if json_stems.contains(&file.file_stem().map(|stem| stem.to_os_string())) {
+            continue;
+        }
+        let source_id = file_name(file);
+        match read_file(file) {
+            Ok(decoded) => parsed.notes.push(ExternalNote {
+                source_id,
+                body: html::to_markdown(&decoded.content),
+                modified_at: modified_ms(file),
+                ..Default::default()
+            }),
+            Err(err) => parsed.skipped.push(ExternalImportSkip {
+                source_id,
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_note(json: &str) -> Result<ExternalNote, Message> {
+    let note: KeepNote = serde_json::from_str(json)
+        .map_err(|err| Message::new("error.keep_invalid").arg("error", err))?;
+    Ok(ExternalNote {
+        source_id: String::new(),
+        title: Some(note.title).filter(|title| !title.trim().is_empty()),
+        body: note.text_content,
+        checklist: note
+            .list_content
+            .into_iter()
+            .map(|item| (item.text, item.is_checked))
+            .collect(),
+        tags: note
+            .labels
+            .into_iter()
+            .map(|label| label.name)
+            .filter(|name| !name.trim().is_empty())
+            .collect(),
+        is_pinned: note.is_pinned,
+        is_archived: note.is_archived,
+        is_trashed: note.is_trashed,
+        created_at: note.created_timestamp_usec.map(|usec| usec / 1_000),
+        modified_at: note.user_edited_timestamp_usec.map(|usec| usec / 1_000),
+        expires_at: None,
+        front_matter: None,
+    })
+}
+
+fn find_keep_dir(path: &Path) -> Option<PathBuf> {
+    [
+        path.to_path_buf(),
+        path.join("Keep"),
+        path.join("Takeout").join("Keep"),
+    ]
+    .into_iter()
+    .find(|dir| {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .any(|entry| has_extension(&entry.path(), "json"))
+            })
+            .unwrap_or(false)
+    })
+    .or_else(|| path.is_dir().then(|| path.to_path_buf()))
+}
+
+fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|value| value.to_str())
+        .is_some_and(|value| value.eq_ignore_ascii_case(ext))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_checklist_note() {
+        let note = parse_note(
+            r#"{
+              "color": "DEFAULT",
+              "isTrashed": false,
+              "isPinned": true,
+              "isArchived": false,
+              "listContent": [
+                {"textHtml": "Milk", "text": "Milk", "isChecked": false},
+                {"textHtml": "Eggs", "text": "Eggs", "isChecked": true}
+              ],
+              "title": "Shopping",
+              "userEditedTimestampUsec": 1700000000123456,
+              "createdTimestampUsec": 1690000000000000,
+              "labels": [{"name": "Home"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(note.title.as_deref(), Some("Shopping"));
+        assert_eq!(
+            note.checklist,
+            vec![("Milk".to_string(), false), ("Eggs".to_string(), true)]
+        );
+        assert_eq!(note.tags, vec!["Home".to_string()]);
+        assert!(note.is_pinned);
+        assert_eq!(note.modified_at, Some(1_700_000_000_123));
+        assert_eq!(note.created_at, Some(1_690_000_000_000));
+    }
+
+    #[test]
+    fn rejects_unrelated_json() {
+        assert!(parse_note("[1, 2]").is_err());
+    }
+}
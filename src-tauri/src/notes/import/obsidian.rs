@@ -0,0 +1,138 @@
+//! Obsidian vault folder: every markdown file below it (hidden folders such as `.obsidian` are
+//! skipped). Front matter maps onto note fields and is kept as the note's block, so keys like
+//! `aliases` survive; the note title is the file name unless the front matter sets one.
+
+use crate::i18n::Message;
+use crate::types::ExternalImportSkip;
+use std::path::{Path, PathBuf};
+
+use super::super::files::{modified_ms, read_file};
+use super::super::frontmatter;
+use super::external::{ExternalNote, ParsedExport};
+
+pub(super) fn parse(path: &Path) -> Result<ParsedExport, Message> {
+    if !path.is_dir() {
+        return Err(Message::new("error.obsidian_folder"));
+    }
+
+    let mut files = Vec::new();
+    collect_markdown(path, &mut files)?;
+    files.sort();
+
+    let mut parsed = ParsedExport::default();
+    for file in files {
+        let source_id = file
+            .strip_prefix(path)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .to_string();
+        match read_file(&file) {
+            Ok(decoded) => {
+                let stem = file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                parsed.notes.push(to_note(
+                    source_id,
+                    &stem,
+                    &decoded.content,
+                    modified_ms(&file),
+                ));
+            }
+            Err(err) => parsed.skipped.push(ExternalImportSkip {
+                source_id,
+                reason: err.to_string(),
+            }),
+        }
+    }
+    Ok(parsed)
+}
+
+fn to_note(source_id: String, stem: &str, content: &str, modified_at: Option<i64>) -> ExternalNote {
+    let (front_matter, body) = frontmatter::parse(content);
+    // Only a block that parsed; an invalid one stays in the body as written.
+    let block = frontmatter::split(content)
+        .map(|(block, _)| block.to_string())
+        .filter(|_| body.len() < content.len());
+    let title = front_matter.title.unwrap_or_else(|| stem.to_string());
+    let starts_with_title = body
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| line.trim().strip_prefix("# "))
+        .is_some_and(|heading| heading.trim() == title.trim());
+
+    // Tags already written inline stay where they are.
+    let inline = frontmatter::inline_tags(body);
+    let tags = front_matter
+        .tags
+        .into_iter()
+        .filter(|tag| !inline.contains(tag))
+        .collect();
+
+    ExternalNote {
+        source_id,
+        title: (!starts_with_title).then_some(title),
+        body: body.to_string(),
+        checklist: Vec::new(),
+        tags,
+        is_pinned: front_matter.pinned.unwrap_or(false),
+        is_archived: false,
+        is_trashed: false,
+        created_at: front_matter.created,
+        modified_at,
+        expires_at: front_matter.expires,
+        front_matter: block,
+    }
+}
+
+fn collect_markdown(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Message> {
+    let entries = std::fs::read_dir(dir).map_err(|err| err.to_string())?;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_markdown(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_front_matter_onto_note() {
+        let note = to_note(
+            "Inbox/Call Anna.md".to_string(),
+            "Call Anna",
+            "---\npinned: true\ntags: [calls, todo]\nexpires: 2030-01-01\naliases: [Anna]\n---\nAbout the offer #todo\n",
+            Some(5),
+        );
+        assert_eq!(
+            note.front_matter.as_deref(),
+            Some("pinned: true\ntags: [calls, todo]\nexpires: 2030-01-01\naliases: [Anna]\n")
+        );
+        assert_eq!(note.title.as_deref(), Some("Call Anna"));
+        assert_eq!(note.body, "About the offer #todo\n");
+        assert_eq!(note.tags, vec!["calls"]);
+        assert!(note.is_pinned);
+        assert!(note.expires_at.is_some());
+        assert_eq!(note.modified_at, Some(5));
+    }
+
+    #[test]
+    fn keeps_existing_heading() {
+        let note = to_note("a.md".to_string(), "Plan", "# Plan\n\nBody\n", None);
+        assert_eq!(note.title, None);
+        assert_eq!(note.front_matter, None);
+    }
+}
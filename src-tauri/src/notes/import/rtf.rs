@@ -0,0 +1,260 @@
+//! Extracts the plain text of an RTF document. Formatting is dropped; paragraphs, line breaks
+//! and tabs are kept.
+
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// Destinations whose content is metadata rather than document text.
+const IGNORED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "object",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "footnote",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "generator",
+    "xmlnstbl",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "latentstyles",
+    "filetbl",
+    "revtbl",
+];
+
+#[derive(Clone, Copy)]
+struct GroupState {
+    skip: bool,
+    unicode_skip: usize,
+}
+
+pub(super) fn to_text(rtf: &str) -> String {
+    let mut out = String::new();
+    let mut pending_bytes: Vec<u8> = Vec::new();
+    let mut codepage: &'static Encoding = WINDOWS_1252;
+    let mut stack: Vec<GroupState> = Vec::new();
+    let mut state = GroupState {
+        skip: false,
+        unicode_skip: 1,
+    };
+    // Characters still to drop after a `\uN` escape (its ANSI fallback).
+    let mut fallback_left = 0usize;
+
+    let bytes = rtf.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let byte = bytes[idx];
+        match byte {
+            b'{' => {
+                flush_bytes(&mut pending_bytes, codepage, &mut out);
+                stack.push(state);
+                fallback_left = 0;
+                idx += 1;
+            }
+            b'}' => {
+                flush_bytes(&mut pending_bytes, codepage, &mut out);
+                state = stack.pop().unwrap_or(state);
+                fallback_left = 0;
+                idx += 1;
+            }
+            b'\\' => {
+                idx += 1;
+                let Some(&next) = bytes.get(idx) else {
+                    break;
+                };
+
+                if next == b'\'' {
+                    let hex = rtf.get(idx + 1..idx + 3).unwrap_or("");
+                    idx += 3;
+                    if fallback_left > 0 {
+                        fallback_left -= 1;
+                        continue;
+                    }
+                    if let Ok(value) = u8::from_str_radix(hex, 16) {
+                        if !state.skip {
+                            pending_bytes.push(value);
+                        }
+                    }
+                    continue;
+                }
+
+                if !next.is_ascii_alphabetic() {
+                    idx += 1;
+                    if fallback_left > 0 {
+                        fallback_left -= 1;
+                        continue;
+                    }
+                    if state.skip {
+                        continue;
+                    }
+                    match next {
+                        b'\\' | b'{' | b'}' => {
+                            push_char(&mut pending_bytes, codepage, &mut out, next as char)
+                        }
+                        b'~' => push_char(&mut pending_bytes, codepage, &mut out, ' '),
+                        b'_' => push_char(&mut pending_bytes, codepage, &mut out, '-'),
+                        b'*' => state.skip = true,
+                        b'\n' | b'\r' => push_char(&mut pending_bytes, codepage, &mut out, '\n'),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                let word_start = idx;
+                while idx < bytes.len() && bytes[idx].is_ascii_alphabetic() {
+                    idx += 1;
+                }
+                let word = &rtf[word_start..idx];
+                let param_start = idx;
+                if idx < bytes.len() && bytes[idx] == b'-' {
+                    idx += 1;
+                }
+                while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+                    idx += 1;
+                }
+                let param = rtf[param_start..idx].parse::<i32>().ok();
+                if idx < bytes.len() && bytes[idx] == b' ' {
+                    idx += 1;
+                }
+
+                if IGNORED_DESTINATIONS.contains(&word) {
+                    state.skip = true;
+                    continue;
+                }
+                if fallback_left > 0 {
+                    fallback_left -= 1;
+                    continue;
+                }
+
+                match word {
+                    "ansicpg" => {
+                        codepage = param
+                            .and_then(encoding_for_codepage)
+                            .unwrap_or(WINDOWS_1252);
+                    }
+                    "uc" => state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+                    "u" if !state.skip => {
+                        // Code points above 32767 are written as negative numbers.
+                        let code = param.unwrap_or(0);
+                        let code = (if code < 0 { code + 65_536 } else { code }) as u32;
+                        flush_bytes(&mut pending_bytes, codepage, &mut out);
+                        if let Some(ch) = char::from_u32(code) {
+                            out.push(ch);
+                        }
+                        fallback_left = state.unicode_skip;
+                    }
+                    _ if state.skip => {}
+                    "par" | "line" | "sect" | "page" => {
+                        push_char(&mut pending_bytes, codepage, &mut out, '\n')
+                    }
+                    "tab" => push_char(&mut pending_bytes, codepage, &mut out, '\t'),
+                    "cell" => push_char(&mut pending_bytes, codepage, &mut out, '\t'),
+                    "row" => push_char(&mut pending_bytes, codepage, &mut out, '\n'),
+                    "emdash" => push_char(&mut pending_bytes, codepage, &mut out, '—'),
+                    "endash" => push_char(&mut pending_bytes, codepage, &mut out, '–'),
+                    "bullet" => push_char(&mut pending_bytes, codepage, &mut out, '•'),
+                    "lquote" => push_char(&mut pending_bytes, codepage, &mut out, '‘'),
+                    "rquote" => push_char(&mut pending_bytes, codepage, &mut out, '’'),
+                    "ldblquote" => push_char(&mut pending_bytes, codepage, &mut out, '“'),
+                    "rdblquote" => push_char(&mut pending_bytes, codepage, &mut out, '”'),
+                    _ => {}
+                }
+            }
+            b'\r' | b'\n' => idx += 1,
+            _ => {
+                let ch_len = utf8_len(byte);
+                if fallback_left > 0 {
+                    fallback_left -= 1;
+                } else if !state.skip {
+                    if byte.is_ascii() {
+                        pending_bytes.push(byte);
+                    } else {
+                        flush_bytes(&mut pending_bytes, codepage, &mut out);
+                        out.push_str(rtf.get(idx..idx + ch_len).unwrap_or(""));
+                    }
+                }
+                idx += ch_len;
+            }
+        }
+    }
+    flush_bytes(&mut pending_bytes, codepage, &mut out);
+
+    let lines: Vec<&str> = out.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_matches('\n').to_string() + "\n"
+}
+
+fn push_char(pending: &mut Vec<u8>, codepage: &'static Encoding, out: &mut String, ch: char) {
+    flush_bytes(pending, codepage, out);
+    out.push(ch);
+}
+
+fn flush_bytes(pending: &mut Vec<u8>, codepage: &'static Encoding, out: &mut String) {
+    if pending.is_empty() {
+        return;
+    }
+    out.push_str(&codepage.decode_without_bom_handling(pending).0);
+    pending.clear();
+}
+
+fn encoding_for_codepage(codepage: i32) -> Option<&'static Encoding> {
+    let label = match codepage {
+        437 | 850 | 1252 => "windows-1252",
+        874 => "windows-874",
+        932 => "shift_jis",
+        936 => "gbk",
+        949 => "euc-kr",
+        950 => "big5",
+        1250..=1258 => return Encoding::for_label(format!("windows-{codepage}").as_bytes()),
+        10000 => "macintosh",
+        65001 => "utf-8",
+        _ => return None,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+fn utf8_len(first_byte: u8) -> usize {
+    match first_byte {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_paragraphs() {
+        let rtf = r"{\rtf1\ansi\ansicpg1252\deff0{\fonttbl{\f0\fswiss Helvetica;}}{\colortbl;\red0\green0\blue0;}
+\f0\fs24 \b Meeting\b0 \par
+Gr\'fc\'dfe an alle\line zweite Zeile\par
+}";
+        assert_eq!(to_text(rtf), "Meeting\nGrüße an alle\nzweite Zeile\n");
+    }
+
+    #[test]
+    fn unicode_escapes_skip_fallback() {
+        let rtf = r"{\rtf1\ansi\uc1 caf\u233?\par {\*\generator Riched20;}end}";
+        assert_eq!(to_text(rtf), "café\nend\n");
+    }
+
+    #[test]
+    fn escaped_braces() {
+        let rtf = r"{\rtf1 a \{b\} c\\d\tab e}";
+        assert_eq!(to_text(rtf), "a {b} c\\d\te\n");
+    }
+}
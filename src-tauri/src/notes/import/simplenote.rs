@@ -0,0 +1,119 @@
+//! Simplenote export: `source/notes.json` inside the exported (and unzipped) archive.
+
+use crate::i18n::Message;
+use chrono::DateTime;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use super::super::files::read_file;
+use super::external::{ExternalNote, ParsedExport};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SimplenoteExport {
+    active_notes: Vec<SimplenoteNote>,
+    trashed_notes: Vec<SimplenoteNote>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SimplenoteNote {
+    id: String,
+    content: String,
+    creation_date: String,
+    last_modified: String,
+    pinned: bool,
+    tags: Vec<String>,
+}
+
+pub(super) fn parse(path: &Path) -> Result<ParsedExport, Message> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        return Err(Message::new("error.simplenote_zip"));
+    }
+
+    let file = find_notes_json(path).ok_or_else(|| Message::new("error.simplenote_missing"))?;
+    let json = read_file(&file)?.content;
+    parse_export(&json)
+}
+
+fn parse_export(json: &str) -> Result<ParsedExport, Message> {
+    let export: SimplenoteExport = serde_json::from_str(json)
+        .map_err(|err| Message::new("error.simplenote_invalid").arg("error", err))?;
+
+    let active = export.active_notes.into_iter().map(|note| (note, false));
+    let trashed = export.trashed_notes.into_iter().map(|note| (note, true));
+    let notes = active
+        .chain(trashed)
+        .map(|(note, is_trashed)| ExternalNote {
+            source_id: note.id,
+            title: None,
+            body: note.content,
+            checklist: Vec::new(),
+            tags: note.tags,
+            is_pinned: note.pinned,
+            is_archived: false,
+            is_trashed,
+            created_at: parse_date(&note.creation_date),
+            modified_at: parse_date(&note.last_modified),
+            expires_at: None,
+            front_matter: None,
+        })
+        .collect();
+
+    Ok(ParsedExport {
+        notes,
+        skipped: Vec::new(),
+    })
+}
+
+fn find_notes_json(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    [
+        path.join("source").join("notes.json"),
+        path.join("notes.json"),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())
+}
+
+fn parse_date(raw: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|date| date.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_active_and_trashed_notes() {
+        let parsed = parse_export(
+            r#"{
+              "activeNotes": [{
+                "id": "a1",
+                "content": "Title\r\nBody",
+                "creationDate": "2023-05-01T10:00:00.000Z",
+                "lastModified": "2023-05-02T10:00:00.000Z",
+                "pinned": true,
+                "tags": ["work"]
+              }],
+              "trashedNotes": [{"id": "t1", "content": "Old"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.notes.len(), 2);
+        let active = &parsed.notes[0];
+        assert!(active.is_pinned && !active.is_trashed);
+        assert_eq!(active.tags, vec!["work".to_string()]);
+        assert_eq!(active.created_at, Some(1_682_935_200_000));
+        assert_eq!(active.modified_at, Some(1_683_021_600_000));
+        assert!(parsed.notes[1].is_trashed);
+        assert_eq!(parsed.notes[1].created_at, None);
+    }
+}
@@ -0,0 +1,360 @@
+//! `[[wikilinks]]` between notes. Links are stored as written, per source note, and resolved
+//! when queried, so renaming or trashing a note never leaves a stale resolution behind.
+
+use crate::i18n::Message;
+use crate::types::{LinkStatus, NoteLink};
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use super::meta::display_title;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParsedLink {
+    target: String,
+    label: Option<String>,
+}
+
+struct Target {
+    id: String,
+    title: String,
+    is_trashed: bool,
+    last_interaction: i64,
+}
+
+/// Replaces the stored outgoing links of `id` with the ones in `body`.
+pub(super) fn index(conn: &Connection, id: &str, body: &str) -> Result<(), Message> {
+    conn.execute("DELETE FROM note_links WHERE source_id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+    for (position, link) in parse(body).into_iter().enumerate() {
+        conn.execute(
+            "INSERT INTO note_links (source_id, position, target, label) VALUES (?1, ?2, ?3, ?4)",
+            params![id, position as i64, link.target, link.label],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+pub(super) fn outgoing(conn: &Connection, id: &str) -> Result<Vec<NoteLink>, Message> {
+    let resolver = Resolver::load(conn)?;
+    let source_title = resolver.title_of(id).unwrap_or_default();
+    let mut stmt = conn
+        .prepare("SELECT target, label FROM note_links WHERE source_id = ?1 ORDER BY position")
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(target, label)| resolver.link(id, &source_title, target, label))
+        .collect())
+}
+
+/// Links in active notes that resolve to `id`, most recently used source first.
+pub(super) fn backlinks(conn: &Connection, id: &str) -> Result<Vec<NoteLink>, Message> {
+    let resolver = Resolver::load(conn)?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT l.source_id, s.title, l.target, l.label
+FROM note_links l
+JOIN notes s ON s.id = l.source_id
+WHERE s.is_trashed = 0 AND l.source_id != ?1
+ORDER BY s.last_interaction DESC, l.source_id, l.position
+"#,
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                display_title(row.get(1)?),
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(source_id, source_title, target, label)| {
+            resolver.link(&source_id, &source_title, target, label)
+        })
+        .filter(|link| link.target_id.as_deref() == Some(id))
+        .collect())
+}
+
+/// Wikilinks outside code, in document order. `[[Title#Heading]]` and `[[Title^block]]` link to
+/// the note itself.
+fn parse(body: &str) -> Vec<ParsedLink> {
+    let mut links = Vec::new();
+    let mut open: Option<(String, bool, String)> = None;
+    for event in Parser::new_ext(body, Options::ENABLE_WIKILINKS | Options::ENABLE_TABLES) {
+        match event {
+            Event::Start(Tag::Link {
+                link_type: LinkType::WikiLink { has_pothole },
+                dest_url,
+                ..
+            }) => open = Some((dest_url.to_string(), has_pothole, String::new())),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, label)) = open.as_mut() {
+                    label.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                let Some((dest, has_pothole, label)) = open.take() else {
+                    continue;
+                };
+                let target = dest.split(['#', '^']).next().unwrap_or_default().trim();
+                if target.is_empty() {
+                    continue;
+                }
+                let label = label.trim();
+                links.push(ParsedLink {
+                    target: target.to_string(),
+                    label: (has_pothole && !label.is_empty()).then(|| label.to_string()),
+                });
+            }
+            _ => {}
+        }
+    }
+    links
+}
+
+/// All notes, keyed by id and by lowercased title.
+struct Resolver {
+    notes: Vec<Target>,
+    by_id: HashMap<String, usize>,
+    by_title: HashMap<String, Vec<usize>>,
+}
+
+impl Resolver {
+    fn load(conn: &Connection) -> Result<Self, Message> {
+        let mut stmt = conn
+            .prepare("SELECT id, title, is_trashed, last_interaction FROM notes")
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(Target {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    is_trashed: row.get::<_, i64>(2)? != 0,
+                    last_interaction: row.get(3)?,
+                })
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+
+        let mut notes = Vec::with_capacity(rows.len());
+        let mut by_id = HashMap::new();
+        let mut by_title: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, mut note) in rows.into_iter().enumerate() {
+            by_id.insert(note.id.clone(), idx);
+            // Untitled notes have no name to link to.
+            if !note.title.is_empty() {
+                by_title
+                    .entry(title_key(&note.title))
+                    .or_default()
+                    .push(idx);
+            }
+            note.title = display_title(note.title);
+            notes.push(note);
+        }
+        Ok(Self {
+            notes,
+            by_id,
+            by_title,
+        })
+    }
+
+    fn title_of(&self, id: &str) -> Option<String> {
+        self.by_id.get(id).map(|&idx| self.notes[idx].title.clone())
+    }
+
+    /// An id wins over a title. Several notes with the same title resolve to an active one
+    /// before a trashed one, then to the most recently used, and are marked ambiguous.
+    fn link(
+        &self,
+        source_id: &str,
+        source_title: &str,
+        target: String,
+        label: Option<String>,
+    ) -> NoteLink {
+        let (resolved, candidates) = match self.by_id.get(&target) {
+            Some(&idx) => (Some(&self.notes[idx]), 1),
+            None => {
+                let matches = self
+                    .by_title
+                    .get(&title_key(&target))
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let best = matches
+                    .iter()
+                    .map(|&idx| &self.notes[idx])
+                    .max_by_key(|note| (!note.is_trashed, note.last_interaction));
+                let active = matches
+                    .iter()
+                    .filter(|&&idx| !self.notes[idx].is_trashed)
+                    .count();
+                (best, active)
+            }
+        };
+
+        let status = match resolved {
+            None => LinkStatus::Missing,
+            Some(note) if note.is_trashed => LinkStatus::Dangling,
+            Some(_) if candidates > 1 => LinkStatus::Ambiguous,
+            Some(_) => LinkStatus::Resolved,
+        };
+        NoteLink {
+            source_id: source_id.to_string(),
+            source_title: source_title.to_string(),
+            target,
+            label,
+            target_id: resolved.map(|note| note.id.clone()),
+            target_title: resolved.map(|note| note.title.clone()),
+            status,
+        }
+    }
+}
+
+fn title_key(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+    use crate::notes::{self, get_meta};
+
+    fn link(target: &str, label: Option<&str>) -> ParsedLink {
+        ParsedLink {
+            target: target.to_string(),
+            label: label.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_wikilinks_outside_code() {
+        let body = "See [[Plan]] and [[Plan#Q3|the Q3 part]].\n\n```\n[[Not a link]]\n```\n`[[inline]]` [[ ]] [[abc-123^block]]\n";
+        assert_eq!(
+            parse(body),
+            vec![
+                link("Plan", None),
+                link("Plan", Some("the Q3 part")),
+                link("abc-123", None),
+            ]
+        );
+    }
+
+    fn statuses(env: &TestEnv, id: &str) -> Vec<(String, Option<String>, LinkStatus)> {
+        outgoing(&env.conn, id)
+            .unwrap()
+            .into_iter()
+            .map(|link| (link.target, link.target_id, link.status))
+            .collect()
+    }
+
+    fn touch(env: &TestEnv, id: &str, last_interaction: i64) {
+        env.conn
+            .execute(
+                "UPDATE notes SET last_interaction = ?1 WHERE id = ?2",
+                params![last_interaction, id],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn resolves_titles_and_ids_first() {
+        let env = TestEnv::new();
+        let plan = env.draft("# Plan");
+        // A note titled like another note's id loses to that note.
+        let decoy = env.draft(&format!("# {plan}"));
+        let source = env.draft(&format!("[[plan]] [[{plan}]] [[Nowhere]]"));
+
+        assert_eq!(
+            statuses(&env, &source),
+            vec![
+                ("plan".to_string(), Some(plan.clone()), LinkStatus::Resolved),
+                (plan.clone(), Some(plan.clone()), LinkStatus::Resolved),
+                ("Nowhere".to_string(), None, LinkStatus::Missing),
+            ]
+        );
+        assert!(backlinks(&env.conn, &decoy).unwrap().is_empty());
+    }
+
+    #[test]
+    fn shared_titles_are_ambiguous_and_pick_the_most_recent() {
+        let env = TestEnv::new();
+        let older = env.draft("# Twin");
+        let newer = env.draft("# Twin");
+        touch(&env, &older, 1_000);
+        touch(&env, &newer, 2_000);
+        let source = env.draft("[[Twin]]");
+
+        assert_eq!(
+            statuses(&env, &source),
+            vec![(
+                "Twin".to_string(),
+                Some(newer.clone()),
+                LinkStatus::Ambiguous
+            )]
+        );
+
+        // A trashed twin no longer competes.
+        notes::trash(&env.conn, &env.paths, &newer).unwrap();
+        assert_eq!(
+            statuses(&env, &source),
+            vec![("Twin".to_string(), Some(older), LinkStatus::Resolved)]
+        );
+    }
+
+    #[test]
+    fn links_to_expired_notes_dangle() {
+        let env = TestEnv::new();
+        let old = env.draft("# Old idea");
+        let source = env.draft("See [[Old idea]]");
+
+        let changes = notes::trash_expired(&env.conn, &env.paths, std::slice::from_ref(&old));
+        assert_eq!(changes.len(), 1);
+        assert!(get_meta(&env.conn, &old).unwrap().is_trashed);
+        assert_eq!(
+            statuses(&env, &source),
+            vec![("Old idea".to_string(), Some(old), LinkStatus::Dangling)]
+        );
+    }
+
+    #[test]
+    fn backlinks_leave_out_trashed_sources() {
+        let env = TestEnv::new();
+        let target = env.draft("# Target");
+        let active = env.draft("# Active\n\n[[Target]]");
+        let trashed = env.draft("# Trashed\n\n[[Target]]");
+        notes::trash(&env.conn, &env.paths, &trashed).unwrap();
+
+        let links = backlinks(&env.conn, &target).unwrap();
+        let sources: Vec<&str> = links.iter().map(|link| link.source_id.as_str()).collect();
+        assert_eq!(sources, [active.as_str()]);
+        assert_eq!(links[0].source_title, "Active");
+        assert_eq!(links[0].status, LinkStatus::Resolved);
+    }
+
+    #[test]
+    fn title_keys_ignore_case_and_spacing() {
+        assert_eq!(title_key("  Über   Plan "), title_key("über plan"));
+    }
+}
@@ -1,15 +1,19 @@
+use crate::i18n::Message;
 use crate::types::NotesList;
 use rusqlite::Connection;
 
 use super::meta::row_to_meta;
 
-pub(super) fn list(conn: &Connection) -> Result<NotesList, String> {
+pub(super) fn list(conn: &Connection) -> Result<NotesList, Message> {
     let mut active_stmt = conn
         .prepare(
             r#"
 SELECT
   id, title, preview, file_path, storage, is_pinned, is_trashed, sort_order,
-  created_at, last_interaction, trashed_at
+  created_at, last_interaction, trashed_at, encoding, has_bom, line_ending, expires_at,
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 0),
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 1),
+  daily_date, remind_at, expiry_directive, directive_expires_at
 FROM notes
 WHERE is_trashed = 0
 ORDER BY is_pinned DESC, sort_order ASC
@@ -28,7 +32,10 @@ ORDER BY is_pinned DESC, sort_order ASC
             r#"
 SELECT
   id, title, preview, file_path, storage, is_pinned, is_trashed, sort_order,
-  created_at, last_interaction, trashed_at
+  created_at, last_interaction, trashed_at, encoding, has_bom, line_ending, expires_at,
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 0),
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 1),
+  daily_date, remind_at, expiry_directive, directive_expires_at
 FROM notes
 WHERE is_trashed = 1
 ORDER BY trashed_at DESC, sort_order ASC
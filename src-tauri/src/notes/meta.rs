@@ -1,12 +1,18 @@
-use crate::types::{NoteMeta, NoteStorage};
+use crate::i18n::{self, Message};
+use crate::types::{ExpiryDirective, LineEnding, NoteMeta, NoteStorage, TextEncoding};
 use rusqlite::{params, Connection};
 
-pub(super) fn get_meta(conn: &Connection, id: &str) -> Result<NoteMeta, String> {
+use super::encoding::TextFormat;
+
+pub(super) fn get_meta(conn: &Connection, id: &str) -> Result<NoteMeta, Message> {
     conn.query_row(
         r#"
 SELECT
   id, title, preview, file_path, storage, is_pinned, is_trashed, sort_order,
-  created_at, last_interaction, trashed_at
+  created_at, last_interaction, trashed_at, encoding, has_bom, line_ending, expires_at,
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 0),
+  (SELECT COUNT(*) FROM note_tasks t WHERE t.note_id = notes.id AND t.checked = 1),
+  daily_date, remind_at, expiry_directive, directive_expires_at
 FROM notes
 WHERE id = ?1
 LIMIT 1
@@ -14,14 +20,26 @@ LIMIT 1
         params![id],
         row_to_meta,
     )
-    .map_err(|err| err.to_string())
+    .map_err(|err| err.to_string().into())
+}
+
+/// Notes without a title are stored with an empty one and named here, so the name follows the
+/// current language instead of the one the note was created in.
+pub(super) fn display_title(title: String) -> String {
+    if title.is_empty() {
+        i18n::t("note.new_title")
+    } else {
+        title
+    }
 }
 
 pub(super) fn row_to_meta(row: &rusqlite::Row<'_>) -> rusqlite::Result<NoteMeta> {
     let storage_raw: String = row.get(4)?;
+    let encoding_raw: String = row.get(11)?;
+    let line_ending_raw: String = row.get(13)?;
     Ok(NoteMeta {
         id: row.get(0)?,
-        title: row.get(1)?,
+        title: display_title(row.get(1)?),
         preview: row.get(2)?,
         file_path: row.get(3)?,
         storage: storage_from_db(&storage_raw),
@@ -31,6 +49,18 @@ pub(super) fn row_to_meta(row: &rusqlite::Row<'_>) -> rusqlite::Result<NoteMeta>
         created_at: row.get(8)?,
         last_interaction: row.get(9)?,
         trashed_at: row.get(10)?,
+        encoding: encoding_from_db(&encoding_raw),
+        has_bom: row.get::<_, i64>(12)? != 0,
+        line_ending: line_ending_from_db(&line_ending_raw),
+        expires_at: row.get(14)?,
+        open_tasks: row.get(15)?,
+        done_tasks: row.get(16)?,
+        daily_date: row.get(17)?,
+        remind_at: row.get(18)?,
+        expiry_directive: match (row.get(19)?, row.get(20)?) {
+            (Some(phrase), Some(expires_at)) => Some(ExpiryDirective { phrase, expires_at }),
+            _ => None,
+        },
     })
 }
 
@@ -47,3 +77,55 @@ fn storage_from_db(raw: &str) -> NoteStorage {
         _ => NoteStorage::Draft,
     }
 }
+
+pub(super) fn update_format(
+    conn: &Connection,
+    id: &str,
+    format: TextFormat,
+) -> Result<(), Message> {
+    conn.execute(
+        "UPDATE notes SET encoding = ?1, has_bom = ?2, line_ending = ?3 WHERE id = ?4",
+        params![
+            encoding_to_db(format.encoding),
+            format.has_bom as i64,
+            line_ending_to_db(format.line_ending),
+            id
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub(super) fn encoding_to_db(encoding: TextEncoding) -> &'static str {
+    match encoding {
+        TextEncoding::Utf8 => "utf-8",
+        TextEncoding::Utf16Le => "utf-16le",
+        TextEncoding::Utf16Be => "utf-16be",
+        TextEncoding::Windows1252 => "windows-1252",
+    }
+}
+
+fn encoding_from_db(raw: &str) -> TextEncoding {
+    match raw {
+        "utf-16le" => TextEncoding::Utf16Le,
+        "utf-16be" => TextEncoding::Utf16Be,
+        "windows-1252" => TextEncoding::Windows1252,
+        _ => TextEncoding::Utf8,
+    }
+}
+
+pub(super) fn line_ending_to_db(line_ending: LineEnding) -> &'static str {
+    match line_ending {
+        LineEnding::Lf => "lf",
+        LineEnding::Crlf => "crlf",
+        LineEnding::Cr => "cr",
+    }
+}
+
+fn line_ending_from_db(raw: &str) -> LineEnding {
+    match raw {
+        "crlf" => LineEnding::Crlf,
+        "cr" => LineEnding::Cr,
+        _ => LineEnding::Lf,
+    }
+}
@@ -1,9 +1,12 @@
+use crate::i18n::Message;
 use crate::types::NoteMeta;
 use rusqlite::{params, Connection};
 
 use super::meta::get_meta;
 
-pub(super) fn next_sort_order(conn: &Connection) -> Result<i64, String> {
+pub(super) const MAX_PINNED: i64 = 5;
+
+pub(super) fn next_sort_order(conn: &Connection) -> Result<i64, Message> {
     let max_sort: i64 = conn
         .query_row(
             "SELECT COALESCE(MAX(sort_order), 0) FROM notes WHERE is_trashed = 0 AND is_pinned = 0",
@@ -14,35 +17,40 @@ pub(super) fn next_sort_order(conn: &Connection) -> Result<i64, String> {
     Ok(max_sort + 1)
 }
 
-pub(super) fn set_pinned(conn: &Connection, id: &str, pinned: bool) -> Result<NoteMeta, String> {
+pub(super) fn pinned_count(conn: &Connection) -> Result<i64, Message> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM notes WHERE is_pinned = 1 AND is_trashed = 0",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string().into())
+}
+
+pub(super) fn next_pinned_sort_order(conn: &Connection) -> Result<i64, Message> {
+    let max_sort: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(sort_order), 0) FROM notes WHERE is_pinned = 1 AND is_trashed = 0",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(max_sort + 1)
+}
+
+pub(super) fn set_pinned(conn: &Connection, id: &str, pinned: bool) -> Result<NoteMeta, Message> {
     let meta = get_meta(conn, id)?;
     if meta.is_pinned == pinned {
         return Ok(meta);
     }
 
     if pinned {
-        let pinned_count: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM notes WHERE is_pinned = 1 AND is_trashed = 0",
-                [],
-                |row| row.get(0),
-            )
-            .map_err(|err| err.to_string())?;
-        if pinned_count >= 5 {
-            return Err("You can only pin up to 5 notes.".to_string());
+        if pinned_count(conn)? >= MAX_PINNED {
+            return Err(Message::new("error.pin_limit").arg("max", MAX_PINNED));
         }
 
-        let max_pinned_sort: i64 = conn
-            .query_row(
-                "SELECT COALESCE(MAX(sort_order), 0) FROM notes WHERE is_pinned = 1 AND is_trashed = 0",
-                [],
-                |row| row.get(0),
-            )
-            .map_err(|err| err.to_string())?;
-
         conn.execute(
             "UPDATE notes SET is_pinned = 1, sort_order = ?1 WHERE id = ?2",
-            params![max_pinned_sort + 1, id],
+            params![next_pinned_sort_order(conn)?, id],
         )
         .map_err(|err| err.to_string())?;
     } else {
@@ -66,7 +74,7 @@ pub(super) fn set_pinned(conn: &Connection, id: &str, pinned: bool) -> Result<No
     get_meta(conn, id)
 }
 
-pub(super) fn reorder(conn: &mut Connection, ids: &[String]) -> Result<(), String> {
+pub(super) fn reorder(conn: &mut Connection, ids: &[String]) -> Result<(), Message> {
     let tx = conn.transaction().map_err(|err| err.to_string())?;
     for (idx, id) in ids.iter().enumerate() {
         tx.execute(
@@ -0,0 +1,666 @@
+//! Recurring notes: a template plus a schedule in a small RRULE subset
+//! (`FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0`). The scheduler turns the latest due occurrence
+//! into a draft; occurrences missed while the app was closed collapse into one catch-up note.
+
+use crate::app_state::AppPaths;
+use crate::i18n::Message;
+use crate::types::{Recurrence, RecurrenceInput};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Weekday};
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use super::templates;
+use super::time::now_ms;
+
+/// How far back catch-up and forward `next_occurrence_at` look.
+const SEARCH_DAYS: i64 = 366;
+
+pub(super) fn list(conn: &Connection) -> Result<Vec<Recurrence>, Message> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT id, name, template, rule, expiry_minutes, created_at, last_occurrence_at, last_error
+FROM recurrences
+ORDER BY name COLLATE NOCASE ASC, created_at ASC
+"#,
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], row_to_recurrence)
+        .map_err(|err| err.to_string())?;
+
+    let now = Local::now();
+    let mut recurrences = Vec::new();
+    for row in rows {
+        let mut recurrence = row.map_err(|err| err.to_string())?;
+        recurrence.next_occurrence_at = Rule::parse(&recurrence.rule).ok().and_then(|rule| {
+            let after = recurrence
+                .last_occurrence_at
+                .map_or(now.timestamp_millis(), |last| {
+                    last.max(now.timestamp_millis())
+                });
+            rule.next_after(local(recurrence.created_at)?, after)
+                .map(|at| at.timestamp_millis())
+        });
+        recurrences.push(recurrence);
+    }
+    Ok(recurrences)
+}
+
+/// Creates the recurrence, or updates it when `input.id` is set. A changed schedule only affects
+/// occurrences after the last generated one.
+pub(super) fn save(
+    conn: &Connection,
+    paths: &AppPaths,
+    input: &RecurrenceInput,
+) -> Result<Recurrence, Message> {
+    let template = input.template.trim();
+    templates::template_path(paths, template)?;
+    let rule = input.rule.trim();
+    Rule::parse(rule)?;
+    if input.expiry_minutes.is_some_and(|minutes| minutes <= 0) {
+        return Err(Message::new("error.expiry_min"));
+    }
+    if input
+        .expiry_minutes
+        .is_some_and(|minutes| minutes > super::MAX_EXPIRY_MINUTES)
+    {
+        return Err(Message::new("error.expiry_max").arg("days", super::MAX_EXPIRY_MINUTES / 1440));
+    }
+    let name = match input.name.trim() {
+        "" => template,
+        name => name,
+    };
+
+    let id = match &input.id {
+        Some(id) => {
+            let updated = conn
+                .execute(
+                    r#"
+UPDATE recurrences SET name = ?1, template = ?2, rule = ?3, expiry_minutes = ?4
+WHERE id = ?5
+"#,
+                    params![name, template, rule, input.expiry_minutes, id],
+                )
+                .map_err(|err| err.to_string())?;
+            if updated == 0 {
+                return Err(Message::new("error.recurring_missing").arg("id", id));
+            }
+            id.clone()
+        }
+        None => {
+            let id = Uuid::new_v4().to_string();
+            conn.execute(
+                r#"
+INSERT INTO recurrences (id, name, template, rule, expiry_minutes, created_at, last_occurrence_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL)
+"#,
+                params![id, name, template, rule, input.expiry_minutes, now_ms()],
+            )
+            .map_err(|err| err.to_string())?;
+            id
+        }
+    };
+
+    list(conn)?
+        .into_iter()
+        .find(|recurrence| recurrence.id == id)
+        .ok_or_else(|| Message::new("error.recurring_missing").arg("id", &id))
+}
+
+/// Removes the schedule; notes it already generated stay as ordinary drafts.
+pub(super) fn delete(conn: &Connection, id: &str) -> Result<(), Message> {
+    conn.execute(
+        "UPDATE notes SET recurrence_id = NULL WHERE recurrence_id = ?1",
+        params![id],
+    )
+    .map_err(|err| err.to_string())?;
+    conn.execute("DELETE FROM recurrences WHERE id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Creates a draft for every recurrence that has come due since its last occurrence and returns
+/// the new note ids. A recurrence that fails (missing template, broken rule) is skipped so it
+/// can't hold up the others; the failure is kept in `last_error` and logged only when it changes,
+/// so a deleted template doesn't log every minute. The occurrence stays due and is caught up once
+/// the template is back.
+pub(super) fn materialize_due(
+    conn: &mut Connection,
+    paths: &AppPaths,
+    now: DateTime<Local>,
+) -> Result<Vec<String>, Message> {
+    let due: Vec<Recurrence> = {
+        let mut stmt = conn
+            .prepare(
+                r#"
+SELECT id, name, template, rule, expiry_minutes, created_at, last_occurrence_at, last_error
+FROM recurrences
+"#,
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map([], row_to_recurrence)
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|err| err.to_string())?
+    };
+
+    let mut created = Vec::new();
+    for recurrence in due {
+        let tx = conn.transaction().map_err(|err| err.to_string())?;
+        let error = match materialize(&tx, paths, &recurrence, now) {
+            Ok(id) => {
+                tx.commit().map_err(|err| err.to_string())?;
+                created.extend(id);
+                None
+            }
+            // Dropping the transaction rolls back a half-made note.
+            Err(err) => {
+                drop(tx);
+                Some(err.to_string())
+            }
+        };
+        if error != recurrence.last_error {
+            set_last_error(conn, &recurrence.id, error.as_deref())?;
+        }
+    }
+    Ok(created)
+}
+
+fn set_last_error(conn: &Connection, id: &str, error: Option<&str>) -> Result<(), Message> {
+    if let Some(err) = error {
+        crate::logs::error(
+            "recurring",
+            &format!("materialize failed: id={id} err=\"{err}\""),
+        );
+    }
+    conn.execute(
+        "UPDATE recurrences SET last_error = ?1 WHERE id = ?2",
+        params![error, id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn materialize(
+    conn: &Connection,
+    paths: &AppPaths,
+    recurrence: &Recurrence,
+    now: DateTime<Local>,
+) -> Result<Option<String>, Message> {
+    let rule = Rule::parse(&recurrence.rule)?;
+    let anchor =
+        local(recurrence.created_at).ok_or_else(|| Message::new("error.recurrence_created_at"))?;
+    // Occurrences before the recurrence existed never count, even on the day it was set up.
+    let after = recurrence
+        .last_occurrence_at
+        .unwrap_or(recurrence.created_at - 1);
+    let Some(occurrence) = rule.latest_between(anchor, after, now) else {
+        return Ok(None);
+    };
+    let occurrence_ms = occurrence.timestamp_millis();
+
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM notes WHERE recurrence_id = ?1 AND occurrence_at = ?2",
+            params![recurrence.id, occurrence_ms],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let created = match existing {
+        Some(_) => None,
+        None => {
+            let note = templates::render(conn, paths, &recurrence.template, None, occurrence)?.note;
+            let id = note.meta.id;
+            conn.execute(
+                "UPDATE notes SET recurrence_id = ?1, occurrence_at = ?2 WHERE id = ?3",
+                params![recurrence.id, occurrence_ms, id],
+            )
+            .map_err(|err| err.to_string())?;
+            if let Some(minutes) = recurrence.expiry_minutes {
+                // Rows saved before the cap can still hold values that would overflow.
+                let expires_at = minutes
+                    .checked_mul(60_000)
+                    .and_then(|ms| now.timestamp_millis().checked_add(ms))
+                    .ok_or_else(|| {
+                        Message::new("error.expiry_max")
+                            .arg("days", super::MAX_EXPIRY_MINUTES / 1440)
+                    })?;
+                conn.execute(
+                    "UPDATE notes SET expires_at = ?1 WHERE id = ?2",
+                    params![expires_at, id],
+                )
+                .map_err(|err| err.to_string())?;
+            }
+            Some(id)
+        }
+    };
+
+    conn.execute(
+        "UPDATE recurrences SET last_occurrence_at = ?1 WHERE id = ?2",
+        params![occurrence_ms, recurrence.id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(created)
+}
+
+fn row_to_recurrence(row: &rusqlite::Row<'_>) -> rusqlite::Result<Recurrence> {
+    Ok(Recurrence {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        template: row.get(2)?,
+        rule: row.get(3)?,
+        expiry_minutes: row.get(4)?,
+        created_at: row.get(5)?,
+        last_occurrence_at: row.get(6)?,
+        next_occurrence_at: None,
+        last_error: row.get(7)?,
+    })
+}
+
+fn local(ms: i64) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(ms).single()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// A parsed schedule. Parts left out default to the recurrence's creation time, as `DTSTART`
+/// does for a real RRULE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    freq: Freq,
+    interval: i64,
+    by_day: Vec<Weekday>,
+    by_month_day: Option<u32>,
+    hour: Option<u32>,
+    minute: Option<u32>,
+}
+
+impl Rule {
+    fn parse(raw: &str) -> Result<Self, Message> {
+        let raw = raw.trim();
+        let raw = raw.strip_prefix("RRULE:").unwrap_or(raw);
+        let mut freq = None;
+        let mut rule = Rule {
+            freq: Freq::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: None,
+            hour: None,
+            minute: None,
+        };
+        for part in raw.split(';').filter(|part| !part.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| Message::new("error.schedule_part").arg("part", part))?;
+            let value = value.trim();
+            let invalid = || {
+                Message::new("error.schedule_value")
+                    .arg("key", key.trim())
+                    .arg("value", value)
+            };
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        _ => return Err(Message::new("error.schedule_freq").arg("value", value)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(invalid)?
+                }
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(|day| weekday(day.trim()).ok_or_else(invalid))
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|day| (1..=31).contains(day))
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                "BYHOUR" => {
+                    rule.hour = Some(value.parse().ok().filter(|h| *h < 24).ok_or_else(invalid)?)
+                }
+                "BYMINUTE" => {
+                    rule.minute = Some(value.parse().ok().filter(|m| *m < 60).ok_or_else(invalid)?)
+                }
+                other => return Err(Message::new("error.schedule_unsupported").arg("part", other)),
+            }
+        }
+        rule.freq = freq.ok_or_else(|| Message::new("error.schedule_no_freq"))?;
+        if rule.freq == Freq::Monthly && !rule.by_day.is_empty() {
+            return Err(Message::new("error.schedule_by_day"));
+        }
+        if rule.freq != Freq::Monthly && rule.by_month_day.is_some() {
+            return Err(Message::new("error.schedule_by_month_day"));
+        }
+        Ok(rule)
+    }
+
+    /// The latest occurrence in `(after_ms, until]`.
+    fn latest_between(
+        &self,
+        anchor: DateTime<Local>,
+        after_ms: i64,
+        until: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let mut date = until.date_naive();
+        for _ in 0..=SEARCH_DAYS {
+            if let Some(at) = self.occurrence_on(anchor, date) {
+                let ms = at.timestamp_millis();
+                if ms <= after_ms {
+                    return None;
+                }
+                if at <= until {
+                    return Some(at);
+                }
+            }
+            date = date.pred_opt()?;
+        }
+        None
+    }
+
+    /// The first occurrence after `after_ms`.
+    fn next_after(&self, anchor: DateTime<Local>, after_ms: i64) -> Option<DateTime<Local>> {
+        let start = local(after_ms)?.max(anchor);
+        let mut date = start.date_naive();
+        for _ in 0..=SEARCH_DAYS {
+            if let Some(at) = self.occurrence_on(anchor, date) {
+                if at.timestamp_millis() > after_ms {
+                    return Some(at);
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// When the rule fires on `date`, if it does. Times that fall into a DST gap are skipped.
+    fn occurrence_on(&self, anchor: DateTime<Local>, date: NaiveDate) -> Option<DateTime<Local>> {
+        let start = anchor.date_naive();
+        if date < start {
+            return None;
+        }
+        let matches = match self.freq {
+            Freq::Daily => {
+                (date - start).num_days() % self.interval == 0
+                    && (self.by_day.is_empty() || self.by_day.contains(&date.weekday()))
+            }
+            Freq::Weekly => {
+                let days = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.clone()
+                };
+                let weeks = (week_start(date) - week_start(start)).num_days() / 7;
+                weeks % self.interval == 0 && days.contains(&date.weekday())
+            }
+            Freq::Monthly => {
+                let months = (date.year() - start.year()) as i64 * 12 + date.month() as i64
+                    - start.month() as i64;
+                months % self.interval == 0
+                    && date.day() == self.by_month_day.unwrap_or(start.day())
+            }
+        };
+        if !matches {
+            return None;
+        }
+        let time = date.and_hms_opt(
+            self.hour.unwrap_or(anchor.hour()),
+            self.minute.unwrap_or(anchor.minute()),
+            0,
+        )?;
+        Local.from_local_datetime(&time).earliest()
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn weekday(raw: &str) -> Option<Weekday> {
+    Some(match raw.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    /// A daily 09:00 recurrence of the `standup` template, set up at noon on 2026-10-01.
+    fn standup(env: &TestEnv, expiry_minutes: Option<i64>) -> Recurrence {
+        std::fs::write(
+            env.paths.templates_dir.join("standup.md"),
+            "# Standup {{date}}\n",
+        )
+        .unwrap();
+        let input = RecurrenceInput {
+            id: None,
+            name: String::new(),
+            template: "standup".to_string(),
+            rule: "FREQ=DAILY;BYHOUR=9;BYMINUTE=0".to_string(),
+            expiry_minutes,
+        };
+        let recurrence = save(&env.conn, &env.paths, &input).unwrap();
+        env.conn
+            .execute(
+                "UPDATE recurrences SET created_at = ?1 WHERE id = ?2",
+                params![at(2026, 10, 1, 12, 0).timestamp_millis(), recurrence.id],
+            )
+            .unwrap();
+        recurrence
+    }
+
+    fn occurrences(env: &TestEnv, id: &str) -> Vec<i64> {
+        let mut stmt = env
+            .conn
+            .prepare("SELECT occurrence_at FROM notes WHERE recurrence_id = ?1 ORDER BY 1")
+            .unwrap();
+        stmt.query_map(params![id], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn catches_up_once_and_never_twice() {
+        let mut env = TestEnv::new();
+        let recurrence = standup(&env, Some(60));
+        let now = at(2026, 10, 10, 12, 0);
+
+        // Nine missed mornings become one note for the latest.
+        let created = materialize_due(&mut env.conn, &env.paths, now).unwrap();
+        assert_eq!(created.len(), 1);
+        let latest = at(2026, 10, 10, 9, 0).timestamp_millis();
+        assert_eq!(occurrences(&env, &recurrence.id), vec![latest]);
+        let meta = crate::notes::get_meta(&env.conn, &created[0]).unwrap();
+        assert_eq!(meta.title, "Standup 2026-10-10");
+        assert_eq!(meta.expires_at, Some(now.timestamp_millis() + 60 * 60_000));
+
+        assert!(materialize_due(&mut env.conn, &env.paths, now)
+            .unwrap()
+            .is_empty());
+        assert_eq!(occurrences(&env, &recurrence.id), vec![latest]);
+    }
+
+    #[test]
+    fn a_missing_template_is_recorded_once_and_caught_up_later() {
+        let mut env = TestEnv::new();
+        let recurrence = standup(&env, None);
+        let template = env.paths.templates_dir.join("standup.md");
+        let content = std::fs::read(&template).unwrap();
+        std::fs::remove_file(&template).unwrap();
+        env.conn
+            .execute_batch(
+                "CREATE TEMP TABLE error_writes (error TEXT);
+                 CREATE TEMP TRIGGER count_error_writes AFTER UPDATE OF last_error ON recurrences
+                 BEGIN INSERT INTO error_writes VALUES (NEW.last_error); END;",
+            )
+            .unwrap();
+        let error_writes = |env: &TestEnv| -> i64 {
+            env.conn
+                .query_row("SELECT COUNT(*) FROM error_writes", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        for hour in [10, 11] {
+            let now = at(2026, 10, 2, hour, 0);
+            assert!(materialize_due(&mut env.conn, &env.paths, now)
+                .unwrap()
+                .is_empty());
+        }
+        let (last_occurrence_at, last_error): (Option<i64>, Option<String>) = env
+            .conn
+            .query_row(
+                "SELECT last_occurrence_at, last_error FROM recurrences WHERE id = ?1",
+                params![recurrence.id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(last_occurrence_at, None);
+        assert!(last_error.unwrap().contains("standup"));
+        assert_eq!(error_writes(&env), 1);
+        let notes: i64 = env
+            .conn
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(notes, 0);
+
+        std::fs::write(&template, content).unwrap();
+        let created = materialize_due(&mut env.conn, &env.paths, at(2026, 10, 2, 12, 0)).unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(
+            occurrences(&env, &recurrence.id),
+            vec![at(2026, 10, 2, 9, 0).timestamp_millis()]
+        );
+        let cleared: Option<String> = env
+            .conn
+            .query_row(
+                "SELECT last_error FROM recurrences WHERE id = ?1",
+                params![recurrence.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(cleared, None);
+        assert_eq!(error_writes(&env), 2);
+    }
+
+    #[test]
+    fn refuses_expiries_past_the_cap() {
+        let env = TestEnv::new();
+        standup(&env, None);
+        let input = RecurrenceInput {
+            id: None,
+            name: String::new(),
+            template: "standup".to_string(),
+            rule: "FREQ=DAILY".to_string(),
+            expiry_minutes: Some(i64::MAX / 1000),
+        };
+        assert!(save(&env.conn, &env.paths, &input).is_err());
+        let input = RecurrenceInput {
+            expiry_minutes: Some(crate::notes::MAX_EXPIRY_MINUTES),
+            ..input
+        };
+        assert!(save(&env.conn, &env.paths, &input).is_ok());
+    }
+
+    #[test]
+    fn parses_the_supported_subset() {
+        let rule = Rule::parse("RRULE:FREQ=WEEKLY;BYDAY=MO,fr;BYHOUR=9;BYMINUTE=30").unwrap();
+        assert_eq!(rule.freq, Freq::Weekly);
+        assert_eq!(rule.by_day, vec![Weekday::Mon, Weekday::Fri]);
+        assert_eq!((rule.hour, rule.minute), (Some(9), Some(30)));
+
+        assert!(Rule::parse("BYHOUR=9").is_err());
+        assert!(Rule::parse("FREQ=YEARLY").is_err());
+        assert!(Rule::parse("FREQ=DAILY;COUNT=3").is_err());
+        assert!(Rule::parse("FREQ=MONTHLY;BYDAY=MO").is_err());
+        assert!(Rule::parse("FREQ=DAILY;BYHOUR=24").is_err());
+    }
+
+    #[test]
+    fn finds_the_latest_missed_weekly_occurrence() {
+        // Created Wednesday 2026-10-14; Mondays at 09:00.
+        let anchor = at(2026, 10, 14, 15, 0);
+        let rule = Rule::parse("FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0").unwrap();
+        let after = anchor.timestamp_millis() - 1;
+
+        assert_eq!(
+            rule.latest_between(anchor, after, at(2026, 10, 19, 8, 59)),
+            None
+        );
+        assert_eq!(
+            rule.latest_between(anchor, after, at(2026, 10, 19, 9, 0)),
+            Some(at(2026, 10, 19, 9, 0))
+        );
+        // Closed for three weeks: only the most recent Monday is generated.
+        assert_eq!(
+            rule.latest_between(anchor, after, at(2026, 11, 4, 12, 0)),
+            Some(at(2026, 11, 2, 9, 0))
+        );
+        let last = at(2026, 11, 2, 9, 0).timestamp_millis();
+        assert_eq!(
+            rule.latest_between(anchor, last, at(2026, 11, 4, 12, 0)),
+            None
+        );
+        assert_eq!(rule.next_after(anchor, last), Some(at(2026, 11, 9, 9, 0)));
+    }
+
+    #[test]
+    fn handles_weekdays_intervals_and_month_days() {
+        let anchor = at(2026, 10, 16, 8, 0); // a Friday
+        let weekdays = Rule::parse("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9").unwrap();
+        assert_eq!(
+            weekdays.next_after(anchor, at(2026, 10, 16, 9, 0).timestamp_millis()),
+            Some(at(2026, 10, 19, 9, 0))
+        );
+
+        let biweekly = Rule::parse("FREQ=WEEKLY;INTERVAL=2").unwrap();
+        assert_eq!(
+            biweekly.next_after(anchor, anchor.timestamp_millis()),
+            Some(at(2026, 10, 30, 8, 0))
+        );
+
+        let monthly = Rule::parse("FREQ=MONTHLY;BYMONTHDAY=31;BYHOUR=18").unwrap();
+        assert_eq!(
+            monthly.next_after(anchor, anchor.timestamp_millis()),
+            Some(at(2026, 10, 31, 18, 0))
+        );
+        assert_eq!(
+            monthly.next_after(anchor, at(2026, 10, 31, 18, 0).timestamp_millis()),
+            Some(at(2026, 12, 31, 18, 0))
+        );
+    }
+}
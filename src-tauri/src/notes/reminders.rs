@@ -0,0 +1,146 @@
+//! Per-note reminders. `remind_at` stays set once the reminder has fired so the tray can list
+//! the note as due; `reminder_notified_at` records that the notification went out.
+
+use crate::i18n::Message;
+use crate::types::NoteMeta;
+use rusqlite::{params, Connection};
+
+use super::meta::get_meta;
+use super::time::now_ms;
+
+/// Sets (`Some`) or dismisses (`None`) the reminder of an active note.
+pub(super) fn set(conn: &Connection, id: &str, at: Option<i64>) -> Result<NoteMeta, Message> {
+    let meta = get_meta(conn, id)?;
+    if meta.is_trashed && at.is_some() {
+        return Err(Message::new("error.reminder_trashed"));
+    }
+    conn.execute(
+        "UPDATE notes SET remind_at = ?1, reminder_notified_at = NULL WHERE id = ?2",
+        params![at, id],
+    )
+    .map_err(|err| err.to_string())?;
+    get_meta(conn, id)
+}
+
+pub(super) fn snooze(conn: &Connection, id: &str, minutes: i64) -> Result<NoteMeta, Message> {
+    if minutes <= 0 {
+        return Err(Message::new("error.snooze_min"));
+    }
+    let at = minutes
+        .checked_mul(60_000)
+        .and_then(|ms| now_ms().checked_add(ms))
+        .ok_or_else(|| Message::new("error.snooze_range"))?;
+    set(conn, id, Some(at))
+}
+
+/// Active notes whose reminder time has passed, earliest first.
+pub(super) fn due(conn: &Connection, now: i64) -> Result<Vec<NoteMeta>, Message> {
+    due_ids(conn, now, false)?
+        .iter()
+        .map(|id| get_meta(conn, id))
+        .collect()
+}
+
+/// Due reminders that haven't been announced yet, marked as announced. Reminders that came due
+/// while the app was closed are returned on the first call after launch.
+pub(super) fn take_unnotified(conn: &Connection, now: i64) -> Result<Vec<NoteMeta>, Message> {
+    let ids = due_ids(conn, now, true)?;
+    for id in &ids {
+        conn.execute(
+            "UPDATE notes SET reminder_notified_at = ?1 WHERE id = ?2",
+            params![now, id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    ids.iter().map(|id| get_meta(conn, id)).collect()
+}
+
+fn due_ids(conn: &Connection, now: i64, unnotified_only: bool) -> Result<Vec<String>, Message> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT id FROM notes
+WHERE is_trashed = 0
+  AND remind_at IS NOT NULL
+  AND remind_at <= ?1
+  AND (?2 = 0 OR reminder_notified_at IS NULL)
+ORDER BY remind_at ASC
+"#,
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![now, unnotified_only], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|err| err.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+    use crate::notes::trash;
+
+    #[test]
+    fn fires_once_and_stays_due() {
+        let env = TestEnv::new();
+        let id = env.draft("# Call back");
+        set(&env.conn, &id, Some(1_000)).unwrap();
+
+        assert!(take_unnotified(&env.conn, 999).unwrap().is_empty());
+        let fired = take_unnotified(&env.conn, 1_000).unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, id);
+        assert!(take_unnotified(&env.conn, 2_000).unwrap().is_empty());
+        assert_eq!(due(&env.conn, 2_000).unwrap().len(), 1);
+
+        // A new time fires again.
+        set(&env.conn, &id, Some(3_000)).unwrap();
+        assert_eq!(take_unnotified(&env.conn, 3_000).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn survives_a_relaunch() {
+        let env = TestEnv::new();
+        let fired = env.draft("# Fired");
+        let pending = env.draft("# Pending");
+        set(&env.conn, &fired, Some(1_000)).unwrap();
+        set(&env.conn, &pending, Some(5_000)).unwrap();
+        take_unnotified(&env.conn, 1_000).unwrap();
+
+        let conn = crate::db::open(&env.paths.db_path).unwrap();
+        // Came due while the app was closed: announced at launch, the fired one isn't again.
+        let announced: Vec<String> = take_unnotified(&conn, 6_000)
+            .unwrap()
+            .into_iter()
+            .map(|note| note.id)
+            .collect();
+        assert_eq!(announced, vec![pending]);
+        assert_eq!(due(&conn, 6_000).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn trashed_notes_are_not_due() {
+        let env = TestEnv::new();
+        let id = env.draft("# Gone");
+        set(&env.conn, &id, Some(1_000)).unwrap();
+        trash::trash(&env.conn, &env.paths, &id).unwrap();
+
+        assert!(due(&env.conn, 2_000).unwrap().is_empty());
+        assert!(take_unnotified(&env.conn, 2_000).unwrap().is_empty());
+        assert!(set(&env.conn, &id, Some(3_000)).is_err());
+    }
+
+    #[test]
+    fn refuses_snoozes_out_of_range() {
+        let env = TestEnv::new();
+        let id = env.draft("# Later");
+        assert!(snooze(&env.conn, &id, 0).is_err());
+        assert_eq!(
+            snooze(&env.conn, &id, i64::MAX).unwrap_err(),
+            Message::new("error.snooze_range")
+        );
+        let at = snooze(&env.conn, &id, 10).unwrap().remind_at.unwrap();
+        assert!(at > now_ms() + 9 * 60_000);
+    }
+}
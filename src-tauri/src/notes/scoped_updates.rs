@@ -1,13 +1,14 @@
+use crate::i18n::Message;
 use rusqlite::{params, Connection};
 use std::path::PathBuf;
 
-pub(super) fn get_bookmark(conn: &Connection, id: &str) -> Result<Option<Vec<u8>>, String> {
+pub(super) fn get_bookmark(conn: &Connection, id: &str) -> Result<Option<Vec<u8>>, Message> {
     conn.query_row(
         "SELECT bookmark FROM notes WHERE id = ?1 LIMIT 1",
         params![id],
         |row| row.get::<_, Option<Vec<u8>>>(0),
     )
-    .map_err(|err| err.to_string())
+    .map_err(|err| err.to_string().into())
 }
 
 pub(super) fn apply_scoped_updates(
@@ -15,7 +16,7 @@ pub(super) fn apply_scoped_updates(
     id: &str,
     refreshed_bookmark: Option<Vec<u8>>,
     resolved_path: Option<PathBuf>,
-) -> Result<(), String> {
+) -> Result<(), Message> {
     if let Some(bookmark) = refreshed_bookmark {
         conn.execute(
             "UPDATE notes SET bookmark = ?1 WHERE id = ?2",
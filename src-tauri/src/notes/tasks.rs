@@ -0,0 +1,209 @@
+//! GFM task items (`- [ ]` / `- [x]`) across notes. The index is rebuilt per note whenever its
+//! content is written or re-read; toggling edits the marker in place and writes the note through
+//! the regular draft/save paths.
+
+use crate::i18n::Message;
+use crate::types::{NoteMeta, NoteStorage, TaskFilter, TaskItem, TaskState};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use rusqlite::{params, Connection};
+use std::ops::Range;
+
+use super::meta::display_title;
+use super::write::{get, save, write_draft};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParsedTask {
+    /// 1-based line in the note body (front matter excluded).
+    line: i64,
+    text: String,
+    checked: bool,
+    /// Byte range of the `[ ]` / `[x]` marker.
+    marker: Range<usize>,
+}
+
+/// Replaces the stored tasks of `id` with the ones in `body`.
+pub(super) fn index(conn: &Connection, id: &str, body: &str) -> Result<(), Message> {
+    conn.execute("DELETE FROM note_tasks WHERE note_id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+    for task in parse(body) {
+        conn.execute(
+            "INSERT INTO note_tasks (note_id, line, text, checked) VALUES (?1, ?2, ?3, ?4)",
+            params![id, task.line, task.text, task.checked as i64],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Tasks in active notes, in sidebar order and then by line.
+pub(super) fn list(conn: &Connection, filter: &TaskFilter) -> Result<Vec<TaskItem>, Message> {
+    let checked = match filter.state {
+        TaskState::Open => Some(0),
+        TaskState::Done => Some(1),
+        TaskState::All => None,
+    };
+    let query = filter
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|query| !query.is_empty())
+        .map(|query| {
+            let escaped = query
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{escaped}%")
+        });
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT t.note_id, n.title, t.line, t.text, t.checked
+FROM note_tasks t
+JOIN notes n ON n.id = t.note_id
+WHERE n.is_trashed = 0
+  AND (?1 IS NULL OR t.checked = ?1)
+  AND (?2 IS NULL OR t.note_id = ?2)
+  AND (?3 IS NULL OR t.text LIKE ?3 ESCAPE '\')
+ORDER BY n.is_pinned DESC, n.sort_order ASC, t.line ASC
+"#,
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![checked, filter.note_id, query], |row| {
+            Ok(TaskItem {
+                note_id: row.get(0)?,
+                note_title: display_title(row.get(1)?),
+                line: row.get(2)?,
+                text: row.get(3)?,
+                checked: row.get::<_, i64>(4)? != 0,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string().into())
+}
+
+/// Sets the task on `line` of the note body to `checked` and writes the note.
+pub(super) fn toggle(
+    conn: &Connection,
+    note_id: &str,
+    line: i64,
+    checked: bool,
+) -> Result<NoteMeta, Message> {
+    let note = get(conn, note_id)?;
+    let task = parse(&note.content)
+        .into_iter()
+        .find(|task| task.line == line)
+        .ok_or_else(|| Message::new("error.task_missing").arg("line", line))?;
+    if task.checked == checked {
+        return Ok(note.meta);
+    }
+
+    let mut content = note.content;
+    content.replace_range(task.marker, if checked { "[x]" } else { "[ ]" });
+    match note.meta.storage {
+        NoteStorage::Draft => write_draft(conn, note_id, &content),
+        NoteStorage::Saved => save(conn, note_id, &content),
+    }
+}
+
+/// The unchecked tasks of `body` as top-level list items, with their inline markup kept. Only a
+/// task's first line is taken.
+pub(super) fn open_items(body: &str) -> Vec<String> {
+    parse(body)
+        .into_iter()
+        .filter(|task| !task.checked)
+        .filter_map(|task| {
+            let rest = body[task.marker.end..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim();
+            (!rest.is_empty()).then(|| format!("- [ ] {rest}"))
+        })
+        .collect()
+}
+
+/// Task items outside code blocks. A task's text is its first paragraph, with inline markup
+/// reduced to text.
+fn parse(body: &str) -> Vec<ParsedTask> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(body.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) as i64;
+
+    let mut tasks = Vec::new();
+    let mut open: Option<ParsedTask> = None;
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    for (event, range) in Parser::new_ext(body, options).into_offset_iter() {
+        match event {
+            Event::TaskListMarker(checked) => {
+                open = Some(ParsedTask {
+                    line: line_of(range.start),
+                    text: String::new(),
+                    checked,
+                    marker: range,
+                })
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(task) = open.as_mut() {
+                    task.text.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(task) = open.as_mut() {
+                    task.text.push(' ');
+                }
+            }
+            Event::End(TagEnd::Paragraph | TagEnd::Item) | Event::Start(Tag::List(_)) => {
+                if let Some(mut task) = open.take() {
+                    task.text = task.text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    tasks.push(task);
+                }
+            }
+            _ => {}
+        }
+    }
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tasks_with_lines() {
+        let body = "# List\n- [ ] Buy **milk**\n- [x] Pay `rent`\n  - [ ] nested\n\n```\n- [ ] code\n```\n> - [X] quoted\n";
+        let tasks: Vec<_> = parse(body)
+            .into_iter()
+            .map(|task| (task.line, task.text, task.checked))
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                (2, "Buy milk".to_string(), false),
+                (3, "Pay rent".to_string(), true),
+                (4, "nested".to_string(), false),
+                (9, "quoted".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn open_items_keep_markup() {
+        let body = "- [ ] Call **Anna**\n- [x] done\n  * [ ] nested `code`\n- [ ]\n";
+        assert_eq!(
+            open_items(body),
+            vec!["- [ ] Call **Anna**", "- [ ] nested `code`"]
+        );
+    }
+
+    #[test]
+    fn marker_range_covers_brackets() {
+        let body = "1. [ ] first\n* [x] second\n";
+        let tasks = parse(body);
+        assert_eq!(&body[tasks[0].marker.clone()], "[ ]");
+        assert_eq!(&body[tasks[1].marker.clone()], "[x]");
+    }
+}
@@ -0,0 +1,264 @@
+//! Markdown templates for new drafts, read from `templates/` in the app data dir. Placeholders
+//! (`{{date}}`, `{{time}}`, `{{weekday}}`, `{{clipboard}}`, `{{cursor}}`) are filled in when a
+//! note is created; `expires_after` in a template's front matter sets the new note's expiry.
+
+use crate::app_state::AppPaths;
+use crate::i18n::{self, Message};
+use crate::types::{NoteFromTemplate, NoteTemplate};
+use chrono::{DateTime, Datelike, Local};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+use super::files::read_file;
+use super::frontmatter;
+use super::time::{duration_minutes, weekday_key};
+use super::write::{create_with_content, get};
+
+const EXPIRY_KEY: &str = "expires_after";
+const CLIPBOARD: &str = "{{clipboard}}";
+const CURSOR: &str = "{{cursor}}";
+
+/// Templates in the folder, by name.
+pub(super) fn list(paths: &AppPaths) -> Result<Vec<NoteTemplate>, Message> {
+    let entries = match std::fs::read_dir(&paths.templates_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(Message::new("error.templates_read").arg("error", err)),
+    };
+
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        let Ok(decoded) = read_file(&path) else {
+            continue;
+        };
+        let (expires_after, _) = frontmatter::take(&decoded.content, EXPIRY_KEY);
+        templates.push(NoteTemplate {
+            name: name.to_string(),
+            expiry_minutes: expires_after.as_deref().and_then(duration_minutes),
+            uses_clipboard: decoded.content.contains(CLIPBOARD),
+        });
+    }
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    Ok(templates)
+}
+
+/// Creates a draft from the template `name`. `clipboard` fills `{{clipboard}}`; the frontend
+/// reads it because only the webview may access the clipboard.
+pub(super) fn create_from_template(
+    conn: &Connection,
+    paths: &AppPaths,
+    name: &str,
+    clipboard: Option<&str>,
+) -> Result<NoteFromTemplate, Message> {
+    render(conn, paths, name, clipboard, Local::now())
+}
+
+/// Like [`create_from_template`], with the date placeholders filled in for `at`.
+pub(super) fn render(
+    conn: &Connection,
+    paths: &AppPaths,
+    name: &str,
+    clipboard: Option<&str>,
+    at: DateTime<Local>,
+) -> Result<NoteFromTemplate, Message> {
+    let path = template_path(paths, name)?;
+    let decoded = read_file(&path).map_err(|err| {
+        Message::new("error.template")
+            .arg("name", name)
+            .arg("error", err)
+    })?;
+
+    let (expires_after, content) = frontmatter::take(&decoded.content, EXPIRY_KEY);
+    let expiry_minutes = match expires_after {
+        Some(raw) => Some(duration_minutes(&raw).ok_or_else(|| {
+            Message::new("error.template_expiry")
+                .arg("name", name)
+                .arg("key", EXPIRY_KEY)
+                .arg("value", &raw)
+        })?),
+        None => None,
+    };
+
+    let vars = Vars {
+        now: at,
+        clipboard: clipboard.unwrap_or_default(),
+    };
+    let (yaml, body) = match frontmatter::split(&content) {
+        Some((yaml, body)) => (Some(expand(yaml, &vars).0), body),
+        None => (None, content.as_str()),
+    };
+    let (body, cursor) = expand(body, &vars);
+    // The editor only gets the body, so that is what the cursor offset counts in.
+    let cursor = cursor.map(|offset| body[..offset].encode_utf16().count());
+    let content = match yaml {
+        Some(yaml) => format!("---\n{yaml}---\n{body}"),
+        None => body,
+    };
+
+    let meta = create_with_content(conn, paths, &content, expiry_minutes)?;
+
+    Ok(NoteFromTemplate {
+        note: get(conn, &meta.id)?,
+        cursor,
+    })
+}
+
+pub(super) fn template_path(paths: &AppPaths, name: &str) -> Result<PathBuf, Message> {
+    let valid = !name.trim().is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && Path::new(name).components().count() == 1;
+    if !valid {
+        return Err(Message::new("error.template_name").arg("name", name));
+    }
+    let path = paths.templates_dir.join(format!("{name}.md"));
+    if !path.is_file() {
+        return Err(Message::new("error.template_missing").arg("name", name));
+    }
+    Ok(path)
+}
+
+struct Vars<'a> {
+    now: DateTime<Local>,
+    clipboard: &'a str,
+}
+
+/// Fills in the placeholders of `text` and returns the byte offset of the first `{{cursor}}`,
+/// which is removed like any later one. Unknown placeholders stay as written.
+fn expand(text: &str, vars: &Vars) -> (String, Option<usize>) {
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = None;
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(after);
+            rest = "";
+            break;
+        };
+        let placeholder = &after[..end + 2];
+        match placeholder {
+            "{{date}}" => out.push_str(&vars.now.format("%Y-%m-%d").to_string()),
+            "{{time}}" => out.push_str(&vars.now.format("%H:%M").to_string()),
+            "{{weekday}}" => out.push_str(&i18n::t(weekday_key(vars.now.weekday()))),
+            CLIPBOARD => out.push_str(vars.clipboard),
+            CURSOR => {
+                cursor.get_or_insert(out.len());
+            }
+            _ => out.push_str(placeholder),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    (out, cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+    use crate::notes::write::read_stored;
+    use chrono::TimeZone;
+
+    fn monday() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 5, 0).unwrap()
+    }
+
+    fn template(env: &TestEnv, name: &str, content: &str) {
+        std::fs::write(env.paths.templates_dir.join(format!("{name}.md")), content).unwrap();
+    }
+
+    #[test]
+    fn template_names_stay_in_the_folder() {
+        let env = TestEnv::new();
+        template(&env, "standup", "# Standup");
+        assert!(template_path(&env.paths, "standup").is_ok());
+        for name in ["../x", ".hidden", "a/b", "a\\b", " "] {
+            assert_eq!(
+                template_path(&env.paths, name),
+                Err(Message::new("error.template_name").arg("name", name)),
+                "{name}"
+            );
+        }
+        assert_eq!(
+            template_path(&env.paths, "missing"),
+            Err(Message::new("error.template_missing").arg("name", "missing"))
+        );
+    }
+
+    #[test]
+    fn renders_front_matter_expiry_and_cursor() {
+        let env = TestEnv::new();
+        template(
+            &env,
+            "standup",
+            "---\nexpires_after: 2h\nproject: Standup {{date}}\n---\n# Grüße 😀 {{cursor}}done\n",
+        );
+
+        let before = crate::notes::now_ms();
+        let created = render(&env.conn, &env.paths, "standup", None, monday()).unwrap();
+        let after = crate::notes::now_ms();
+
+        let expires_at = created.note.meta.expires_at.unwrap();
+        assert!((before + 2 * 3_600_000..=after + 2 * 3_600_000).contains(&expires_at));
+        let stored = read_stored(&env.conn, &created.note.meta.id)
+            .unwrap()
+            .content;
+        assert!(stored.contains("Standup 2026-10-19"), "{stored}");
+        assert!(!stored.contains("expires_after"), "{stored}");
+        // "# Grüße 😀 " is 15 bytes but 11 UTF-16 code units; the emoji takes two.
+        assert_eq!(created.cursor, Some(11));
+        assert!(created.note.content.ends_with("# Grüße 😀 done\n"));
+    }
+
+    #[test]
+    fn refuses_an_expiry_that_is_not_a_duration() {
+        let env = TestEnv::new();
+        template(&env, "soon", "---\nexpires_after: soon\n---\nBody");
+
+        let err = render(&env.conn, &env.paths, "soon", None, monday()).unwrap_err();
+        assert_eq!(
+            err,
+            Message::new("error.template_expiry")
+                .arg("name", "soon")
+                .arg("key", EXPIRY_KEY)
+                .arg("value", "soon")
+        );
+        let notes: i64 = env
+            .conn
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(notes, 0);
+    }
+
+    #[test]
+    fn expands_placeholders() {
+        let vars = Vars {
+            now: monday(),
+            clipboard: "INC-42",
+        };
+        let (text, cursor) = expand(
+            "# Standup {{weekday}} {{date}} {{time}}\nTicket: {{clipboard}}\n- {{cursor}}\n{{cursor}}{{unknown}} {{",
+            &vars,
+        );
+        assert_eq!(
+            text,
+            "# Standup Monday 2026-10-19 09:05\nTicket: INC-42\n- \n{{unknown}} {{"
+        );
+        assert_eq!(
+            &text[..cursor.unwrap()],
+            "# Standup Monday 2026-10-19 09:05\nTicket: INC-42\n- "
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! A fresh database and app data directory for tests that go through the notes API.
+
+use crate::app_state::AppPaths;
+use rusqlite::Connection;
+use tempfile::TempDir;
+
+pub(crate) struct TestEnv {
+    pub conn: Connection,
+    pub paths: AppPaths,
+    // Removed with the environment.
+    _dir: TempDir,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths {
+            app_data_dir: dir.path().to_path_buf(),
+            drafts_dir: dir.path().join("drafts"),
+            trash_dir: dir.path().join("trash"),
+            templates_dir: dir.path().join("templates"),
+            db_path: dir.path().join("augenblick.db"),
+        };
+        for dir in [&paths.drafts_dir, &paths.trash_dir, &paths.templates_dir] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let conn = crate::db::open(&paths.db_path).unwrap();
+        Self {
+            conn,
+            paths,
+            _dir: dir,
+        }
+    }
+
+    /// A draft with `content`; returns its id.
+    pub fn draft(&self, content: &str) -> String {
+        let meta = super::create_draft(&self.conn, &self.paths).unwrap();
+        super::write_draft(&self.conn, &meta.id, content).unwrap();
+        meta.id
+    }
+}
@@ -1,5 +1,49 @@
-use chrono::Utc;
+use chrono::{Utc, Weekday};
 
 pub(super) fn now_ms() -> i64 {
     Utc::now().timestamp_millis()
 }
+
+/// The message key of the day's name, e.g. `weekday.monday`.
+pub(super) fn weekday_key(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "weekday.monday",
+        Weekday::Tue => "weekday.tuesday",
+        Weekday::Wed => "weekday.wednesday",
+        Weekday::Thu => "weekday.thursday",
+        Weekday::Fri => "weekday.friday",
+        Weekday::Sat => "weekday.saturday",
+        Weekday::Sun => "weekday.sunday",
+    }
+}
+
+/// `90m`, `12h`, `3d`, `2w`; a bare number is minutes.
+pub(super) fn duration_minutes(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (amount, unit) = raw.split_at(split);
+    let amount = amount.parse::<i64>().ok().filter(|amount| *amount > 0)?;
+    let factor = match unit.trim() {
+        "" | "m" | "min" => 1,
+        "h" => 60,
+        "d" => 1440,
+        "w" => 10_080,
+        _ => return None,
+    };
+    amount.checked_mul(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(duration_minutes("90"), Some(90));
+        assert_eq!(duration_minutes("12h"), Some(720));
+        assert_eq!(duration_minutes(" 3d "), Some(4320));
+        assert_eq!(duration_minutes("2w"), Some(20_160));
+        assert_eq!(duration_minutes("0d"), None);
+        assert_eq!(duration_minutes("soon"), None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app_state::AppPaths;
+use crate::i18n::Message;
 use crate::types::{NoteMeta, NoteStorage};
 use rusqlite::{params, Connection};
 use std::path::{Path, PathBuf};
@@ -7,7 +8,7 @@ use super::files::move_file;
 use super::meta::get_meta;
 use super::time::now_ms;
 
-pub(super) fn trash(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, String> {
+pub(super) fn trash(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, Message> {
     let meta = get_meta(conn, id)?;
     if meta.is_trashed {
         return Ok(meta);
@@ -18,10 +19,10 @@ pub(super) fn trash(conn: &Connection, paths: &AppPaths, id: &str) -> Result<Not
     if meta.storage == NoteStorage::Draft {
         let file_name = Path::new(&meta.file_path)
             .file_name()
-            .ok_or_else(|| "Invalid file path".to_string())?;
+            .ok_or_else(|| Message::new("error.invalid_path"))?;
         let target = paths.trash_dir.join(file_name);
         move_file(Path::new(&meta.file_path), &target)
-            .map_err(|err| format!("Move to trash failed: {err}"))?;
+            .map_err(|err| Message::new("error.trash_failed").arg("error", err))?;
         new_file_path = Some(target);
     }
 
@@ -45,7 +46,7 @@ WHERE id = ?3
     get_meta(conn, id)
 }
 
-pub(super) fn restore(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, String> {
+pub(super) fn restore(conn: &Connection, paths: &AppPaths, id: &str) -> Result<NoteMeta, Message> {
     let meta = get_meta(conn, id)?;
     if !meta.is_trashed {
         return Ok(meta);
@@ -55,29 +56,35 @@ pub(super) fn restore(conn: &Connection, paths: &AppPaths, id: &str) -> Result<N
     if meta.storage == NoteStorage::Draft {
         let file_name = Path::new(&meta.file_path)
             .file_name()
-            .ok_or_else(|| "Invalid file path".to_string())?;
+            .ok_or_else(|| Message::new("error.invalid_path"))?;
         let target = paths.drafts_dir.join(file_name);
         move_file(Path::new(&meta.file_path), &target)
-            .map_err(|err| format!("Restore failed: {err}"))?;
+            .map_err(|err| Message::new("error.restore_failed").arg("error", err))?;
         new_file_path = Some(target);
     }
 
+    // A fixed expiry that has already passed would send the note straight back to the trash.
     conn.execute(
         r#"
 UPDATE notes
 SET is_trashed = 0,
     trashed_at = NULL,
-    file_path = COALESCE(?1, file_path)
-WHERE id = ?2
+    file_path = COALESCE(?1, file_path),
+    expires_at = CASE WHEN expires_at <= ?2 THEN NULL ELSE expires_at END
+WHERE id = ?3
 "#,
-        params![new_file_path.as_ref().map(|p| p.to_string_lossy()), id],
+        params![
+            new_file_path.as_ref().map(|p| p.to_string_lossy()),
+            now_ms(),
+            id
+        ],
     )
     .map_err(|err| err.to_string())?;
 
     get_meta(conn, id)
 }
 
-pub(super) fn delete_forever(conn: &Connection, id: &str) -> Result<(), String> {
+pub(super) fn delete_forever(conn: &Connection, id: &str) -> Result<(), Message> {
     let meta = get_meta(conn, id)?;
 
     if meta.storage == NoteStorage::Draft {
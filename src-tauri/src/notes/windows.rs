@@ -0,0 +1,165 @@
+//! Which notes have their own window, in which mode and where. The windows themselves live in
+//! `note_windows.rs`; this is what survives a relaunch and what the expiry sweeper protects.
+
+use crate::i18n::Message;
+use crate::types::{NoteWindow, NoteWindowMode, WindowBounds};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::meta::get_meta;
+
+const COLUMNS: &str = "note_id, mode, x, y, width, height, is_open";
+
+pub(super) fn get(conn: &Connection, id: &str) -> Result<Option<NoteWindow>, Message> {
+    conn.query_row(
+        &format!("SELECT {COLUMNS} FROM note_windows WHERE note_id = ?1"),
+        params![id],
+        from_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string().into())
+}
+
+/// Marks the note's window open in `mode`, keeping its last bounds.
+pub(super) fn open(
+    conn: &Connection,
+    id: &str,
+    mode: NoteWindowMode,
+) -> Result<NoteWindow, Message> {
+    let meta = get_meta(conn, id)?;
+    if meta.is_trashed {
+        return Err(Message::new("error.window_trashed"));
+    }
+    conn.execute(
+        r#"
+INSERT INTO note_windows(note_id, mode, is_open) VALUES (?1, ?2, 1)
+ON CONFLICT(note_id) DO UPDATE SET mode = excluded.mode, is_open = 1
+"#,
+        params![id, mode.as_str()],
+    )
+    .map_err(|err| err.to_string())?;
+    get(conn, id)?.ok_or_else(|| Message::new("error.window_missing").arg("id", id))
+}
+
+pub(super) fn close(conn: &Connection, id: &str) -> Result<(), Message> {
+    conn.execute(
+        "UPDATE note_windows SET is_open = 0 WHERE note_id = ?1",
+        params![id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub(super) fn set_bounds(conn: &Connection, id: &str, bounds: WindowBounds) -> Result<(), Message> {
+    conn.execute(
+        "UPDATE note_windows SET x = ?1, y = ?2, width = ?3, height = ?4 WHERE note_id = ?5",
+        params![bounds.x, bounds.y, bounds.width, bounds.height, id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Windows to reopen on launch; trashed notes don't get one.
+pub(super) fn list_open(conn: &Connection) -> Result<Vec<NoteWindow>, Message> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT w.note_id, w.mode, w.x, w.y, w.width, w.height, w.is_open
+FROM note_windows w
+JOIN notes n ON n.id = w.note_id
+WHERE w.is_open = 1 AND n.is_trashed = 0
+ORDER BY w.rowid
+"#,
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], from_row)
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    Ok(rows)
+}
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<NoteWindow> {
+    let mode: String = row.get(1)?;
+    let bounds = match (row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?) {
+        (Some(x), Some(y), Some(width), Some(height)) => Some(WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => None,
+    };
+    Ok(NoteWindow {
+        note_id: row.get(0)?,
+        mode: NoteWindowMode::from_setting(&mode),
+        bounds,
+        is_open: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes::test_support::TestEnv;
+
+    const BOUNDS: WindowBounds = WindowBounds {
+        x: 40,
+        y: 60,
+        width: 480,
+        height: 320,
+    };
+
+    #[test]
+    fn reopening_keeps_the_bounds_and_takes_the_new_mode() {
+        let env = TestEnv::new();
+        let id = env.draft("Sticky");
+        let opened = open(&env.conn, &id, NoteWindowMode::Normal).unwrap();
+        assert!(opened.is_open);
+        assert_eq!(opened.bounds, None);
+        set_bounds(&env.conn, &id, BOUNDS).unwrap();
+        close(&env.conn, &id).unwrap();
+        assert!(!get(&env.conn, &id).unwrap().unwrap().is_open);
+
+        let reopened = open(&env.conn, &id, NoteWindowMode::Sticky).unwrap();
+        assert_eq!(
+            reopened,
+            NoteWindow {
+                note_id: id,
+                mode: NoteWindowMode::Sticky,
+                bounds: Some(BOUNDS),
+                is_open: true,
+            }
+        );
+    }
+
+    #[test]
+    fn lists_open_windows_of_active_notes_only() {
+        let env = TestEnv::new();
+        let open_id = env.draft("Open");
+        let closed_id = env.draft("Closed");
+        let trashed_id = env.draft("Trashed");
+        for id in [&open_id, &closed_id, &trashed_id] {
+            open(&env.conn, id, NoteWindowMode::Normal).unwrap();
+        }
+        close(&env.conn, &closed_id).unwrap();
+        crate::notes::trash(&env.conn, &env.paths, &trashed_id).unwrap();
+
+        let ids: Vec<String> = list_open(&env.conn)
+            .unwrap()
+            .into_iter()
+            .map(|window| window.note_id)
+            .collect();
+        assert_eq!(ids, vec![open_id]);
+    }
+
+    #[test]
+    fn trashed_notes_get_no_window() {
+        let env = TestEnv::new();
+        let id = env.draft("Gone");
+        crate::notes::trash(&env.conn, &env.paths, &id).unwrap();
+        let err = open(&env.conn, &id, NoteWindowMode::Sticky).unwrap_err();
+        assert_eq!(err, Message::new("error.window_trashed"));
+        assert_eq!(get(&env.conn, &id).unwrap(), None);
+    }
+}
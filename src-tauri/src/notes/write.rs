@@ -1,18 +1,21 @@
 use crate::app_state::AppPaths;
+use crate::i18n::Message;
 use crate::scoped_file;
 use crate::types::{NoteMeta, NoteStorage, NoteWithContent};
 use rusqlite::{params, Connection};
 use std::path::Path;
 use uuid::Uuid;
 
-use super::derive::derive_title_preview;
+use super::content_index::index_body;
+use super::encoding::TextFormat;
 use super::files::{read_file, write_file};
-use super::meta::{get_meta, storage_to_db};
+use super::frontmatter::{self, BlockSource};
+use super::meta::{display_title, get_meta, storage_to_db, update_format};
 use super::ordering::next_sort_order;
 use super::scoped_updates::{apply_scoped_updates, get_bookmark};
 use super::time::now_ms;
 
-pub(super) fn create_draft(conn: &Connection, paths: &AppPaths) -> Result<NoteMeta, String> {
+pub(super) fn create_draft(conn: &Connection, paths: &AppPaths) -> Result<NoteMeta, Message> {
     let id = Uuid::new_v4().to_string();
     let file_path = paths.drafts_dir.join(format!("{id}.md"));
     std::fs::write(&file_path, "").map_err(|err| err.to_string())?;
@@ -20,7 +23,7 @@ pub(super) fn create_draft(conn: &Connection, paths: &AppPaths) -> Result<NoteMe
     let now = now_ms();
     let sort_order = next_sort_order(conn)?;
 
-    conn.execute(
+    let inserted = conn.execute(
         r#"
 INSERT INTO notes (
   id, title, preview, file_path, storage, bookmark, is_pinned, is_trashed,
@@ -32,7 +35,7 @@ INSERT INTO notes (
 "#,
         params![
             id,
-            "New note",
+            "",
             "",
             file_path.to_string_lossy(),
             storage_to_db(NoteStorage::Draft),
@@ -40,12 +43,15 @@ INSERT INTO notes (
             now,
             now
         ],
-    )
-    .map_err(|err| err.to_string())?;
+    );
+    if let Err(err) = inserted {
+        let _ = std::fs::remove_file(&file_path);
+        return Err(err.to_string().into());
+    }
 
     Ok(NoteMeta {
         id,
-        title: "New note".to_string(),
+        title: display_title(String::new()),
         preview: "".to_string(),
         file_path: file_path.to_string_lossy().to_string(),
         storage: NoteStorage::Draft,
@@ -55,31 +61,70 @@ INSERT INTO notes (
         created_at: now,
         last_interaction: now,
         trashed_at: None,
+        encoding: TextFormat::UTF8_LF.encoding,
+        has_bom: TextFormat::UTF8_LF.has_bom,
+        line_ending: TextFormat::UTF8_LF.line_ending,
+        expires_at: None,
+        open_tasks: 0,
+        done_tasks: 0,
+        daily_date: None,
+        remind_at: None,
+        expiry_directive: None,
+    })
+}
+
+/// The note's file as it is on disk, front matter included. Unlike [`get`] nothing is synced,
+/// indexed or refreshed, so reading a note this way doesn't change it.
+pub(super) fn read_stored(conn: &Connection, id: &str) -> Result<NoteWithContent, Message> {
+    let meta = get_meta(conn, id)?;
+    let decoded = if meta.storage == NoteStorage::Saved {
+        let bookmark = get_bookmark(conn, id)?;
+        scoped_file::with_scoped_file(Path::new(&meta.file_path), bookmark.as_deref(), read_file)
+            .map(|outcome| outcome.value)
+    } else {
+        read_file(Path::new(&meta.file_path))
+    }
+    .map_err(|err| Message::new("error.read_failed").arg("error", err))?;
+    Ok(NoteWithContent {
+        meta,
+        content: decoded.content,
     })
 }
 
-pub(super) fn get(conn: &Connection, id: &str) -> Result<NoteWithContent, String> {
+pub(super) fn get(conn: &Connection, id: &str) -> Result<NoteWithContent, Message> {
     let meta = get_meta(conn, id)?;
     if meta.storage != NoteStorage::Saved {
-        let content =
-            read_file(Path::new(&meta.file_path)).map_err(|err| format!("Read failed: {err}"))?;
-        return Ok(NoteWithContent { meta, content });
+        let decoded = read_file(Path::new(&meta.file_path))
+            .map_err(|err| Message::new("error.read_failed").arg("error", err))?;
+        let format = decoded.format_or(TextFormat::of(&meta));
+        if format != TextFormat::of(&meta) {
+            update_format(conn, id, format)?;
+        }
+        let content = frontmatter::sync(conn, id, &decoded.content, BlockSource::File)?;
+        return Ok(NoteWithContent {
+            meta: get_meta(conn, id)?,
+            content: content.to_string(),
+        });
     }
 
     let bookmark = get_bookmark(conn, id)?;
     let outcome =
         scoped_file::with_scoped_file(Path::new(&meta.file_path), bookmark.as_deref(), read_file)
-            .map_err(|err| format!("Read failed: {err}"))?;
+            .map_err(|err| Message::new("error.read_failed").arg("error", err))?;
     apply_scoped_updates(conn, id, outcome.refreshed_bookmark, outcome.resolved_path)?;
+    // Saved files can be edited elsewhere, so the file on disk decides the format.
+    update_format(conn, id, outcome.value.format_or(TextFormat::of(&meta)))?;
+    let content = frontmatter::sync(conn, id, &outcome.value.content, BlockSource::File)?;
+    index_body(conn, id, content)?;
 
     let meta = get_meta(conn, id)?;
     Ok(NoteWithContent {
         meta,
-        content: outcome.value,
+        content: content.to_string(),
     })
 }
 
-pub(super) fn set_active(conn: &Connection, id: &str) -> Result<(), String> {
+pub(super) fn set_active(conn: &Connection, id: &str) -> Result<(), Message> {
     let now = now_ms();
     conn.execute(
         "UPDATE notes SET last_interaction = ?1 WHERE id = ?2",
@@ -89,16 +134,23 @@ pub(super) fn set_active(conn: &Connection, id: &str) -> Result<(), String> {
     Ok(())
 }
 
-pub(super) fn write_draft(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, String> {
+pub(super) fn write_draft(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, Message> {
     let meta = get_meta(conn, id)?;
     if meta.storage != NoteStorage::Draft {
-        return Err("Only drafts can be auto-saved.".to_string());
+        return Err(Message::new("error.draft_only"));
     }
 
-    write_file(Path::new(&meta.file_path), content)
-        .map_err(|err| format!("Write failed: {err}"))?;
+    let body = frontmatter::sync(conn, id, content, BlockSource::Editor)?;
+    let file_content = frontmatter::compose(conn, &get_meta(conn, id)?, body)?;
+    write_file(
+        Path::new(&meta.file_path),
+        &file_content,
+        TextFormat::of(&meta),
+    )
+    .map_err(|err| Message::new("error.write_failed").arg("error", err))?;
 
-    let (title, preview) = derive_title_preview(content);
+    let (title, preview) = frontmatter::title_preview(conn, id, body)?;
+    index_body(conn, id, body)?;
     let now = now_ms();
     conn.execute(
         r#"
@@ -113,21 +165,78 @@ WHERE id = ?4
     get_meta(conn, id)
 }
 
-pub(super) fn save(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, String> {
+/// A new draft with `content`. With `expiry_minutes` it expires that long from now instead of
+/// after the usual inactivity.
+pub(super) fn create_with_content(
+    conn: &Connection,
+    paths: &AppPaths,
+    content: &str,
+    expiry_minutes: Option<i64>,
+) -> Result<NoteMeta, Message> {
+    create_filled(conn, paths, |conn, draft| {
+        let meta = write_draft(conn, &draft.id, content)?;
+        let Some(minutes) = expiry_minutes else {
+            return Ok(meta);
+        };
+        let expires_at = now_ms().saturating_add(minutes.saturating_mul(60_000));
+        conn.execute(
+            "UPDATE notes SET expires_at = ?1 WHERE id = ?2",
+            params![expires_at, meta.id],
+        )
+        .map_err(|err| err.to_string())?;
+        get_meta(conn, &meta.id)
+    })
+}
+
+/// Creates a draft and runs `fill` on it as one step: if anything fails, the row is rolled back
+/// and the file removed, so no empty "New note" is left behind. Inside a caller's transaction
+/// (recurring notes) the caller rolls back instead.
+pub(super) fn create_filled(
+    conn: &Connection,
+    paths: &AppPaths,
+    fill: impl FnOnce(&Connection, &NoteMeta) -> Result<NoteMeta, Message>,
+) -> Result<NoteMeta, Message> {
+    let tx = if conn.is_autocommit() {
+        Some(
+            conn.unchecked_transaction()
+                .map_err(|err| err.to_string())?,
+        )
+    } else {
+        None
+    };
+    let draft = create_draft(conn, paths)?;
+    let filled = fill(conn, &draft).and_then(|meta| match tx {
+        Some(tx) => tx
+            .commit()
+            .map(|()| meta)
+            .map_err(|err| err.to_string().into()),
+        None => Ok(meta),
+    });
+    if filled.is_err() {
+        let _ = std::fs::remove_file(&draft.file_path);
+    }
+    filled
+}
+
+pub(super) fn save(conn: &Connection, id: &str, content: &str) -> Result<NoteMeta, Message> {
     let meta = get_meta(conn, id)?;
     if meta.storage != NoteStorage::Saved {
-        return Err("Only saved notes can be saved with Cmd+S.".to_string());
+        return Err(Message::new("error.saved_only"));
     }
 
+    let body = frontmatter::sync(conn, id, content, BlockSource::Editor)?;
+    let file_content = frontmatter::compose(conn, &get_meta(conn, id)?, body)?;
     let bookmark = get_bookmark(conn, id)?;
+    let format = TextFormat::of(&meta);
     let outcome =
         scoped_file::with_scoped_file(Path::new(&meta.file_path), bookmark.as_deref(), |p| {
-            write_file(p, content)
+            write_file(p, &file_content, format)
         })
-        .map_err(|err| format!("Write failed: {err}"))?;
+        .map_err(|err| Message::new("error.write_failed").arg("error", err))?;
     apply_scoped_updates(conn, id, outcome.refreshed_bookmark, outcome.resolved_path)?;
 
-    let (title, preview) = derive_title_preview(content);
+    let (title, preview) = frontmatter::title_preview(conn, id, body)?;
+    index_body(conn, id, body)?;
     let now = now_ms();
     conn.execute(
         r#"
@@ -148,18 +257,23 @@ pub(super) fn save_as(
     id: &str,
     new_path: &Path,
     content: &str,
-) -> Result<NoteMeta, String> {
+) -> Result<NoteMeta, Message> {
     let meta = get_meta(conn, id)?;
 
-    let outcome = scoped_file::with_scoped_file(new_path, None, |p| write_file(p, content))
-        .map_err(|err| format!("Write failed: {err}"))?;
+    let body = frontmatter::sync(conn, id, content, BlockSource::Editor)?;
+    let file_content = frontmatter::compose(conn, &get_meta(conn, id)?, body)?;
+    let format = TextFormat::of(&meta);
+    let outcome =
+        scoped_file::with_scoped_file(new_path, None, |p| write_file(p, &file_content, format))
+            .map_err(|err| Message::new("error.write_failed").arg("error", err))?;
     let effective_new_path = outcome.resolved_path.as_deref().unwrap_or(new_path);
 
     if meta.storage == NoteStorage::Draft {
         let _ = std::fs::remove_file(&meta.file_path);
     }
 
-    let (title, preview) = derive_title_preview(content);
+    let (title, preview) = frontmatter::title_preview(conn, id, body)?;
+    index_body(conn, id, body)?;
     let now = now_ms();
     conn.execute(
         r#"
@@ -189,3 +303,64 @@ WHERE id = ?7
 
     get_meta(conn, id)
 }
+
+/// Rewrites the note as UTF-8 without BOM and with `\n` line breaks, through the regular
+/// draft/save paths.
+pub(super) fn convert_to_utf8(
+    conn: &Connection,
+    id: &str,
+    content: &str,
+) -> Result<NoteMeta, Message> {
+    let meta = get_meta(conn, id)?;
+    let previous = TextFormat::of(&meta);
+    update_format(conn, id, TextFormat::UTF8_LF)?;
+
+    let result = match meta.storage {
+        NoteStorage::Draft => write_draft(conn, id, content),
+        NoteStorage::Saved => save(conn, id, content),
+    };
+    if result.is_err() {
+        update_format(conn, id, previous)?;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::CommandError;
+    use crate::notes::test_support::TestEnv;
+
+    #[test]
+    fn a_failed_read_reaches_the_command_with_its_key() {
+        let env = TestEnv::new();
+        let id = env.draft("Lost");
+        std::fs::remove_file(get_meta(&env.conn, &id).unwrap().file_path).unwrap();
+
+        let err = CommandError::from(get(&env.conn, &id).unwrap_err());
+        assert_eq!(err.key, "error.read_failed");
+        assert!(err.args.contains_key("error"), "{err:?}");
+    }
+
+    #[test]
+    fn a_failed_create_leaves_no_draft() {
+        let env = TestEnv::new();
+        env.conn
+            .execute_batch(
+                "CREATE TEMP TRIGGER fail_expiry BEFORE UPDATE OF expires_at ON notes
+                 BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+            )
+            .unwrap();
+
+        assert!(create_with_content(&env.conn, &env.paths, "# Later", Some(60)).is_err());
+        let count: i64 = env
+            .conn
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(std::fs::read_dir(&env.paths.drafts_dir).unwrap().count(), 0);
+
+        let meta = create_with_content(&env.conn, &env.paths, "# Now", None).unwrap();
+        assert_eq!(meta.title, "Now");
+    }
+}
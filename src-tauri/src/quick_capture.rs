@@ -0,0 +1,163 @@
+//! Quick capture: a system-wide shortcut brings up a small undecorated window to jot something
+//! down without the main window. Submitting files the text under the inbox note chosen in the
+//! settings, or in a new draft.
+
+use crate::app_state::AppState;
+use crate::i18n::{self, Message};
+use crate::logs;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+pub const SHORTCUT_SETTING: &str = "quick_capture_shortcut";
+pub const INBOX_SETTING: &str = "quick_capture_inbox_id";
+pub const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Alt+KeyN";
+pub const LABEL: &str = "capture";
+
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                toggle(app);
+            }
+        })
+        .build()
+}
+
+/// Registers the shortcut from the settings at launch.
+pub fn register_saved(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let shortcut = match state.db.lock() {
+        Ok(conn) => shortcut(&conn),
+        Err(_) => Err(Message::new("error.db_lock").into()),
+    };
+    let result =
+        shortcut.and_then(|shortcut| replace_shortcut(app, "", &shortcut).map_err(String::from));
+    if let Err(err) = result {
+        logs::error("quick-capture", &err);
+    }
+}
+
+/// Swaps the registered shortcut for `next`; an empty one turns quick capture off. If `next` is
+/// invalid or another app holds it, `previous` stays registered. Call it without the DB lock:
+/// registering waits for the main thread.
+pub fn replace_shortcut(app: &AppHandle, previous: &str, next: &str) -> Result<(), Message> {
+    let next = parse(next)?;
+    let previous = parse(previous).ok().flatten();
+    let shortcuts = app.global_shortcut();
+    shortcuts.unregister_all().map_err(|err| err.to_string())?;
+    let Some(next) = next else {
+        return Ok(());
+    };
+    if let Err(err) = shortcuts.register(next) {
+        if let Some(previous) = previous {
+            let _ = shortcuts.register(previous);
+        }
+        return Err(Message::new("error.shortcut_register").arg("error", err));
+    }
+    Ok(())
+}
+
+fn parse(shortcut: &str) -> Result<Option<Shortcut>, Message> {
+    let shortcut = shortcut.trim();
+    if shortcut.is_empty() {
+        return Ok(None);
+    }
+    shortcut.parse::<Shortcut>().map(Some).map_err(|err| {
+        Message::new("error.shortcut_invalid")
+            .arg("shortcut", shortcut)
+            .arg("error", err)
+    })
+}
+
+/// The shortcut from the settings; empty when quick capture is off.
+pub fn shortcut(conn: &Connection) -> Result<String, String> {
+    Ok(setting(conn, SHORTCUT_SETTING)?.unwrap_or_else(|| DEFAULT_SHORTCUT.to_string()))
+}
+
+/// The note captures are appended to, if one is chosen.
+pub fn inbox_id(conn: &Connection) -> Result<Option<String>, String> {
+    Ok(setting(conn, INBOX_SETTING)?.filter(|id| !id.trim().is_empty()))
+}
+
+fn setting(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|err| err.to_string())
+}
+
+/// Shows the capture window, or hides it when it already has focus.
+pub fn toggle(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        if window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false) {
+            hide(app);
+            return;
+        }
+    }
+    if let Err(err) = show(app) {
+        logs::error("quick-capture", &err);
+    }
+}
+
+fn show(app: &AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => window,
+        None => {
+            WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("index.html?capture=1".into()))
+                .title(i18n::t("quick_capture.title"))
+                .inner_size(520.0, 180.0)
+                .resizable(false)
+                .decorations(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .visible_on_all_workspaces(true)
+                .disable_drag_drop_handler()
+                .visible(false)
+                .build()
+                .map_err(|err| err.to_string())?
+        }
+    };
+    let _ = window.center();
+    activate();
+    let _ = window.show();
+    let _ = window.set_focus();
+    Ok(())
+}
+
+/// Hides the capture window. The activation policy is left alone; on macOS, when no other window
+/// is showing, the app hides so focus goes back to whatever was in front before.
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.hide();
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let others_visible = app
+            .webview_windows()
+            .iter()
+            .any(|(label, window)| label != LABEL && window.is_visible().unwrap_or(false));
+        if !others_visible {
+            let _ = app.hide();
+        }
+    }
+}
+
+/// Brings the app to front so the capture window gets keyboard focus, without switching an
+/// accessory app to a regular one.
+#[cfg(target_os = "macos")]
+fn activate() {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSApplication;
+
+    if let Some(mtm) = MainThreadMarker::new() {
+        NSApplication::sharedApplication(mtm).activate();
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn activate() {}
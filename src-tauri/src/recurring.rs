@@ -0,0 +1,28 @@
+use crate::app_state::AppState;
+use crate::events;
+use crate::i18n::Message;
+use crate::notes;
+use crate::types::{ChangeReason, NoteChange};
+use chrono::Local;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Checks recurring notes once a minute, starting right away so notes missed while the app was
+/// closed show up at launch.
+pub fn start_background_scheduler(app_handle: AppHandle, state: AppState) {
+    std::thread::spawn(move || loop {
+        match run(&state) {
+            Ok(created) => events::publish(&app_handle, ChangeReason::Recurring, created),
+            Err(err) => eprintln!("recurring notes error: {err}"),
+        }
+        std::thread::sleep(Duration::from_secs(60));
+    });
+}
+
+pub fn run(state: &AppState) -> Result<Vec<NoteChange>, Message> {
+    let mut conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    notes::materialize_recurring(&mut conn, &state.paths, Local::now())?
+        .iter()
+        .map(|id| notes::get_meta(&conn, id).map(NoteChange::created))
+        .collect()
+}
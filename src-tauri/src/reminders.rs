@@ -0,0 +1,124 @@
+use crate::app_state::AppState;
+use crate::events;
+use crate::i18n::{self, Message};
+use crate::logs;
+use crate::notes;
+use crate::types::{ChangeReason, NoteChange, NoteMeta};
+use notify_rust::NotificationResponse;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Fires due reminders every 15 seconds, starting right away so reminders that came due while
+/// the app was closed are announced at launch.
+pub fn start_background_reminders(app_handle: AppHandle, state: AppState) {
+    std::thread::spawn(move || loop {
+        match take_due(&state) {
+            Ok(due) if !due.is_empty() => {
+                for note in &due {
+                    notify(&app_handle, note);
+                    let _ = app_handle.emit("note-reminder", note);
+                }
+                crate::refresh_tray_menu(&app_handle);
+            }
+            Ok(_) => {}
+            Err(err) => logs::error("reminders", &format!("check failed: err=\"{err}\"")),
+        }
+        std::thread::sleep(Duration::from_secs(15));
+    });
+}
+
+fn take_due(state: &AppState) -> Result<Vec<NoteMeta>, Message> {
+    let conn = state.db.lock().map_err(|_| Message::new("error.db_lock"))?;
+    notes::take_unnotified_reminders(&conn, notes::now_ms())
+}
+
+/// Shows the reminder through `notify-rust` itself, because the notification plugin doesn't
+/// report clicks or actions on desktop. A thread waits for the response: a click opens the note
+/// and the snooze actions move the reminder like the tray's "Due" items do.
+fn notify(app_handle: &AppHandle, note: &NoteMeta) {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .summary(&i18n::t("reminder.title"))
+        .body(note.title.trim())
+        .action("default", &i18n::t("tray.open"))
+        .auto_icon();
+    for (action, label, _) in SNOOZE_ACTIONS {
+        notification.action(action, &i18n::t(label));
+    }
+    #[cfg(windows)]
+    if !tauri::is_dev() {
+        notification.app_id(&tauri::Manager::config(app_handle).identifier);
+    }
+    #[cfg(target_os = "macos")]
+    let _ = notify_rust::set_application(if tauri::is_dev() {
+        "com.apple.Terminal"
+    } else {
+        &tauri::Manager::config(app_handle).identifier
+    });
+
+    let app_handle = app_handle.clone();
+    let id = note.id.clone();
+    std::thread::spawn(move || {
+        let result = notification.show().and_then(|handle| {
+            handle.wait_for_response(|response: &NotificationResponse| match response {
+                NotificationResponse::Default => open(&app_handle, &id),
+                NotificationResponse::Action(action) if action == "default" => {
+                    open(&app_handle, &id)
+                }
+                NotificationResponse::Action(action) => {
+                    let snooze = SNOOZE_ACTIONS
+                        .iter()
+                        .find(|(name, _, _)| *name == action.as_str());
+                    if let Some((_, _, minutes)) = snooze {
+                        update(&app_handle, &id, Some(*minutes));
+                    }
+                }
+                _ => {}
+            })
+        });
+        if let Err(err) = result {
+            logs::error(
+                "reminders",
+                &format!("notification failed: id={id} err=\"{err}\""),
+            );
+        }
+    });
+}
+
+/// Notification action id, label key and minutes of each snooze action.
+const SNOOZE_ACTIONS: [(&str, &str, i64); 2] = [
+    ("snooze_10m", "tray.snooze_10m", 10),
+    ("snooze_1h", "tray.snooze_1h", 60),
+];
+
+fn open(app_handle: &AppHandle, id: &str) {
+    if let Err(err) = crate::cli::show_note(app_handle, id) {
+        logs::error("reminders", &format!("open failed: id={id} err=\"{err}\""));
+    }
+}
+
+/// Snoozes (`Some(minutes)`) or dismisses a reminder from the tray's "Due" section or the
+/// notification, publishes the change and rebuilds the tray.
+pub fn update<R: Runtime>(app_handle: &AppHandle<R>, note_id: &str, snooze_minutes: Option<i64>) {
+    let result = {
+        let state = app_handle.state::<AppState>();
+        let Ok(conn) = state.db.lock() else {
+            logs::error("reminders", &i18n::t("error.db_lock"));
+            return;
+        };
+        notes::get_meta(&conn, note_id).and_then(|before| {
+            let after = match snooze_minutes {
+                Some(minutes) => notes::snooze_reminder(&conn, note_id, minutes),
+                None => notes::set_reminder(&conn, note_id, None),
+            }?;
+            Ok(NoteChange::updated(before, after))
+        })
+    };
+    match result {
+        Ok(change) => {
+            events::publish_one(app_handle, ChangeReason::Reminder, change);
+            crate::refresh_tray_menu(app_handle);
+        }
+        Err(err) => logs::error("reminders", &err.to_string()),
+    }
+}
@@ -1,5 +1,9 @@
+#[cfg(target_os = "macos")]
+use std::fmt;
 use std::path::{Path, PathBuf};
 
+#[cfg(target_os = "macos")]
+use crate::i18n::Message;
 use crate::logs;
 
 #[derive(Debug)]
@@ -10,11 +14,11 @@ pub struct ScopedOutcome<T> {
 }
 
 #[cfg(not(target_os = "macos"))]
-pub fn with_scoped_file<T>(
+pub fn with_scoped_file<T, E>(
     path: &Path,
     _bookmark: Option<&[u8]>,
-    op: impl FnOnce(&Path) -> Result<T, String>,
-) -> Result<ScopedOutcome<T>, String> {
+    op: impl FnOnce(&Path) -> Result<T, E>,
+) -> Result<ScopedOutcome<T>, E> {
     let value = op(path)?;
     Ok(ScopedOutcome {
         value,
@@ -24,11 +28,11 @@ pub fn with_scoped_file<T>(
 }
 
 #[cfg(target_os = "macos")]
-pub fn with_scoped_file<T>(
+pub fn with_scoped_file<T, E: From<Message> + fmt::Display>(
     path: &Path,
     bookmark: Option<&[u8]>,
-    op: impl FnOnce(&Path) -> Result<T, String>,
-) -> Result<ScopedOutcome<T>, String> {
+    op: impl FnOnce(&Path) -> Result<T, E>,
+) -> Result<ScopedOutcome<T>, E> {
     use objc2::rc::autoreleasepool;
     use objc2::runtime::Bool;
     use objc2_foundation::{
@@ -40,7 +44,7 @@ pub fn with_scoped_file<T>(
             Some(path_str) => path_str,
             None => {
                 log_scoped_failure("path_non_utf8", path, "path is not valid UTF-8");
-                return Err("Non-UTF8 paths are not supported.".to_string());
+                return Err(Message::new("error.non_utf8_path").into());
             }
         };
 
@@ -69,7 +73,8 @@ pub fn with_scoped_file<T>(
                         &format!("had_bookmark=true err=\"{desc}\" debug=\"{debug}\""),
                     );
                     ns_error(err)
-                })?;
+                })
+                .map_err(|err| E::from(Message::from(err)))?;
 
                 (url, is_stale.as_bool())
             }
@@ -127,7 +132,7 @@ pub fn with_scoped_file<T>(
                         path,
                         &format!("had_bookmark=false err=\"{desc}\" debug=\"{debug}\""),
                     );
-                    return Err(ns_error(err));
+                    return Err(Message::from(ns_error(err)).into());
                 }
                 Err(err) if started => {
                     let desc = err.localizedDescription().to_string();
@@ -137,7 +142,7 @@ pub fn with_scoped_file<T>(
                         path,
                         &format!("had_bookmark=true started=true err=\"{desc}\" debug=\"{debug}\""),
                     );
-                    return Err(ns_error(err));
+                    return Err(Message::from(ns_error(err)).into());
                 }
                 Err(_) => None,
             }
@@ -7,6 +7,26 @@ pub enum NoteStorage {
     Saved,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "windows-1252")]
+    Windows1252,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteMeta {
@@ -21,6 +41,199 @@ pub struct NoteMeta {
     pub created_at: i64,
     pub last_interaction: i64,
     pub trashed_at: Option<i64>,
+    pub encoding: TextEncoding,
+    pub has_bom: bool,
+    pub line_ending: LineEnding,
+    /// Fixed expiry time, e.g. from front matter. `None` means the inactivity timer applies.
+    pub expires_at: Option<i64>,
+    /// Unchecked task items; they can keep the note from expiring.
+    pub open_tasks: i64,
+    /// Checked task items.
+    pub done_tasks: i64,
+    /// `YYYY-MM-DD` for the daily note of that day.
+    pub daily_date: Option<String>,
+    /// When to remind about the note; stays set after it fires until snoozed or dismissed.
+    pub remind_at: Option<i64>,
+    /// An expiry directive found in the content, e.g. `expires: friday`.
+    pub expiry_directive: Option<ExpiryDirective>,
+}
+
+/// What an expiry directive in a note's content was understood as. It takes precedence over
+/// `expires_at` while it is in the note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiryDirective {
+    /// The directive as written, lowercased.
+    pub phrase: String,
+    pub expires_at: i64,
+}
+
+/// Where the markdown converted from an HTML or RTF import ends up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConvertedImportTarget {
+    #[default]
+    Draft,
+    Sibling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalSource {
+    Keep,
+    Simplenote,
+    Obsidian,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportReport {
+    pub source: ExternalSource,
+    pub committed: bool,
+    pub notes: Vec<ExternalImportItem>,
+    pub skipped: Vec<ExternalImportSkip>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportItem {
+    pub source_id: String,
+    pub title: String,
+    pub preview: String,
+    pub tags: Vec<String>,
+    pub is_pinned: bool,
+    /// The note was pinned in the source but the pin limit was already reached.
+    pub pin_dropped: bool,
+    /// Archived in the source. There is no archive here, so these import into the trash.
+    pub is_archived: bool,
+    pub is_trashed: bool,
+    pub created_at: i64,
+    pub last_interaction: i64,
+    pub expires_at: Option<i64>,
+    /// Id of the created note; only set once the import is committed.
+    pub note_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportSkip {
+    pub source_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianExportItem {
+    pub id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStatus {
+    Resolved,
+    /// Several notes share the title; the link points at the most recently used one.
+    Ambiguous,
+    /// The target is in the trash, e.g. after it expired.
+    Dangling,
+    Missing,
+}
+
+/// A `[[wikilink]]` from `source_id` to whatever `target` currently resolves to.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteLink {
+    pub source_id: String,
+    pub source_title: String,
+    /// Title or id as written in the link.
+    pub target: String,
+    /// Display text of a `[[target|label]]` link.
+    pub label: Option<String>,
+    pub target_id: Option<String>,
+    pub target_title: Option<String>,
+    pub status: LinkStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    #[default]
+    Open,
+    Done,
+    All,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskFilter {
+    pub state: TaskState,
+    /// Only tasks of this note.
+    pub note_id: Option<String>,
+    /// Substring of the task text.
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskItem {
+    pub note_id: String,
+    pub note_title: String,
+    /// 1-based line in the note as the editor shows it (front matter excluded).
+    pub line: i64,
+    pub text: String,
+    pub checked: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTemplate {
+    /// File name in the templates folder, without `.md`.
+    pub name: String,
+    /// From `expires_after` in the template's front matter.
+    pub expiry_minutes: Option<i64>,
+    pub uses_clipboard: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteFromTemplate {
+    pub note: NoteWithContent,
+    /// Where `{{cursor}}` was, as a UTF-16 offset into `note.content`.
+    pub cursor: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recurrence {
+    pub id: String,
+    pub name: String,
+    /// Template name, as in [`NoteTemplate::name`].
+    pub template: String,
+    /// RRULE subset, e.g. `FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0`.
+    pub rule: String,
+    /// Lifetime of each generated note; `None` leaves it to the usual expiry.
+    pub expiry_minutes: Option<i64>,
+    pub created_at: i64,
+    pub last_occurrence_at: Option<i64>,
+    pub next_occurrence_at: Option<i64>,
+    /// Why the last due occurrence couldn't be generated, e.g. a missing template. Cleared once
+    /// one is generated again.
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceInput {
+    /// Set to update an existing recurrence.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Defaults to the template name when empty.
+    #[serde(default)]
+    pub name: String,
+    pub template: String,
+    pub rule: String,
+    #[serde(default)]
+    pub expiry_minutes: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -30,6 +243,167 @@ pub struct NotesList {
     pub trashed: Vec<NoteMeta>,
 }
 
+/// Why `notes-changed` was published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeReason {
+    Created,
+    Written,
+    Saved,
+    Imported,
+    Trashed,
+    Restored,
+    Deleted,
+    Pinned,
+    Reordered,
+    Reminder,
+    Recurring,
+    /// Trashed or purged by the expiry sweeper.
+    Expired,
+    /// A setting changed; carries no note changes.
+    Settings,
+    /// A journaled operation was reversed or reapplied.
+    Undo,
+    Redo,
+}
+
+/// One note before and after a mutation. `before` is `None` for new notes, `after` for notes
+/// that were deleted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteChange {
+    pub id: String,
+    pub before: Option<NoteMeta>,
+    pub after: Option<NoteMeta>,
+}
+
+impl NoteChange {
+    pub fn created(after: NoteMeta) -> Self {
+        Self {
+            id: after.id.clone(),
+            before: None,
+            after: Some(after),
+        }
+    }
+
+    pub fn updated(before: NoteMeta, after: NoteMeta) -> Self {
+        Self {
+            id: after.id.clone(),
+            before: Some(before),
+            after: Some(after),
+        }
+    }
+
+    pub fn deleted(before: NoteMeta) -> Self {
+        Self {
+            id: before.id.clone(),
+            before: Some(before),
+            after: None,
+        }
+    }
+}
+
+/// What `history_undo`/`history_redo` would do next, as shown in the Edit menu.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryState {
+    pub undo: Option<String>,
+    pub redo: Option<String>,
+}
+
+/// A journaled operation that was just undone or redone.
+#[derive(Debug, Clone)]
+pub struct HistoryStep {
+    pub label: String,
+    pub changes: Vec<NoteChange>,
+}
+
+/// How `note_open_window` shows a note: a regular window, or a compact one that floats above
+/// other apps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteWindowMode {
+    #[default]
+    Normal,
+    Sticky,
+}
+
+impl NoteWindowMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NoteWindowMode::Normal => "normal",
+            NoteWindowMode::Sticky => "sticky",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "sticky" => NoteWindowMode::Sticky,
+            _ => NoteWindowMode::Normal,
+        }
+    }
+}
+
+/// Outer position and size of a window in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A note's own window. `bounds` is kept after the window closes, so it reopens where it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteWindow {
+    pub note_id: String,
+    pub mode: NoteWindowMode,
+    pub bounds: Option<WindowBounds>,
+    pub is_open: bool,
+}
+
+/// How one id fared in a batch command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatchStatus {
+    Done,
+    Failed,
+    /// Worked on its own, but undone because another id in the batch failed.
+    RolledBack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    pub id: String,
+    pub status: BatchStatus,
+    pub error: Option<String>,
+    /// The note afterwards; `None` once deleted or when nothing changed.
+    pub meta: Option<NoteMeta>,
+    /// Where `notes_export_many` wrote the note.
+    pub path: Option<String>,
+}
+
+/// Per-id results of a batch command. A batch is all or nothing: `committed` is false when any
+/// id failed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    pub committed: bool,
+    pub results: Vec<BatchResult>,
+    /// For the `notes-changed` event; empty unless committed.
+    #[serde(skip)]
+    pub changes: Vec<NoteChange>,
+}
+
+/// Payload of the `notes-changed` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesChanged {
+    pub reason: ChangeReason,
+    pub changes: Vec<NoteChange>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteWithContent {
@@ -37,10 +411,55 @@ pub struct NoteWithContent {
     pub content: String,
 }
 
+/// A note looked up by file or date, and whether the lookup had to create it.
+#[derive(Debug, Clone)]
+pub struct OpenedNote {
+    pub note: NoteWithContent,
+    pub created: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
     pub expiry_minutes: i64,
     pub trash_retention_days: i64,
     pub theme: String,
+    /// Write pin/expiry changes back into the front matter of saved files.
+    pub front_matter_write_back: bool,
+    /// `off`, `extend` (up to `open_tasks_max_days`) or `exempt`.
+    pub open_tasks_expiry: String,
+    pub open_tasks_max_days: i64,
+    /// Notes whose tasks are all checked expire after this; 0 is off.
+    pub done_tasks_grace_minutes: i64,
+    /// Inactivity window for daily notes, used instead of `expiry_minutes`.
+    pub daily_expiry_minutes: i64,
+    /// Copy unchecked tasks from the previous daily note into a new one.
+    pub daily_carry_tasks: bool,
+    pub calendar_feed_path: String,
+    /// System-wide shortcut for the quick-capture window; empty is off.
+    pub quick_capture_shortcut: String,
+    /// Note that quick captures are appended to; empty creates a new draft each time.
+    pub quick_capture_inbox_id: String,
+    /// Read from the OS login items rather than the settings table.
+    pub autostart: Autostart,
+    /// `system` or a language code such as `de`.
+    pub locale: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Autostart {
+    pub enabled: bool,
+    /// Start in the tray without showing the main window.
+    pub hidden: bool,
+}
+
+/// A menu action's keyboard shortcut; an empty accelerator means none.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keybinding {
+    pub id: String,
+    pub label: String,
+    pub accelerator: String,
+    pub default_accelerator: String,
 }
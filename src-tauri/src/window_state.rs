@@ -63,14 +63,30 @@ fn clamp_window_to_monitor(window: &tauri::WebviewWindow, monitor: &Monitor) ->
     Ok(())
 }
 
+/// Everything but visibility, which `setup` decides: a hidden start leaves the window closed.
+pub fn restored_state() -> StateFlags {
+    StateFlags::all() - StateFlags::VISIBLE
+}
+
 pub fn restore_and_clamp(app: &AppHandle) -> tauri::Result<()> {
     let Some(window) = app.get_webview_window("main") else {
         return Ok(());
     };
 
     // Ignore errors: state file may not exist on first launch.
-    let _ = window.restore_state(StateFlags::all());
+    let _ = window.restore_state(restored_state());
+
+    clamp_to_monitors(&window);
+
+    // Persist sanitized state for next launch.
+    let _ = app.save_window_state(StateFlags::all());
+
+    Ok(())
+}
 
+/// Moves and shrinks the window so it fits the monitor under its center, e.g. after a display
+/// was disconnected.
+pub fn clamp_to_monitors(window: &tauri::WebviewWindow) {
     let monitors = window.available_monitors().unwrap_or_default();
 
     let pos = window
@@ -88,13 +104,8 @@ pub fn restore_and_clamp(app: &AppHandle) -> tauri::Result<()> {
         .or_else(|| window.primary_monitor().ok().flatten());
 
     if let Some(m) = monitor {
-        let _ = clamp_window_to_monitor(&window, &m);
+        let _ = clamp_window_to_monitor(window, &m);
     }
-
-    // Persist sanitized state for next launch.
-    let _ = app.save_window_state(StateFlags::all());
-
-    Ok(())
 }
 
 /// Show the main window and bring the app to front on macOS.
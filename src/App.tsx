@@ -1,4 +1,4 @@
-import { Suspense, lazy, useCallback, useMemo, useState } from "react";
+import { Suspense, lazy, useCallback, useEffect, useMemo, useState } from "react";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { toast } from "sonner";
@@ -28,6 +28,7 @@ import { getDirtySavedCount, getDirtySavedMap, isNoteDirty } from "@/stores/note
 import { useNotesStore } from "@/stores/notesStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { api } from "@/lib/api";
+import type { NoteTemplate, Recurrence } from "@/lib/types";
 
 const LazyEditor = lazy(() =>
   import("@/features/editor/Editor").then((mod) => ({ default: mod.Editor })),
@@ -46,7 +47,11 @@ function setQuitInProgress(value: boolean) {
 
 function App() {
   const [showCommandPalette, setShowCommandPalette] = useState(false);
+  // Filled in when a search link opens the palette.
+  const [paletteQuery, setPaletteQuery] = useState("");
   const [showSettings, setShowSettings] = useState(false);
+  const [templates, setTemplates] = useState<NoteTemplate[]>([]);
+  const [recurrences, setRecurrences] = useState<Recurrence[] | null>(null);
   const {
     isCheckingUpdates,
     handleCheckUpdates,
@@ -62,7 +67,23 @@ function App() {
 
   const expiryMinutes = useSettingsStore((s) => s.expiryMinutes);
   const trashRetentionDays = useSettingsStore((s) => s.trashRetentionDays);
+  const frontMatterWriteBack = useSettingsStore((s) => s.frontMatterWriteBack);
+  const openTasksExpiry = useSettingsStore((s) => s.openTasksExpiry);
+  const openTasksMaxDays = useSettingsStore((s) => s.openTasksMaxDays);
+  const doneTasksGraceMinutes = useSettingsStore((s) => s.doneTasksGraceMinutes);
+  const dailyExpiryMinutes = useSettingsStore((s) => s.dailyExpiryMinutes);
+  const dailyCarryTasks = useSettingsStore((s) => s.dailyCarryTasks);
+  const calendarFeedPath = useSettingsStore((s) => s.calendarFeedPath);
+  const quickCaptureShortcut = useSettingsStore((s) => s.quickCaptureShortcut);
+  const quickCaptureInboxId = useSettingsStore((s) => s.quickCaptureInboxId);
+  const autostart = useSettingsStore((s) => s.autostart);
+  const locale = useSettingsStore((s) => s.locale);
+  const keybindings = useSettingsStore((s) => s.keybindings);
   const theme = useSettingsStore((s) => s.theme);
+  const expiryRule = useMemo(
+    () => ({ openTasksExpiry, openTasksMaxDays, doneTasksGraceMinutes, dailyExpiryMinutes }),
+    [openTasksExpiry, openTasksMaxDays, doneTasksGraceMinutes, dailyExpiryMinutes],
+  );
 
   const pinned = useMemo(() => list.active.filter((n) => n.isPinned), [list.active]);
   const notes = useMemo(() => list.active.filter((n) => !n.isPinned), [list.active]);
@@ -130,6 +151,10 @@ function App() {
       toast: { success: (message) => toast.success(message) },
       openFile: open,
       saveFile: save,
+      calendar: {
+        exportTo: api.calendarExport,
+        setFeedPath: (path) => useSettingsStore.getState().setCalendarFeedPath(path),
+      },
       getSelectedId: () => useNotesStore.getState().selectedId,
       getSelectedMeta: () => {
         const s = useNotesStore.getState();
@@ -210,10 +235,57 @@ function App() {
   useExpiryScheduler({
     notes: list.active,
     expiryMinutes,
+    expiryRule,
     runExpirySweep: () => useNotesStore.getState().runExpirySweep(),
     runOrAlert,
   });
 
+  // Templates are files the user edits outside the app, so they are re-read on every open.
+  useEffect(() => {
+    if (!showCommandPalette) return;
+    let cancelled = false;
+    api.templatesList().then(
+      (next) => {
+        if (!cancelled) setTemplates(next);
+      },
+      () => {
+        if (!cancelled) setTemplates([]);
+      },
+    );
+    return () => {
+      cancelled = true;
+    };
+  }, [showCommandPalette]);
+
+  const openSearch = useCallback((query: string) => {
+    setPaletteQuery(query);
+    setShowCommandPalette(true);
+  }, []);
+
+  // Recurrences show when each one runs next, so they are reloaded whenever settings open.
+  useEffect(() => {
+    if (!showSettings) {
+      setRecurrences(null);
+      return;
+    }
+    let cancelled = false;
+    Promise.all([api.recurrencesList(), api.templatesList()]).then(
+      ([nextRecurrences, nextTemplates]) => {
+        if (cancelled) return;
+        setRecurrences(nextRecurrences);
+        setTemplates(nextTemplates);
+      },
+      (err) => console.error("recurrences load failed", err),
+    );
+    return () => {
+      cancelled = true;
+    };
+  }, [showSettings]);
+
+  const reloadRecurrences = useCallback(async () => {
+    setRecurrences(await api.recurrencesList());
+  }, []);
+
   useWindowAndMenuEvents({
     enabled: isBootstrapped,
     actions: {
@@ -225,6 +297,7 @@ function App() {
     runOrAlert,
     requestQuit,
     setShowCommandPalette,
+    openSearch,
     setShowSettings,
     syncMacActivationPolicy,
   });
@@ -242,6 +315,7 @@ function App() {
               selectedId={selectedId}
               dirtyIds={dirtySavedById}
               expiryMinutes={expiryMinutes}
+              expiryRule={expiryRule}
               trashRetentionDays={trashRetentionDays}
               viewMode={viewMode}
               onSelect={(id) => void runOrAlert(() => useNotesStore.getState().select(id))}
@@ -254,6 +328,10 @@ function App() {
               onDeleteForever={(id) => void runOrAlert(() => actions.deleteForeverFromTrash(id))}
               onTogglePin={(id) => void runOrAlert(() => useNotesStore.getState().togglePin(id))}
               onTrash={(id) => void runOrAlert(() => actions.trashNoteById(id))}
+              onSetReminder={(id, at) =>
+                void runOrAlert(() => useNotesStore.getState().setReminder(id, at))
+              }
+              onOpenWindow={(id, mode) => void runOrAlert(() => api.noteOpenWindow(id, mode))}
               onNewNote={() => void runOrAlert(() => useNotesStore.getState().createNote())}
             />
           </div>
@@ -332,9 +410,20 @@ function App() {
 
         {showCommandPalette ? (
           <CommandPalette
+            key={paletteQuery}
             notes={list.active}
-            onClose={() => setShowCommandPalette(false)}
+            templates={templates}
+            initialQuery={paletteQuery}
+            keybindings={keybindings}
+            onClose={() => {
+              setShowCommandPalette(false);
+              setPaletteQuery("");
+            }}
             onNewNote={() => void runOrAlert(() => useNotesStore.getState().createNote())}
+            onNewFromTemplate={(template) =>
+              void runOrAlert(() => useNotesStore.getState().createNoteFromTemplate(template))
+            }
+            onOpenDailyNote={() => void runOrAlert(() => useNotesStore.getState().openDailyNote())}
             onTogglePinCurrent={() => {
               const id = useNotesStore.getState().selectedId;
               if (id) void runOrAlert(() => useNotesStore.getState().togglePin(id));
@@ -343,6 +432,11 @@ function App() {
             onOpenFile={() => void runOrAlert(() => actions.openMarkdown())}
             onSave={() => void runOrAlert(() => actions.saveCurrent())}
             onSaveAs={() => void runOrAlert(() => actions.saveAs())}
+            onRemoveFrontMatter={() => {
+              const id = useNotesStore.getState().selectedId;
+              if (id) void runOrAlert(() => useNotesStore.getState().removeFrontMatter(id));
+            }}
+            onExportCalendar={() => void runOrAlert(() => actions.exportCalendar())}
             onSelectNote={(id) => void runOrAlert(() => useNotesStore.getState().select(id))}
             onOpenSettings={() => setShowSettings(true)}
           />
@@ -350,13 +444,76 @@ function App() {
 
         {showSettings ? (
           <SettingsDialog
-            settings={{ expiryMinutes, trashRetentionDays, theme }}
+            settings={{
+              expiryMinutes,
+              trashRetentionDays,
+              theme,
+              frontMatterWriteBack,
+              ...expiryRule,
+              dailyCarryTasks,
+              calendarFeedPath,
+              quickCaptureShortcut,
+              quickCaptureInboxId,
+              autostart,
+              locale,
+            }}
             onClose={() => setShowSettings(false)}
             onTheme={(theme) => void runOrAlert(() => useSettingsStore.getState().setTheme(theme))}
             onExpiryMinutes={(minutes) =>
               void runOrAlert(() => useSettingsStore.getState().setExpiryMinutes(minutes))
             }
             onTrashDays={(days) => void runOrAlert(() => useSettingsStore.getState().setTrashRetentionDays(days))}
+            onFrontMatterWriteBack={(enabled) =>
+              void runOrAlert(() => useSettingsStore.getState().setFrontMatterWriteBack(enabled))
+            }
+            onOpenTasksExpiry={(rule) =>
+              void runOrAlert(() => useSettingsStore.getState().setOpenTasksExpiry(rule))
+            }
+            onOpenTasksMaxDays={(days) =>
+              void runOrAlert(() => useSettingsStore.getState().setOpenTasksMaxDays(days))
+            }
+            onDoneTasksGraceMinutes={(minutes) =>
+              void runOrAlert(() => useSettingsStore.getState().setDoneTasksGraceMinutes(minutes))
+            }
+            onDailyExpiryMinutes={(minutes) =>
+              void runOrAlert(() => useSettingsStore.getState().setDailyExpiryMinutes(minutes))
+            }
+            onDailyCarryTasks={(enabled) =>
+              void runOrAlert(() => useSettingsStore.getState().setDailyCarryTasks(enabled))
+            }
+            onChooseCalendarFeed={() => void runOrAlert(() => actions.chooseCalendarFeed())}
+            onCalendarFeedOff={() =>
+              void runOrAlert(() => useSettingsStore.getState().setCalendarFeedPath(""))
+            }
+            inboxNotes={list.active}
+            onQuickCaptureShortcut={(shortcut) =>
+              void runOrAlert(() => useSettingsStore.getState().setQuickCaptureShortcut(shortcut))
+            }
+            onQuickCaptureInbox={(id) =>
+              void runOrAlert(() => useSettingsStore.getState().setQuickCaptureInboxId(id))
+            }
+            onAutostart={(next) =>
+              void runOrAlert(() => useSettingsStore.getState().setAutostart(next))
+            }
+            keybindings={keybindings}
+            onKeybinding={(id, accelerator) =>
+              void runOrAlert(() => useSettingsStore.getState().setKeybinding(id, accelerator))
+            }
+            onLocale={(next) => void runOrAlert(() => useSettingsStore.getState().setLocale(next))}
+            recurrences={recurrences ?? undefined}
+            templates={templates}
+            onAddRecurrence={(recurrence) =>
+              void runOrAlert(async () => {
+                await api.recurrenceSave(recurrence);
+                await reloadRecurrences();
+              })
+            }
+            onDeleteRecurrence={(id) =>
+              void runOrAlert(async () => {
+                await api.recurrenceDelete(id);
+                await reloadRecurrences();
+              })
+            }
             isCheckingUpdates={isCheckingUpdates}
             onCheckUpdates={handleCheckUpdates}
           />
@@ -1,21 +1,36 @@
 import { useEffect } from "react";
 import type { NoteMeta } from "@/lib/types";
-import { noteExpiryTime } from "@/lib/utils/expiry";
+import {
+  DEFAULT_EXPIRY_RULE,
+  noteExpiryTime,
+  noteExpiresAt,
+  noteExpiryWindow,
+  type ExpiryRule,
+} from "@/lib/utils/expiry";
 
 type Params = {
   notes: NoteMeta[];
   expiryMinutes: number;
+  expiryRule?: ExpiryRule;
   runExpirySweep: () => Promise<void>;
   runOrAlert: (task: () => void | Promise<void>) => Promise<void>;
 };
 
-export function useExpiryScheduler({ notes, expiryMinutes, runExpirySweep, runOrAlert }: Params) {
+export function useExpiryScheduler({
+  notes,
+  expiryMinutes,
+  expiryRule = DEFAULT_EXPIRY_RULE,
+  runExpirySweep,
+  runOrAlert,
+}: Params) {
   useEffect(() => {
     let nextExpiryAt: number | null = null;
 
     for (const note of notes) {
       if (note.isPinned) continue;
-      const noteExpiry = noteExpiryTime(note.lastInteraction, expiryMinutes);
+      const { minutes } = noteExpiryWindow(expiryMinutes, note, expiryRule);
+      if (minutes === null) continue;
+      const noteExpiry = noteExpiryTime(note.lastInteraction, minutes, noteExpiresAt(note));
       if (nextExpiryAt === null || noteExpiry < nextExpiryAt) nextExpiryAt = noteExpiry;
     }
 
@@ -27,6 +42,6 @@ export function useExpiryScheduler({ notes, expiryMinutes, runExpirySweep, runOr
     }, delay);
 
     return () => window.clearTimeout(timer);
-  }, [expiryMinutes, notes, runExpirySweep, runOrAlert]);
+  }, [expiryMinutes, expiryRule, notes, runExpirySweep, runOrAlert]);
 }
 
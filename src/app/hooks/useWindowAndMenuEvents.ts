@@ -1,8 +1,11 @@
 import { useEffect, type Dispatch, type SetStateAction } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { toast } from "sonner";
 import { createPageKeydownHandler } from "@/routes/pageHotkeys";
+import type { NotesChanged } from "@/lib/types";
 import { useNotesStore } from "@/stores/notesStore";
+import { useSettingsStore } from "@/stores/settingsStore";
 
 type QuitWindow = Window & {
   __augenblickQuitInProgress?: boolean;
@@ -21,6 +24,7 @@ type Params = {
   runOrAlert: (task: () => void | Promise<void>) => Promise<void>;
   requestQuit: () => Promise<void>;
   setShowCommandPalette: Dispatch<SetStateAction<boolean>>;
+  openSearch: (query: string) => void;
   setShowSettings: Dispatch<SetStateAction<boolean>>;
   syncMacActivationPolicy: (visible?: boolean) => Promise<void>;
 };
@@ -31,6 +35,7 @@ export function useWindowAndMenuEvents({
   runOrAlert,
   requestQuit,
   setShowCommandPalette,
+  openSearch,
   setShowSettings,
   syncMacActivationPolicy,
 }: Params) {
@@ -68,6 +73,32 @@ export function useWindowAndMenuEvents({
       if (disposed) return;
       registerUnlisten(await listen("tray-new-note", () => void runOrAlert(() => useNotesStore.getState().createNote())));
 
+      if (disposed) return;
+      registerUnlisten(await listen("tray-daily-note", () => void runOrAlert(() => useNotesStore.getState().openDailyNote())));
+
+      if (disposed) return;
+      registerUnlisten(
+        await listen<NotesChanged>("notes-changed", (event) => {
+          if (event.payload.reason === "settings") {
+            void runOrAlert(() => useSettingsStore.getState().reload());
+            return;
+          }
+          void runOrAlert(async () => {
+            const { applyChanges } = useNotesStore.getState();
+            const conflicts = await applyChanges(event.payload.changes, event.payload.reason);
+            for (const id of conflicts) {
+              const change = event.payload.changes.find((c) => c.id === id);
+              toast.warning("Note changed in another window", {
+                description: `${change?.after?.title ?? id}: your unsaved edits here are kept, and saving them replaces the other changes.`,
+              });
+            }
+          });
+        }),
+      );
+
+      if (disposed) return;
+      registerUnlisten(await listen("note-reminder", () => void runOrAlert(() => useNotesStore.getState().refresh())));
+
       if (disposed) return;
       registerUnlisten(await listen("tray-show-all", () => useNotesStore.getState().setViewMode("notes")));
 
@@ -80,6 +111,22 @@ export function useWindowAndMenuEvents({
         }),
       );
 
+      if (disposed) return;
+      registerUnlisten(
+        await listen<string>("open-note", (event) => {
+          const id = event.payload;
+          if (!id) return;
+          void runOrAlert(() => useNotesStore.getState().select(id));
+        }),
+      );
+
+      if (disposed) return;
+      registerUnlisten(
+        await listen<string>("open-search", (event) => {
+          if (event.payload) openSearch(event.payload);
+        }),
+      );
+
       if (disposed) return;
       registerUnlisten(await listen("tray-quit", () => void runOrAlert(requestQuit)));
 
@@ -142,12 +189,13 @@ export function useWindowAndMenuEvents({
       selectNote: (id) => {
         void runOrAlert(() => useNotesStore.getState().select(id));
       },
-      undoReorder: () => {
-        void runOrAlert(() => useNotesStore.getState().undoReorder());
+      undo: () => {
+        void runOrAlert(() => useNotesStore.getState().undo());
       },
-      redoReorder: () => {
-        void runOrAlert(() => useNotesStore.getState().redoReorder());
+      redo: () => {
+        void runOrAlert(() => useNotesStore.getState().redo());
       },
+      getKeybindings: () => useSettingsStore.getState().keybindings,
     });
 
     window.addEventListener("keydown", onKeyDown);
@@ -163,6 +211,7 @@ export function useWindowAndMenuEvents({
   }, [
     actions,
     enabled,
+    openSearch,
     requestQuit,
     runOrAlert,
     setShowCommandPalette,
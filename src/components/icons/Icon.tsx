@@ -1,4 +1,6 @@
 import {
+  AppWindow,
+  Bell,
   Bold,
   Code,
   FileText,
@@ -10,6 +12,7 @@ import {
   Search,
   Save,
   Settings,
+  StickyNote,
   Strikethrough,
   Trash2,
   Undo2,
@@ -17,6 +20,8 @@ import {
 } from "lucide-react";
 
 export type IconName =
+  | "app-window"
+  | "bell"
   | "bold"
   | "code"
   | "file-text"
@@ -28,6 +33,7 @@ export type IconName =
   | "search"
   | "save"
   | "settings"
+  | "sticky-note"
   | "strike"
   | "trash"
   | "undo"
@@ -50,6 +56,8 @@ export function Icon({ name, size = 18, className = "", label, strokeWidth = 1.7
     "aria-hidden": label ? undefined : "true",
   } as const;
 
+  if (name === "app-window") return <AppWindow {...shared} />;
+  if (name === "bell") return <Bell {...shared} />;
   if (name === "bold") return <Bold {...shared} />;
   if (name === "code") return <Code {...shared} />;
   if (name === "file-text") return <FileText {...shared} />;
@@ -61,6 +69,7 @@ export function Icon({ name, size = 18, className = "", label, strokeWidth = 1.7
   if (name === "search") return <Search {...shared} />;
   if (name === "save") return <Save {...shared} />;
   if (name === "settings") return <Settings {...shared} />;
+  if (name === "sticky-note") return <StickyNote {...shared} />;
   if (name === "trash") return <Trash2 {...shared} />;
   if (name === "restore") return <RotateCcw {...shared} />;
   if (name === "undo") return <Undo2 {...shared} />;
@@ -1,5 +1,6 @@
 import { useEffect, useMemo, useRef, useState } from "react";
-import type { NoteMeta } from "@/lib/types";
+import type { Keybinding, NoteMeta, NoteTemplate } from "@/lib/types";
+import { DEFAULT_KEYBINDINGS, acceleratorFor, formatAccelerator } from "@/lib/utils/keybindings";
 import { Icon } from "@/components/icons/Icon";
 import {
   CommandDialog,
@@ -14,15 +15,24 @@ import {
 
 type Props = {
   notes: NoteMeta[];
+  templates?: NoteTemplate[];
   onClose: () => void;
   onNewNote: () => void;
+  onNewFromTemplate?: (template: NoteTemplate) => void;
+  onOpenDailyNote?: () => void;
   onTogglePinCurrent: () => void;
   onCloseNote: () => void;
   onOpenFile: () => void;
   onSave: () => void;
   onSaveAs: () => void;
+  onRemoveFrontMatter?: () => void;
+  onExportCalendar?: () => void;
   onSelectNote: (id: string) => void;
   onOpenSettings: () => void;
+  // Search text to start with, e.g. from an augenblick://search link.
+  initialQuery?: string;
+  // Shortcuts of the menu actions, as configured in the settings.
+  keybindings?: Keybinding[];
 };
 
 type CommandItemDef = {
@@ -30,24 +40,34 @@ type CommandItemDef = {
   label: string;
   icon: "file-text" | "folder-open" | "pin" | "save" | "settings" | "trash";
   shortcut?: string;
+  // Menu action whose configured shortcut is shown instead of `shortcut`.
+  action?: string;
   keywords: string[];
   onSelect: () => void;
 };
 
 export function CommandPalette({
   notes,
+  templates = [],
   onClose,
   onNewNote,
+  onNewFromTemplate,
+  onOpenDailyNote,
   onTogglePinCurrent,
   onCloseNote,
   onOpenFile,
   onSave,
   onSaveAs,
+  onRemoveFrontMatter,
+  onExportCalendar,
   onSelectNote,
   onOpenSettings,
+  initialQuery = "",
+  keybindings = DEFAULT_KEYBINDINGS,
 }: Props) {
   const inputRef = useRef<HTMLInputElement | null>(null);
   const [open, setOpen] = useState(true);
+  const [query, setQuery] = useState(initialQuery);
 
   const commands = useMemo<CommandItemDef[]>(
     () => [
@@ -55,13 +75,27 @@ export function CommandPalette({
         id: "new-note",
         label: "New note",
         icon: "file-text",
-        shortcut: "⌘N",
+        action: "file_new_note",
         keywords: ["new", "note"],
         onSelect: () => {
           onNewNote();
           onClose();
         },
       },
+      ...(onOpenDailyNote
+        ? [
+            {
+              id: "daily-note",
+              label: "Today's note",
+              icon: "file-text" as const,
+              keywords: ["today", "daily", "journal"],
+              onSelect: () => {
+                onOpenDailyNote();
+                onClose();
+              },
+            },
+          ]
+        : []),
       {
         id: "pin-current",
         label: "Pin / Unpin",
@@ -77,7 +111,7 @@ export function CommandPalette({
         id: "save",
         label: "Save",
         icon: "save",
-        shortcut: "⌘S",
+        action: "file_save_note",
         keywords: ["save"],
         onSelect: () => {
           onSave();
@@ -88,18 +122,46 @@ export function CommandPalette({
         id: "save-as",
         label: "Save As…",
         icon: "save",
-        shortcut: "⇧⌘S",
+        action: "file_save_as_note",
         keywords: ["save", "as"],
         onSelect: () => {
           onSaveAs();
           onClose();
         },
       },
+      ...(onRemoveFrontMatter
+        ? [
+            {
+              id: "remove-front-matter",
+              label: "Remove front matter",
+              icon: "file-text" as const,
+              keywords: ["front", "matter", "yaml", "metadata", "remove"],
+              onSelect: () => {
+                onRemoveFrontMatter();
+                onClose();
+              },
+            },
+          ]
+        : []),
+      ...(onExportCalendar
+        ? [
+            {
+              id: "export-calendar",
+              label: "Export Calendar (.ics)…",
+              icon: "save" as const,
+              keywords: ["export", "calendar", "ics", "ical", "reminders", "expiry"],
+              onSelect: () => {
+                onExportCalendar();
+                onClose();
+              },
+            },
+          ]
+        : []),
       {
         id: "open",
         label: "Open…",
         icon: "folder-open",
-        shortcut: "⌘O",
+        action: "file_open_markdown",
         keywords: ["open", "import", "markdown", "md"],
         onSelect: () => {
           onOpenFile();
@@ -110,7 +172,7 @@ export function CommandPalette({
         id: "settings",
         label: "Settings",
         icon: "settings",
-        shortcut: "⌘,",
+        action: "app_settings",
         keywords: ["settings", "preferences"],
         onSelect: () => {
           onOpenSettings();
@@ -121,7 +183,7 @@ export function CommandPalette({
         id: "trash",
         label: "Trash",
         icon: "trash",
-        shortcut: "Delete",
+        action: "file_trash_note",
         keywords: ["trash", "close"],
         onSelect: () => {
           onCloseNote();
@@ -129,7 +191,19 @@ export function CommandPalette({
         },
       },
     ],
-    [onClose, onCloseNote, onNewNote, onOpenFile, onOpenSettings, onSave, onSaveAs, onTogglePinCurrent],
+    [
+      onClose,
+      onCloseNote,
+      onExportCalendar,
+      onNewNote,
+      onOpenDailyNote,
+      onOpenFile,
+      onOpenSettings,
+      onRemoveFrontMatter,
+      onSave,
+      onSaveAs,
+      onTogglePinCurrent,
+    ],
   );
 
   useEffect(() => {
@@ -153,25 +227,54 @@ export function CommandPalette({
         setOpen(true);
       }}
     >
-      <CommandInput ref={inputRef} placeholder="Type a command or search…" />
+      <CommandInput
+        ref={inputRef}
+        value={query}
+        onValueChange={setQuery}
+        placeholder="Type a command or search…"
+      />
       <CommandList>
         <CommandEmpty>No results found.</CommandEmpty>
 
         <CommandGroup heading="Commands">
-          {commands.map((item) => (
-            <CommandItem
-              key={item.id}
-              value={item.id}
-              keywords={[item.label, ...item.keywords]}
-              onSelect={item.onSelect}
-            >
-              <Icon name={item.icon} className="text-muted-foreground" />
-              <span>{item.label}</span>
-              {item.shortcut ? <CommandShortcut>{item.shortcut}</CommandShortcut> : null}
-            </CommandItem>
-          ))}
+          {commands.map((item) => {
+            const shortcut = item.action
+              ? formatAccelerator(acceleratorFor(keybindings, item.action))
+              : item.shortcut;
+            return (
+              <CommandItem
+                key={item.id}
+                value={item.id}
+                keywords={[item.label, ...item.keywords]}
+                onSelect={item.onSelect}
+              >
+                <Icon name={item.icon} className="text-muted-foreground" />
+                <span>{item.label}</span>
+                {shortcut ? <CommandShortcut>{shortcut}</CommandShortcut> : null}
+              </CommandItem>
+            );
+          })}
         </CommandGroup>
 
+        {templates.length > 0 && onNewFromTemplate ? (
+          <CommandGroup heading="New from Template">
+            {templates.map((template) => (
+              <CommandItem
+                key={template.name}
+                value={`template:${template.name}`}
+                keywords={[template.name, "template", "new"]}
+                onSelect={() => {
+                  onNewFromTemplate(template);
+                  onClose();
+                }}
+              >
+                <Icon name="file-text" className="text-muted-foreground" />
+                <span className="truncate">{template.name}</span>
+              </CommandItem>
+            ))}
+          </CommandGroup>
+        ) : null}
+
         <CommandSeparator />
 
         <CommandGroup heading="Notes">
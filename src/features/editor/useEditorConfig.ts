@@ -1,5 +1,6 @@
 import { useEffect, useRef } from "react";
-import { Extension } from "@tiptap/core";
+import { Extension, type Editor } from "@tiptap/core";
+import { Selection } from "@tiptap/pm/state";
 import { useEditor } from "@tiptap/react";
 import StarterKit from "@tiptap/starter-kit";
 import { Markdown } from "@tiptap/markdown";
@@ -42,6 +43,23 @@ async function openLink(href: string) {
   }
 }
 
+type FocusEditorDetail = {
+  // Markdown before the caret, e.g. up to `{{cursor}}` in a template.
+  markdownBefore?: string;
+};
+
+// The document parsed from the text before the caret shares its structure with the full
+// document up to that point, so its end is where the caret goes.
+function positionAfterMarkdown(editor: Editor, markdown: string): number | "end" {
+  if (!editor.markdown) return "end";
+  try {
+    const prefix = editor.schema.nodeFromJSON(editor.markdown.parse(markdown));
+    return Math.min(Selection.atEnd(prefix).from, editor.state.doc.content.size);
+  } catch {
+    return "end";
+  }
+}
+
 type Params = {
   value: string;
   onChange: (next: string) => void;
@@ -156,8 +174,12 @@ export function useEditorConfig({ value, onChange, readOnly }: Params) {
 
   useEffect(() => {
     if (!editor) return;
-    const onFocusEditor = () => {
-      editor.commands.focus("end");
+    const onFocusEditor = (event: Event) => {
+      const markdownBefore = (event as CustomEvent<FocusEditorDetail | undefined>).detail
+        ?.markdownBefore;
+      editor.commands.focus(
+        markdownBefore === undefined ? "end" : positionAfterMarkdown(editor, markdownBefore),
+      );
     };
     window.addEventListener("augenblick:focus-editor", onFocusEditor);
     return () => {
@@ -0,0 +1,144 @@
+import { useState } from "react";
+import type { NoteTemplate, Recurrence, RecurrenceInput } from "@/lib/types";
+import { formatRelativeTimeFromNow } from "@/lib/utils/time";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+
+type Props = {
+  recurrences: Recurrence[];
+  templates: NoteTemplate[];
+  onAdd: (recurrence: RecurrenceInput) => void;
+  onDelete: (id: string) => void;
+};
+
+// Common schedules; anything else the backend understands can be typed into the rule field.
+export const SCHEDULE_PRESETS = [
+  { rule: "FREQ=DAILY;BYHOUR=9;BYMINUTE=0", label: "Every day at 09:00" },
+  { rule: "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=0", label: "Weekdays at 09:00" },
+  { rule: "FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0", label: "Mondays at 09:00" },
+  { rule: "FREQ=WEEKLY;BYDAY=FR;BYHOUR=16;BYMINUTE=0", label: "Fridays at 16:00" },
+  { rule: "FREQ=MONTHLY;BYMONTHDAY=1;BYHOUR=9;BYMINUTE=0", label: "1st of the month at 09:00" },
+];
+
+const EXPIRY_OPTIONS = [
+  { minutes: 0, label: "Normal expiry" },
+  { minutes: 1440, label: "Expires after 1 day" },
+  { minutes: 4320, label: "Expires after 3 days" },
+  { minutes: 10_080, label: "Expires after 7 days" },
+];
+
+function scheduleLabel(rule: string): string {
+  return SCHEDULE_PRESETS.find((preset) => preset.rule === rule)?.label ?? rule;
+}
+
+export function RecurrencesSection({ recurrences, templates, onAdd, onDelete }: Props) {
+  const [template, setTemplate] = useState("");
+  const [rule, setRule] = useState("FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0");
+  const [expiryMinutes, setExpiryMinutes] = useState(0);
+  const selectedTemplate = template || templates[0]?.name || "";
+
+  return (
+    <div>
+      <div className="mb-2 text-sm font-medium">Recurring Notes</div>
+      {recurrences.length > 0 && (
+        <div className="mb-2 space-y-2">
+          {recurrences.map((recurrence) => (
+            <div key={recurrence.id} className="flex items-center gap-2">
+              <div className="min-w-0 flex-1">
+                <div className="truncate text-sm">{recurrence.name}</div>
+                <div className="truncate text-xs text-muted-foreground">
+                  {scheduleLabel(recurrence.rule)}
+                  {recurrence.nextOccurrenceAt !== null &&
+                    ` · next ${formatRelativeTimeFromNow(recurrence.nextOccurrenceAt)}`}
+                </div>
+                {recurrence.lastError && (
+                  <div className="truncate text-xs text-destructive">{recurrence.lastError}</div>
+                )}
+              </div>
+              <Button
+                variant="outline"
+                aria-label={`Delete ${recurrence.name} recurrence`}
+                onClick={() => onDelete(recurrence.id)}
+              >
+                Delete
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+      {templates.length === 0 ? (
+        <div className="text-xs text-muted-foreground">
+          Add a template to the templates folder to schedule notes from it.
+        </div>
+      ) : (
+        <div className="space-y-2">
+          <Select value={selectedTemplate} onValueChange={setTemplate}>
+            <SelectTrigger className="w-full">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {templates.map((t) => (
+                <SelectItem key={t.name} value={t.name}>
+                  {t.name}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Select
+            value={SCHEDULE_PRESETS.some((preset) => preset.rule === rule) ? rule : ""}
+            onValueChange={setRule}
+          >
+            <SelectTrigger className="w-full">
+              <SelectValue placeholder="Custom schedule" />
+            </SelectTrigger>
+            <SelectContent>
+              {SCHEDULE_PRESETS.map((preset) => (
+                <SelectItem key={preset.rule} value={preset.rule}>
+                  {preset.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Input
+            value={rule}
+            aria-label="Recurrence rule"
+            onChange={(e) => setRule(e.currentTarget.value)}
+          />
+          <Select value={String(expiryMinutes)} onValueChange={(v) => setExpiryMinutes(Number(v))}>
+            <SelectTrigger className="w-full">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {EXPIRY_OPTIONS.map((option) => (
+                <SelectItem key={option.minutes} value={String(option.minutes)}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Button
+            className="w-full"
+            variant="outline"
+            disabled={!rule.trim()}
+            onClick={() =>
+              onAdd({
+                template: selectedTemplate,
+                rule: rule.trim(),
+                expiryMinutes: expiryMinutes > 0 ? expiryMinutes : null,
+              })
+            }
+          >
+            Add Recurring Note
+          </Button>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,6 +1,15 @@
-import type { AppSettings } from "@/lib/types";
+import type {
+  AppSettings,
+  Autostart,
+  Keybinding,
+  NoteMeta,
+  NoteTemplate,
+  Recurrence,
+  RecurrenceInput,
+} from "@/lib/types";
 import { Button } from "@/components/ui/button";
 import { Dialog, DialogContent, DialogDescription, DialogHeader, DialogTitle } from "@/components/ui/dialog";
+import { Input } from "@/components/ui/input";
 import {
   Select,
   SelectContent,
@@ -9,6 +18,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { Tabs, TabsList, TabsTrigger } from "@/components/ui/tabs";
+import { RecurrencesSection } from "@/features/settings/RecurrencesSection";
 
 type Props = {
   settings: AppSettings;
@@ -16,6 +26,27 @@ type Props = {
   onTheme: (theme: AppSettings["theme"]) => void;
   onExpiryMinutes: (minutes: number) => void;
   onTrashDays: (days: number) => void;
+  onFrontMatterWriteBack: (enabled: boolean) => void;
+  onOpenTasksExpiry: (rule: AppSettings["openTasksExpiry"]) => void;
+  onOpenTasksMaxDays: (days: number) => void;
+  onDoneTasksGraceMinutes: (minutes: number) => void;
+  onDailyExpiryMinutes: (minutes: number) => void;
+  onDailyCarryTasks: (enabled: boolean) => void;
+  onChooseCalendarFeed: () => void;
+  onCalendarFeedOff: () => void;
+  // Notes that quick captures can be appended to.
+  inboxNotes: NoteMeta[];
+  onQuickCaptureShortcut: (shortcut: string) => void;
+  onQuickCaptureInbox: (id: string) => void;
+  onAutostart: (autostart: Autostart) => void;
+  keybindings: Keybinding[];
+  onKeybinding: (id: string, accelerator: string | null) => void;
+  onLocale: (locale: string) => void;
+  // Recurring notes are shown once they have been loaded.
+  recurrences?: Recurrence[];
+  templates?: NoteTemplate[];
+  onAddRecurrence?: (recurrence: RecurrenceInput) => void;
+  onDeleteRecurrence?: (id: string) => void;
   isCheckingUpdates: boolean;
   onCheckUpdates: () => void;
 };
@@ -26,6 +57,25 @@ export function SettingsDialog({
   onTheme,
   onExpiryMinutes,
   onTrashDays,
+  onFrontMatterWriteBack,
+  onOpenTasksExpiry,
+  onOpenTasksMaxDays,
+  onDoneTasksGraceMinutes,
+  onDailyExpiryMinutes,
+  onDailyCarryTasks,
+  onChooseCalendarFeed,
+  onCalendarFeedOff,
+  inboxNotes,
+  onQuickCaptureShortcut,
+  onQuickCaptureInbox,
+  onAutostart,
+  keybindings,
+  onKeybinding,
+  onLocale,
+  recurrences,
+  templates = [],
+  onAddRecurrence,
+  onDeleteRecurrence,
   isCheckingUpdates,
   onCheckUpdates,
 }: Props) {
@@ -39,6 +89,14 @@ export function SettingsDialog({
     { minutes: 43_200, label: "30 days" },
   ];
   const trashOptions = [7, 14, 30, 60, 90];
+  const openTasksMaxOptions = [14, 30, 60, 90];
+  const doneGraceOptions = [
+    { minutes: 0, label: "Normal expiry" },
+    { minutes: 60, label: "Expire after 1 hour" },
+    { minutes: 360, label: "Expire after 6 hours" },
+    { minutes: 1440, label: "Expire after 1 day" },
+    { minutes: 4320, label: "Expire after 3 days" },
+  ];
 
   return (
     <Dialog
@@ -107,6 +165,235 @@ export function SettingsDialog({
             </Select>
           </div>
 
+          <div>
+            <div className="mb-2 text-sm font-medium">Notes with Open Tasks</div>
+            <Tabs
+              value={settings.openTasksExpiry}
+              onValueChange={(value) => onOpenTasksExpiry(value as AppSettings["openTasksExpiry"])}
+              className="w-full"
+            >
+              <TabsList className="grid w-full grid-cols-3">
+                <TabsTrigger value="off">Normal expiry</TabsTrigger>
+                <TabsTrigger value="extend">Keep longer</TabsTrigger>
+                <TabsTrigger value="exempt">Keep</TabsTrigger>
+              </TabsList>
+            </Tabs>
+            {settings.openTasksExpiry === "extend" && (
+              <Select
+                value={String(settings.openTasksMaxDays)}
+                onValueChange={(v) => onOpenTasksMaxDays(Number(v))}
+              >
+                <SelectTrigger className="mt-2 w-full">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {openTasksMaxOptions.map((d) => (
+                    <SelectItem key={d} value={String(d)}>
+                      Up to {d} days
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            )}
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Notes with All Tasks Done</div>
+            <Select
+              value={String(settings.doneTasksGraceMinutes)}
+              onValueChange={(v) => onDoneTasksGraceMinutes(Number(v))}
+            >
+              <SelectTrigger className="w-full">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {doneGraceOptions.map((option) => (
+                  <SelectItem key={option.minutes} value={String(option.minutes)}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Daily Note</div>
+            <Select
+              value={String(settings.dailyExpiryMinutes)}
+              onValueChange={(v) => onDailyExpiryMinutes(Number(v))}
+            >
+              <SelectTrigger className="w-full">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {expiryOptions.map((option) => (
+                  <SelectItem key={option.minutes} value={String(option.minutes)}>
+                    Expires after {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <Tabs
+              value={settings.dailyCarryTasks ? "carry" : "empty"}
+              onValueChange={(value) => onDailyCarryTasks(value === "carry")}
+              className="mt-2 w-full"
+            >
+              <TabsList className="grid w-full grid-cols-2">
+                <TabsTrigger value="carry">Carry open tasks</TabsTrigger>
+                <TabsTrigger value="empty">Start empty</TabsTrigger>
+              </TabsList>
+            </Tabs>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Front Matter in Saved Files</div>
+            <Tabs
+              value={settings.frontMatterWriteBack ? "write" : "keep"}
+              onValueChange={(value) => onFrontMatterWriteBack(value === "write")}
+              className="w-full"
+            >
+              <TabsList className="grid w-full grid-cols-2">
+                <TabsTrigger value="keep">Read only</TabsTrigger>
+                <TabsTrigger value="write">Write pin &amp; expiry</TabsTrigger>
+              </TabsList>
+            </Tabs>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Calendar Feed</div>
+            <div className="mb-2 truncate text-xs text-muted-foreground">
+              {settings.calendarFeedPath || "Off"}
+            </div>
+            <div className="grid grid-cols-2 gap-2">
+              <Button variant="outline" onClick={onChooseCalendarFeed}>
+                Choose File…
+              </Button>
+              <Button
+                variant="outline"
+                onClick={onCalendarFeedOff}
+                disabled={!settings.calendarFeedPath}
+              >
+                Turn Off
+              </Button>
+            </div>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Quick Capture</div>
+            <Input
+              key={settings.quickCaptureShortcut}
+              defaultValue={settings.quickCaptureShortcut}
+              placeholder="Off"
+              aria-label="Quick capture shortcut"
+              onBlur={(e) => {
+                const next = e.currentTarget.value.trim();
+                if (next !== settings.quickCaptureShortcut) onQuickCaptureShortcut(next);
+              }}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") e.currentTarget.blur();
+              }}
+            />
+            <div className="mt-1 mb-2 text-xs text-muted-foreground">
+              For example CmdOrCtrl+Alt+KeyN. Leave empty to turn it off.
+            </div>
+            <Select
+              value={settings.quickCaptureInboxId || "new"}
+              onValueChange={(v) => onQuickCaptureInbox(v === "new" ? "" : v)}
+            >
+              <SelectTrigger className="w-full">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="new">New note each time</SelectItem>
+                {inboxNotes.map((note) => (
+                  <SelectItem key={note.id} value={note.id}>
+                    Append to {note.title}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Launch at Login</div>
+            <Tabs
+              value={
+                !settings.autostart.enabled ? "off" : settings.autostart.hidden ? "tray" : "window"
+              }
+              onValueChange={(value) =>
+                onAutostart({ enabled: value !== "off", hidden: value === "tray" })
+              }
+              className="w-full"
+            >
+              <TabsList className="grid w-full grid-cols-3">
+                <TabsTrigger value="off">Off</TabsTrigger>
+                <TabsTrigger value="window">Open window</TabsTrigger>
+                <TabsTrigger value="tray">Start in tray</TabsTrigger>
+              </TabsList>
+            </Tabs>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Keyboard Shortcuts</div>
+            <div className="space-y-2">
+              {keybindings.map((binding) => (
+                <div key={binding.id} className="flex items-center gap-2">
+                  <span className="w-40 shrink-0 truncate text-sm">{binding.label}</span>
+                  <Input
+                    key={binding.accelerator}
+                    defaultValue={binding.accelerator}
+                    placeholder="None"
+                    aria-label={`${binding.label} shortcut`}
+                    onBlur={(e) => {
+                      const next = e.currentTarget.value.trim();
+                      if (next !== binding.accelerator) onKeybinding(binding.id, next);
+                    }}
+                    onKeyDown={(e) => {
+                      if (e.key === "Enter") e.currentTarget.blur();
+                    }}
+                  />
+                  <Button
+                    variant="outline"
+                    aria-label={`Reset ${binding.label} shortcut`}
+                    disabled={binding.accelerator === binding.defaultAccelerator}
+                    onClick={() => onKeybinding(binding.id, null)}
+                  >
+                    Reset
+                  </Button>
+                </div>
+              ))}
+            </div>
+            <div className="mt-1 text-xs text-muted-foreground">
+              For example CmdOrCtrl+Shift+KeyS. Leave empty for no shortcut.
+            </div>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium">Language</div>
+            <Select value={settings.locale} onValueChange={onLocale}>
+              <SelectTrigger className="w-full">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="system">System</SelectItem>
+                <SelectItem value="en">English</SelectItem>
+                <SelectItem value="de">Deutsch</SelectItem>
+              </SelectContent>
+            </Select>
+            <div className="mt-1 text-xs text-muted-foreground">
+              Used by the menus, the tray and messages from the app.
+            </div>
+          </div>
+
+          {recurrences && onAddRecurrence && onDeleteRecurrence ? (
+            <RecurrencesSection
+              recurrences={recurrences}
+              templates={templates}
+              onAdd={onAddRecurrence}
+              onDelete={onDeleteRecurrence}
+            />
+          ) : null}
+
           <div>
             <div className="mb-2 text-sm font-medium">Updates</div>
             <Button className="w-full" onClick={onCheckUpdates} disabled={isCheckingUpdates}>
@@ -6,19 +6,30 @@ import { formatRelativeTimeFromNow } from "@/lib/utils/time";
 type Props = {
   lastInteraction: number;
   expiryMinutes: number;
+  expiresAt?: number | null;
   paused?: boolean;
+  // Kept regardless of inactivity, e.g. while it has open tasks.
+  kept?: boolean;
+  reason?: string | null;
 };
 
-export function ExpiryRing({ lastInteraction, expiryMinutes, paused = false }: Props) {
+export function ExpiryRing({
+  lastInteraction,
+  expiryMinutes,
+  expiresAt = null,
+  paused = false,
+  kept = false,
+  reason = null,
+}: Props) {
   const [now, setNow] = useState(() => Date.now());
   const expiryAt = useMemo(
-    () => noteExpiryTime(lastInteraction, expiryMinutes),
-    [lastInteraction, expiryMinutes],
+    () => noteExpiryTime(lastInteraction, expiryMinutes, expiresAt),
+    [lastInteraction, expiryMinutes, expiresAt],
   );
 
   // Adaptive refresh: 1s when < 1h remaining (seconds shown), 60s otherwise
   useEffect(() => {
-    if (paused) return;
+    if (paused || kept) return;
     const updateInterval = () => {
       const remaining = expiryAt - Date.now();
       return remaining < 3_600_000 ? 1_000 : 60_000;
@@ -32,13 +43,14 @@ export function ExpiryRing({ lastInteraction, expiryMinutes, paused = false }: P
     intervalId = window.setTimeout(tick, updateInterval());
 
     return () => window.clearTimeout(intervalId);
-  }, [expiryAt, paused]);
+  }, [expiryAt, paused, kept]);
 
   const r = 8;
   const circumference = 2 * Math.PI * r;
-  const progress = paused ? 1 : expiryProgress(lastInteraction, expiryMinutes, now);
+  const progress =
+    paused || kept ? 1 : expiryProgress(lastInteraction, expiryMinutes, now, expiresAt);
   const dashOffset = circumference * (1 - progress);
-  const status = paused ? "fresh" : expiryStatus(progress);
+  const status = paused || kept ? "fresh" : expiryStatus(progress);
   const stroke =
     status === "fresh"
       ? "var(--ring-green)"
@@ -50,7 +62,9 @@ export function ExpiryRing({ lastInteraction, expiryMinutes, paused = false }: P
 
   const tooltipText = paused
     ? "Active (expiry paused)"
-    : `Trashed ${formatRelativeTimeFromNow(expiryAt, now)}`;
+    : kept
+      ? capitalize(reason ?? "kept")
+      : `Trashed ${formatRelativeTimeFromNow(expiryAt, now)}${reason ? ` (${reason})` : ""}`;
 
   return (
     <TooltipProvider delayDuration={550}>
@@ -100,3 +114,7 @@ export function ExpiryRing({ lastInteraction, expiryMinutes, paused = false }: P
     </TooltipProvider>
   );
 }
+
+function capitalize(text: string): string {
+  return text.charAt(0).toUpperCase() + text.slice(1);
+}
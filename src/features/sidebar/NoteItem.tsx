@@ -1,12 +1,22 @@
-import type { NoteMeta } from "@/lib/types";
-import { formatRelativeTime } from "@/lib/utils/time";
+import type { NoteMeta, NoteWindowMode } from "@/lib/types";
+import { formatRelativeTime, reminderPresets } from "@/lib/utils/time";
 import { ExpiryRing } from "@/features/sidebar/ExpiryRing";
+import {
+  DEFAULT_EXPIRY_RULE,
+  noteExpiresAt,
+  noteExpiryWindow,
+  type ExpiryRule,
+} from "@/lib/utils/expiry";
 import { Icon } from "@/components/icons/Icon";
 import {
   ContextMenu,
   ContextMenuContent,
   ContextMenuItem,
+  ContextMenuSeparator,
   ContextMenuShortcut,
+  ContextMenuSub,
+  ContextMenuSubContent,
+  ContextMenuSubTrigger,
   ContextMenuTrigger,
 } from "@/components/ui/context-menu";
 
@@ -15,12 +25,29 @@ type Props = {
   selected: boolean;
   dirty: boolean;
   expiryMinutes: number;
+  expiryRule?: ExpiryRule;
   onSelect: (id: string) => void;
   onTogglePin: (id: string) => void;
   onTrash: (id: string) => void;
+  // Sets a reminder time (ms), or dismisses it with null.
+  onSetReminder?: (id: string, at: number | null) => void;
+  onOpenWindow?: (id: string, mode: NoteWindowMode) => void;
 };
 
-export function NoteItem({ note, selected, dirty, expiryMinutes, onSelect, onTogglePin, onTrash }: Props) {
+export function NoteItem({
+  note,
+  selected,
+  dirty,
+  expiryMinutes,
+  expiryRule = DEFAULT_EXPIRY_RULE,
+  onSelect,
+  onTogglePin,
+  onTrash,
+  onSetReminder,
+  onOpenWindow,
+}: Props) {
+  const expiryWindow = noteExpiryWindow(expiryMinutes, note, expiryRule);
+
   return (
     <ContextMenu>
       <ContextMenuTrigger asChild>
@@ -35,6 +62,14 @@ export function NoteItem({ note, selected, dirty, expiryMinutes, onSelect, onTog
             <div className="truncate font-semibold">{note.title}</div>
             <div className="flex items-center gap-1.5 text-[11px] text-muted-foreground">
               <span className="truncate">{formatRelativeTime(note.lastInteraction)}</span>
+              {note.remindAt !== null ? (
+                <Icon
+                  name="bell"
+                  size={11}
+                  className="shrink-0"
+                  label={`Reminder ${new Date(note.remindAt).toLocaleString()}`}
+                />
+              ) : null}
               {dirty ? (
                 <span
                   className="shrink-0 rounded-full"
@@ -45,7 +80,14 @@ export function NoteItem({ note, selected, dirty, expiryMinutes, onSelect, onTog
           </div>
 
           {!note.isTrashed && !note.isPinned ? (
-            <ExpiryRing lastInteraction={note.lastInteraction} expiryMinutes={expiryMinutes} paused={selected} />
+            <ExpiryRing
+              lastInteraction={note.lastInteraction}
+              expiryMinutes={expiryWindow.minutes ?? expiryMinutes}
+              expiresAt={noteExpiresAt(note)}
+              paused={selected}
+              kept={expiryWindow.minutes === null}
+              reason={expiryWindow.reason}
+            />
           ) : null}
         </button>
       </ContextMenuTrigger>
@@ -55,6 +97,46 @@ export function NoteItem({ note, selected, dirty, expiryMinutes, onSelect, onTog
           {note.isPinned ? "Unpin" : "Pin"}
           <ContextMenuShortcut>⌘P</ContextMenuShortcut>
         </ContextMenuItem>
+        {onSetReminder && !note.isTrashed ? (
+          <ContextMenuSub>
+            <ContextMenuSubTrigger>
+              <Icon name="bell" size={14} className="text-muted-foreground" />
+              Remind Me
+            </ContextMenuSubTrigger>
+            <ContextMenuSubContent>
+              {reminderPresets().map((preset) => (
+                <ContextMenuItem
+                  key={preset.label}
+                  onSelect={() => onSetReminder(note.id, preset.at)}
+                >
+                  {preset.label}
+                </ContextMenuItem>
+              ))}
+              {note.remindAt !== null ? (
+                <>
+                  <ContextMenuSeparator />
+                  <ContextMenuItem onSelect={() => onSetReminder(note.id, null)}>
+                    Clear Reminder
+                  </ContextMenuItem>
+                </>
+              ) : null}
+            </ContextMenuSubContent>
+          </ContextMenuSub>
+        ) : null}
+        {onOpenWindow && !note.isTrashed ? (
+          <>
+            <ContextMenuSeparator />
+            <ContextMenuItem onSelect={() => onOpenWindow(note.id, "normal")}>
+              <Icon name="app-window" size={14} className="text-muted-foreground" />
+              Open in New Window
+            </ContextMenuItem>
+            <ContextMenuItem onSelect={() => onOpenWindow(note.id, "sticky")}>
+              <Icon name="sticky-note" size={14} className="text-muted-foreground" />
+              Float as Sticky Note
+            </ContextMenuItem>
+            <ContextMenuSeparator />
+          </>
+        ) : null}
         <ContextMenuItem onSelect={() => onTrash(note.id)}>
           <Icon name="trash" size={14} className="text-muted-foreground" />
           Trash
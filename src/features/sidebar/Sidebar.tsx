@@ -1,6 +1,7 @@
 import { useEffect, useMemo, useRef, useState } from "react";
-import type { NoteMeta } from "@/lib/types";
+import type { NoteMeta, NoteWindowMode } from "@/lib/types";
 import { NoteItem } from "@/features/sidebar/NoteItem";
+import type { ExpiryRule } from "@/lib/utils/expiry";
 import { TrashItem } from "@/features/sidebar/TrashItem";
 import { Icon } from "@/components/icons/Icon";
 import {
@@ -28,6 +29,7 @@ type Props = {
   selectedId: string | null;
   dirtyIds: Record<string, true>;
   expiryMinutes: number;
+  expiryRule?: ExpiryRule;
   trashRetentionDays: number;
   viewMode: "notes" | "trash";
   onSelect: (id: string) => void;
@@ -38,6 +40,8 @@ type Props = {
   onDeleteForever: (id: string) => void;
   onTogglePin: (id: string) => void;
   onTrash: (id: string) => void;
+  onSetReminder?: (id: string, at: number | null) => void;
+  onOpenWindow?: (id: string, mode: NoteWindowMode) => void;
   onNewNote: () => void;
 };
 
@@ -46,12 +50,26 @@ type SortableRowProps = {
   selected: boolean;
   dirty: boolean;
   expiryMinutes: number;
+  expiryRule?: ExpiryRule;
   onSelect: (id: string) => void;
   onTogglePin: (id: string) => void;
   onTrash: (id: string) => void;
+  onSetReminder?: (id: string, at: number | null) => void;
+  onOpenWindow?: (id: string, mode: NoteWindowMode) => void;
 };
 
-function SortableRow({ note, selected, dirty, expiryMinutes, onSelect, onTogglePin, onTrash }: SortableRowProps) {
+function SortableRow({
+  note,
+  selected,
+  dirty,
+  expiryMinutes,
+  expiryRule,
+  onSelect,
+  onTogglePin,
+  onTrash,
+  onSetReminder,
+  onOpenWindow,
+}: SortableRowProps) {
   const { attributes, listeners, setNodeRef, transform, transition, isDragging } = useSortable({
     id: note.id,
   });
@@ -69,9 +87,12 @@ function SortableRow({ note, selected, dirty, expiryMinutes, onSelect, onToggleP
         selected={selected}
         dirty={dirty}
         expiryMinutes={expiryMinutes}
+        expiryRule={expiryRule}
         onSelect={onSelect}
         onTogglePin={onTogglePin}
         onTrash={onTrash}
+        onSetReminder={onSetReminder}
+        onOpenWindow={onOpenWindow}
       />
     </div>
   );
@@ -138,6 +159,7 @@ export function Sidebar({
   selectedId,
   dirtyIds,
   expiryMinutes,
+  expiryRule,
   trashRetentionDays,
   viewMode,
   onSelect,
@@ -148,6 +170,8 @@ export function Sidebar({
   onDeleteForever,
   onTogglePin,
   onTrash,
+  onSetReminder,
+  onOpenWindow,
   onNewNote,
 }: Props) {
   const sensors = useSensors(useSensor(PointerSensor, { activationConstraint: { distance: 4 } }));
@@ -197,9 +221,12 @@ export function Sidebar({
                             selected={note.id === selectedId}
                             dirty={!!dirtyIds[note.id]}
                             expiryMinutes={expiryMinutes}
+                            expiryRule={expiryRule}
                             onSelect={onSelect}
                             onTogglePin={onTogglePin}
                             onTrash={onTrash}
+                            onSetReminder={onSetReminder}
+                            onOpenWindow={onOpenWindow}
                           />
                         </div>
                       ))}
@@ -243,9 +270,12 @@ export function Sidebar({
                         selected={note.id === selectedId}
                         dirty={!!dirtyIds[note.id]}
                         expiryMinutes={expiryMinutes}
+                        expiryRule={expiryRule}
                         onSelect={onSelect}
                         onTogglePin={onTogglePin}
                         onTrash={onTrash}
+                        onSetReminder={onSetReminder}
+                        onOpenWindow={onOpenWindow}
                       />
                     </div>
                   ))}
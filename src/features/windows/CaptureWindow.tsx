@@ -0,0 +1,80 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { toast } from "sonner";
+import "@/lib/tauri/shim";
+import { ErrorBoundary } from "@/app/ErrorBoundary";
+import { Toaster } from "@/components/ui/sonner";
+import { Textarea } from "@/components/ui/textarea";
+import { api } from "@/lib/api";
+import { useSettingsStore } from "@/stores/settingsStore";
+
+/**
+ * The quick-capture window opened by the global shortcut. Enter files the text (Shift+Enter for a
+ * new line), Escape or clicking elsewhere hides the window and keeps the text for next time.
+ */
+export function CaptureWindow() {
+  const [text, setText] = useState("");
+  const [busy, setBusy] = useState(false);
+  const input = useRef<HTMLTextAreaElement>(null);
+
+  useEffect(() => {
+    void useSettingsStore
+      .getState()
+      .init()
+      .catch((err) => console.error("settings load failed", err));
+    input.current?.focus();
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) input.current?.focus();
+      else void api.quickCaptureHide().catch(() => {});
+    });
+    return () => void unlisten.then((fn) => fn());
+  }, []);
+
+  const submit = useCallback(async () => {
+    if (busy || !text.trim()) return;
+    setBusy(true);
+    try {
+      await api.quickCaptureSubmit(text);
+      setText("");
+    } catch (err) {
+      toast.error("Capture failed", { description: String(err) });
+    } finally {
+      setBusy(false);
+    }
+  }, [busy, text]);
+
+  return (
+    <ErrorBoundary>
+      <div
+        className="flex h-screen flex-col gap-2 p-3"
+        style={{ background: "var(--bg-primary)" }}
+      >
+        <div
+          data-tauri-drag-region
+          className="select-none text-[11px] font-semibold text-[var(--text-tertiary)]"
+        >
+          QUICK CAPTURE
+        </div>
+        <Textarea
+          ref={input}
+          value={text}
+          disabled={busy}
+          placeholder="Jot something down…"
+          aria-label="Quick capture"
+          className="flex-1 resize-none"
+          onChange={(e) => setText(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Escape") {
+              e.preventDefault();
+              void api.quickCaptureHide();
+            } else if (e.key === "Enter" && !e.shiftKey && !e.nativeEvent.isComposing) {
+              e.preventDefault();
+              void submit();
+            }
+          }}
+        />
+      </div>
+      <Toaster position="bottom-right" closeButton />
+    </ErrorBoundary>
+  );
+}
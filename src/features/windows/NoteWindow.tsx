@@ -0,0 +1,116 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { toast } from "sonner";
+import { AppShell } from "@/app/AppShell";
+import { ErrorBoundary } from "@/app/ErrorBoundary";
+import { Spinner } from "@/components/ui/spinner";
+import { Editor } from "@/features/editor/Editor";
+import { api } from "@/lib/api";
+import type { NoteMeta, NotesChanged } from "@/lib/types";
+import { clearDraftSaveTimer, scheduleDraftSave } from "@/stores/notes/persistenceTimers";
+import { useSettingsStore } from "@/stores/settingsStore";
+
+type Props = {
+  id: string;
+};
+
+/**
+ * One note on its own, opened with `note_open_window`. Drafts save as you type and saved notes
+ * on Cmd/Ctrl+S, like in the main window. Edits made elsewhere show up when the window gets
+ * focus again, unless this window has unsaved changes.
+ */
+export function NoteWindow({ id }: Props) {
+  const [meta, setMeta] = useState<NoteMeta | null>(null);
+  const [revision, setRevision] = useState(0);
+  const content = useRef("");
+  const savedContent = useRef("");
+  const loaded = useRef(false);
+
+  const report = useCallback((err: unknown) => {
+    toast.error("Error", { description: String(err) });
+  }, []);
+
+  const load = useCallback(async () => {
+    const note = await api.noteGet(id);
+    savedContent.current = note.content;
+    setMeta(note.meta);
+    if (loaded.current && note.content === content.current) return;
+    loaded.current = true;
+    content.current = note.content;
+    setRevision((r) => r + 1);
+  }, [id]);
+
+  const onChange = useCallback(
+    (next: string) => {
+      content.current = next;
+      if (meta?.storage !== "draft") return;
+      scheduleDraftSave(id, async () => {
+        try {
+          const updated = await api.noteWriteDraft(id, next);
+          savedContent.current = next;
+          setMeta(updated);
+        } catch (err) {
+          report(err);
+        }
+      });
+    },
+    [id, meta?.storage, report],
+  );
+
+  useEffect(() => {
+    void useSettingsStore.getState().init().catch(report);
+    void load().catch(report);
+    return () => clearDraftSaveTimer(id);
+  }, [id, load, report]);
+
+  useEffect(() => {
+    const unlisteners: Array<Promise<() => void>> = [
+      getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+        if (focused && content.current === savedContent.current) void load().catch(report);
+      }),
+      listen<NotesChanged>("notes-changed", (event) => {
+        if (event.payload.reason === "settings") {
+          void useSettingsStore.getState().reload().catch(report);
+        }
+      }),
+    ];
+    return () => {
+      for (const unlisten of unlisteners) void unlisten.then((fn) => fn());
+    };
+  }, [load, report]);
+
+  useEffect(() => {
+    function onKeyDown(e: KeyboardEvent) {
+      if (!(e.metaKey || e.ctrlKey) || e.key.toLowerCase() !== "s") return;
+      e.preventDefault();
+      if (meta?.storage !== "saved") return;
+      const next = content.current;
+      api
+        .noteSave(id, next)
+        .then((updated) => {
+          savedContent.current = next;
+          setMeta(updated);
+        })
+        .catch(report);
+    }
+    window.addEventListener("keydown", onKeyDown);
+    return () => window.removeEventListener("keydown", onKeyDown);
+  }, [id, meta?.storage, report]);
+
+  return (
+    <ErrorBoundary>
+      <AppShell>
+        <div className="h-screen" style={{ background: "var(--bg-primary)" }}>
+          {meta ? (
+            <Editor key={revision} value={content.current} onChange={onChange} />
+          ) : (
+            <div className="flex h-full items-center justify-center">
+              <Spinner className="size-4 text-[var(--text-secondary)]" />
+            </div>
+          )}
+        </div>
+      </AppShell>
+    </ErrorBoundary>
+  );
+}
@@ -1,29 +1,127 @@
-import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings, NoteMeta, NoteWithContent, NotesList } from "@/lib/types";
+import { invoke as tauriInvoke } from "@tauri-apps/api/core";
+import type {
+  AppSettings,
+  BatchReport,
+  CommandFailure,
+  ConvertedImportTarget,
+  ExternalImportReport,
+  ExternalSource,
+  Keybinding,
+  NoteLink,
+  NoteFromTemplate,
+  NoteMeta,
+  NoteTemplate,
+  NoteWindowMode,
+  NoteWithContent,
+  NotesList,
+  ObsidianExportItem,
+  Recurrence,
+  RecurrenceInput,
+  TaskFilter,
+  TaskItem,
+} from "@/lib/types";
+
+/** A failed command. `key` tells errors apart; the message is already in the app's locale. */
+export class CommandError extends Error {
+  readonly key: string;
+  readonly args: Record<string, string>;
+
+  constructor({ key, args, message }: CommandFailure) {
+    super(message);
+    this.name = "CommandError";
+    this.key = key;
+    this.args = args;
+  }
+
+  toString() {
+    return this.message;
+  }
+}
+
+function isCommandFailure(err: unknown): err is CommandFailure {
+  return typeof err === "object" && err !== null && "key" in err && "message" in err;
+}
+
+async function invoke<T>(...params: Parameters<typeof tauriInvoke>): Promise<T> {
+  try {
+    return await tauriInvoke<T>(...params);
+  } catch (err) {
+    throw isCommandFailure(err) ? new CommandError(err) : err;
+  }
+}
 
 export const api = {
   notesList: () => invoke<NotesList>("notes_list"),
   noteCreate: () => invoke<NoteMeta>("note_create"),
+  templatesList: () => invoke<NoteTemplate[]>("templates_list"),
+  noteCreateFromTemplate: (name: string, clipboard?: string) =>
+    invoke<NoteFromTemplate>("note_create_from_template", { name, clipboard }),
+  noteDaily: (date?: string) => invoke<NoteWithContent>("note_daily", { date }),
+  recurrencesList: () => invoke<Recurrence[]>("recurrences_list"),
+  recurrenceSave: (recurrence: RecurrenceInput) =>
+    invoke<Recurrence>("recurrence_save", { recurrence }),
+  recurrenceDelete: (id: string) => invoke<void>("recurrence_delete", { id }),
   noteGet: (id: string) => invoke<NoteWithContent>("note_get", { id }),
+  noteRemoveFrontMatter: (id: string) =>
+    invoke<NoteWithContent>("note_remove_front_matter", { id }),
   noteSetActive: (id: string) => invoke<void>("note_set_active", { id }),
   noteWriteDraft: (id: string, content: string) =>
     invoke<NoteMeta>("note_write_draft", { id, content }),
   noteSave: (id: string, content: string) => invoke<NoteMeta>("note_save", { id, content }),
+  noteConvertToUtf8: (id: string, content: string) =>
+    invoke<NoteMeta>("note_convert_to_utf8", { id, content }),
   noteSaveAs: (id: string, path: string, content: string) =>
     invoke<NoteMeta>("note_save_as", { id, path, content }),
-  noteImportFile: (path: string) => invoke<NoteWithContent>("note_import_file", { path }),
+  noteImportFile: (path: string, convertedTarget?: ConvertedImportTarget) =>
+    invoke<NoteWithContent>("note_import_file", { path, convertedTarget }),
+  notesImportExternal: (source: ExternalSource, path: string, dryRun: boolean) =>
+    invoke<ExternalImportReport>("notes_import_external", { source, path, dryRun }),
+  notesExportObsidian: (ids: string[], dir: string) =>
+    invoke<ObsidianExportItem[]>("notes_export_obsidian", { ids, dir }),
+  noteOutgoingLinks: (id: string) => invoke<NoteLink[]>("note_outgoing_links", { id }),
+  noteBacklinks: (id: string) => invoke<NoteLink[]>("note_backlinks", { id }),
+  tasksList: (filter?: TaskFilter) => invoke<TaskItem[]>("tasks_list", { filter }),
+  taskToggle: (noteId: string, line: number, checked: boolean) =>
+    invoke<NoteMeta>("task_toggle", { noteId, line, checked }),
+  noteSetReminder: (id: string, at: number | null) =>
+    invoke<NoteMeta>("note_set_reminder", { id, at }),
+  noteSnoozeReminder: (id: string, minutes: number) =>
+    invoke<NoteMeta>("note_snooze_reminder", { id, minutes }),
   noteTrash: (id: string) => invoke<NoteMeta>("note_trash", { id }),
   noteRestore: (id: string) => invoke<NoteMeta>("note_restore", { id }),
   noteDeleteForever: (id: string) => invoke<void>("note_delete_forever", { id }),
   notePin: (id: string, pinned: boolean) => invoke<NoteMeta>("note_pin", { id, pinned }),
   notesReorder: (ids: string[]) => invoke<void>("notes_reorder", { ids }),
+  notesTrashMany: (ids: string[]) => invoke<BatchReport>("notes_trash_many", { ids }),
+  notesRestoreMany: (ids: string[]) => invoke<BatchReport>("notes_restore_many", { ids }),
+  notesDeleteForeverMany: (ids: string[]) =>
+    invoke<BatchReport>("notes_delete_forever_many", { ids }),
+  notesPinMany: (ids: string[], pinned: boolean) =>
+    invoke<BatchReport>("notes_pin_many", { ids, pinned }),
+  notesExportMany: (ids: string[], dir: string) =>
+    invoke<BatchReport>("notes_export_many", { ids, dir }),
+  trashEmpty: () => invoke<BatchReport>("trash_empty"),
+  // Resolve to the label of what was undone/redone, or null when the journal has nothing left.
+  historyUndo: () => invoke<string | null>("history_undo"),
+  historyRedo: () => invoke<string | null>("history_redo"),
   settingsGetAll: () => invoke<AppSettings>("settings_get_all"),
   settingsSet: (key: string, value: string) => invoke<void>("settings_set", { key, value }),
   appStateGetAll: () => invoke<Record<string, string>>("app_state_get_all"),
   appStateSet: (key: string, value: string) => invoke<void>("app_state_set", { key, value }),
   expiryRunNow: () => invoke<void>("expiry_run_now"),
+  calendarExport: (path: string) => invoke<void>("calendar_export", { path }),
   appSetActivationPolicy: (policy: "regular" | "accessory" | "prohibited") =>
     invoke<void>("app_set_activation_policy", { policy }),
   appShowMainWindow: () => invoke<void>("app_show_main_window"),
+  noteOpenWindow: (id: string, mode: NoteWindowMode) =>
+    invoke<void>("note_open_window", { id, mode }),
+  quickCaptureSubmit: (text: string) => invoke<NoteMeta>("quick_capture_submit", { text }),
+  quickCaptureHide: () => invoke<void>("quick_capture_hide"),
+  autostartSet: (enabled: boolean, hidden: boolean) =>
+    invoke<void>("autostart_set", { enabled, hidden }),
+  keybindingsGet: () => invoke<Keybinding[]>("keybindings_get"),
+  // `null` restores the default shortcut; an empty accelerator removes it.
+  keybindingsSet: (id: string, accelerator: string | null) =>
+    invoke<Keybinding[]>("keybindings_set", { id, accelerator }),
   appExit: () => invoke<void>("app_exit"),
 };
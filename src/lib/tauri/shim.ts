@@ -1,4 +1,13 @@
-import type { AppSettings, NoteMeta, NoteWithContent, NotesList } from "@/lib/types";
+import type {
+  AppSettings,
+  BatchReport,
+  BatchResult,
+  NoteMeta,
+  NoteWithContent,
+  NotesList,
+} from "@/lib/types";
+import { noteExpiryWindow } from "@/lib/utils/expiry";
+import { DEFAULT_KEYBINDINGS } from "@/lib/utils/keybindings";
 
 type WebDb = {
   notes: Record<string, { meta: NoteMeta; content: string }>;
@@ -12,6 +21,17 @@ const DEFAULT_SETTINGS: AppSettings = {
   expiryMinutes: 10_080,
   trashRetentionDays: 30,
   theme: "dark",
+  frontMatterWriteBack: false,
+  openTasksExpiry: "off",
+  openTasksMaxDays: 30,
+  doneTasksGraceMinutes: 0,
+  dailyExpiryMinutes: 4320,
+  dailyCarryTasks: true,
+  calendarFeedPath: "",
+  quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+  quickCaptureInboxId: "",
+  autostart: { enabled: false, hidden: false },
+  locale: "system",
 };
 
 function now() {
@@ -23,7 +43,9 @@ function uuid() {
 }
 
 function deriveTitlePreview(content: string): Pick<NoteMeta, "title" | "preview"> {
+  const frontMatter = /^---\r?\n[\s\S]*?^(?:---|\.\.\.)[ \t]*(?:\r?\n|$)/m.exec(content);
   const lines = content
+    .slice(frontMatter?.index === 0 ? frontMatter[0].length : 0)
     .split("\n")
     .map((l) => l.trim())
     .filter((l) => l.length > 0);
@@ -62,6 +84,12 @@ function deriveTitlePreview(content: string): Pick<NoteMeta, "title" | "preview"
   return { title, preview };
 }
 
+function countTasks(content: string): Pick<NoteMeta, "openTasks" | "doneTasks"> {
+  const markers = content.match(/^[ \t]*(?:[-*+]|\d+[.)])[ \t]+\[[ xX]\]/gm) ?? [];
+  const doneTasks = markers.filter((m) => /\[[xX]\]$/.test(m)).length;
+  return { openTasks: markers.length - doneTasks, doneTasks };
+}
+
 function loadDb(): WebDb {
   try {
     const raw = window.localStorage.getItem(STORAGE_KEY);
@@ -108,6 +136,33 @@ function getNote(db: WebDb, id: string): NoteWithContent {
   return { meta: entry.meta, content: entry.content };
 }
 
+// Runs the single-note command per id; if any fails, the stored notes go back as they were.
+async function invokeBatch(
+  cmd: string,
+  ids: string[],
+  args: Record<string, unknown> = {},
+): Promise<BatchReport> {
+  const snapshot = loadDb();
+  const results: BatchResult[] = [];
+  for (const id of new Set(ids)) {
+    try {
+      const meta = (await invokeWeb(cmd, { ...args, id })) as NoteMeta | undefined;
+      results.push({ id, status: "done", error: null, meta: meta ?? null, path: null });
+    } catch (err) {
+      const error = err instanceof Error ? err.message : String(err);
+      results.push({ id, status: "failed", error, meta: null, path: null });
+    }
+  }
+  const committed = results.every((r) => r.status === "done");
+  if (!committed) {
+    saveDb(snapshot);
+    for (const r of results) {
+      if (r.status === "done") Object.assign(r, { status: "rolled-back", meta: null });
+    }
+  }
+  return { committed, results };
+}
+
 async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined): Promise<unknown> {
   const db = loadDb();
 
@@ -130,14 +185,67 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
         createdAt: t,
         lastInteraction: t,
         trashedAt: null,
+        encoding: "utf-8",
+        hasBom: false,
+        lineEnding: "lf",
+        expiresAt: null,
+        openTasks: 0,
+        doneTasks: 0,
+        dailyDate: null,
+        remindAt: null,
+        expiryDirective: null,
       };
       db.notes[id] = { meta, content: "" };
       saveDb(db);
       return meta;
     }
+    case "note_daily": {
+      const date = String(args?.date ?? new Date().toLocaleDateString("sv-SE"));
+      const existing = Object.values(db.notes).find(
+        (n) => n.meta.dailyDate === date && !n.meta.isTrashed,
+      );
+      if (existing) return getNote(db, existing.meta.id);
+
+      const weekday = new Date(`${date}T00:00:00`).toLocaleDateString("en-US", {
+        weekday: "long",
+      });
+      const content = `# ${date} ${weekday}\n`;
+      const id = uuid();
+      const t = now();
+      db.notes[id] = {
+        content,
+        meta: {
+          id,
+          ...deriveTitlePreview(content),
+          filePath: `web://draft/${id}.md`,
+          storage: "draft",
+          isPinned: false,
+          isTrashed: false,
+          sortOrder: nextSortOrder(db),
+          createdAt: t,
+          lastInteraction: t,
+          trashedAt: null,
+          encoding: "utf-8",
+          hasBom: false,
+          lineEnding: "lf",
+          expiresAt: null,
+          openTasks: 0,
+          doneTasks: 0,
+          dailyDate: date,
+          remindAt: null,
+          expiryDirective: null,
+        },
+      };
+      saveDb(db);
+      return getNote(db, id);
+    }
     case "note_get": {
       return getNote(db, String(args?.id ?? ""));
     }
+    case "note_remove_front_matter": {
+      // The web build keeps no front matter, so there is nothing to take out.
+      return getNote(db, String(args?.id ?? ""));
+    }
     case "note_set_active": {
       const id = String(args?.id ?? "");
       const entry = db.notes[id];
@@ -155,7 +263,7 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
       const t = now();
       const { title, preview } = deriveTitlePreview(content);
       entry.content = content;
-      entry.meta = { ...entry.meta, title, preview, lastInteraction: t };
+      entry.meta = { ...entry.meta, title, preview, ...countTasks(content), lastInteraction: t };
       saveDb(db);
       return entry.meta;
     }
@@ -168,7 +276,7 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
       const t = now();
       const { title, preview } = deriveTitlePreview(content);
       entry.content = content;
-      entry.meta = { ...entry.meta, title, preview, lastInteraction: t };
+      entry.meta = { ...entry.meta, title, preview, ...countTasks(content), lastInteraction: t };
       saveDb(db);
       return entry.meta;
     }
@@ -185,6 +293,7 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
         ...entry.meta,
         title,
         preview,
+        ...countTasks(content),
         filePath: path || `web://saved/${id}.md`,
         storage: "saved",
         lastInteraction: t,
@@ -195,6 +304,19 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
     case "note_import_file": {
       throw new Error("Open/Import is not supported in browser mode.");
     }
+    case "templates_list": {
+      return [];
+    }
+    case "note_create_from_template": {
+      throw new Error("Templates are not supported in browser mode.");
+    }
+    case "recurrences_list": {
+      return [];
+    }
+    case "recurrence_save":
+    case "recurrence_delete": {
+      throw new Error("Recurring notes are not supported in browser mode.");
+    }
     case "note_trash": {
       const id = String(args?.id ?? "");
       const entry = db.notes[id];
@@ -221,6 +343,8 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
         trashedAt: null,
         sortOrder: nextSortOrder(db),
         lastInteraction: t,
+        // A fixed expiry that already passed would send the note straight back to the trash.
+        expiresAt: (entry.meta.expiresAt ?? Infinity) <= t ? null : (entry.meta.expiresAt ?? null),
       };
       saveDb(db);
       return entry.meta;
@@ -231,6 +355,19 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
       saveDb(db);
       return;
     }
+    case "note_set_reminder":
+    case "note_snooze_reminder": {
+      const id = String(args?.id ?? "");
+      const entry = db.notes[id];
+      if (!entry) throw new Error(`Note not found: ${id}`);
+      const remindAt =
+        cmd === "note_snooze_reminder"
+          ? now() + Number(args?.minutes ?? 10) * 60_000
+          : ((args?.at as number | null | undefined) ?? null);
+      entry.meta = { ...entry.meta, remindAt };
+      saveDb(db);
+      return entry.meta;
+    }
     case "note_pin": {
       const id = String(args?.id ?? "");
       const pinned = Boolean(args?.pinned);
@@ -280,6 +417,29 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
         next.expiryMinutes = Number(value) || next.expiryMinutes;
       } else if (key === "trash_retention_days") {
         next.trashRetentionDays = Number(value) || next.trashRetentionDays;
+      } else if (key === "front_matter_write_back") {
+        next.frontMatterWriteBack = value === "true";
+      } else if (
+        key === "open_tasks_expiry" &&
+        (value === "off" || value === "extend" || value === "exempt")
+      ) {
+        next.openTasksExpiry = value;
+      } else if (key === "open_tasks_max_days") {
+        next.openTasksMaxDays = Number(value) || next.openTasksMaxDays;
+      } else if (key === "done_tasks_grace_minutes") {
+        next.doneTasksGraceMinutes = Number(value) || 0;
+      } else if (key === "daily_expiry_minutes") {
+        next.dailyExpiryMinutes = Number(value) || next.dailyExpiryMinutes;
+      } else if (key === "daily_carry_tasks") {
+        next.dailyCarryTasks = value === "true";
+      } else if (key === "calendar_feed_path") {
+        next.calendarFeedPath = value;
+      } else if (key === "quick_capture_shortcut") {
+        next.quickCaptureShortcut = value;
+      } else if (key === "quick_capture_inbox_id") {
+        next.quickCaptureInboxId = value;
+      } else if (key === "locale") {
+        next.locale = value;
       }
       db.settings = next;
       saveDb(db);
@@ -296,20 +456,67 @@ async function invokeWeb(cmd: string, args: Record<string, unknown> | undefined)
       return;
     }
     case "expiry_run_now": {
-      const expiryMinutes = db.settings?.expiryMinutes ?? DEFAULT_SETTINGS.expiryMinutes;
-      const cutoff = now() - Math.max(1, expiryMinutes) * 60_000;
+      const settings: AppSettings = { ...DEFAULT_SETTINGS, ...(db.settings ?? {}) };
       const selectedNoteId = db.appState?.selectedNoteId ?? null;
       for (const entry of Object.values(db.notes)) {
         if (entry.meta.isTrashed) continue;
         if (entry.meta.isPinned) continue;
         if (selectedNoteId && entry.meta.id === selectedNoteId) continue;
-        if (entry.meta.lastInteraction > cutoff) continue;
+        const expiresAt = entry.meta.expiresAt ?? null;
+        const { minutes } = noteExpiryWindow(settings.expiryMinutes, entry.meta, settings);
+        if (minutes === null) continue;
+        const deadline = expiresAt ?? entry.meta.lastInteraction + Math.max(1, minutes) * 60_000;
+        if (deadline > now()) continue;
         const t = now();
         entry.meta = { ...entry.meta, isTrashed: true, isPinned: false, trashedAt: t };
       }
       saveDb(db);
       return;
     }
+    case "notes_trash_many": {
+      return invokeBatch("note_trash", (args?.ids as string[]) ?? []);
+    }
+    case "notes_restore_many": {
+      return invokeBatch("note_restore", (args?.ids as string[]) ?? []);
+    }
+    case "notes_delete_forever_many": {
+      return invokeBatch("note_delete_forever", (args?.ids as string[]) ?? []);
+    }
+    case "notes_pin_many": {
+      const pinned = Boolean(args?.pinned);
+      return invokeBatch("note_pin", (args?.ids as string[]) ?? [], { pinned });
+    }
+    case "trash_empty": {
+      const ids = listNotes(db).trashed.map((m) => m.id);
+      return invokeBatch("note_delete_forever", ids);
+    }
+    case "notes_export_many": {
+      throw new Error("Export is not supported in browser mode.");
+    }
+    case "history_undo":
+    case "history_redo": {
+      // Browser mode keeps no journal.
+      return null;
+    }
+    case "calendar_export": {
+      throw new Error("Calendar export is not supported in browser mode.");
+    }
+    case "note_open_window": {
+      throw new Error("Note windows are not supported in browser mode.");
+    }
+    case "quick_capture_submit":
+    case "quick_capture_hide": {
+      throw new Error("Quick capture is not supported in browser mode.");
+    }
+    case "autostart_set": {
+      throw new Error("Launching at login is not supported in browser mode.");
+    }
+    case "keybindings_get": {
+      return DEFAULT_KEYBINDINGS;
+    }
+    case "keybindings_set": {
+      throw new Error("Keyboard shortcuts can't be changed in browser mode.");
+    }
     default: {
       throw new Error(`Unsupported command in browser mode: ${cmd}`);
     }
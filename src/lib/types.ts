@@ -1,5 +1,9 @@
 export type NoteStorage = "draft" | "saved";
 
+export type TextEncoding = "utf-8" | "utf-16le" | "utf-16be" | "windows-1252";
+
+export type LineEnding = "lf" | "crlf" | "cr";
+
 export type NoteMeta = {
   id: string;
   title: string;
@@ -12,6 +16,123 @@ export type NoteMeta = {
   createdAt: number;
   lastInteraction: number;
   trashedAt: number | null;
+  encoding: TextEncoding;
+  hasBom: boolean;
+  lineEnding: LineEnding;
+  expiresAt: number | null;
+  openTasks: number;
+  doneTasks: number;
+  dailyDate: string | null;
+  // Stays set after the reminder fires until it is snoozed or dismissed.
+  remindAt: number | null;
+  // An expiry directive found in the content, e.g. `expires: friday`; wins over `expiresAt`.
+  expiryDirective: ExpiryDirective | null;
+};
+
+export type ExpiryDirective = {
+  // The directive as written, lowercased.
+  phrase: string;
+  expiresAt: number;
+};
+
+export type ConvertedImportTarget = "draft" | "sibling";
+
+export type ExternalSource = "keep" | "simplenote" | "obsidian";
+
+export type ExternalImportItem = {
+  sourceId: string;
+  title: string;
+  preview: string;
+  tags: string[];
+  isPinned: boolean;
+  pinDropped: boolean;
+  isArchived: boolean;
+  isTrashed: boolean;
+  createdAt: number;
+  lastInteraction: number;
+  expiresAt: number | null;
+  noteId: string | null;
+};
+
+export type ExternalImportSkip = {
+  sourceId: string;
+  reason: string;
+};
+
+export type ExternalImportReport = {
+  source: ExternalSource;
+  committed: boolean;
+  notes: ExternalImportItem[];
+  skipped: ExternalImportSkip[];
+};
+
+export type ObsidianExportItem = {
+  id: string;
+  path: string;
+};
+
+export type LinkStatus = "resolved" | "ambiguous" | "dangling" | "missing";
+
+export type NoteLink = {
+  sourceId: string;
+  sourceTitle: string;
+  target: string;
+  label: string | null;
+  targetId: string | null;
+  targetTitle: string | null;
+  status: LinkStatus;
+};
+
+export type TaskState = "open" | "done" | "all";
+
+export type TaskFilter = {
+  state?: TaskState;
+  noteId?: string;
+  query?: string;
+};
+
+export type TaskItem = {
+  noteId: string;
+  noteTitle: string;
+  line: number;
+  text: string;
+  checked: boolean;
+};
+
+export type NoteTemplate = {
+  name: string;
+  expiryMinutes: number | null;
+  usesClipboard: boolean;
+};
+
+export type NoteFromTemplate = {
+  note: NoteWithContent;
+  // UTF-16 offset of `{{cursor}}` in `note.content`.
+  cursor: number | null;
+};
+
+export type Recurrence = {
+  id: string;
+  name: string;
+  template: string;
+  // RRULE subset, e.g. `FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0`.
+  rule: string;
+  // Lifetime of each generated note; null leaves it to the usual expiry.
+  expiryMinutes: number | null;
+  createdAt: number;
+  lastOccurrenceAt: number | null;
+  nextOccurrenceAt: number | null;
+  // Why the last due occurrence couldn't be generated, e.g. a missing template.
+  lastError: string | null;
+};
+
+export type RecurrenceInput = {
+  // Set to update an existing recurrence.
+  id?: string;
+  name?: string;
+  template: string;
+  rule: string;
+  expiryMinutes?: number | null;
 };
 
 export type NotesList = {
@@ -19,13 +140,102 @@ export type NotesList = {
   trashed: NoteMeta[];
 };
 
+export type ChangeReason =
+  | "created"
+  | "written"
+  | "saved"
+  | "imported"
+  | "trashed"
+  | "restored"
+  | "deleted"
+  | "pinned"
+  | "reordered"
+  | "reminder"
+  | "recurring"
+  | "expired"
+  | "settings"
+  | "undo"
+  | "redo";
+
+/** A note before and after a mutation; `before` is null when created, `after` when deleted. */
+export type NoteChange = {
+  id: string;
+  before: NoteMeta | null;
+  after: NoteMeta | null;
+};
+
+/** Payload of the backend's `notes-changed` event. */
+export type NotesChanged = {
+  reason: ChangeReason;
+  changes: NoteChange[];
+};
+
+export type BatchStatus = "done" | "failed" | "rolled-back";
+
+export type BatchResult = {
+  id: string;
+  status: BatchStatus;
+  error: string | null;
+  meta: NoteMeta | null;
+  // Where notes_export_many wrote the note.
+  path: string | null;
+};
+
+/** Batch commands are all or nothing: `committed` is false when any id failed. */
+export type BatchReport = {
+  committed: boolean;
+  results: BatchResult[];
+};
+
+/** A note in its own window, or a small always-on-top sticky note. */
+export type NoteWindowMode = "normal" | "sticky";
+
 export type NoteWithContent = {
   meta: NoteMeta;
   content: string;
 };
 
+export type OpenTasksExpiry = "off" | "extend" | "exempt";
+
 export type AppSettings = {
   expiryMinutes: number;
   trashRetentionDays: number;
   theme: "dark" | "light" | "system";
+  frontMatterWriteBack: boolean;
+  openTasksExpiry: OpenTasksExpiry;
+  openTasksMaxDays: number;
+  doneTasksGraceMinutes: number;
+  dailyExpiryMinutes: number;
+  dailyCarryTasks: boolean;
+  /** Where the iCalendar feed is kept up to date; empty when off. */
+  calendarFeedPath: string;
+  /** System-wide shortcut for the quick-capture window; empty when off. */
+  quickCaptureShortcut: string;
+  /** Note that quick captures are appended to; empty creates a new note each time. */
+  quickCaptureInboxId: string;
+  /** Launch at login, read from the OS login items. */
+  autostart: Autostart;
+  /** Language of the native menus, tray and backend messages: `system`, `en` or `de`. */
+  locale: string;
+};
+
+export type Autostart = {
+  enabled: boolean;
+  /** Start in the tray without opening the main window. */
+  hidden: boolean;
+};
+
+/** A menu action's keyboard shortcut, e.g. `CmdOrCtrl+Shift+KeyS`; empty when it has none. */
+export type Keybinding = {
+  id: string;
+  label: string;
+  accelerator: string;
+  defaultAccelerator: string;
+};
+
+/** What a command rejects with: the catalog key and its arguments, and the text to show. */
+export type CommandFailure = {
+  key: string;
+  args: Record<string, string>;
+  message: string;
 };
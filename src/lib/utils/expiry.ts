@@ -1,17 +1,86 @@
+import type { AppSettings, NoteMeta } from "@/lib/types";
+
 export type ExpiryStatus = "fresh" | "aging" | "warning" | "danger";
 
+export type ExpiryRule = Pick<
+  AppSettings,
+  "openTasksExpiry" | "openTasksMaxDays" | "doneTasksGraceMinutes" | "dailyExpiryMinutes"
+>;
+
+export const DEFAULT_EXPIRY_RULE: ExpiryRule = {
+  openTasksExpiry: "off",
+  openTasksMaxDays: 30,
+  doneTasksGraceMinutes: 0,
+  dailyExpiryMinutes: 4320,
+};
+
+export type ExpiryWindow = {
+  // Inactivity window in minutes; `null` keeps the note.
+  minutes: number | null;
+  reason: string | null;
+};
+
+// A fixed expiry (`expiresAt`) replaces the inactivity deadline; the ring still drains over the
+// last `expiryMinutes` before it.
 export function expiryProgress(
   lastInteractionMs: number,
   expiryMinutes: number,
   nowMs = Date.now(),
+  expiresAt: number | null = null,
 ): number {
   const totalMs = Math.max(1, expiryMinutes) * 60_000;
-  const elapsed = Math.max(0, nowMs - lastInteractionMs);
-  return clamp01(1 - elapsed / totalMs);
+  const remaining = noteExpiryTime(lastInteractionMs, expiryMinutes, expiresAt) - nowMs;
+  return clamp01(remaining / totalMs);
 }
 
-export function noteExpiryTime(lastInteractionMs: number, expiryMinutes: number): number {
-  return lastInteractionMs + Math.max(1, expiryMinutes) * 60_000;
+export function noteExpiryTime(
+  lastInteractionMs: number,
+  expiryMinutes: number,
+  expiresAt: number | null = null,
+): number {
+  return expiresAt ?? lastInteractionMs + Math.max(1, expiryMinutes) * 60_000;
+}
+
+// The fixed deadline of a note: an expiry directive in its content, else `expiresAt`.
+export function noteExpiresAt(
+  note: Pick<NoteMeta, "expiresAt" | "expiryDirective">,
+): number | null {
+  return note.expiryDirective?.expiresAt ?? note.expiresAt;
+}
+
+// Mirrors `ExpiryPolicy::deadline` in the backend: daily notes have their own window, open tasks
+// extend it (up to a cap) or suspend it, finished tasks can shorten it. A reminder suspends it;
+// otherwise a fixed expiry is left alone.
+export function noteExpiryWindow(
+  defaultExpiryMinutes: number,
+  note: Pick<
+    NoteMeta,
+    "openTasks" | "doneTasks" | "expiresAt" | "dailyDate" | "remindAt" | "expiryDirective"
+  >,
+  rule: ExpiryRule,
+): ExpiryWindow {
+  if (note.remindAt != null) return { minutes: null, reason: "kept: reminder set" };
+  const expiryMinutes = note.dailyDate ? rule.dailyExpiryMinutes : defaultExpiryMinutes;
+  const base: ExpiryWindow = { minutes: expiryMinutes, reason: null };
+  if (note.expiryDirective) return { ...base, reason: `from "${note.expiryDirective.phrase}"` };
+  if (note.expiresAt !== null) return base;
+
+  const open = note.openTasks ?? 0;
+  if (open > 0) {
+    const reason = `kept: ${open} open ${open === 1 ? "task" : "tasks"}`;
+    if (rule.openTasksExpiry === "exempt") return { minutes: null, reason };
+    const capMinutes = rule.openTasksMaxDays * 1440;
+    if (rule.openTasksExpiry === "extend" && capMinutes > expiryMinutes) {
+      return { minutes: capMinutes, reason };
+    }
+    return base;
+  }
+
+  const grace = rule.doneTasksGraceMinutes;
+  if ((note.doneTasks ?? 0) > 0 && grace > 0 && grace < expiryMinutes) {
+    return { minutes: grace, reason: "all tasks done" };
+  }
+  return base;
 }
 
 export function expiryStatus(progress: number): ExpiryStatus {
@@ -0,0 +1,93 @@
+import type { Keybinding } from "@/lib/types";
+
+function binding(id: string, label: string, accelerator: string): Keybinding {
+  return { id, label, accelerator, defaultAccelerator: accelerator };
+}
+
+// Mirrors `keybindings::ACTIONS` in Rust; browser mode has no native menu to ask.
+export const DEFAULT_KEYBINDINGS: Keybinding[] = [
+  binding("app_settings", "Settings…", "CmdOrCtrl+Comma"),
+  binding("app_quit", "Quit Augenblick", "CmdOrCtrl+KeyQ"),
+  binding("file_new_note", "New note", "CmdOrCtrl+KeyN"),
+  binding("file_open_markdown", "Open…", "CmdOrCtrl+KeyO"),
+  binding("file_save_note", "Save", "CmdOrCtrl+KeyS"),
+  binding("file_save_as_note", "Save As…", "CmdOrCtrl+Shift+KeyS"),
+  binding("file_trash_note", "Move to Trash", "Delete"),
+  binding("edit_history_undo", "Undo Note Change", "CmdOrCtrl+Alt+KeyZ"),
+  binding("edit_history_redo", "Redo Note Change", "CmdOrCtrl+Alt+Shift+KeyZ"),
+  binding("view_toggle_devtools", "Toggle Developer Tools", "CmdOrCtrl+Alt+KeyI"),
+];
+
+/** The accelerator bound to action `id`; empty when it has none. */
+export function acceleratorFor(bindings: Keybinding[], id: string) {
+  const found =
+    bindings.find((b) => b.id === id) ?? DEFAULT_KEYBINDINGS.find((b) => b.id === id);
+  return found?.accelerator ?? "";
+}
+
+const KEY_CHARS: Record<string, string> = {
+  Comma: ",",
+  Period: ".",
+  Slash: "/",
+  Backslash: "\\",
+  Semicolon: ";",
+  Quote: "'",
+  Backquote: "`",
+  Minus: "-",
+  Equal: "=",
+  BracketLeft: "[",
+  BracketRight: "]",
+  Space: " ",
+};
+
+const KEY_GLYPHS: Record<string, string> = {
+  ArrowUp: "↑",
+  ArrowDown: "↓",
+  ArrowLeft: "←",
+  ArrowRight: "→",
+  Enter: "↩",
+  Escape: "Esc",
+  Space: "Space",
+};
+
+function parse(accelerator: string) {
+  const parts = accelerator.split("+").map((t) => t.trim());
+  const tokens = parts.map((t) => t.toLowerCase());
+  return {
+    cmdOrCtrl: tokens.includes("cmdorctrl") || tokens.includes("commandorcontrol"),
+    ctrl: tokens.includes("ctrl") || tokens.includes("control"),
+    alt: tokens.includes("alt") || tokens.includes("option"),
+    shift: tokens.includes("shift"),
+    super: tokens.includes("super") || tokens.includes("cmd") || tokens.includes("command"),
+    key: parts[parts.length - 1] ?? "",
+  };
+}
+
+function keyLabel(key: string) {
+  if (/^Key[A-Z]$/.test(key)) return key.slice(3);
+  if (/^Digit[0-9]$/.test(key)) return key.slice(5);
+  return KEY_GLYPHS[key] ?? KEY_CHARS[key] ?? key;
+}
+
+/** Shows an accelerator the way the palette lists shortcuts, e.g. `CmdOrCtrl+Shift+KeyS` → ⇧⌘S. */
+export function formatAccelerator(accelerator: string) {
+  if (!accelerator) return "";
+  const a = parse(accelerator);
+  const modifiers = `${a.ctrl ? "⌃" : ""}${a.alt ? "⌥" : ""}${a.shift ? "⇧" : ""}${
+    a.cmdOrCtrl || a.super ? "⌘" : ""
+  }`;
+  return `${modifiers}${keyLabel(a.key)}`;
+}
+
+/** Whether `e` is the accelerator's key combination. Cmd and Ctrl both count for CmdOrCtrl. */
+export function matchesAccelerator(e: KeyboardEvent, accelerator: string) {
+  if (!accelerator) return false;
+  const a = parse(accelerator);
+  const mod = a.cmdOrCtrl || a.ctrl || a.super;
+  if ((e.metaKey || e.ctrlKey) !== mod) return false;
+  if ((a.ctrl && !e.ctrlKey) || (a.super && !e.metaKey)) return false;
+  if (e.altKey !== a.alt || e.shiftKey !== a.shift) return false;
+  if (e.code) return e.code === a.key;
+  const label = /^(Key|Digit)/.test(a.key) ? keyLabel(a.key) : (KEY_CHARS[a.key] ?? a.key);
+  return e.key.toLowerCase() === label.toLowerCase();
+}
@@ -38,3 +38,20 @@ export function formatRelativeTimeFromNow(targetMs: number, nowMs = Date.now()):
   }
   return `in ${seconds}s`;
 }
+
+export type ReminderPreset = { label: string; at: number };
+
+// Quick picks for the "Remind Me" menu, in local time. "This Evening" is offered until 17:00.
+export function reminderPresets(nowMs = Date.now()): ReminderPreset[] {
+  const now = new Date(nowMs);
+  const at = (daysAhead: number, hour: number) => {
+    const date = new Date(now.getFullYear(), now.getMonth(), now.getDate() + daysAhead, hour);
+    return date.getTime();
+  };
+  const presets: ReminderPreset[] = [{ label: "In 1 Hour", at: nowMs + 3_600_000 }];
+  if (now.getHours() < 17) presets.push({ label: "This Evening", at: at(0, 18) });
+  presets.push({ label: "Tomorrow Morning", at: at(1, 9) });
+  const daysToMonday = (8 - now.getDay()) % 7 || 7;
+  presets.push({ label: "Next Monday", at: at(daysToMonday, 9) });
+  return presets;
+}
@@ -1,7 +1,17 @@
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { CaptureWindow } from "./features/windows/CaptureWindow";
+import { NoteWindow } from "./features/windows/NoteWindow";
 import "./App.css";
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <App />,
-);
+// Note windows load the same page with `?note=<id>`, the quick-capture window with `?capture`.
+const params = new URLSearchParams(window.location.search);
+const noteId = params.get("note");
+
+function Root() {
+  if (noteId) return <NoteWindow id={noteId} />;
+  if (params.has("capture")) return <CaptureWindow />;
+  return <App />;
+}
+
+ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(<Root />);
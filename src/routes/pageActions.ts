@@ -40,6 +40,10 @@ export function createPageActions(deps: {
   getSidebarWidth: () => number;
   setSidebarWidth: (width: number) => void;
   getTrashedCount: () => number;
+  calendar?: {
+    exportTo: (path: string) => Promise<void>;
+    setFeedPath: (path: string) => Promise<void>;
+  };
 }) {
   let openMarkdownInFlight = false;
 
@@ -51,7 +55,9 @@ export function createPageActions(deps: {
       const picked = await deps.openFile({
         multiple: false,
         directory: false,
-        filters: [{ name: "Markdown", extensions: ["md"] }],
+        filters: [
+          { name: "Notes", extensions: ["md", "markdown", "txt", "html", "htm", "rtf"] },
+        ],
         fileAccessMode: "scoped",
       });
       if (!picked || Array.isArray(picked)) return;
@@ -88,6 +94,31 @@ export function createPageActions(deps: {
     await deps.notesStore.saveAs(id, finalPath);
   }
 
+  async function pickCalendarPath(title: string) {
+    const picked = await deps.saveFile({
+      title,
+      defaultPath: "Augenblick.ics",
+      filters: [{ name: "iCalendar", extensions: ["ics"] }],
+    });
+    if (!picked) return null;
+    return picked.endsWith(".ics") ? picked : `${picked}.ics`;
+  }
+
+  async function exportCalendar() {
+    if (!deps.calendar) return;
+    const path = await pickCalendarPath("Export Calendar");
+    if (!path) return;
+    await deps.calendar.exportTo(path);
+    deps.toast.success("Calendar exported");
+  }
+
+  async function chooseCalendarFeed() {
+    if (!deps.calendar) return;
+    const path = await pickCalendarPath("Keep Calendar Feed At");
+    if (!path) return;
+    await deps.calendar.setFeedPath(path);
+  }
+
   function resolveMetaById(id: string) {
     if (deps.getMetaById) return deps.getMetaById(id);
     return deps.getSelectedId() === id ? deps.getSelectedMeta() : null;
@@ -184,6 +215,8 @@ export function createPageActions(deps: {
     openMarkdown,
     saveCurrent,
     saveAs,
+    exportCalendar,
+    chooseCalendarFeed,
     closeCurrent,
     trashNoteById,
     onEditorChange,
@@ -1,3 +1,6 @@
+import type { Keybinding } from "@/lib/types";
+import { DEFAULT_KEYBINDINGS, acceleratorFor, matchesAccelerator } from "@/lib/utils/keybindings";
+
 type NotesSnapshot = {
   list: { active: Array<{ id: string; isPinned: boolean }>; trashed: Array<{ id: string }> };
   selectedId: string | null;
@@ -29,16 +32,46 @@ type Deps = {
   saveAs: () => void;
   quit?: () => void;
   selectNote: (id: string) => void;
-  undoReorder: () => void;
-  redoReorder: () => void;
+  undo: () => void;
+  redo: () => void;
+  // The menu's shortcuts, which can be rebound in the settings.
+  getKeybindings?: () => Keybinding[];
 };
 
 export function createPageKeydownHandler(deps: Deps) {
+  // Menu actions the page handles too, for when the native menu doesn't get the keys first.
+  const menuActions: Array<[string, () => void]> = [
+    ["app_quit", () => deps.quit?.()],
+    ["app_settings", deps.openSettings],
+    ["file_new_note", deps.createNote],
+    ["file_open_markdown", deps.openMarkdown],
+    ["file_save_note", deps.saveCurrent],
+    ["file_save_as_note", deps.saveAs],
+  ];
+
   return (e: KeyboardEvent) => {
-    if (e.key === "Backspace" || e.key === "Delete") {
-      if (!isTypingTarget(e.target)) {
+    const bindings = deps.getKeybindings?.() ?? DEFAULT_KEYBINDINGS;
+    const trash = acceleratorFor(bindings, "file_trash_note");
+    // The key labelled "delete" on Mac keyboards sends Backspace, so it trashes too while the
+    // default is kept.
+    const trashKeys =
+      trash === acceleratorFor(DEFAULT_KEYBINDINGS, "file_trash_note")
+        ? [trash, "Backspace"]
+        : [trash];
+    // Trashing is usually bound to a bare key, which has to keep working in text fields.
+    if (
+      !isTypingTarget(e.target) &&
+      trashKeys.some((accelerator) => matchesAccelerator(e, accelerator))
+    ) {
+      e.preventDefault();
+      deps.closeCurrent();
+      return;
+    }
+
+    for (const [id, run] of menuActions) {
+      if (matchesAccelerator(e, acceleratorFor(bindings, id))) {
         e.preventDefault();
-        deps.closeCurrent();
+        run();
         return;
       }
     }
@@ -57,8 +90,8 @@ export function createPageKeydownHandler(deps: Deps) {
     if (key === "z") {
       if (isTypingTarget(e.target)) return;
       e.preventDefault();
-      if (e.shiftKey) deps.redoReorder();
-      else deps.undoReorder();
+      if (e.shiftKey) deps.redo();
+      else deps.undo();
       return;
     }
 
@@ -68,18 +101,6 @@ export function createPageKeydownHandler(deps: Deps) {
       return;
     }
 
-    if (key === "q") {
-      e.preventDefault();
-      deps.quit?.();
-      return;
-    }
-
-    if (key === ",") {
-      e.preventDefault();
-      deps.openSettings();
-      return;
-    }
-
     if (e.shiftKey && (e.key === "ArrowUp" || e.key === "ArrowDown")) {
       e.preventDefault();
       const snapshot = deps.getNotesSnapshot();
@@ -106,33 +127,9 @@ export function createPageKeydownHandler(deps: Deps) {
       return;
     }
 
-    if (key === "n") {
-      e.preventDefault();
-      deps.createNote();
-      return;
-    }
-
     if (key === "p") {
       e.preventDefault();
       deps.togglePinCurrent();
-      return;
-    }
-
-    if (key === "s" && e.shiftKey) {
-      e.preventDefault();
-      deps.saveAs();
-      return;
-    }
-
-    if (key === "s") {
-      e.preventDefault();
-      deps.saveCurrent();
-      return;
-    }
-
-    if (key === "o") {
-      e.preventDefault();
-      deps.openMarkdown();
     }
   };
 }
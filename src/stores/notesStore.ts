@@ -1,10 +1,9 @@
 import { create } from "zustand";
 import { api } from "@/lib/api";
-import type { NoteMeta, NotesList } from "@/lib/types";
+import type { ChangeReason, NoteChange, NoteMeta, NoteTemplate, NotesList } from "@/lib/types";
 import {
   bumpLastInteraction,
   findMetaById,
-  getSectionIds,
   listIds,
   pruneByIds,
   removeMeta,
@@ -15,13 +14,6 @@ import {
   type ReorderSection,
 } from "@/stores/notes/helpers";
 import { getDirtySavedIds } from "@/stores/notes/dirty";
-import {
-  popReorderRedo,
-  popReorderUndo,
-  pushReorderRedo,
-  pushReorderUndo,
-  pushUndoFromRedo,
-} from "@/stores/notes/reorderHistory";
 import {
   clearDraftSaveTimer,
   scheduleAppStateWrite,
@@ -40,7 +32,12 @@ type NotesState = {
   loading: boolean;
   init: () => Promise<void>;
   refresh: () => Promise<void>;
+  // Applies a `notes-changed` event; resolves to the ids whose unsaved edits here now conflict
+  // with what another window wrote.
+  applyChanges: (changes: NoteChange[], reason?: ChangeReason) => Promise<string[]>;
   createNote: () => Promise<void>;
+  createNoteFromTemplate: (template: NoteTemplate) => Promise<void>;
+  openDailyNote: (date?: string) => Promise<void>;
   select: (id: string) => Promise<void>;
   setViewMode: (viewMode: ViewMode) => Promise<void> | void;
   setSidebarWidth: (sidebarWidth: number) => void;
@@ -49,14 +46,18 @@ type NotesState = {
   saveAs: (id: string, path: string) => Promise<void>;
   saveAllDirty: () => Promise<void>;
   importFile: (path: string) => Promise<void>;
+  removeFrontMatter: (id: string) => Promise<void>;
   trash: (id: string) => Promise<void>;
   restore: (id: string) => Promise<void>;
   deleteForever: (id: string) => Promise<void>;
   clearTrash: () => Promise<void>;
   togglePin: (id: string) => Promise<void>;
+  // Sets the reminder time (ms), or dismisses it with null.
+  setReminder: (id: string, at: number | null) => Promise<void>;
   reorder: (section: ReorderSection, ids: string[]) => Promise<void>;
-  undoReorder: () => Promise<void>;
-  redoReorder: () => Promise<void>;
+  // Reverse or reapply the latest trash, delete, pin, reorder or auto-expiry.
+  undo: () => Promise<void>;
+  redo: () => Promise<void>;
   heartbeatSelected: () => Promise<void>;
   runExpirySweep: () => Promise<void>;
 };
@@ -66,6 +67,8 @@ const DEFAULT_STATE: Omit<
   | "init"
   | "refresh"
   | "createNote"
+  | "createNoteFromTemplate"
+  | "openDailyNote"
   | "select"
   | "setViewMode"
   | "setSidebarWidth"
@@ -74,14 +77,16 @@ const DEFAULT_STATE: Omit<
   | "saveAs"
   | "saveAllDirty"
   | "importFile"
+  | "removeFrontMatter"
   | "trash"
   | "restore"
   | "deleteForever"
   | "clearTrash"
   | "togglePin"
+  | "setReminder"
   | "reorder"
-  | "undoReorder"
-  | "redoReorder"
+  | "undo"
+  | "redo"
   | "heartbeatSelected"
   | "runExpirySweep"
 > = {
@@ -132,6 +137,41 @@ function appStateSnapshot(getState: () => NotesState) {
   };
 }
 
+// Reasons whose changes may have rewritten a note's content.
+const CONTENT_REASONS: ChangeReason[] = ["written", "saved", "imported"];
+
+// The content this window last sent to the backend per note, so the `notes-changed` event echoing
+// its own write isn't mistaken for an edit made in another window.
+const lastWrittenById = new Map<string, string>();
+
+function hasLocalEdits(state: NotesState, id: string): boolean {
+  return state.contentById[id] !== state.lastSavedContentById[id];
+}
+
+// The text added to the end of `base` to get `next`, e.g. by quick capture; null if `next` is
+// anything else.
+function appendedText(base: string, next: string): string | null {
+  const kept = base.trimEnd();
+  if (!next.startsWith(kept)) return null;
+  const added = next.slice(kept.length).trim();
+  return added ? added : null;
+}
+
+// Adds `text` as a new paragraph, the way quick capture does.
+function appendParagraph(content: string, text: string): string {
+  const existing = content.trimEnd();
+  return existing ? `${existing}\n\n${text}\n` : `${text}\n`;
+}
+
+// A template without clipboard text is still worth creating, so a denied read yields "".
+async function readClipboardText(): Promise<string> {
+  try {
+    return (await navigator.clipboard?.readText()) ?? "";
+  } catch {
+    return "";
+  }
+}
+
 export const useNotesStore = create<NotesState>((set, get) => ({
   ...DEFAULT_STATE,
   init: async () => {
@@ -169,6 +209,77 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     const list = await api.notesList();
     set((s) => ({ ...s, list }));
   },
+  applyChanges: async (changes, reason) => {
+    if (changes.length === 0) return [];
+    set((s) => {
+      let list = s.list;
+      let selectedId = s.selectedId;
+      for (const change of changes) {
+        if (change.after) {
+          list = upsertMeta(list, change.after);
+        } else {
+          list = removeMeta(list, change.id);
+          if (selectedId === change.id) selectedId = null;
+        }
+      }
+      return { ...s, list, selectedId };
+    });
+    if (!reason || !CONTENT_REASONS.includes(reason)) return [];
+
+    // Another window (or the backend) may have rewritten these notes. A cache without edits is
+    // dropped, or reloaded for the open note; edits here are kept and reported as a conflict.
+    const conflicts: string[] = [];
+    for (const { id, after } of changes) {
+      const s = get();
+      if (!after || typeof s.contentById[id] !== "string") continue;
+      if (id !== s.selectedId && !hasLocalEdits(s, id)) {
+        set((st) => {
+          const nextContent = { ...st.contentById };
+          delete nextContent[id];
+          const nextLastSaved = { ...st.lastSavedContentById };
+          delete nextLastSaved[id];
+          return { ...st, contentById: nextContent, lastSavedContentById: nextLastSaved };
+        });
+        continue;
+      }
+
+      const note = await api.noteGet(id);
+      const st = get();
+      if (note.content === st.contentById[id]) {
+        set((cur) => ({
+          ...cur,
+          lastSavedContentById: { ...cur.lastSavedContentById, [id]: note.content },
+        }));
+      } else if (
+        note.content === lastWrittenById.get(id) ||
+        note.content === st.lastSavedContentById[id]
+      ) {
+        // Our own write, or nothing new on disk.
+      } else if (!hasLocalEdits(st, id)) {
+        set((cur) => ({
+          ...cur,
+          list: upsertMeta(cur.list, note.meta),
+          contentById: { ...cur.contentById, [id]: note.content },
+          lastSavedContentById: { ...cur.lastSavedContentById, [id]: note.content },
+        }));
+      } else {
+        // Text appended elsewhere (quick capture) is carried over into the edits here, so the
+        // next autosave or save keeps it; any other change is a conflict.
+        const appended = appendedText(st.lastSavedContentById[id] ?? "", note.content);
+        if (appended === null) {
+          conflicts.push(id);
+          continue;
+        }
+        set((cur) => ({
+          ...cur,
+          list: upsertMeta(cur.list, note.meta),
+          lastSavedContentById: { ...cur.lastSavedContentById, [id]: note.content },
+        }));
+        get().updateContent(id, appendParagraph(st.contentById[id] ?? "", appended));
+      }
+    }
+    return conflicts;
+  },
   createNote: async () => {
     const meta = await api.noteCreate();
     set((s) => ({
@@ -188,6 +299,47 @@ export const useNotesStore = create<NotesState>((set, get) => ({
       window.dispatchEvent(new CustomEvent("augenblick:focus-editor"));
     });
   },
+  createNoteFromTemplate: async (template) => {
+    const clipboard = template.usesClipboard ? await readClipboardText() : undefined;
+    const { note, cursor } = await api.noteCreateFromTemplate(template.name, clipboard);
+    set((s) => ({
+      ...s,
+      list: replaceOrAppendActive(s.list, note.meta),
+      selectedId: note.meta.id,
+      viewMode: "notes",
+      contentById: { ...s.contentById, [note.meta.id]: note.content },
+      lastSavedContentById: { ...s.lastSavedContentById, [note.meta.id]: note.content },
+    }));
+
+    scheduleAppStateWrite(() => appStateSnapshot(get));
+    await api.noteSetActive(note.meta.id);
+
+    const markdownBefore = cursor === null ? undefined : note.content.slice(0, cursor);
+    requestAnimationFrame(() => {
+      window.dispatchEvent(
+        new CustomEvent("augenblick:focus-editor", { detail: { markdownBefore } }),
+      );
+    });
+  },
+  openDailyNote: async (date) => {
+    const note = await api.noteDaily(date);
+    // An open daily note may have edits that are not written yet.
+    if (typeof get().contentById[note.meta.id] === "string") {
+      await get().select(note.meta.id);
+      return;
+    }
+    set((s) => ({
+      ...s,
+      list: upsertMeta(s.list, note.meta),
+      selectedId: note.meta.id,
+      viewMode: "notes",
+      contentById: { ...s.contentById, [note.meta.id]: note.content },
+      lastSavedContentById: { ...s.lastSavedContentById, [note.meta.id]: note.content },
+    }));
+
+    scheduleAppStateWrite(() => appStateSnapshot(get));
+    await api.noteSetActive(note.meta.id);
+  },
   select: async (id) => {
     const before = get();
     const shouldOpenTrash = before.list.trashed.some((n) => n.id === id);
@@ -219,6 +371,7 @@ export const useNotesStore = create<NotesState>((set, get) => ({
       await api.noteSetActive(id);
     }
 
+    // applyChanges drops or refreshes the cache when another window writes the note.
     const state = get();
     const cached = state.contentById[id];
     if (typeof cached === "string") {
@@ -273,8 +426,13 @@ export const useNotesStore = create<NotesState>((set, get) => ({
 
     scheduleDraftSave(id, async () => {
       try {
+        lastWrittenById.set(id, content);
         const updated = await api.noteWriteDraft(id, content);
-        set((s) => ({ ...s, list: upsertMeta(s.list, updated) }));
+        set((s) => ({
+          ...s,
+          list: upsertMeta(s.list, updated),
+          lastSavedContentById: { ...s.lastSavedContentById, [id]: content },
+        }));
       } catch (err) {
         console.error(`Draft auto-save failed for ${id}:`, err);
       }
@@ -286,6 +444,7 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     if (!meta) return;
 
     const content = s.contentById[id] ?? "";
+    lastWrittenById.set(id, content);
     const updated = await api.noteSave(id, content);
 
     set((st) => ({
@@ -298,6 +457,7 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     clearDraftSaveTimer(id);
     const s = get();
     const content = s.contentById[id] ?? "";
+    lastWrittenById.set(id, content);
     const updated = await api.noteSaveAs(id, path, content);
 
     set((st) => ({
@@ -314,6 +474,7 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     const updates: Array<{ id: string; meta: NoteMeta; content: string }> = [];
     for (const id of dirtyIds) {
       const content = s.contentById[id] ?? "";
+      lastWrittenById.set(id, content);
       const updated = await api.noteSave(id, content);
       updates.push({ id, meta: updated, content });
     }
@@ -342,6 +503,17 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     scheduleAppStateWrite(() => appStateSnapshot(get));
     await api.noteSetActive(note.meta.id);
   },
+  removeFrontMatter: async (id) => {
+    // The backend rewrites the file from disk, so unsaved edits have to land there first.
+    if (getDirtySavedIds(get()).includes(id)) await get().save(id);
+    const note = await api.noteRemoveFrontMatter(id);
+    set((s) => ({
+      ...s,
+      list: upsertMeta(s.list, note.meta),
+      contentById: { ...s.contentById, [id]: note.content },
+      lastSavedContentById: { ...s.lastSavedContentById, [id]: note.content },
+    }));
+  },
   trash: async (id) => {
     const updated = await api.noteTrash(id);
     set((s) => {
@@ -389,9 +561,11 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     const ids = get().list.trashed.map((n) => n.id);
     if (ids.length === 0) return;
 
-    for (const id of ids) {
-      clearDraftSaveTimer(id);
-      await api.noteDeleteForever(id);
+    for (const id of ids) clearDraftSaveTimer(id);
+    const report = await api.trashEmpty();
+    if (!report.committed) {
+      const failed = report.results.find((r) => r.status === "failed");
+      throw new Error(failed?.error ?? "Emptying the trash failed.");
     }
 
     set((s) => {
@@ -418,34 +592,23 @@ export const useNotesStore = create<NotesState>((set, get) => ({
     const updated = await api.notePin(id, !meta.isPinned);
     set((st) => ({ ...st, list: upsertMeta(st.list, updated) }));
   },
+  setReminder: async (id, at) => {
+    const updated = await api.noteSetReminder(id, at);
+    set((st) => ({ ...st, list: upsertMeta(st.list, updated) }));
+  },
   reorder: async (section, ids) => {
-    const prev = getSectionIds(get().list, section);
-    pushReorderUndo(section, prev);
     applyReorderState(set, section, ids);
 
     await api.notesReorder(ids);
     await get().refresh();
   },
-  undoReorder: async () => {
-    const entry = popReorderUndo();
-    if (!entry) return;
-
-    const current = getSectionIds(get().list, entry.section);
-    pushReorderRedo(entry.section, current);
-
-    applyReorderState(set, entry.section, entry.ids);
-    await api.notesReorder(entry.ids);
+  // Undo/redo go through the backend journal, which also covers trash, delete, pin and expiry.
+  undo: async () => {
+    await api.historyUndo();
     await get().refresh();
   },
-  redoReorder: async () => {
-    const entry = popReorderRedo();
-    if (!entry) return;
-
-    const current = getSectionIds(get().list, entry.section);
-    pushUndoFromRedo(entry.section, current);
-
-    applyReorderState(set, entry.section, entry.ids);
-    await api.notesReorder(entry.ids);
+  redo: async () => {
+    await api.historyRedo();
     await get().refresh();
   },
   heartbeatSelected: async () => {
@@ -1,11 +1,23 @@
 import { create } from "zustand";
 import { api } from "@/lib/api";
-import type { AppSettings } from "@/lib/types";
+import type { AppSettings, Autostart, Keybinding } from "@/lib/types";
+import { DEFAULT_KEYBINDINGS } from "@/lib/utils/keybindings";
 
 const DEFAULT_SETTINGS: AppSettings = {
   expiryMinutes: 10_080,
   trashRetentionDays: 30,
   theme: "dark",
+  frontMatterWriteBack: false,
+  openTasksExpiry: "off",
+  openTasksMaxDays: 30,
+  doneTasksGraceMinutes: 0,
+  dailyExpiryMinutes: 4320,
+  dailyCarryTasks: true,
+  calendarFeedPath: "",
+  quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+  quickCaptureInboxId: "",
+  autostart: { enabled: false, hidden: false },
+  locale: "system",
 };
 
 function applyRootMode(mode: "light" | "dark") {
@@ -45,19 +57,45 @@ function applyTheme(theme: AppSettings["theme"]) {
 
 type SettingsState = AppSettings & {
   initialized: boolean;
+  keybindings: Keybinding[];
   init: () => Promise<void>;
+  reload: () => Promise<void>;
   setTheme: (theme: AppSettings["theme"]) => Promise<void>;
   setExpiryMinutes: (minutes: number) => Promise<void>;
   setTrashRetentionDays: (days: number) => Promise<void>;
+  setFrontMatterWriteBack: (enabled: boolean) => Promise<void>;
+  setOpenTasksExpiry: (rule: AppSettings["openTasksExpiry"]) => Promise<void>;
+  setOpenTasksMaxDays: (days: number) => Promise<void>;
+  setDoneTasksGraceMinutes: (minutes: number) => Promise<void>;
+  setDailyExpiryMinutes: (minutes: number) => Promise<void>;
+  setDailyCarryTasks: (enabled: boolean) => Promise<void>;
+  setCalendarFeedPath: (path: string) => Promise<void>;
+  setQuickCaptureShortcut: (shortcut: string) => Promise<void>;
+  setQuickCaptureInboxId: (id: string) => Promise<void>;
+  setAutostart: (autostart: Autostart) => Promise<void>;
+  setLocale: (locale: string) => Promise<void>;
+  setKeybinding: (id: string, accelerator: string | null) => Promise<void>;
 };
 
 export const useSettingsStore = create<SettingsState>((set, get) => ({
   ...DEFAULT_SETTINGS,
   initialized: false,
+  keybindings: DEFAULT_KEYBINDINGS,
   init: async () => {
     if (get().initialized) return;
-    const settings = await api.settingsGetAll();
-    set({ ...settings, initialized: true });
+    const [settings, keybindings] = await Promise.all([
+      api.settingsGetAll(),
+      api.keybindingsGet(),
+    ]);
+    set({ ...settings, keybindings, initialized: true });
+    applyTheme(settings.theme);
+  },
+  reload: async () => {
+    const [settings, keybindings] = await Promise.all([
+      api.settingsGetAll(),
+      api.keybindingsGet(),
+    ]);
+    set({ ...settings, keybindings });
     applyTheme(settings.theme);
   },
   setTheme: async (theme) => {
@@ -73,4 +111,69 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     set((s) => ({ ...s, trashRetentionDays }));
     await api.settingsSet("trash_retention_days", String(trashRetentionDays));
   },
+  setFrontMatterWriteBack: async (frontMatterWriteBack) => {
+    set((s) => ({ ...s, frontMatterWriteBack }));
+    await api.settingsSet("front_matter_write_back", String(frontMatterWriteBack));
+  },
+  setOpenTasksExpiry: async (openTasksExpiry) => {
+    set((s) => ({ ...s, openTasksExpiry }));
+    await api.settingsSet("open_tasks_expiry", openTasksExpiry);
+  },
+  setOpenTasksMaxDays: async (openTasksMaxDays) => {
+    set((s) => ({ ...s, openTasksMaxDays }));
+    await api.settingsSet("open_tasks_max_days", String(openTasksMaxDays));
+  },
+  setDoneTasksGraceMinutes: async (doneTasksGraceMinutes) => {
+    set((s) => ({ ...s, doneTasksGraceMinutes }));
+    await api.settingsSet("done_tasks_grace_minutes", String(doneTasksGraceMinutes));
+  },
+  setDailyExpiryMinutes: async (dailyExpiryMinutes) => {
+    set((s) => ({ ...s, dailyExpiryMinutes }));
+    await api.settingsSet("daily_expiry_minutes", String(dailyExpiryMinutes));
+  },
+  setDailyCarryTasks: async (dailyCarryTasks) => {
+    set((s) => ({ ...s, dailyCarryTasks }));
+    await api.settingsSet("daily_carry_tasks", String(dailyCarryTasks));
+  },
+  setCalendarFeedPath: async (calendarFeedPath) => {
+    set((s) => ({ ...s, calendarFeedPath }));
+    await api.settingsSet("calendar_feed_path", calendarFeedPath);
+  },
+  setQuickCaptureShortcut: async (quickCaptureShortcut) => {
+    const previous = get().quickCaptureShortcut;
+    set((s) => ({ ...s, quickCaptureShortcut }));
+    try {
+      // Fails when the shortcut is invalid or another app already uses it.
+      await api.settingsSet("quick_capture_shortcut", quickCaptureShortcut);
+    } catch (err) {
+      set((s) => ({ ...s, quickCaptureShortcut: previous }));
+      throw err;
+    }
+  },
+  setQuickCaptureInboxId: async (quickCaptureInboxId) => {
+    set((s) => ({ ...s, quickCaptureInboxId }));
+    await api.settingsSet("quick_capture_inbox_id", quickCaptureInboxId);
+  },
+  setAutostart: async (autostart) => {
+    const previous = get().autostart;
+    set((s) => ({ ...s, autostart }));
+    try {
+      await api.autostartSet(autostart.enabled, autostart.hidden);
+    } catch (err) {
+      set((s) => ({ ...s, autostart: previous }));
+      throw err;
+    }
+  },
+  setLocale: async (locale) => {
+    set((s) => ({ ...s, locale }));
+    await api.settingsSet("locale", locale);
+    // Action labels come from the backend in its language.
+    const keybindings = await api.keybindingsGet();
+    set((s) => ({ ...s, keybindings }));
+  },
+  setKeybinding: async (id, accelerator) => {
+    // Fails when the shortcut is invalid or already in use; the list stays as it was.
+    const keybindings = await api.keybindingsSet(id, accelerator);
+    set((s) => ({ ...s, keybindings }));
+  },
 }));
@@ -15,6 +15,7 @@ const apiMock = {
   appSetActivationPolicy: vi.fn(async () => {}),
   appShowMainWindow: vi.fn(async () => {}),
   appExit: vi.fn(async () => {}),
+  templatesList: vi.fn(async () => []),
 };
 
 vi.mock("@tauri-apps/plugin-updater", () => ({ check }));
@@ -87,8 +88,8 @@ const notesState = {
   clearTrash: vi.fn(async () => {}),
   togglePin: vi.fn(async () => {}),
   reorder: vi.fn(async () => {}),
-  undoReorder: vi.fn(async () => {}),
-  redoReorder: vi.fn(async () => {}),
+  undo: vi.fn(async () => {}),
+  redo: vi.fn(async () => {}),
   heartbeatSelected: vi.fn(async () => {}),
   runExpirySweep: vi.fn(async () => {}),
 };
@@ -100,6 +101,16 @@ const settingsState = {
   expiryMinutes: 10_080,
   trashRetentionDays: 30,
   theme: "dark",
+  openTasksExpiry: "off",
+  openTasksMaxDays: 30,
+  doneTasksGraceMinutes: 0,
+  dailyExpiryMinutes: 4320,
+  dailyCarryTasks: true,
+  calendarFeedPath: "",
+  quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+  quickCaptureInboxId: "",
+  autostart: { enabled: false, hidden: false },
+  locale: "system",
   init: vi.fn(async () => {}),
   setTheme: vi.fn(async () => {}),
   setExpiryMinutes: vi.fn(async () => {}),
@@ -22,7 +22,9 @@ vi.mock("@/components/ui/command", () => ({
   CommandGroup: ({ heading, children }: any) => (
     <section data-heading={heading}>{children}</section>
   ),
-  CommandInput: React.forwardRef((props: any, ref) => <input ref={ref} {...props} />),
+  CommandInput: React.forwardRef(({ onValueChange, ...props }: any, ref) => (
+    <input ref={ref} onChange={(e) => onValueChange?.(e.target.value)} {...props} />
+  )),
   CommandItem: ({ value, onSelect, children }: any) => (
     <button type="button" data-value={value} onClick={() => onSelect?.(value)}>
       {children}
@@ -62,6 +64,15 @@ describe("CommandPalette", () => {
         createdAt: 1,
         lastInteraction: 1,
         trashedAt: null,
+        encoding: "utf-8",
+        hasBom: false,
+        lineEnding: "lf",
+        expiresAt: null,
+        openTasks: 0,
+        doneTasks: 0,
+        dailyDate: null,
+        remindAt: null,
+        expiryDirective: null,
       },
       {
         id: "n2",
@@ -75,6 +86,15 @@ describe("CommandPalette", () => {
         createdAt: 1,
         lastInteraction: 1,
         trashedAt: null,
+        encoding: "utf-8",
+        hasBom: false,
+        lineEnding: "lf",
+        expiresAt: null,
+        openTasks: 0,
+        doneTasks: 0,
+        dailyDate: null,
+        remindAt: null,
+        expiryDirective: null,
       },
     ];
 
@@ -134,6 +154,38 @@ describe("CommandPalette", () => {
     await unmount();
   });
 
+  it("creates notes from templates", async () => {
+    const onClose = vi.fn();
+    const onNewFromTemplate = vi.fn();
+    const template = { name: "Standup", expiryMinutes: 1440, usesClipboard: false };
+    const { container, unmount } = await render(
+      React.createElement((await import("@/features/command/CommandPalette")).CommandPalette, {
+        notes: [],
+        templates: [template],
+        onClose,
+        onNewNote: vi.fn(),
+        onNewFromTemplate,
+        onTogglePinCurrent: vi.fn(),
+        onCloseNote: vi.fn(),
+        onOpenFile: vi.fn(),
+        onSave: vi.fn(),
+        onSaveAs: vi.fn(),
+        onSelectNote: vi.fn(),
+        onOpenSettings: vi.fn(),
+      }),
+    );
+
+    expect(container.querySelector('section[data-heading="New from Template"]')).not.toBeNull();
+    const item = container.querySelector(
+      'button[data-value="template:Standup"]',
+    ) as HTMLButtonElement;
+    item.click();
+    expect(onNewFromTemplate).toHaveBeenCalledWith(template);
+    expect(onClose).toHaveBeenCalled();
+
+    await unmount();
+  });
+
   it("closes on dialog open change", async () => {
     const onClose = vi.fn();
     const { unmount } = await render(
@@ -164,4 +216,91 @@ describe("CommandPalette", () => {
 
     await unmount();
   });
+
+  it("offers the calendar export when wired", async () => {
+    const onClose = vi.fn();
+    const onExportCalendar = vi.fn();
+    const { container, unmount } = await render(
+      React.createElement((await import("@/features/command/CommandPalette")).CommandPalette, {
+        notes: [],
+        onClose,
+        onNewNote: vi.fn(),
+        onTogglePinCurrent: vi.fn(),
+        onCloseNote: vi.fn(),
+        onOpenFile: vi.fn(),
+        onSave: vi.fn(),
+        onSaveAs: vi.fn(),
+        onExportCalendar,
+        onSelectNote: vi.fn(),
+        onOpenSettings: vi.fn(),
+      }),
+    );
+
+    const item = container.querySelector(
+      'button[data-value="export-calendar"]',
+    ) as HTMLButtonElement;
+    item.click();
+    expect(onExportCalendar).toHaveBeenCalled();
+    expect(onClose).toHaveBeenCalled();
+
+    await unmount();
+  });
+
+  it("starts with the query from a search link", async () => {
+    const { container, unmount } = await render(
+      React.createElement((await import("@/features/command/CommandPalette")).CommandPalette, {
+        notes: [],
+        initialQuery: "invoice",
+        onClose: vi.fn(),
+        onNewNote: vi.fn(),
+        onTogglePinCurrent: vi.fn(),
+        onCloseNote: vi.fn(),
+        onOpenFile: vi.fn(),
+        onSave: vi.fn(),
+        onSaveAs: vi.fn(),
+        onSelectNote: vi.fn(),
+        onOpenSettings: vi.fn(),
+      }),
+    );
+
+    expect(container.querySelector("input")?.value).toBe("invoice");
+
+    await unmount();
+  });
+
+  it("shows the configured shortcuts", async () => {
+    const { container, unmount } = await render(
+      React.createElement((await import("@/features/command/CommandPalette")).CommandPalette, {
+        notes: [],
+        keybindings: [
+          { id: "file_new_note", label: "New note", accelerator: "", defaultAccelerator: "CmdOrCtrl+KeyN" },
+          {
+            id: "file_save_as_note",
+            label: "Save As…",
+            accelerator: "CmdOrCtrl+Alt+KeyS",
+            defaultAccelerator: "CmdOrCtrl+Shift+KeyS",
+          },
+        ],
+        onClose: vi.fn(),
+        onNewNote: vi.fn(),
+        onTogglePinCurrent: vi.fn(),
+        onCloseNote: vi.fn(),
+        onOpenFile: vi.fn(),
+        onSave: vi.fn(),
+        onSaveAs: vi.fn(),
+        onSelectNote: vi.fn(),
+        onOpenSettings: vi.fn(),
+      }),
+    );
+
+    const shortcut = (value: string) =>
+      container.querySelector(`button[data-value="${value}"]`)?.querySelectorAll("span")[1]
+        ?.textContent;
+    expect(shortcut("new-note")).toBeUndefined();
+    expect(shortcut("save-as")).toBe("⌥⌘S");
+    expect(shortcut("save")).toBe("⌘S");
+    expect(shortcut("pin-current")).toBe("⌘P");
+
+    await unmount();
+  });
 });
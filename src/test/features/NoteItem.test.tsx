@@ -23,6 +23,10 @@ vi.mock("@/components/ui/context-menu", () => ({
     </button>
   ),
   ContextMenuShortcut: ({ children }: { children: React.ReactNode }) => <span>{children}</span>,
+  ContextMenuSeparator: () => <hr />,
+  ContextMenuSub: ({ children }: { children: React.ReactNode }) => <div>{children}</div>,
+  ContextMenuSubTrigger: ({ children }: { children: React.ReactNode }) => <span>{children}</span>,
+  ContextMenuSubContent: ({ children }: { children: React.ReactNode }) => <div>{children}</div>,
 }));
 
 describe("NoteItem", () => {
@@ -38,6 +42,15 @@ describe("NoteItem", () => {
     createdAt: 1,
     lastInteraction: 1,
     trashedAt: null,
+    encoding: "utf-8",
+    hasBom: false,
+    lineEnding: "lf",
+    expiresAt: null,
+    openTasks: 0,
+    doneTasks: 0,
+    dailyDate: null,
+    remindAt: null,
+    expiryDirective: null,
   };
 
   it("renders expiry ring and handles select", async () => {
@@ -108,4 +121,61 @@ describe("NoteItem", () => {
 
     await unmount();
   });
+
+  it("sets and clears reminders from the context menu", async () => {
+    const onSetReminder = vi.fn();
+
+    const { container, unmount } = await render(
+      React.createElement((await import("@/features/sidebar/NoteItem")).NoteItem, {
+        note: { ...base, remindAt: 5_000 },
+        selected: false,
+        dirty: false,
+        expiryMinutes: 60,
+        onSelect: vi.fn(),
+        onTogglePin: vi.fn(),
+        onTrash: vi.fn(),
+        onSetReminder,
+      }),
+    );
+
+    expect(container.querySelector('[aria-label^="Reminder"]')).toBeTruthy();
+    const buttons = Array.from(container.querySelectorAll("button"));
+    buttons.find((button) => button.textContent === "In 1 Hour")?.click();
+    buttons.find((button) => button.textContent === "Clear Reminder")?.click();
+
+    expect(onSetReminder).toHaveBeenNthCalledWith(1, "n1", expect.any(Number));
+    expect(onSetReminder).toHaveBeenNthCalledWith(2, "n1", null);
+
+    await unmount();
+  });
+
+  it("opens the note in a window or as a sticky note", async () => {
+    const onOpenWindow = vi.fn();
+    const NoteItem = (await import("@/features/sidebar/NoteItem")).NoteItem;
+    const props = {
+      selected: false,
+      dirty: false,
+      expiryMinutes: 60,
+      onSelect: vi.fn(),
+      onTogglePin: vi.fn(),
+      onTrash: vi.fn(),
+      onOpenWindow,
+    };
+
+    const { container, unmount } = await render(
+      React.createElement(NoteItem, { ...props, note: base }),
+    );
+    const buttons = Array.from(container.querySelectorAll("button"));
+    buttons.find((button) => button.textContent === "Open in New Window")?.click();
+    buttons.find((button) => button.textContent === "Float as Sticky Note")?.click();
+    expect(onOpenWindow).toHaveBeenNthCalledWith(1, "n1", "normal");
+    expect(onOpenWindow).toHaveBeenNthCalledWith(2, "n1", "sticky");
+    await unmount();
+
+    const trashed = await render(
+      React.createElement(NoteItem, { ...props, note: { ...base, isTrashed: true } }),
+    );
+    expect(trashed.container.textContent).not.toContain("Float as Sticky Note");
+    await trashed.unmount();
+  });
 });
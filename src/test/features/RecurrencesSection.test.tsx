@@ -0,0 +1,74 @@
+import { afterEach, describe, expect, it, vi } from "vitest";
+import React, { act } from "react";
+import { render } from "@/test/utils/render";
+import type { Recurrence } from "@/lib/types";
+
+const selectHandlers: Array<(value: string) => void> = [];
+
+vi.mock("@/components/ui/select", () => ({
+  Select: ({ onValueChange, children }: any) => {
+    if (onValueChange) selectHandlers.push(onValueChange);
+    return <div>{children}</div>;
+  },
+  SelectContent: ({ children }: any) => <div>{children}</div>,
+  SelectItem: ({ value, children }: any) => <div data-value={value}>{children}</div>,
+  SelectTrigger: ({ children }: any) => <div>{children}</div>,
+  SelectValue: () => <span />,
+}));
+
+const retro: Recurrence = {
+  id: "r1",
+  name: "Retro",
+  template: "Retro",
+  rule: "FREQ=WEEKLY;BYDAY=MO;BYHOUR=9;BYMINUTE=0",
+  expiryMinutes: null,
+  createdAt: 0,
+  lastOccurrenceAt: null,
+  nextOccurrenceAt: null,
+  lastError: "Template not found: Retro",
+};
+
+describe("RecurrencesSection", () => {
+  afterEach(() => {
+    selectHandlers.length = 0;
+  });
+
+  it("lists recurrences and adds one from a template and preset", async () => {
+    const onAdd = vi.fn();
+    const onDelete = vi.fn();
+    const { RecurrencesSection } = await import("@/features/settings/RecurrencesSection");
+    const { container, unmount } = await render(
+      React.createElement(RecurrencesSection, {
+        recurrences: [retro],
+        templates: [
+          { name: "Retro", expiryMinutes: null, usesClipboard: false },
+          { name: "Standup", expiryMinutes: null, usesClipboard: false },
+        ],
+        onAdd,
+        onDelete,
+      }),
+    );
+
+    expect(container.textContent).toContain("Mondays at 09:00");
+    expect(container.textContent).toContain("Template not found: Retro");
+    container.querySelector<HTMLButtonElement>('button[aria-label="Delete Retro recurrence"]')?.click();
+    expect(onDelete).toHaveBeenCalledWith("r1");
+
+    await act(async () => {
+      selectHandlers[0]?.("Standup");
+      selectHandlers[1]?.("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=0");
+      selectHandlers[2]?.("1440");
+    });
+    const add = Array.from(container.querySelectorAll("button")).find((button) =>
+      button.textContent?.includes("Add Recurring Note"),
+    );
+    add?.click();
+    expect(onAdd).toHaveBeenCalledWith({
+      template: "Standup",
+      rule: "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=0",
+      expiryMinutes: 1440,
+    });
+
+    await unmount();
+  });
+});
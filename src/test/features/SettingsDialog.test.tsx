@@ -59,12 +59,34 @@ describe("SettingsDialog", () => {
     const onTheme = vi.fn();
     const onExpiryMinutes = vi.fn();
     const onTrashDays = vi.fn();
+    const onFrontMatterWriteBack = vi.fn();
+    const onOpenTasksExpiry = vi.fn();
+    const onDoneTasksGraceMinutes = vi.fn();
+    const onDailyExpiryMinutes = vi.fn();
+    const onDailyCarryTasks = vi.fn();
+    const onChooseCalendarFeed = vi.fn();
+    const onQuickCaptureShortcut = vi.fn();
+    const onQuickCaptureInbox = vi.fn();
+    const onAutostart = vi.fn();
+    const onKeybinding = vi.fn();
+    const onLocale = vi.fn();
     const onCheckUpdates = vi.fn();
 
     const settings: AppSettings = {
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
       theme: "dark",
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
     };
 
     const { container, unmount } = await render(
@@ -74,12 +96,34 @@ describe("SettingsDialog", () => {
         onTheme,
         onExpiryMinutes,
         onTrashDays,
+        onFrontMatterWriteBack,
+        onOpenTasksExpiry,
+        onOpenTasksMaxDays: vi.fn(),
+        onDoneTasksGraceMinutes,
+        onDailyExpiryMinutes,
+        onDailyCarryTasks,
+        onChooseCalendarFeed,
+        onCalendarFeedOff: vi.fn(),
+        inboxNotes: [],
+        onQuickCaptureShortcut,
+        onQuickCaptureInbox,
+        onAutostart,
+        keybindings: [
+          {
+            id: "file_save_as_note",
+            label: "Save As…",
+            accelerator: "CmdOrCtrl+Alt+KeyS",
+            defaultAccelerator: "CmdOrCtrl+Shift+KeyS",
+          },
+        ],
+        onKeybinding,
+        onLocale,
         isCheckingUpdates: false,
         onCheckUpdates,
       }),
     );
 
-    expect(selectHandlers.length).toBe(2);
+    expect(selectHandlers.length).toBe(6);
     selectHandlers[0]?.("360");
     expect(onExpiryMinutes).toHaveBeenCalledWith(360);
 
@@ -89,12 +133,63 @@ describe("SettingsDialog", () => {
     tabsHandlers[0]?.("light");
     expect(onTheme).toHaveBeenCalledWith("light");
 
+    selectHandlers[2]?.("1440");
+    expect(onDoneTasksGraceMinutes).toHaveBeenCalledWith(1440);
+
+    tabsHandlers[1]?.("exempt");
+    expect(onOpenTasksExpiry).toHaveBeenCalledWith("exempt");
+
+    selectHandlers[3]?.("1440");
+    expect(onDailyExpiryMinutes).toHaveBeenCalledWith(1440);
+
+    tabsHandlers[2]?.("empty");
+    expect(onDailyCarryTasks).toHaveBeenCalledWith(false);
+
+    tabsHandlers[3]?.("write");
+    expect(onFrontMatterWriteBack).toHaveBeenCalledWith(true);
+
+    selectHandlers[4]?.("new");
+    expect(onQuickCaptureInbox).toHaveBeenCalledWith("");
+    const shortcutInput = container.querySelector<HTMLInputElement>(
+      'input[aria-label="Quick capture shortcut"]',
+    );
+    expect(shortcutInput?.value).toBe("CmdOrCtrl+Alt+KeyN");
+    shortcutInput?.focus();
+    if (shortcutInput) shortcutInput.value = "";
+    shortcutInput?.blur();
+    expect(onQuickCaptureShortcut).toHaveBeenCalledWith("");
+
+    tabsHandlers[4]?.("tray");
+    expect(onAutostart).toHaveBeenCalledWith({ enabled: true, hidden: true });
+    tabsHandlers[4]?.("off");
+    expect(onAutostart).toHaveBeenCalledWith({ enabled: false, hidden: false });
+
+    const saveAsInput = container.querySelector<HTMLInputElement>(
+      'input[aria-label="Save As… shortcut"]',
+    );
+    expect(saveAsInput?.value).toBe("CmdOrCtrl+Alt+KeyS");
+    saveAsInput?.focus();
+    if (saveAsInput) saveAsInput.value = "CmdOrCtrl+KeyE";
+    saveAsInput?.blur();
+    expect(onKeybinding).toHaveBeenCalledWith("file_save_as_note", "CmdOrCtrl+KeyE");
+    container.querySelector<HTMLButtonElement>('button[aria-label="Reset Save As… shortcut"]')?.click();
+    expect(onKeybinding).toHaveBeenCalledWith("file_save_as_note", null);
+
+    selectHandlers[5]?.("de");
+    expect(onLocale).toHaveBeenCalledWith("de");
+
     const checkUpdatesButton = Array.from(container.querySelectorAll("button")).find((button) =>
       button.textContent?.includes("Check for updates"),
     );
     checkUpdatesButton?.click();
     expect(onCheckUpdates).toHaveBeenCalled();
 
+    const chooseFeedButton = Array.from(container.querySelectorAll("button")).find((button) =>
+      button.textContent?.includes("Choose File"),
+    );
+    chooseFeedButton?.click();
+    expect(onChooseCalendarFeed).toHaveBeenCalled();
+
     const closeButton = Array.from(container.querySelectorAll("button")).find((button) =>
       button.textContent?.includes("close-dialog"),
     );
@@ -103,4 +198,55 @@ describe("SettingsDialog", () => {
 
     await unmount();
   });
+
+  it("offers a cap only when notes with open tasks are kept longer", async () => {
+    const onOpenTasksMaxDays = vi.fn();
+    const { unmount } = await render(
+      React.createElement((await import("@/features/settings/SettingsDialog")).SettingsDialog, {
+        settings: {
+          expiryMinutes: 10_080,
+          trashRetentionDays: 30,
+          theme: "dark",
+          frontMatterWriteBack: false,
+          openTasksExpiry: "extend",
+          openTasksMaxDays: 30,
+          doneTasksGraceMinutes: 0,
+          dailyExpiryMinutes: 4320,
+          dailyCarryTasks: true,
+          calendarFeedPath: "",
+          quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+          quickCaptureInboxId: "",
+          autostart: { enabled: false, hidden: false },
+          locale: "system",
+        },
+        onClose: vi.fn(),
+        onTheme: vi.fn(),
+        onExpiryMinutes: vi.fn(),
+        onTrashDays: vi.fn(),
+        onFrontMatterWriteBack: vi.fn(),
+        onOpenTasksExpiry: vi.fn(),
+        onOpenTasksMaxDays,
+        onDoneTasksGraceMinutes: vi.fn(),
+        onDailyExpiryMinutes: vi.fn(),
+        onDailyCarryTasks: vi.fn(),
+        onChooseCalendarFeed: vi.fn(),
+        onCalendarFeedOff: vi.fn(),
+        inboxNotes: [],
+        onQuickCaptureShortcut: vi.fn(),
+        onQuickCaptureInbox: vi.fn(),
+        onAutostart: vi.fn(),
+        keybindings: [],
+        onKeybinding: vi.fn(),
+        onLocale: vi.fn(),
+        isCheckingUpdates: false,
+        onCheckUpdates: vi.fn(),
+      }),
+    );
+
+    expect(selectHandlers.length).toBe(7);
+    selectHandlers[2]?.("90");
+    expect(onOpenTasksMaxDays).toHaveBeenCalledWith(90);
+
+    await unmount();
+  });
 });
@@ -78,6 +78,15 @@ describe("Sidebar", () => {
     createdAt: 1,
     lastInteraction: 1,
     trashedAt: null,
+    encoding: "utf-8",
+    hasBom: false,
+    lineEnding: "lf",
+    expiresAt: null,
+    openTasks: 0,
+    doneTasks: 0,
+    dailyDate: null,
+    remindAt: null,
+    expiryDirective: null,
     ...overrides,
   });
 
@@ -20,6 +20,15 @@ describe("TrashItem", () => {
     createdAt: 1,
     lastInteraction: 1,
     trashedAt: 1_000_000,
+    encoding: "utf-8",
+    hasBom: false,
+    lineEnding: "lf",
+    expiresAt: null,
+    openTasks: 0,
+    doneTasks: 0,
+    dailyDate: null,
+    remindAt: null,
+    expiryDirective: null,
   };
 
   beforeEach(() => {
@@ -14,35 +14,105 @@ describe("api", () => {
 
     await api.notesList();
     await api.noteCreate();
+    await api.templatesList();
+    await api.noteCreateFromTemplate("Standup");
+    await api.noteCreateFromTemplate("Incident", "INC-7");
+    await api.noteDaily();
+    await api.noteDaily("2026-10-18");
+    await api.recurrencesList();
+    await api.recurrenceSave({ template: "Retro", rule: "FREQ=WEEKLY;BYDAY=MO;BYHOUR=9" });
+    await api.recurrenceDelete("r1");
     await api.noteGet("n1");
+    await api.noteRemoveFrontMatter("n1");
     await api.noteSetActive("n1");
     await api.noteWriteDraft("n1", "content");
     await api.noteSave("n1", "content");
+    await api.noteConvertToUtf8("n1", "content");
     await api.noteSaveAs("n1", "/tmp/file.md", "content");
     await api.noteImportFile("/tmp/file.md");
+    await api.noteImportFile("/tmp/page.html", "sibling");
+    await api.notesImportExternal("keep", "/tmp/Takeout", true);
+    await api.notesExportObsidian(["n1"], "/tmp/vault");
+    await api.noteOutgoingLinks("n1");
+    await api.noteBacklinks("n1");
+    await api.tasksList({ state: "all" });
+    await api.taskToggle("n1", 3, true);
+    await api.noteSetReminder("n1", 1_000);
+    await api.noteSetReminder("n1", null);
+    await api.noteSnoozeReminder("n1", 10);
     await api.noteTrash("n1");
     await api.noteRestore("n1");
     await api.noteDeleteForever("n1");
     await api.notePin("n1", true);
     await api.notesReorder(["a", "b"]);
+    await api.notesTrashMany(["a", "b"]);
+    await api.notesRestoreMany(["a"]);
+    await api.notesDeleteForeverMany(["a"]);
+    await api.notesPinMany(["a"], true);
+    await api.notesExportMany(["a"], "/tmp/out");
+    await api.trashEmpty();
+    await api.historyUndo();
+    await api.historyRedo();
     await api.settingsGetAll();
     await api.settingsSet("theme", "dark");
     await api.appStateGetAll();
     await api.appStateSet("viewMode", "notes");
     await api.expiryRunNow();
+    await api.calendarExport("/tmp/notes.ics");
+    await api.noteOpenWindow("n1", "sticky");
+    await api.quickCaptureSubmit("Call Anna");
+    await api.quickCaptureHide();
 
     expect(invoke).toHaveBeenCalledWith("notes_list");
     expect(invoke).toHaveBeenCalledWith("note_create");
+    expect(invoke).toHaveBeenCalledWith("templates_list");
+    expect(invoke).toHaveBeenCalledWith("note_create_from_template", {
+      name: "Standup",
+      clipboard: undefined,
+    });
+    expect(invoke).toHaveBeenCalledWith("note_create_from_template", {
+      name: "Incident",
+      clipboard: "INC-7",
+    });
+    expect(invoke).toHaveBeenCalledWith("note_daily", { date: undefined });
+    expect(invoke).toHaveBeenCalledWith("note_daily", { date: "2026-10-18" });
+    expect(invoke).toHaveBeenCalledWith("recurrences_list");
+    expect(invoke).toHaveBeenCalledWith("recurrence_save", {
+      recurrence: { template: "Retro", rule: "FREQ=WEEKLY;BYDAY=MO;BYHOUR=9" },
+    });
+    expect(invoke).toHaveBeenCalledWith("recurrence_delete", { id: "r1" });
     expect(invoke).toHaveBeenCalledWith("note_get", { id: "n1" });
+    expect(invoke).toHaveBeenCalledWith("note_remove_front_matter", { id: "n1" });
     expect(invoke).toHaveBeenCalledWith("note_set_active", { id: "n1" });
     expect(invoke).toHaveBeenCalledWith("note_write_draft", { id: "n1", content: "content" });
     expect(invoke).toHaveBeenCalledWith("note_save", { id: "n1", content: "content" });
+    expect(invoke).toHaveBeenCalledWith("note_convert_to_utf8", { id: "n1", content: "content" });
     expect(invoke).toHaveBeenCalledWith("note_save_as", {
       id: "n1",
       path: "/tmp/file.md",
       content: "content",
     });
     expect(invoke).toHaveBeenCalledWith("note_import_file", { path: "/tmp/file.md" });
+    expect(invoke).toHaveBeenCalledWith("note_import_file", {
+      path: "/tmp/page.html",
+      convertedTarget: "sibling",
+    });
+    expect(invoke).toHaveBeenCalledWith("notes_import_external", {
+      source: "keep",
+      path: "/tmp/Takeout",
+      dryRun: true,
+    });
+    expect(invoke).toHaveBeenCalledWith("notes_export_obsidian", {
+      ids: ["n1"],
+      dir: "/tmp/vault",
+    });
+    expect(invoke).toHaveBeenCalledWith("note_outgoing_links", { id: "n1" });
+    expect(invoke).toHaveBeenCalledWith("note_backlinks", { id: "n1" });
+    expect(invoke).toHaveBeenCalledWith("tasks_list", { filter: { state: "all" } });
+    expect(invoke).toHaveBeenCalledWith("task_toggle", { noteId: "n1", line: 3, checked: true });
+    expect(invoke).toHaveBeenCalledWith("note_set_reminder", { id: "n1", at: 1_000 });
+    expect(invoke).toHaveBeenCalledWith("note_set_reminder", { id: "n1", at: null });
+    expect(invoke).toHaveBeenCalledWith("note_snooze_reminder", { id: "n1", minutes: 10 });
     expect(invoke).toHaveBeenCalledWith("note_trash", { id: "n1" });
     expect(invoke).toHaveBeenCalledWith("note_restore", { id: "n1" });
     expect(invoke).toHaveBeenCalledWith("note_delete_forever", { id: "n1" });
@@ -53,5 +123,33 @@ describe("api", () => {
     expect(invoke).toHaveBeenCalledWith("app_state_get_all");
     expect(invoke).toHaveBeenCalledWith("app_state_set", { key: "viewMode", value: "notes" });
     expect(invoke).toHaveBeenCalledWith("expiry_run_now");
+    expect(invoke).toHaveBeenCalledWith("notes_trash_many", { ids: ["a", "b"] });
+    expect(invoke).toHaveBeenCalledWith("notes_restore_many", { ids: ["a"] });
+    expect(invoke).toHaveBeenCalledWith("notes_delete_forever_many", { ids: ["a"] });
+    expect(invoke).toHaveBeenCalledWith("notes_pin_many", { ids: ["a"], pinned: true });
+    expect(invoke).toHaveBeenCalledWith("notes_export_many", { ids: ["a"], dir: "/tmp/out" });
+    expect(invoke).toHaveBeenCalledWith("trash_empty");
+    expect(invoke).toHaveBeenCalledWith("history_undo");
+    expect(invoke).toHaveBeenCalledWith("history_redo");
+    expect(invoke).toHaveBeenCalledWith("note_open_window", { id: "n1", mode: "sticky" });
+    expect(invoke).toHaveBeenCalledWith("quick_capture_submit", { text: "Call Anna" });
+    expect(invoke).toHaveBeenCalledWith("quick_capture_hide");
+  });
+
+  it("rejects with the command's key, arguments and message", async () => {
+    const { api, CommandError } = await import("@/lib/api");
+    invoke.mockRejectedValueOnce({
+      key: "error.pin_limit",
+      args: { max: "5" },
+      message: "You can only pin up to 5 notes.",
+    });
+
+    const err = await api.notePin("n1", true).catch((reason: unknown) => reason);
+    expect(err).toBeInstanceOf(CommandError);
+    expect(err).toMatchObject({ key: "error.pin_limit", args: { max: "5" } });
+    expect(String(err)).toBe("You can only pin up to 5 notes.");
+
+    invoke.mockRejectedValueOnce("plain failure");
+    await expect(api.notesList()).rejects.toBe("plain failure");
   });
 });
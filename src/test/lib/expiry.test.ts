@@ -1,5 +1,12 @@
 import { describe, expect, it } from "vitest";
-import { expiryProgress, expiryStatus } from "@/lib/utils/expiry";
+import {
+  DEFAULT_EXPIRY_RULE,
+  expiryProgress,
+  expiryStatus,
+  noteExpiresAt,
+  noteExpiryTime,
+  noteExpiryWindow,
+} from "@/lib/utils/expiry";
 
 describe("expiryProgress", () => {
   it("computes progress with minute granularity", () => {
@@ -14,6 +21,13 @@ describe("expiryProgress", () => {
     expect(expiryProgress(now - 120 * 60_000, 60, now)).toBe(0);
     expect(expiryProgress(now, Number.NaN, now)).toBe(0);
   });
+
+  it("uses a fixed expiry when set", () => {
+    const now = 1_000_000;
+    expect(expiryProgress(now, 60, now, now + 15 * 60_000)).toBeCloseTo(0.25, 5);
+    expect(expiryProgress(now, 60, now, now + 120 * 60_000)).toBe(1);
+    expect(noteExpiryTime(now, 60, 42)).toBe(42);
+  });
 });
 
 describe("expiryStatus", () => {
@@ -27,3 +41,77 @@ describe("expiryStatus", () => {
     expect(expiryStatus(0.09)).toBe("danger");
   });
 });
+
+describe("noteExpiryWindow", () => {
+  const note = {
+    openTasks: 0,
+    doneTasks: 0,
+    expiresAt: null,
+    dailyDate: null,
+    remindAt: null,
+    expiryDirective: null,
+  };
+
+  it("keeps the base window without tasks or rules", () => {
+    expect(noteExpiryWindow(1440, note, DEFAULT_EXPIRY_RULE)).toEqual({
+      minutes: 1440,
+      reason: null,
+    });
+    const withTasks = { ...note, openTasks: 2 };
+    expect(noteExpiryWindow(1440, withTasks, DEFAULT_EXPIRY_RULE).minutes).toBe(1440);
+  });
+
+  it("extends or suspends expiry for open tasks", () => {
+    const extend = {
+      ...DEFAULT_EXPIRY_RULE,
+      openTasksExpiry: "extend" as const,
+      openTasksMaxDays: 3,
+    };
+    expect(noteExpiryWindow(1440, { ...note, openTasks: 1 }, extend)).toEqual({
+      minutes: 4320,
+      reason: "kept: 1 open task",
+    });
+    const exempt = { ...DEFAULT_EXPIRY_RULE, openTasksExpiry: "exempt" as const };
+    expect(noteExpiryWindow(1440, { ...note, openTasks: 2 }, exempt)).toEqual({
+      minutes: null,
+      reason: "kept: 2 open tasks",
+    });
+    const fixed = { ...note, openTasks: 2, expiresAt: 5 };
+    expect(noteExpiryWindow(1440, fixed, exempt).minutes).toBe(1440);
+  });
+
+  it("shortens expiry once all tasks are done", () => {
+    const grace = { ...DEFAULT_EXPIRY_RULE, doneTasksGraceMinutes: 60 };
+    expect(noteExpiryWindow(1440, { ...note, doneTasks: 3 }, grace)).toEqual({
+      minutes: 60,
+      reason: "all tasks done",
+    });
+    const unfinished = { ...note, openTasks: 1, doneTasks: 3 };
+    expect(noteExpiryWindow(1440, unfinished, grace).minutes).toBe(1440);
+  });
+
+  it("uses the daily window for daily notes", () => {
+    const daily = { ...note, dailyDate: "2026-10-18" };
+    expect(noteExpiryWindow(10_080, daily, DEFAULT_EXPIRY_RULE).minutes).toBe(4320);
+  });
+
+  it("prefers an expiry directive and names it", () => {
+    const expiryDirective = { phrase: "expires: friday", expiresAt: 9 };
+    const directed = { ...note, expiresAt: 5, expiryDirective };
+    expect(noteExpiresAt(directed)).toBe(9);
+    expect(noteExpiresAt({ ...note, expiresAt: 5 })).toBe(5);
+    expect(noteExpiryWindow(1440, directed, DEFAULT_EXPIRY_RULE)).toEqual({
+      minutes: 1440,
+      reason: 'from "expires: friday"',
+    });
+  });
+
+  it("keeps notes with a reminder, even over a fixed expiry", () => {
+    const reminded = { ...note, doneTasks: 1, expiresAt: 5, remindAt: 10 };
+    const grace = { ...DEFAULT_EXPIRY_RULE, doneTasksGraceMinutes: 60 };
+    expect(noteExpiryWindow(1440, reminded, grace)).toEqual({
+      minutes: null,
+      reason: "kept: reminder set",
+    });
+  });
+});
@@ -0,0 +1,34 @@
+import { describe, expect, it } from "vitest";
+import { acceleratorFor, formatAccelerator, matchesAccelerator } from "@/lib/utils/keybindings";
+
+function key(init: Partial<KeyboardEvent>) {
+  const base = { metaKey: false, ctrlKey: false, altKey: false, shiftKey: false, code: "" };
+  return { ...base, ...init } as KeyboardEvent;
+}
+
+describe("keybindings", () => {
+  it("formats accelerators like the palette lists shortcuts", () => {
+    expect(formatAccelerator("CmdOrCtrl+Shift+KeyS")).toBe("⇧⌘S");
+    expect(formatAccelerator("CmdOrCtrl+Comma")).toBe("⌘,");
+    expect(formatAccelerator("Ctrl+Alt+Digit1")).toBe("⌃⌥1");
+    expect(formatAccelerator("Delete")).toBe("Delete");
+    expect(formatAccelerator("")).toBe("");
+  });
+
+  it("matches key events by code or key", () => {
+    expect(matchesAccelerator(key({ key: "s", metaKey: true }), "CmdOrCtrl+KeyS")).toBe(true);
+    expect(matchesAccelerator(key({ key: "s", ctrlKey: true }), "CmdOrCtrl+KeyS")).toBe(true);
+    const shifted = key({ key: "S", ctrlKey: true, shiftKey: true });
+    expect(matchesAccelerator(shifted, "CmdOrCtrl+KeyS")).toBe(false);
+    const option = key({ key: "ß", code: "KeyS", metaKey: true, altKey: true });
+    expect(matchesAccelerator(option, "CmdOrCtrl+Alt+KeyS")).toBe(true);
+    expect(matchesAccelerator(key({ key: ",", metaKey: true }), "CmdOrCtrl+Comma")).toBe(true);
+    expect(matchesAccelerator(key({ key: "s", metaKey: true }), "Ctrl+KeyS")).toBe(false);
+    expect(matchesAccelerator(key({ key: "s", metaKey: true }), "")).toBe(false);
+  });
+
+  it("falls back to the default for actions missing from the list", () => {
+    expect(acceleratorFor([], "file_save_note")).toBe("CmdOrCtrl+KeyS");
+    expect(acceleratorFor([], "unknown")).toBe("");
+  });
+});
@@ -86,6 +86,17 @@ describe("tauri web shim", () => {
     expect(updated.preview.length).toBe(140);
   });
 
+  it("ignores front matter when deriving the title", async () => {
+    const created = (await invoke("note_create")) as { id: string };
+    const updated = (await invoke("note_write_draft", {
+      id: created.id,
+      content: "---\ntags: [a]\n---\n# Title\nBody",
+    })) as { title: string; preview: string };
+
+    expect(updated.title).toBe("Title");
+    expect(updated.preview).toBe("Body");
+  });
+
   it("falls back to default title when empty", async () => {
     const created = (await invoke("note_create")) as { id: string };
     const updated = (await invoke("note_write_draft", {
@@ -190,6 +201,31 @@ describe("tauri web shim", () => {
     expect(same.isPinned).toBe(false);
   });
 
+  it("pins in batches all or nothing", async () => {
+    const ids: string[] = [];
+    for (let i = 0; i < 6; i += 1) {
+      ids.push(((await invoke("note_create")) as { id: string }).id);
+    }
+
+    const failed = (await invoke("notes_pin_many", { ids, pinned: true })) as {
+      committed: boolean;
+      results: { status: string }[];
+    };
+    expect(failed.committed).toBe(false);
+    expect(failed.results.map((r) => r.status)).toEqual([
+      ...Array(5).fill("rolled-back"),
+      "failed",
+    ]);
+    let list = (await invoke("notes_list")) as { active: { isPinned: boolean }[] };
+    expect(list.active.some((n) => n.isPinned)).toBe(false);
+
+    await invoke("notes_trash_many", { ids: ids.slice(0, 2) });
+    const emptied = (await invoke("trash_empty")) as { committed: boolean };
+    expect(emptied.committed).toBe(true);
+    list = (await invoke("notes_list")) as { active: { isPinned: boolean }[] };
+    expect(list.active).toHaveLength(4);
+  });
+
   it("reorders notes and skips trashed entries", async () => {
     const a = (await invoke("note_create")) as { id: string };
     const b = (await invoke("note_create")) as { id: string };
@@ -247,6 +283,30 @@ describe("tauri web shim", () => {
     expect(list.trashed.map((n) => n.id)).not.toContain(pinned.id);
   });
 
+  it("keeps notes with open tasks when they are exempt", async () => {
+    const created = (await invoke("note_create")) as { id: string };
+    const meta = (await invoke("note_write_draft", {
+      id: created.id,
+      content: "# Todo\n- [ ] call back\n- [x] book room\n",
+    })) as { openTasks: number; doneTasks: number };
+    expect(meta.openTasks).toBe(1);
+    expect(meta.doneTasks).toBe(1);
+
+    await invoke("settings_set", { key: "open_tasks_expiry", value: "exempt" });
+    const storageKey = "augenblick:webdb:v1";
+    const db = JSON.parse(localStorage.getItem(storageKey) ?? "{}") as {
+      notes: Record<string, { meta: { lastInteraction: number } }>;
+      settings: { expiryMinutes: number };
+    };
+    db.settings.expiryMinutes = 1;
+    db.notes[created.id].meta.lastInteraction = Date.now() - 120_000;
+    localStorage.setItem(storageKey, JSON.stringify(db));
+
+    await invoke("expiry_run_now");
+    const list = (await invoke("notes_list")) as { active: { id: string }[] };
+    expect(list.active.map((n) => n.id)).toContain(created.id);
+  });
+
   it("rejects unsupported import", async () => {
     await expect(invoke("note_import_file", { path: "/tmp/file.md" })).rejects.toThrow(
       "Open/Import is not supported",
@@ -1,5 +1,5 @@
 import { describe, expect, it } from "vitest";
-import { formatRelativeTime, formatRelativeTimeFromNow } from "@/lib/utils/time";
+import { formatRelativeTime, formatRelativeTimeFromNow, reminderPresets } from "@/lib/utils/time";
 
 describe("formatRelativeTime", () => {
   it("formats seconds, minutes, hours, days", () => {
@@ -57,3 +57,24 @@ describe("formatRelativeTimeFromNow", () => {
     expect(formatRelativeTimeFromNow(now + 90_000, now)).toBe("in 1m 30s");
   });
 });
+
+describe("reminderPresets", () => {
+  it("offers the evening only before 17:00 and skips to the next Monday", () => {
+    // Monday 2026-10-19 10:00 local
+    const monday = new Date(2026, 9, 19, 10).getTime();
+    expect(reminderPresets(monday)).toEqual([
+      { label: "In 1 Hour", at: monday + 3_600_000 },
+      { label: "This Evening", at: new Date(2026, 9, 19, 18).getTime() },
+      { label: "Tomorrow Morning", at: new Date(2026, 9, 20, 9).getTime() },
+      { label: "Next Monday", at: new Date(2026, 9, 26, 9).getTime() },
+    ]);
+
+    const saturdayNight = new Date(2026, 9, 24, 21).getTime();
+    expect(reminderPresets(saturdayNight).map((p) => p.label)).toEqual([
+      "In 1 Hour",
+      "Tomorrow Morning",
+      "Next Monday",
+    ]);
+    expect(reminderPresets(saturdayNight)[2].at).toBe(new Date(2026, 9, 26, 9).getTime());
+  });
+});
@@ -609,4 +609,40 @@ describe("pageActions", () => {
     expect(setSidebarWidth).toHaveBeenCalledWith(200);
     expect(setSidebarWidth).toHaveBeenCalledWith(400);
   });
+
+  it("exportCalendar writes an .ics file and chooseCalendarFeed stores the path", async () => {
+    const notesStore = {
+      importFile: vi.fn(async () => {}),
+      save: vi.fn(async () => {}),
+      saveAs: vi.fn(async () => {}),
+      trash: vi.fn(async () => {}),
+      updateContent: vi.fn(),
+      deleteForever: vi.fn(async () => {}),
+      clearTrash: vi.fn(async () => {}),
+    };
+    const calendar = { exportTo: vi.fn(async () => {}), setFeedPath: vi.fn(async () => {}) };
+    const toast = { success: vi.fn() };
+
+    const actions = createPageActions({
+      notesStore,
+      dialog: { openDialog: vi.fn(), confirmDialog: vi.fn() },
+      toast,
+      openFile: vi.fn(async () => null),
+      saveFile: vi.fn(async () => "/tmp/notes"),
+      getSelectedId: () => null,
+      getSelectedMeta: () => null,
+      isDirtySaved: () => false,
+      getSidebarWidth: () => 260,
+      setSidebarWidth: vi.fn(),
+      getTrashedCount: () => 0,
+      calendar,
+    });
+
+    await actions.exportCalendar();
+    expect(calendar.exportTo).toHaveBeenCalledWith("/tmp/notes.ics");
+    expect(toast.success).toHaveBeenCalledWith("Calendar exported");
+
+    await actions.chooseCalendarFeed();
+    expect(calendar.setFeedPath).toHaveBeenCalledWith("/tmp/notes.ics");
+  });
 });
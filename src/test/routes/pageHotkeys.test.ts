@@ -18,8 +18,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -43,8 +43,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -68,8 +68,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -93,8 +93,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -110,10 +110,10 @@ describe("pageHotkeys", () => {
     } as unknown as KeyboardEvent;
 
     handler(e);
-    expect(deps.undoReorder).not.toHaveBeenCalled();
+    expect(deps.undo).not.toHaveBeenCalled();
   });
 
-  it("undoes/redo reorder on Cmd+Z / Cmd+Shift+Z when not typing", () => {
+  it("undoes/redoes note changes on Cmd+Z / Cmd+Shift+Z when not typing", () => {
     const deps = {
       getNotesSnapshot: () => ({ list: { active: [], trashed: [] }, selectedId: null, viewMode: "notes" as const }),
       getSelectedId: () => null,
@@ -128,8 +128,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -144,7 +144,7 @@ describe("pageHotkeys", () => {
       altKey: false,
     } as unknown as KeyboardEvent;
     handler(e1);
-    expect(deps.undoReorder).toHaveBeenCalled();
+    expect(deps.undo).toHaveBeenCalled();
 
     const e2 = {
       key: "z",
@@ -156,7 +156,7 @@ describe("pageHotkeys", () => {
       altKey: false,
     } as unknown as KeyboardEvent;
     handler(e2);
-    expect(deps.redoReorder).toHaveBeenCalled();
+    expect(deps.redo).toHaveBeenCalled();
   });
 
   it("moves selection with Cmd+Shift+ArrowDown", () => {
@@ -181,8 +181,8 @@ describe("pageHotkeys", () => {
       selectNote: vi.fn((id: string) => {
         selectedId = id;
       }),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -215,8 +215,8 @@ describe("pageHotkeys", () => {
       saveAs: vi.fn(),
       quit: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
     const handler = createPageKeydownHandler(deps);
 
@@ -276,8 +276,8 @@ describe("pageHotkeys", () => {
       saveAs: vi.fn(),
       quit: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
     const handler = createPageKeydownHandler(deps);
 
@@ -311,8 +311,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -330,6 +330,90 @@ describe("pageHotkeys", () => {
     expect(deps.closeCurrent).toHaveBeenCalled();
   });
 
+  it("also trashes on Backspace while the default shortcut is kept", () => {
+    const deps = {
+      getNotesSnapshot: () => ({ list: { active: [], trashed: [] }, selectedId: null, viewMode: "notes" as const }),
+      getSelectedId: () => null,
+      toggleCommandPalette: vi.fn(),
+      closeCommandPalette: vi.fn(),
+      openSettings: vi.fn(),
+      setViewMode: vi.fn(),
+      createNote: vi.fn(),
+      togglePinCurrent: vi.fn(),
+      closeCurrent: vi.fn(),
+      openMarkdown: vi.fn(),
+      saveCurrent: vi.fn(),
+      saveAs: vi.fn(),
+      selectNote: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
+    };
+
+    const handler = createPageKeydownHandler(deps);
+    const press = (target: EventTarget) =>
+      handler({
+        key: "Backspace",
+        code: "Backspace",
+        metaKey: false,
+        ctrlKey: false,
+        shiftKey: false,
+        altKey: false,
+        target,
+        preventDefault: vi.fn(),
+      } as unknown as KeyboardEvent);
+
+    press(document.createElement("textarea"));
+    expect(deps.closeCurrent).not.toHaveBeenCalled();
+    press(document.body);
+    expect(deps.closeCurrent).toHaveBeenCalledTimes(1);
+  });
+
+  it("trashes with the rebound shortcut only", () => {
+    const deps = {
+      getNotesSnapshot: () => ({ list: { active: [], trashed: [] }, selectedId: null, viewMode: "notes" as const }),
+      getSelectedId: () => null,
+      toggleCommandPalette: vi.fn(),
+      closeCommandPalette: vi.fn(),
+      openSettings: vi.fn(),
+      setViewMode: vi.fn(),
+      createNote: vi.fn(),
+      togglePinCurrent: vi.fn(),
+      closeCurrent: vi.fn(),
+      openMarkdown: vi.fn(),
+      saveCurrent: vi.fn(),
+      saveAs: vi.fn(),
+      selectNote: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
+      getKeybindings: () => [
+        {
+          id: "file_trash_note",
+          label: "Move to Trash",
+          accelerator: "CmdOrCtrl+Backspace",
+          defaultAccelerator: "Delete",
+        },
+      ],
+    };
+
+    const handler = createPageKeydownHandler(deps);
+    const press = (key: string, metaKey: boolean) =>
+      handler({
+        key,
+        metaKey,
+        ctrlKey: false,
+        shiftKey: false,
+        altKey: false,
+        target: document.body,
+        preventDefault: vi.fn(),
+      } as unknown as KeyboardEvent);
+
+    press("Delete", false);
+    press("Backspace", false);
+    expect(deps.closeCurrent).not.toHaveBeenCalled();
+    press("Backspace", true);
+    expect(deps.closeCurrent).toHaveBeenCalledTimes(1);
+  });
+
   it("creates note on Cmd+N", () => {
     const deps = {
       getNotesSnapshot: () => ({ list: { active: [], trashed: [] }, selectedId: null, viewMode: "notes" as const }),
@@ -345,8 +429,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -380,8 +464,8 @@ describe("pageHotkeys", () => {
       saveCurrent: vi.fn(),
       saveAs: vi.fn(),
       selectNote: vi.fn(),
-      undoReorder: vi.fn(),
-      redoReorder: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
     };
 
     const handler = createPageKeydownHandler(deps);
@@ -399,4 +483,56 @@ describe("pageHotkeys", () => {
     expect(e.preventDefault).toHaveBeenCalled();
     expect(deps.togglePinCurrent).toHaveBeenCalled();
   });
+
+  it("follows rebound menu shortcuts", () => {
+    const deps = {
+      getNotesSnapshot: () => ({ list: { active: [], trashed: [] }, selectedId: null, viewMode: "notes" as const }),
+      getSelectedId: () => null,
+      toggleCommandPalette: vi.fn(),
+      closeCommandPalette: vi.fn(),
+      openSettings: vi.fn(),
+      setViewMode: vi.fn(),
+      createNote: vi.fn(),
+      togglePinCurrent: vi.fn(),
+      closeCurrent: vi.fn(),
+      openMarkdown: vi.fn(),
+      saveCurrent: vi.fn(),
+      saveAs: vi.fn(),
+      selectNote: vi.fn(),
+      undo: vi.fn(),
+      redo: vi.fn(),
+      getKeybindings: () => [
+        { id: "file_new_note", label: "New note", accelerator: "", defaultAccelerator: "CmdOrCtrl+KeyN" },
+        {
+          id: "file_save_as_note",
+          label: "Save As…",
+          accelerator: "CmdOrCtrl+Alt+KeyS",
+          defaultAccelerator: "CmdOrCtrl+Shift+KeyS",
+        },
+      ],
+    };
+    const handler = createPageKeydownHandler(deps);
+    const press = (key: string, mods: { shiftKey?: boolean; altKey?: boolean } = {}) =>
+      handler({
+        key,
+        metaKey: false,
+        ctrlKey: true,
+        shiftKey: false,
+        altKey: false,
+        ...mods,
+        target: document.body,
+        preventDefault: vi.fn(),
+      } as unknown as KeyboardEvent);
+
+    press("n");
+    expect(deps.createNote).not.toHaveBeenCalled();
+
+    press("S", { shiftKey: true });
+    expect(deps.saveAs).not.toHaveBeenCalled();
+    press("s", { altKey: true });
+    expect(deps.saveAs).toHaveBeenCalled();
+
+    press("s");
+    expect(deps.saveCurrent).toHaveBeenCalled();
+  });
 });
@@ -5,6 +5,8 @@ import { getDirtySavedMap } from "@/stores/notes/dirty";
 const apiMock = {
   notesList: vi.fn(),
   noteCreate: vi.fn(),
+  noteCreateFromTemplate: vi.fn(),
+  noteDaily: vi.fn(),
   noteGet: vi.fn(),
   noteSetActive: vi.fn(),
   noteWriteDraft: vi.fn(),
@@ -15,7 +17,11 @@ const apiMock = {
   noteRestore: vi.fn(),
   noteDeleteForever: vi.fn(),
   notePin: vi.fn(),
+  noteSetReminder: vi.fn(),
   notesReorder: vi.fn(),
+  historyUndo: vi.fn(),
+  trashEmpty: vi.fn(),
+  historyRedo: vi.fn(),
   settingsGetAll: vi.fn(),
   settingsSet: vi.fn(),
   appStateGetAll: vi.fn(),
@@ -38,6 +44,15 @@ function meta(overrides: Partial<NoteMeta>): NoteMeta {
     createdAt: 1,
     lastInteraction: 1,
     trashedAt: null,
+    encoding: "utf-8",
+    hasBom: false,
+    lineEnding: "lf",
+    expiresAt: null,
+    openTasks: 0,
+    doneTasks: 0,
+    dailyDate: null,
+    remindAt: null,
+    expiryDirective: null,
     ...overrides,
   };
 }
@@ -206,6 +221,48 @@ describe("notesStore", () => {
     expect(useNotesStore.getState().list.active.find((n) => n.id === "d1")?.title).toBe("Hello");
   });
 
+  it("creates a note from a template with clipboard text", async () => {
+    const created = meta({ id: "t1", title: "Incident" });
+    apiMock.notesList.mockResolvedValue({ active: [], trashed: [] });
+    apiMock.appStateGetAll.mockResolvedValue({});
+    apiMock.noteCreateFromTemplate.mockResolvedValue({
+      note: { meta: created, content: "# Incident\nTicket: INC-7\n" },
+      cursor: 10,
+    });
+    const readText = vi.fn(async () => "INC-7");
+    Object.defineProperty(navigator, "clipboard", { value: { readText }, configurable: true });
+
+    const { useNotesStore } = await import("@/stores/notesStore");
+    await useNotesStore.getState().init();
+    await useNotesStore
+      .getState()
+      .createNoteFromTemplate({ name: "Incident", expiryMinutes: null, usesClipboard: true });
+
+    expect(apiMock.noteCreateFromTemplate).toHaveBeenCalledWith("Incident", "INC-7");
+    const state = useNotesStore.getState();
+    expect(state.selectedId).toBe("t1");
+    expect(state.contentById.t1).toBe("# Incident\nTicket: INC-7\n");
+    expect(state.lastSavedContentById.t1).toBe("# Incident\nTicket: INC-7\n");
+    expect(apiMock.noteSetActive).toHaveBeenCalledWith("t1");
+  });
+
+  it("opens the daily note", async () => {
+    const daily = meta({ id: "day", title: "2026-10-18 Sunday", dailyDate: "2026-10-18" });
+    apiMock.notesList.mockResolvedValue({ active: [], trashed: [] });
+    apiMock.appStateGetAll.mockResolvedValue({});
+    apiMock.noteDaily.mockResolvedValue({ meta: daily, content: "# 2026-10-18 Sunday\n" });
+
+    const { useNotesStore } = await import("@/stores/notesStore");
+    await useNotesStore.getState().init();
+    await useNotesStore.getState().openDailyNote();
+
+    expect(apiMock.noteDaily).toHaveBeenCalledWith(undefined);
+    const state = useNotesStore.getState();
+    expect(state.selectedId).toBe("day");
+    expect(state.list.active.map((n) => n.id)).toEqual(["day"]);
+    expect(state.contentById.day).toBe("# 2026-10-18 Sunday\n");
+  });
+
   it("auto-saves drafts independently per note", async () => {
     const d1 = meta({ id: "d1", storage: "draft" });
     const d2 = meta({ id: "d2", storage: "draft" });
@@ -453,8 +510,19 @@ describe("notesStore", () => {
     await useNotesStore.getState().init();
 
     apiMock.noteDeleteForever.mockResolvedValue(undefined);
+    apiMock.trashEmpty.mockResolvedValueOnce({
+      committed: false,
+      results: [{ id: "t1", status: "failed", error: "locked", meta: null, path: null }],
+    });
+    await expect(useNotesStore.getState().clearTrash()).rejects.toThrow("locked");
+    expect(useNotesStore.getState().list.trashed.map((n) => n.id)).toEqual(["t1"]);
+
+    apiMock.trashEmpty.mockResolvedValueOnce({
+      committed: true,
+      results: [{ id: "t1", status: "done", error: null, meta: null, path: null }],
+    });
     await useNotesStore.getState().clearTrash();
-    expect(apiMock.noteDeleteForever).toHaveBeenCalledWith("t1");
+    expect(apiMock.trashEmpty).toHaveBeenCalledTimes(2);
     expect(useNotesStore.getState().list.trashed).toEqual([]);
 
     await useNotesStore.getState().trash("a1");
@@ -475,7 +543,7 @@ describe("notesStore", () => {
     await useNotesStore.getState().init();
 
     await useNotesStore.getState().clearTrash();
-    expect(apiMock.noteDeleteForever).not.toHaveBeenCalled();
+    expect(apiMock.trashEmpty).not.toHaveBeenCalled();
   });
 
   it("toggles pin", async () => {
@@ -491,13 +559,137 @@ describe("notesStore", () => {
     expect(useNotesStore.getState().list.active.find((n) => n.id === "p1")?.isPinned).toBe(true);
   });
 
-  it("reorders and supports undo/redo", async () => {
+  it("sets and clears reminders", async () => {
+    const note = meta({ id: "r1" });
+    apiMock.notesList.mockResolvedValue({ active: [note], trashed: [] });
+    apiMock.appStateGetAll.mockResolvedValue({});
+    apiMock.noteSetReminder.mockResolvedValue({ ...note, remindAt: 5_000 });
+
+    const { useNotesStore } = await import("@/stores/notesStore");
+    await useNotesStore.getState().init();
+    await useNotesStore.getState().setReminder("r1", 5_000);
+
+    expect(apiMock.noteSetReminder).toHaveBeenCalledWith("r1", 5_000);
+    expect(useNotesStore.getState().list.active[0].remindAt).toBe(5_000);
+  });
+
+  it("applies notes-changed payloads from other windows and the backend", async () => {
+    const a = meta({ id: "a", title: "A" });
+    const b = meta({ id: "b", title: "B" });
+    apiMock.notesList.mockResolvedValue({ active: [a, b], trashed: [] });
+    apiMock.appStateGetAll.mockResolvedValue({ selectedNoteId: "b" });
+    apiMock.noteGet.mockResolvedValue({ meta: b, content: "" });
+    apiMock.noteSetActive.mockResolvedValue(undefined);
+
+    const { useNotesStore } = await import("@/stores/notesStore");
+    await useNotesStore.getState().init();
+
+    const trashedA = { ...a, isTrashed: true, trashedAt: 10 };
+    const c = meta({ id: "c", title: "C" });
+    await useNotesStore.getState().applyChanges([
+      { id: "a", before: a, after: trashedA },
+      { id: "b", before: b, after: null },
+      { id: "c", before: null, after: c },
+    ]);
+
+    const state = useNotesStore.getState();
+    expect(state.list.active.map((n) => n.id)).toEqual(["c"]);
+    expect(state.list.trashed.map((n) => n.id)).toEqual(["a"]);
+    expect(state.selectedId).toBeNull();
+  });
+
+  it("picks up edits from a note window before saving in the main window", async () => {
+    const s1 = meta({ id: "s1", title: "Plan", storage: "saved" });
+    const d1 = meta({ id: "d1", title: "Draft" });
+    apiMock.notesList.mockResolvedValue({ active: [s1, d1], trashed: [] });
+    apiMock.appStateGetAll.mockResolvedValue({ selectedNoteId: "d1" });
+    apiMock.noteGet.mockResolvedValueOnce({ meta: d1, content: "draft" });
+
+    const { useNotesStore } = await import("@/stores/notesStore");
+    await useNotesStore.getState().init();
+    apiMock.noteGet.mockResolvedValueOnce({ meta: s1, content: "old" });
+    await useNotesStore.getState().select("s1");
+
+    // The note window saves; the main window has no edits of its own, so it reloads.
+    const windowSaved = { ...s1, preview: "from window" };
+    apiMock.noteGet.mockResolvedValueOnce({ meta: windowSaved, content: "from window" });
+    const conflicts = await useNotesStore
+      .getState()
+      .applyChanges([{ id: "s1", before: s1, after: windowSaved }], "saved");
+    expect(conflicts).toEqual([]);
+    expect(useNotesStore.getState().contentById.s1).toBe("from window");
+
+    apiMock.noteSave.mockResolvedValue(windowSaved);
+    await useNotesStore.getState().save("s1");
+    expect(apiMock.noteSave).toHaveBeenLastCalledWith("s1", "from window");
+
+    // The echo of the main window's own save is not a conflict, even with typing since.
+    useNotesStore.getState().updateContent("s1", "mine");
+    apiMock.noteGet.mockResolvedValueOnce({ meta: windowSaved, content: "mine" });
+    const saving = useNotesStore.getState().save("s1");
+    useNotesStore.getState().updateContent("s1", "mine, more");
+    expect(
+      await useNotesStore
+        .getState()
+        .applyChanges([{ id: "s1", before: s1, after: windowSaved }], "saved"),
+    ).toEqual([]);
+    await saving;
+
+    // Edits here that aren't saved yet are kept and reported.
+    useNotesStore.getState().updateContent("s1", "unsaved");
+    apiMock.noteGet.mockResolvedValueOnce({ meta: windowSaved, content: "window again" });
+    expect(
+      await useNotesStore
+        .getState()
+        .applyChanges([{ id: "s1", before: s1, after: windowSaved }], "saved"),
+    ).toEqual(["s1"]);
+    expect(useNotesStore.getState().contentById.s1).toBe("unsaved");
+
+    // A cached note that isn't open is dropped and read again when selected.
+    apiMock.noteGet.mockClear();
+    await useNotesStore
+      .getState()
+      .applyChanges([{ id: "d1", before: d1, after: d1 }], "written");
+    expect(apiMock.noteGet).not.toHaveBeenCalled();
+    expect(useNotesStore.getState().contentById.d1).toBeUndefined();
+    apiMock.noteGet.mockResolvedValueOnce({ meta: d1, content: "draft from window" });
+    await useNotesStore.getState().select("d1");
+    expect(useNotesStore.getState().contentById.d1).toBe("draft from window");
+  });
+
+  it("keeps a capture into the open inbox when its autosave runs", async () => {
+    const inbox = meta({ id: "inbox", title: "Inbox" });
+    apiMock.notesList.mockResolvedValue({ active: [inbox], trashed: [] });
+    apiMock.appStateGetAll.mockResolvedValue({ selectedNoteId: "inbox" });
+    apiMock.noteGet.mockResolvedValueOnce({ meta: inbox, content: "# Inbox\n" });
+    apiMock.noteWriteDraft.mockResolvedValue(inbox);
+
+    const { useNotesStore } = await import("@/stores/notesStore");
+    await useNotesStore.getState().init();
+    useNotesStore.getState().updateContent("inbox", "# Inbox\n\nMilk");
+
+    // Quick capture appends to the file before the pending autosave has run.
+    apiMock.noteGet.mockResolvedValueOnce({ meta: inbox, content: "# Inbox\n\nCall Anna\n" });
+    const conflicts = await useNotesStore
+      .getState()
+      .applyChanges([{ id: "inbox", before: inbox, after: inbox }], "written");
+    expect(conflicts).toEqual([]);
+    expect(useNotesStore.getState().contentById.inbox).toBe("# Inbox\n\nMilk\n\nCall Anna\n");
+
+    await vi.advanceTimersByTimeAsync(500);
+    expect(apiMock.noteWriteDraft).toHaveBeenCalledTimes(1);
+    expect(apiMock.noteWriteDraft).toHaveBeenCalledWith("inbox", "# Inbox\n\nMilk\n\nCall Anna\n");
+  });
+
+  it("reorders and undoes through the backend journal", async () => {
     const p1 = meta({ id: "p1", isPinned: true, sortOrder: 1 });
     const p2 = meta({ id: "p2", isPinned: true, sortOrder: 2 });
     const n1 = meta({ id: "n1", isPinned: false, sortOrder: 3 });
     apiMock.notesList.mockResolvedValue({ active: [p1, p2, n1], trashed: [] });
     apiMock.appStateGetAll.mockResolvedValue({});
     apiMock.notesReorder.mockResolvedValue(undefined);
+    apiMock.historyUndo.mockResolvedValue("Reorder");
+    apiMock.historyRedo.mockResolvedValue("Reorder");
 
     const { useNotesStore } = await import("@/stores/notesStore");
     await useNotesStore.getState().init();
@@ -505,11 +697,14 @@ describe("notesStore", () => {
     await useNotesStore.getState().reorder("pinned", ["p2", "p1"]);
     expect(apiMock.notesReorder).toHaveBeenCalledWith(["p2", "p1"]);
 
-    await useNotesStore.getState().undoReorder();
-    expect(apiMock.notesReorder).toHaveBeenCalledWith(["p1", "p2"]);
+    apiMock.notesList.mockClear();
+    await useNotesStore.getState().undo();
+    expect(apiMock.historyUndo).toHaveBeenCalled();
+    expect(apiMock.notesList).toHaveBeenCalled();
 
-    await useNotesStore.getState().redoReorder();
-    expect(apiMock.notesReorder).toHaveBeenCalledWith(["p2", "p1"]);
+    await useNotesStore.getState().redo();
+    expect(apiMock.historyRedo).toHaveBeenCalled();
+    expect(apiMock.notesReorder).toHaveBeenCalledTimes(1);
   });
 
   it("heartbeats selected and logs errors", async () => {
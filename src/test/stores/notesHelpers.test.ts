@@ -26,6 +26,15 @@ function meta(overrides: Partial<NoteMeta>): NoteMeta {
     createdAt: 1,
     lastInteraction: 1,
     trashedAt: null,
+    encoding: "utf-8",
+    hasBom: false,
+    lineEnding: "lf",
+    expiresAt: null,
+    openTasks: 0,
+    doneTasks: 0,
+    dailyDate: null,
+    remindAt: null,
+    expiryDirective: null,
     ...overrides,
   };
 }
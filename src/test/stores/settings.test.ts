@@ -3,6 +3,9 @@ import { beforeEach, describe, expect, it, vi } from "vitest";
 const apiMock = {
   settingsGetAll: vi.fn(),
   settingsSet: vi.fn(),
+  autostartSet: vi.fn(),
+  keybindingsGet: vi.fn(),
+  keybindingsSet: vi.fn(),
 };
 
 vi.mock("@/lib/api", () => ({ api: apiMock }));
@@ -12,6 +15,10 @@ describe("settingsStore", () => {
     vi.resetModules();
     apiMock.settingsGetAll.mockReset();
     apiMock.settingsSet.mockReset();
+    apiMock.autostartSet.mockReset();
+    apiMock.keybindingsGet.mockReset();
+    apiMock.keybindingsGet.mockResolvedValue([]);
+    apiMock.keybindingsSet.mockReset();
     document.documentElement.className = "";
   });
 
@@ -19,6 +26,17 @@ describe("settingsStore", () => {
     apiMock.settingsGetAll.mockResolvedValue({
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
       theme: "light",
     });
 
@@ -30,12 +48,43 @@ describe("settingsStore", () => {
 
     await useSettingsStore.getState().init();
     expect(apiMock.settingsGetAll).toHaveBeenCalledTimes(1);
+
+    apiMock.settingsGetAll.mockResolvedValue({
+      expiryMinutes: 1440,
+      trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
+      theme: "dark",
+    });
+    await useSettingsStore.getState().reload();
+    expect(useSettingsStore.getState().expiryMinutes).toBe(1440);
+    expect(document.documentElement.classList.contains("dark")).toBe(true);
   });
 
   it("switches themes and persists", async () => {
     apiMock.settingsGetAll.mockResolvedValue({
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
       theme: "dark",
     });
 
@@ -63,6 +112,17 @@ describe("settingsStore", () => {
     apiMock.settingsGetAll.mockResolvedValue({
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
       theme: "system",
     });
 
@@ -88,6 +148,17 @@ describe("settingsStore", () => {
     apiMock.settingsGetAll.mockResolvedValue({
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
       theme: "dark",
     });
 
@@ -119,6 +190,17 @@ describe("settingsStore", () => {
     apiMock.settingsGetAll.mockResolvedValue({
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
       theme: "system",
     });
 
@@ -138,6 +220,17 @@ describe("settingsStore", () => {
     apiMock.settingsGetAll.mockResolvedValue({
       expiryMinutes: 10_080,
       trashRetentionDays: 30,
+      frontMatterWriteBack: false,
+      openTasksExpiry: "off",
+      openTasksMaxDays: 30,
+      doneTasksGraceMinutes: 0,
+      dailyExpiryMinutes: 4320,
+      dailyCarryTasks: true,
+      calendarFeedPath: "",
+      quickCaptureShortcut: "CmdOrCtrl+Alt+KeyN",
+      quickCaptureInboxId: "",
+      autostart: { enabled: false, hidden: false },
+      locale: "system",
       theme: "dark",
     });
 
@@ -149,5 +242,95 @@ describe("settingsStore", () => {
 
     await useSettingsStore.getState().setTrashRetentionDays(60);
     expect(apiMock.settingsSet).toHaveBeenCalledWith("trash_retention_days", "60");
+
+    await useSettingsStore.getState().setFrontMatterWriteBack(true);
+    expect(useSettingsStore.getState().frontMatterWriteBack).toBe(true);
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("front_matter_write_back", "true");
+
+    await useSettingsStore.getState().setOpenTasksExpiry("extend");
+    expect(useSettingsStore.getState().openTasksExpiry).toBe("extend");
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("open_tasks_expiry", "extend");
+
+    await useSettingsStore.getState().setOpenTasksMaxDays(60);
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("open_tasks_max_days", "60");
+
+    await useSettingsStore.getState().setDoneTasksGraceMinutes(360);
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("done_tasks_grace_minutes", "360");
+
+    await useSettingsStore.getState().setDailyExpiryMinutes(1440);
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("daily_expiry_minutes", "1440");
+
+    await useSettingsStore.getState().setDailyCarryTasks(false);
+    expect(useSettingsStore.getState().dailyCarryTasks).toBe(false);
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("daily_carry_tasks", "false");
+
+    await useSettingsStore.getState().setCalendarFeedPath("/tmp/notes.ics");
+    expect(useSettingsStore.getState().calendarFeedPath).toBe("/tmp/notes.ics");
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("calendar_feed_path", "/tmp/notes.ics");
+  });
+
+  it("keeps the old quick-capture shortcut when the new one can't be registered", async () => {
+    const { useSettingsStore } = await import("@/stores/settingsStore");
+    apiMock.settingsSet.mockResolvedValueOnce(undefined);
+    await useSettingsStore.getState().setQuickCaptureShortcut("CmdOrCtrl+Shift+KeyJ");
+    expect(useSettingsStore.getState().quickCaptureShortcut).toBe("CmdOrCtrl+Shift+KeyJ");
+
+    apiMock.settingsSet.mockRejectedValueOnce("Couldn't register the shortcut");
+    await expect(useSettingsStore.getState().setQuickCaptureShortcut("Nope")).rejects.toBe(
+      "Couldn't register the shortcut",
+    );
+    expect(useSettingsStore.getState().quickCaptureShortcut).toBe("CmdOrCtrl+Shift+KeyJ");
+
+    await useSettingsStore.getState().setQuickCaptureInboxId("n1");
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("quick_capture_inbox_id", "n1");
+  });
+
+  it("reverts launch at login when the OS entry can't be written", async () => {
+    const { useSettingsStore } = await import("@/stores/settingsStore");
+    apiMock.autostartSet.mockResolvedValueOnce(undefined);
+    await useSettingsStore.getState().setAutostart({ enabled: true, hidden: true });
+    expect(apiMock.autostartSet).toHaveBeenCalledWith(true, true);
+    expect(useSettingsStore.getState().autostart).toEqual({ enabled: true, hidden: true });
+
+    apiMock.autostartSet.mockRejectedValueOnce("Couldn't write the entry");
+    await expect(
+      useSettingsStore.getState().setAutostart({ enabled: false, hidden: false }),
+    ).rejects.toBe("Couldn't write the entry");
+    expect(useSettingsStore.getState().autostart).toEqual({ enabled: true, hidden: true });
+  });
+
+  it("takes the keybindings the backend returns", async () => {
+    const { useSettingsStore } = await import("@/stores/settingsStore");
+    const saveAs = {
+      id: "file_save_as_note",
+      label: "Save As…",
+      accelerator: "CmdOrCtrl+Alt+KeyS",
+      defaultAccelerator: "CmdOrCtrl+Shift+KeyS",
+    };
+    apiMock.keybindingsSet.mockResolvedValueOnce([saveAs]);
+    await useSettingsStore.getState().setKeybinding("file_save_as_note", "CmdOrCtrl+Alt+KeyS");
+    expect(apiMock.keybindingsSet).toHaveBeenCalledWith("file_save_as_note", "CmdOrCtrl+Alt+KeyS");
+    expect(useSettingsStore.getState().keybindings).toEqual([saveAs]);
+
+    apiMock.keybindingsSet.mockRejectedValueOnce("CmdOrCtrl+KeyO is already used by Open….");
+    await expect(
+      useSettingsStore.getState().setKeybinding("file_save_as_note", "CmdOrCtrl+KeyO"),
+    ).rejects.toBe("CmdOrCtrl+KeyO is already used by Open….");
+    expect(useSettingsStore.getState().keybindings).toEqual([saveAs]);
+  });
+
+  it("switches the language and refetches the translated action labels", async () => {
+    const { useSettingsStore } = await import("@/stores/settingsStore");
+    const settings = {
+      id: "app_settings",
+      label: "Einstellungen …",
+      accelerator: "CmdOrCtrl+Comma",
+      defaultAccelerator: "CmdOrCtrl+Comma",
+    };
+    apiMock.keybindingsGet.mockResolvedValueOnce([settings]);
+    await useSettingsStore.getState().setLocale("de");
+    expect(apiMock.settingsSet).toHaveBeenCalledWith("locale", "de");
+    expect(useSettingsStore.getState().locale).toBe("de");
+    expect(useSettingsStore.getState().keybindings).toEqual([settings]);
   });
 });